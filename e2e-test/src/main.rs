@@ -147,6 +147,7 @@ async fn main() -> color_eyre::Result<()> {
         store_directory: store_path.path().to_path_buf(),
         download_directory: store_path.path().join("downloads"),
         telemetry_config: Some(Vec::new()),
+        telemetry_schedule: edgehog_device_runtime::telemetry::schedule::ScheduleConfig::default(),
         #[cfg(feature = "message-hub")]
         astarte_message_hub: None,
         #[cfg(feature = "containers")]
@@ -155,7 +156,20 @@ async fn main() -> color_eyre::Result<()> {
         service: None,
         #[cfg(target_os = "linux")]
         ota: edgehog_device_runtime::ota::config::OtaConfig::default(),
+        #[cfg(target_os = "linux")]
+        network: edgehog_device_runtime::network::config::NetworkConfig::default(),
+        #[cfg(target_os = "linux")]
+        cellular: edgehog_device_runtime::telemetry::cellular::CellularConfig::default(),
+        #[cfg(target_os = "linux")]
+        time_sync: edgehog_device_runtime::telemetry::time_sync::TimeSyncConfig::default(),
+        gnss: edgehog_device_runtime::telemetry::gnss::GnssConfig::default(),
+        #[cfg(target_os = "linux")]
+        power_policy: edgehog_device_runtime::power_policy::config::PowerPolicyConfig::default(),
+        #[cfg(target_os = "linux")]
+        led: edgehog_device_runtime::led_behavior::config::LedConfig::default(),
         file_transfer: FileTransferArgs::with_store_dir(None, store_path.path()),
+        attributes: edgehog_device_runtime::attributes::config::AttributesConfig::default(),
+        bandwidth: edgehog_device_runtime::bandwidth::config::BandwidthConfig::default(),
     };
 
     let store = connect_store(store_path.path())
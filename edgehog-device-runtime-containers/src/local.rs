@@ -212,6 +212,32 @@ impl ContainerHandle {
         Ok(container)
     }
 
+    /// Checks if all the containers of a deployment are running.
+    ///
+    /// Returns [`None`] if the deployment doesn't exist.
+    pub async fn deployment_running(&self, id: Uuid) -> eyre::Result<Option<bool>> {
+        let Some(containers) = self.store.load_deployment_containers(id).await? else {
+            return Ok(None);
+        };
+
+        for container in containers {
+            let running = self
+                .get(*container)
+                .await?
+                .and_then(|container| container.state)
+                .and_then(|state| state.running)
+                .unwrap_or(false);
+
+            if !running {
+                trace!(%id, container = %*container, "deployment container not running");
+
+                return Ok(Some(false));
+            }
+        }
+
+        Ok(Some(true))
+    }
+
     /// Start the container
     pub async fn start(&self, id: Uuid) -> eyre::Result<Option<()>> {
         let local_id = self.store.load_container_local_id(id).await?;
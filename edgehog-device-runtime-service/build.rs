@@ -0,0 +1,32 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+const PROTOS: &[&str] = &["proto/edgehog/deviceruntime/ota/v1/ota.proto"];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Compiled with protox to not require protoc on the build machine
+    let fds = protox::compile(PROTOS, ["proto"])?;
+
+    for proto in PROTOS {
+        println!("cargo:rerun-if-changed={proto}");
+    }
+
+    tonic_prost_build::configure().compile_fds(fds)?;
+
+    Ok(())
+}
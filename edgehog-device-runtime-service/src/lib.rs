@@ -28,4 +28,5 @@
 //! Used to dynamically configure the [Edgehog Device Runtime](https://github.com/edgehog-device-manager/edghoe-device-runtime)
 
 pub mod config;
+pub mod proto;
 pub mod service;
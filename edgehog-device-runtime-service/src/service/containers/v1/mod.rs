@@ -292,7 +292,7 @@ mod tests {
             .with(predicate::eq(Vec::new()))
             .returning(|_| Ok(HashMap::new()));
 
-        let service = mock_service(handle);
+        let (service, _dir) = mock_service(handle).await;
 
         let req = ListRequest {
             list_info: None,
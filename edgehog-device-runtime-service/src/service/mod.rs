@@ -24,6 +24,7 @@ use std::sync::Arc;
 
 use cfg_if::cfg_if;
 use edgehog_proto::tonic::transport::server::{Connected, TcpIncoming};
+use edgehog_store::db::Handle;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_stream::Stream;
 use tokio_util::sync::CancellationToken;
@@ -33,6 +34,7 @@ use crate::config::Config;
 
 #[cfg(feature = "containers")]
 mod containers;
+mod ota;
 
 /// Options for the [`EdgehogService`]
 #[derive(Debug, Clone)]
@@ -98,6 +100,7 @@ impl Display for Listener {
 #[derive(Debug)]
 pub struct EdgehogService {
     options: ServiceOptions,
+    db: Handle,
     #[cfg(feature = "containers")]
     containers: self::containers::SharedContainerHandle,
 }
@@ -106,10 +109,12 @@ impl EdgehogService {
     /// Create a new instance of the service
     pub fn new(
         options: ServiceOptions,
+        db: Handle,
         #[cfg(feature = "containers")] containers: self::containers::SharedContainerHandle,
     ) -> Self {
         Self {
             options,
+            db,
             #[cfg(feature = "containers")]
             containers,
         }
@@ -168,61 +173,58 @@ impl Drop for EdgehogService {
     }
 }
 
-cfg_if! {
-    if #[cfg(feature = "containers")] {
-        async fn serve<S, IO>(
-            this: Arc<EdgehogService>,
-            cancel: CancellationToken,
-            incoming: S,
-        ) -> eyre::Result<()>
-        where
-            S: Stream<Item = std::io::Result<IO>>,
-            IO: AsyncRead + AsyncWrite + Connected + Unpin + Send + 'static,
-        {
-            use edgehog_proto::tonic::transport::Server;
-
-            let mut builder = Server::builder();
-
-            builder
-                .add_service(edgehog_proto::containers::latest::containers_service_server::ContainersServiceServer::from_arc(this))
-                .serve_with_incoming_shutdown(incoming, async {
-                    cancel.cancelled().await;
-
-                    info!("shutting down edgehog service")
-                })
-                .await?;
-
-            Ok(())
-        }
-    } else {
-        async fn serve<S, IO>(
-            _this: Arc<EdgehogService>,
-            _cancel: CancellationToken,
-            _incoming: S,
-        ) -> eyre::Result<()>
-        where
-            S: Stream<Item = std::io::Result<IO>>,
-            IO: AsyncRead + AsyncWrite + Connected + Unpin + Send + 'static,
-        {
-            unimplemented!("no service implemented");
-        }
-    }
+async fn serve<S, IO>(
+    this: Arc<EdgehogService>,
+    cancel: CancellationToken,
+    incoming: S,
+) -> eyre::Result<()>
+where
+    S: Stream<Item = std::io::Result<IO>>,
+    IO: AsyncRead + AsyncWrite + Connected + Unpin + Send + 'static,
+{
+    use edgehog_proto::tonic::transport::Server;
+
+    use crate::proto::ota::latest::ota_service_server::OtaServiceServer;
+
+    let router = Server::builder().add_service(OtaServiceServer::from_arc(Arc::clone(&this)));
+
+    #[cfg(feature = "containers")]
+    let router = router.add_service(
+        edgehog_proto::containers::latest::containers_service_server::ContainersServiceServer::from_arc(this),
+    );
+
+    router
+        .serve_with_incoming_shutdown(incoming, async {
+            cancel.cancelled().await;
+
+            info!("shutting down edgehog service")
+        })
+        .await?;
+
+    Ok(())
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use tempfile::TempDir;
+
     use super::*;
 
-    #[cfg_attr(not(feature = "containers"), expect(dead_code))]
-    pub(crate) fn mock_service(
+    pub(crate) async fn mock_service(
         #[cfg(feature = "containers")] handle: edgehog_containers::local::MockContainerHandle,
-    ) -> EdgehogService {
-        EdgehogService {
+    ) -> (EdgehogService, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let db = Handle::open(dir.path().join("state.db")).await.unwrap();
+
+        let service = EdgehogService {
             options: ServiceOptions {
                 listener: Listener::Socket("0.0.0.0:0".parse().unwrap()),
             },
+            db,
             #[cfg(feature = "containers")]
             containers: Arc::new(tokio::sync::OnceCell::const_new_with(handle)),
-        }
+        };
+
+        (service, dir)
     }
 }
@@ -0,0 +1,194 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Service for the OTA updates of the device.
+
+use async_trait::async_trait;
+use edgehog_proto::tonic::{self, Response, Status};
+use edgehog_store::diesel::{ExpressionMethods, HasQuery, QueryDsl, RunQueryDsl};
+use edgehog_store::models::ota::OtaUpdate;
+use edgehog_store::schema::runtime::ota_history;
+use tracing::error;
+
+use crate::proto::ota::v1::ota_service_server::OtaService;
+use crate::proto::ota::v1::{self, HistoryRequest, HistoryResponse};
+use crate::service::EdgehogService;
+
+#[async_trait]
+impl OtaService for EdgehogService {
+    async fn history(
+        &self,
+        _request: tonic::Request<HistoryRequest>,
+    ) -> Result<Response<HistoryResponse>, Status> {
+        let history = self
+            .db
+            .for_read(|reader| {
+                let history = OtaUpdate::query()
+                    .order_by(ota_history::acknowledged_at.asc())
+                    .load(reader)?;
+
+                Ok(history)
+            })
+            .await
+            .map_err(|err| {
+                error!(
+                    error = format!("{:#}", eyre::Report::new(err)),
+                    "couldn't read the ota history"
+                );
+
+                Status::internal("couldn't read the ota history")
+            })?;
+
+        let updates = history.into_iter().map(convert_update).collect();
+
+        Ok(Response::new(HistoryResponse { updates }))
+    }
+}
+
+fn convert_update(update: OtaUpdate) -> v1::OtaUpdate {
+    let OtaUpdate {
+        id,
+        url,
+        bundle_version,
+        bundle_compatible,
+        from_slot,
+        to_slot,
+        outcome,
+        message,
+        acknowledged_at,
+        downloading_at,
+        deploying_at,
+        deployed_at,
+        staged_at,
+        rebooting_at,
+        completed_at,
+    } = update;
+
+    v1::OtaUpdate {
+        id: id.to_string(),
+        url,
+        bundle_version,
+        bundle_compatible,
+        from_slot,
+        to_slot,
+        outcome: outcome.to_string(),
+        message,
+        acknowledged_at,
+        completed_at,
+        downloading_at,
+        deploying_at,
+        deployed_at,
+        staged_at,
+        rebooting_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edgehog_store::diesel::insert_into;
+    use edgehog_store::models::ota::outcome::OtaOutcome;
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    use crate::service::tests::mock_service;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_list_history() {
+        let (service, _dir) = mock_service(
+            #[cfg(feature = "containers")]
+            edgehog_containers::local::MockContainerHandle::default(),
+        )
+        .await;
+
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+
+        let mut failed = OtaUpdate::new(second, "https://example.com/ota.bin", 120);
+        failed.outcome = OtaOutcome::Failure;
+        failed.message = Some("install failed".to_string());
+        failed.downloading_at = Some(130);
+        failed.deploying_at = Some(170);
+        failed.completed_at = Some(180);
+
+        let updates = [
+            failed,
+            OtaUpdate::new(first, "/run/media/ota/bundle.raucb", 60),
+        ];
+
+        service
+            .db
+            .for_write(move |writer| {
+                insert_into(ota_history::table)
+                    .values(&updates)
+                    .execute(writer)?;
+
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let res = service
+            .history(tonic::Request::new(HistoryRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let exp = HistoryResponse {
+            updates: vec![
+                v1::OtaUpdate {
+                    id: first.to_string(),
+                    url: "/run/media/ota/bundle.raucb".to_string(),
+                    bundle_version: None,
+                    bundle_compatible: None,
+                    from_slot: None,
+                    to_slot: None,
+                    outcome: "InProgress".to_string(),
+                    message: None,
+                    acknowledged_at: 60,
+                    completed_at: None,
+                    downloading_at: None,
+                    deploying_at: None,
+                    deployed_at: None,
+                    staged_at: None,
+                    rebooting_at: None,
+                },
+                v1::OtaUpdate {
+                    id: second.to_string(),
+                    url: "https://example.com/ota.bin".to_string(),
+                    bundle_version: None,
+                    bundle_compatible: None,
+                    from_slot: None,
+                    to_slot: None,
+                    outcome: "Failure".to_string(),
+                    message: Some("install failed".to_string()),
+                    acknowledged_at: 120,
+                    completed_at: Some(180),
+                    downloading_at: Some(130),
+                    deploying_at: Some(170),
+                    deployed_at: None,
+                    staged_at: None,
+                    rebooting_at: None,
+                },
+            ],
+        };
+
+        assert_eq!(res, exp);
+    }
+}
@@ -0,0 +1,35 @@
+-- This file is part of Edgehog.
+--
+-- Copyright 2026 SECO Mind Srl
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+--
+-- SPDX-License-Identifier: Apache-2.0
+
+CREATE TABLE IF NOT EXISTS ota_history (
+    id BLOB PRIMARY KEY NOT NULL,
+    url TEXT NOT NULL,
+    bundle_version TEXT,
+    bundle_compatible TEXT,
+    from_slot TEXT,
+    to_slot TEXT,
+    outcome INTEGER NOT NULL,
+    message TEXT,
+    acknowledged_at BIGINT NOT NULL,
+    downloading_at BIGINT,
+    deploying_at BIGINT,
+    deployed_at BIGINT,
+    staged_at BIGINT,
+    rebooting_at BIGINT,
+    completed_at BIGINT
+);
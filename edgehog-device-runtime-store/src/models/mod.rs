@@ -30,6 +30,7 @@ use crate::conversions::SqlUuid;
 #[cfg(feature = "containers")]
 pub mod containers;
 pub mod job;
+pub mod ota;
 
 type ById<'a, Id> = Eq<Id, &'a SqlUuid>;
 type FilterById<'a, Table, Id> = Filter<Table, ById<'a, Id>>;
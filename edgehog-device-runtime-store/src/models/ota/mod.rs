@@ -0,0 +1,89 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Models for the history of the OTA updates
+
+use diesel::prelude::*;
+
+use crate::conversions::SqlUuid;
+
+use self::outcome::OtaOutcome;
+
+pub mod outcome;
+
+/// An OTA update received by the device
+///
+/// The timestamps are the seconds from the Unix epoch when the update entered the state.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, HasQuery, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = crate::schema::runtime::ota_history)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct OtaUpdate {
+    /// Unique id of the OTA request
+    pub id: SqlUuid,
+    /// URL or local path of the bundle
+    pub url: String,
+    /// Version of the installed bundle
+    pub bundle_version: Option<String>,
+    /// Compatible of the installed bundle
+    pub bundle_compatible: Option<String>,
+    /// Slot booted before the update
+    pub from_slot: Option<String>,
+    /// Slot booted after the update
+    pub to_slot: Option<String>,
+    /// Result of the update
+    pub outcome: OtaOutcome,
+    /// Error message of the failed update
+    pub message: Option<String>,
+    /// When the request was acknowledged
+    pub acknowledged_at: i64,
+    /// When the bundle download started
+    pub downloading_at: Option<i64>,
+    /// When the bundle installation started
+    pub deploying_at: Option<i64>,
+    /// When the bundle was installed
+    pub deployed_at: Option<i64>,
+    /// When the bundle was staged
+    pub staged_at: Option<i64>,
+    /// When the device was rebooted into the updated slot
+    pub rebooting_at: Option<i64>,
+    /// When the update succeeded or failed
+    pub completed_at: Option<i64>,
+}
+
+impl OtaUpdate {
+    /// Creates the update for an acknowledged request
+    pub fn new(id: impl Into<SqlUuid>, url: impl Into<String>, acknowledged_at: i64) -> Self {
+        Self {
+            id: id.into(),
+            url: url.into(),
+            bundle_version: None,
+            bundle_compatible: None,
+            from_slot: None,
+            to_slot: None,
+            outcome: OtaOutcome::InProgress,
+            message: None,
+            acknowledged_at,
+            downloading_at: None,
+            deploying_at: None,
+            deployed_at: None,
+            staged_at: None,
+            rebooting_at: None,
+            completed_at: None,
+        }
+    }
+}
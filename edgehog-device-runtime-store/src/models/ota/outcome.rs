@@ -0,0 +1,135 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Outcome of an OTA update.
+
+use std::fmt::Display;
+
+use diesel::backend::Backend;
+use diesel::deserialize::{FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::serialize::{IsNull, ToSql};
+use diesel::sql_types::Integer;
+use diesel::sqlite::Sqlite;
+
+/// Outcome of the update
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, FromSqlRow, AsExpression,
+)]
+#[diesel(sql_type = Integer)]
+#[repr(u8)]
+pub enum OtaOutcome {
+    /// The update didn't complete yet
+    #[default]
+    InProgress = 0,
+    /// The updated slot was marked good
+    Success = 1,
+    /// The update failed
+    Failure = 2,
+    /// The update was canceled
+    Canceled = 3,
+    /// The device booted back into the previous slot
+    RolledBack = 4,
+}
+
+impl Display for OtaOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OtaOutcome::InProgress => write!(f, "InProgress"),
+            OtaOutcome::Success => write!(f, "Success"),
+            OtaOutcome::Failure => write!(f, "Failure"),
+            OtaOutcome::Canceled => write!(f, "Canceled"),
+            OtaOutcome::RolledBack => write!(f, "RolledBack"),
+        }
+    }
+}
+
+impl From<OtaOutcome> for i32 {
+    fn from(value: OtaOutcome) -> Self {
+        (value as u8).into()
+    }
+}
+
+impl TryFrom<i32> for OtaOutcome {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, String> {
+        match value {
+            0 => Ok(OtaOutcome::InProgress),
+            1 => Ok(OtaOutcome::Success),
+            2 => Ok(OtaOutcome::Failure),
+            3 => Ok(OtaOutcome::Canceled),
+            4 => Ok(OtaOutcome::RolledBack),
+            _ => Err(format!("unrecognized outcome value {value}")),
+        }
+    }
+}
+
+impl<DB> FromSql<Integer, DB> for OtaOutcome
+where
+    DB: Backend,
+    i32: FromSql<Integer, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let value = i32::from_sql(bytes)?;
+
+        Self::try_from(value).map_err(Into::into)
+    }
+}
+
+impl ToSql<Integer, Sqlite> for OtaOutcome {
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, Sqlite>,
+    ) -> diesel::serialize::Result {
+        let val = i32::from(*self);
+
+        out.set_value(val);
+
+        Ok(IsNull::No)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::{Context, rstest};
+
+    use crate::tests::with_insta;
+
+    use super::*;
+
+    #[rstest]
+    #[case(OtaOutcome::InProgress)]
+    #[case(OtaOutcome::Success)]
+    #[case(OtaOutcome::Failure)]
+    #[case(OtaOutcome::Canceled)]
+    #[case(OtaOutcome::RolledBack)]
+    fn ota_outcome_roundtrip(#[context] ctx: Context, #[case] value: OtaOutcome) {
+        let i = i32::from(value);
+
+        let res = OtaOutcome::try_from(i).unwrap();
+
+        assert_eq!(res, value);
+
+        with_insta!({
+            let name = format!("{}_{}", ctx.name, ctx.case.unwrap());
+
+            insta::assert_snapshot!(name, format!("{value:?} = {i}"));
+        });
+    }
+}
@@ -55,3 +55,103 @@ diesel::table! {
         created_at -> BigInt,
     }
 }
+
+diesel::table! {
+    /// Representation of the `ota_history` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ota_history (id) {
+        /// The `id` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Binary`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Binary,
+        /// The `url` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        url -> Text,
+        /// The `bundle_version` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bundle_version -> Nullable<Text>,
+        /// The `bundle_compatible` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bundle_compatible -> Nullable<Text>,
+        /// The `from_slot` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        from_slot -> Nullable<Text>,
+        /// The `to_slot` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        to_slot -> Nullable<Text>,
+        /// The `outcome` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        outcome -> Integer,
+        /// The `message` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        message -> Nullable<Text>,
+        /// The `acknowledged_at` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `BigInt`.
+        ///
+        /// (Automatically generated by Diesel.)
+        acknowledged_at -> BigInt,
+        /// The `downloading_at` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<BigInt>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        downloading_at -> Nullable<BigInt>,
+        /// The `deploying_at` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<BigInt>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        deploying_at -> Nullable<BigInt>,
+        /// The `deployed_at` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<BigInt>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        deployed_at -> Nullable<BigInt>,
+        /// The `staged_at` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<BigInt>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        staged_at -> Nullable<BigInt>,
+        /// The `rebooting_at` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<BigInt>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        rebooting_at -> Nullable<BigInt>,
+        /// The `completed_at` column of the `ota_history` table.
+        ///
+        /// Its SQL type is `Nullable<BigInt>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        completed_at -> Nullable<BigInt>,
+    }
+}
+
+diesel::allow_tables_to_appear_in_same_query!(job_queue, ota_history,);
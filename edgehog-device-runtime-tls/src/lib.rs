@@ -18,6 +18,7 @@
 
 //! TLS configuration for the Edgehog Device Runtime
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use rustls::crypto::CryptoProvider;
@@ -45,6 +46,22 @@ pub fn config() -> Result<ClientConfig, Error> {
     }
 }
 
+/// Returns the CA file the roots of the TLS client are loaded from.
+///
+/// This is the file used by the native roots, also by the platform verifier on Linux. The webpki
+/// roots are compiled in, so there is no file to return.
+pub fn ca_file() -> Option<PathBuf> {
+    cfg_if::cfg_if! {
+        if #[cfg(all(feature = "webpki-roots", not(feature = "platform-verifier")))] {
+            None
+        } else if #[cfg(all(unix, not(target_vendor = "apple"), not(target_os = "android")))] {
+            openssl_probe::probe().cert_file
+        } else {
+            None
+        }
+    }
+}
+
 #[cfg(feature = "platform-verifier")]
 #[instrument(skip(provider))]
 fn platform_verifier(provider: Arc<CryptoProvider>) -> Result<ClientConfig, Error> {
@@ -17,19 +17,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::io::{self, IsTerminal};
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use clap::Parser;
+use edgehog_service::config::Listener;
 use eyre::eyre;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
 use self::file_transfer::FileTransfer;
 use self::jobs::Jobs;
+use self::ota::Ota;
 
 mod client;
 mod file_transfer;
 mod jobs;
+mod ota;
 
 #[derive(Debug, clap::Parser)]
 struct Cli {
@@ -55,6 +59,19 @@ enum Command {
         #[clap(subcommand)]
         command: Jobs,
     },
+    Ota {
+        /// Unix socket of the runtime service, the default one if no address is passed
+        #[clap(long, conflicts_with = "address")]
+        socket: Option<PathBuf>,
+
+        /// TCP address of the runtime service
+        #[clap(long)]
+        address: Option<SocketAddr>,
+
+        /// OTA commands
+        #[clap(subcommand)]
+        command: Ota,
+    },
 }
 
 #[tokio::main]
@@ -90,6 +107,19 @@ async fn main() -> eyre::Result<()> {
             storage_dir,
             command,
         } => command.run(&storage_dir).await?,
+        Command::Ota {
+            socket,
+            address,
+            command,
+        } => {
+            let listener = match (socket, address) {
+                (Some(path), _) => Listener::Unix(path),
+                (None, Some(addr)) => Listener::Socket(addr),
+                (None, None) => Listener::default(),
+            };
+
+            command.run(listener.try_into()?).await?
+        }
     }
 
     Ok(())
@@ -0,0 +1,119 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Subcommand;
+use edgehog_proto::tonic::transport::{Channel, Endpoint};
+use edgehog_service::proto::ota::latest::ota_service_client::OtaServiceClient;
+use edgehog_service::proto::ota::latest::{HistoryRequest, OtaUpdate};
+use edgehog_service::service::Listener;
+use eyre::WrapErr;
+
+#[derive(Debug, Clone, Copy, Subcommand)]
+pub(crate) enum Ota {
+    /// Lists the OTA updates received by the device
+    History,
+}
+
+impl Ota {
+    pub(crate) async fn run(self, listener: Listener) -> eyre::Result<()> {
+        let mut client = connect(&listener)
+            .await
+            .wrap_err_with(|| format!("couldn't connect to the runtime service {listener}"))?;
+
+        let history = client
+            .history(HistoryRequest {})
+            .await
+            .wrap_err("couldn't get the ota history")?
+            .into_inner()
+            .updates;
+
+        println!(
+            "ID\tURL\tVERSION\tCOMPATIBLE\tFROM_SLOT\tTO_SLOT\tOUTCOME\tACKNOWLEDGED_AT\tDOWNLOADING_AT\tDEPLOYING_AT\tDEPLOYED_AT\tSTAGED_AT\tREBOOTING_AT\tCOMPLETED_AT\tMESSAGE"
+        );
+        for update in history {
+            let OtaUpdate {
+                id,
+                url,
+                bundle_version,
+                bundle_compatible,
+                from_slot,
+                to_slot,
+                outcome,
+                message,
+                acknowledged_at,
+                completed_at,
+                downloading_at,
+                deploying_at,
+                deployed_at,
+                staged_at,
+                rebooting_at,
+            } = update;
+
+            println!(
+                "{id}\t{url}\t{}\t{}\t{}\t{}\t{outcome}\t{acknowledged_at}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                bundle_version.unwrap_or_default(),
+                bundle_compatible.unwrap_or_default(),
+                from_slot.unwrap_or_default(),
+                to_slot.unwrap_or_default(),
+                timestamp(downloading_at),
+                timestamp(deploying_at),
+                timestamp(deployed_at),
+                timestamp(staged_at),
+                timestamp(rebooting_at),
+                timestamp(completed_at),
+                message.unwrap_or_default(),
+            )
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats an optional timestamp, empty when missing.
+fn timestamp(ts: Option<i64>) -> String {
+    ts.map(|ts| ts.to_string()).unwrap_or_default()
+}
+
+async fn connect(listener: &Listener) -> eyre::Result<OtaServiceClient<Channel>> {
+    let channel = match listener {
+        #[cfg(unix)]
+        Listener::Unix(path) => {
+            let path = path.clone();
+
+            // The URI is required by tonic, but the connection uses the socket
+            Endpoint::from_static("http://[::]:50052")
+                .connect_with_connector(tower::service_fn(move |_| {
+                    let path = path.clone();
+
+                    async move {
+                        let stream = tokio::net::UnixStream::connect(path).await?;
+
+                        Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+                    }
+                }))
+                .await?
+        }
+        Listener::Socket(addr) => {
+            Endpoint::from_shared(format!("http://{addr}"))?
+                .connect()
+                .await?
+        }
+    };
+
+    Ok(OtaServiceClient::new(channel))
+}
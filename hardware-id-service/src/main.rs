@@ -18,15 +18,17 @@
 
 use base64::prelude::*;
 use clap::{Parser, Subcommand};
-use eyre::Context;
 use std::path::PathBuf;
 use tracing::{info, level_filters::LevelFilter};
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 use uuid::Uuid;
 use zbus::interface;
 
+use crate::source::{Fallbacks, Source, read_composite};
+
+mod source;
+
 const SERVICE_NAME: &str = "io.edgehog.Device";
-const DMI_SERIAL_FILE_PATH: &str = "/sys/class/dmi/id/board_serial";
 const DEFAULT_NAMESPACE: &str = "f79ad91f-c638-4889-ae74-9d001a3b4cf8";
 
 #[derive(Debug, Parser)]
@@ -55,6 +57,48 @@ enum HardwareId {
         /// The name of the cmdline
         key: String,
     },
+    /// Retrieve the hardware id from "/proc/device-tree/serial-number"
+    DeviceTree,
+    /// Retrieve the hardware id from "/etc/machine-id"
+    MachineId,
+    /// Retrieve the hardware id from an eFuse or nvmem sysfs node
+    Efuse {
+        /// Path of the sysfs node, hex encoded if the content is not valid UTF-8
+        path: PathBuf,
+    },
+    /// Retrieve the hardware id from the MAC address of a network interface
+    Mac {
+        /// Name of the network interface
+        interface: String,
+    },
+    /// Hash an ordered list of sources in a single hardware id
+    ///
+    /// Each component is a list of sources separated by '|', the first available one is used.
+    /// It fails if a component has no available source, for example:
+    /// `composite device-tree|dmi-serial mac:eth0|mac:wlan0 machine-id`
+    Composite {
+        /// Components of the id, in the form name or name:argument
+        #[arg(required = true)]
+        components: Vec<Fallbacks>,
+    },
+}
+
+impl HardwareId {
+    async fn read(self) -> eyre::Result<String> {
+        let source = match self {
+            HardwareId::FilePath { path } => Source::File(path),
+            HardwareId::DmiSerial => Source::DmiSerial,
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            HardwareId::KernelCmdLine { key } => Source::KernelCmdLine(key),
+            HardwareId::DeviceTree => Source::DeviceTree,
+            HardwareId::MachineId => Source::MachineId,
+            HardwareId::Efuse { path } => Source::Efuse(path),
+            HardwareId::Mac { interface } => Source::Mac(interface),
+            HardwareId::Composite { components } => return read_composite(&components).await,
+        };
+
+        source.read().await
+    }
 }
 
 struct Device {
@@ -94,34 +138,7 @@ async fn main() -> eyre::Result<()> {
         )
         .try_init()?;
 
-    let hw_id = match cli.command {
-        HardwareId::FilePath { path } => tokio::fs::read_to_string(&path)
-            .await
-            .wrap_err_with(|| format!("couldn't read file {}", path.display()))?
-            .trim()
-            .to_string(),
-        HardwareId::DmiSerial => tokio::fs::read_to_string(&DMI_SERIAL_FILE_PATH)
-            .await
-            .wrap_err("couldn't read DMI file")?
-            .trim()
-            .to_string(),
-        #[cfg(any(target_os = "linux", target_os = "android"))]
-        HardwareId::KernelCmdLine { key } => {
-            use eyre::OptionExt;
-
-            let cmdline = procfs::cmdline()?;
-
-            cmdline
-                .iter()
-                .find_map(|s| {
-                    s.split_once('=')
-                        .and_then(|(name, value)| (name == key).then_some(value))
-                })
-                .ok_or_eyre("kernel cmdline value not found")?
-                .trim()
-                .to_string()
-        }
-    };
+    let hw_id = cli.command.read().await?;
 
     let builder = if cli.session {
         zbus::connection::Builder::session()?
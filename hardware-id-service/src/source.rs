@@ -0,0 +1,342 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sources to read the hardware ID from.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use eyre::{Context, bail, eyre};
+use tracing::debug;
+
+pub const DMI_SERIAL_FILE_PATH: &str = "/sys/class/dmi/id/board_serial";
+pub const DEVICE_TREE_SERIAL_FILE_PATH: &str = "/proc/device-tree/serial-number";
+pub const MACHINE_ID_FILE_PATH: &str = "/etc/machine-id";
+const NET_CLASS_DIR: &str = "/sys/class/net";
+
+/// Separator of the alternatives of a composite ID component.
+const FALLBACK_SEPARATOR: char = '|';
+
+/// Single source of the hardware ID.
+///
+/// It's parsed from the `name` or `name:argument` form, used for the composite IDs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Content of a file
+    File(PathBuf),
+    /// The DMI board serial
+    DmiSerial,
+    /// Value of a kernel cmdline parameter in the form key=value
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    KernelCmdLine(String),
+    /// Serial number from the device tree
+    DeviceTree,
+    /// The systemd machine ID
+    MachineId,
+    /// eFuse or nvmem sysfs node, hex encoded if it's not valid UTF-8
+    Efuse(PathBuf),
+    /// MAC address of a network interface
+    Mac(String),
+}
+
+impl Source {
+    /// Reads the hardware ID from the source.
+    pub async fn read(&self) -> eyre::Result<String> {
+        let value = match self {
+            Source::File(path) => read_file(path).await?,
+            Source::DmiSerial => read_file(Path::new(DMI_SERIAL_FILE_PATH))
+                .await
+                .wrap_err("couldn't read DMI file")?,
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Source::KernelCmdLine(key) => {
+                let cmdline = procfs::cmdline()?;
+
+                cmdline
+                    .iter()
+                    .find_map(|s| {
+                        s.split_once('=')
+                            .and_then(|(name, value)| (name == key).then_some(value))
+                    })
+                    .ok_or_else(|| eyre!("kernel cmdline value {key} not found"))?
+                    .trim()
+                    .to_string()
+            }
+            Source::DeviceTree => read_file(Path::new(DEVICE_TREE_SERIAL_FILE_PATH))
+                .await
+                .wrap_err("couldn't read the device tree serial number")?,
+            Source::MachineId => read_file(Path::new(MACHINE_ID_FILE_PATH))
+                .await
+                .wrap_err("couldn't read the machine id")?,
+            Source::Efuse(path) => {
+                let content = tokio::fs::read(path)
+                    .await
+                    .wrap_err_with(|| format!("couldn't read eFuse {}", path.display()))?;
+
+                match String::from_utf8(content) {
+                    Ok(value) => trim(&value).to_string(),
+                    Err(err) => hex::encode(err.as_bytes()),
+                }
+            }
+            Source::Mac(interface) => {
+                let path = Path::new(NET_CLASS_DIR).join(interface).join("address");
+
+                let mac = read_file(&path)
+                    .await
+                    .wrap_err_with(|| format!("couldn't read MAC address of {interface}"))?;
+
+                if mac.chars().all(|c| c == '0' || c == ':') {
+                    bail!("interface {interface} has no MAC address");
+                }
+
+                mac
+            }
+        };
+
+        if value.is_empty() {
+            bail!("hardware id from {self} is empty");
+        }
+
+        Ok(value)
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "file:{}", path.display()),
+            Source::DmiSerial => write!(f, "dmi-serial"),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Source::KernelCmdLine(key) => write!(f, "kernel-cmd-line:{key}"),
+            Source::DeviceTree => write!(f, "device-tree"),
+            Source::MachineId => write!(f, "machine-id"),
+            Source::Efuse(path) => write!(f, "efuse:{}", path.display()),
+            Source::Mac(interface) => write!(f, "mac:{interface}"),
+        }
+    }
+}
+
+impl FromStr for Source {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = match s.split_once(':') {
+            Some((name, arg)) if !arg.is_empty() => (name, Some(arg)),
+            Some((name, _)) => bail!("missing argument for source {name}"),
+            None => (s, None),
+        };
+
+        let source = match (name, arg) {
+            ("file", Some(path)) => Source::File(PathBuf::from(path)),
+            ("dmi-serial", None) => Source::DmiSerial,
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            ("kernel-cmd-line", Some(key)) => Source::KernelCmdLine(key.to_string()),
+            ("device-tree", None) => Source::DeviceTree,
+            ("machine-id", None) => Source::MachineId,
+            ("efuse", Some(path)) => Source::Efuse(PathBuf::from(path)),
+            ("mac", Some(interface)) => Source::Mac(interface.to_string()),
+            ("file" | "kernel-cmd-line" | "efuse" | "mac", None) => {
+                bail!("missing argument for source {name}")
+            }
+            ("dmi-serial" | "device-tree" | "machine-id", Some(_)) => {
+                bail!("source {name} doesn't take an argument")
+            }
+            _ => bail!("unknown hardware id source {name}"),
+        };
+
+        Ok(source)
+    }
+}
+
+/// Component of a composite ID, with the sources to try in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fallbacks(Vec<Source>);
+
+impl Fallbacks {
+    /// Returns the value of the first source that can be read.
+    async fn read(&self) -> Option<(&Source, String)> {
+        for source in &self.0 {
+            match source.read().await {
+                Ok(value) => return Some((source, value)),
+                Err(err) => debug!(%source, error = format!("{err:#}"), "source not available"),
+            }
+        }
+
+        None
+    }
+}
+
+impl Display for Fallbacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut sources = self.0.iter();
+
+        if let Some(first) = sources.next() {
+            write!(f, "{first}")?;
+        }
+
+        sources.try_for_each(|source| write!(f, "{FALLBACK_SEPARATOR}{source}"))
+    }
+}
+
+impl FromStr for Fallbacks {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(FALLBACK_SEPARATOR)
+            .map(Source::from_str)
+            .collect::<eyre::Result<Vec<_>>>()
+            .map(Fallbacks)
+    }
+}
+
+/// Builds an ID from an ordered list of components.
+///
+/// Every component uses the first of its sources that can be read, and fails if none is available,
+/// since hashing a partial set would change the hardware ID. Only the values are joined, so the
+/// fallbacks of a component should expose the same value, like the board serial from the device
+/// tree or the DMI. The resulting ID is then hashed by the service.
+pub async fn read_composite(components: &[Fallbacks]) -> eyre::Result<String> {
+    let mut parts = Vec::with_capacity(components.len());
+
+    for component in components {
+        let Some((source, value)) = component.read().await else {
+            bail!("no source available for the composite id component {component}");
+        };
+
+        debug!(%source, "composite id component read");
+
+        parts.push(value);
+    }
+
+    Ok(parts.join("\n"))
+}
+
+async fn read_file(path: &Path) -> eyre::Result<String> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .wrap_err_with(|| format!("couldn't read file {}", path.display()))?;
+
+    Ok(trim(&content).to_string())
+}
+
+/// Device tree properties are NUL terminated.
+fn trim(value: &str) -> &str {
+    value.trim_matches(|c: char| c.is_whitespace() || c == '\0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_sources() {
+        let cases = [
+            ("file:/etc/hw-id", Source::File(PathBuf::from("/etc/hw-id"))),
+            ("dmi-serial", Source::DmiSerial),
+            ("device-tree", Source::DeviceTree),
+            ("machine-id", Source::MachineId),
+            (
+                "efuse:/sys/bus/nvmem/devices/imx-ocotp0/nvmem",
+                Source::Efuse(PathBuf::from("/sys/bus/nvmem/devices/imx-ocotp0/nvmem")),
+            ),
+            ("mac:eth0", Source::Mac("eth0".to_string())),
+        ];
+
+        for (value, exp) in cases {
+            let source = Source::from_str(value).unwrap();
+
+            assert_eq!(source, exp);
+            assert_eq!(source.to_string(), value);
+        }
+
+        for invalid in ["mac", "mac:", "machine-id:foo", "serial"] {
+            assert!(Source::from_str(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn should_parse_fallbacks() {
+        let fallbacks = Fallbacks::from_str("device-tree|dmi-serial|mac:eth0").unwrap();
+
+        assert_eq!(
+            fallbacks,
+            Fallbacks(vec![
+                Source::DeviceTree,
+                Source::DmiSerial,
+                Source::Mac("eth0".to_string())
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn should_read_efuse_as_hex() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nvmem");
+        tokio::fs::write(&path, [0xde, 0xad, 0xbe, 0xef])
+            .await
+            .unwrap();
+
+        let value = Source::Efuse(path).read().await.unwrap();
+
+        assert_eq!(value, "deadbeef");
+    }
+
+    #[tokio::test]
+    async fn composite_should_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let serial = dir.path().join("serial");
+        let missing = dir.path().join("missing");
+        tokio::fs::write(&serial, "ABC123\0").await.unwrap();
+
+        let components = [
+            Fallbacks(vec![
+                Source::File(missing.clone()),
+                Source::File(serial.clone()),
+            ]),
+            Fallbacks(vec![Source::File(serial)]),
+        ];
+
+        let id = read_composite(&components).await.unwrap();
+
+        assert_eq!(id, "ABC123\nABC123");
+    }
+
+    #[tokio::test]
+    async fn composite_should_fail_with_missing_component() {
+        let dir = tempfile::tempdir().unwrap();
+        let serial = dir.path().join("serial");
+        let missing = dir.path().join("missing");
+        tokio::fs::write(&serial, "ABC123").await.unwrap();
+
+        let components = [
+            Fallbacks(vec![Source::File(serial)]),
+            Fallbacks(vec![Source::File(missing)]),
+        ];
+
+        assert!(read_composite(&components).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn composite_should_fail_without_sources() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let components = [Fallbacks(vec![Source::File(dir.path().join("missing"))])];
+
+        assert!(read_composite(&components).await.is_err());
+    }
+}
@@ -0,0 +1,101 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuration of the LEDs.
+
+use std::path::{Path, PathBuf};
+
+use eyre::Context;
+use serde::Deserialize;
+
+/// Default directory of the LED class devices.
+pub const DEFAULT_SYSFS_ROOT: &str = "/sys/class/leds";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Overrides the directory of the LED class devices.
+    pub sysfs_root: Option<PathBuf>,
+    #[serde(default)]
+    pub leds: Vec<LedConfig>,
+}
+
+impl Config {
+    pub async fn read(path: &Path) -> eyre::Result<Self> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .wrap_err_with(|| format!("couldn't read config {}", path.display()))?;
+
+        toml::from_str(&content).wrap_err("couldn't parse config")
+    }
+}
+
+/// Maps a LED id to the LED class device.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LedConfig {
+    /// Id of the LED used on D-Bus.
+    pub id: String,
+    /// Name of the LED in the sysfs, like `green:status`.
+    pub name: String,
+    /// Blink with the kernel `timer` trigger.
+    #[serde(default)]
+    pub timer: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+            sysfs_root = "/tmp/leds"
+
+            [[leds]]
+            id = "gpio1"
+            name = "green:status"
+            timer = true
+
+            [[leds]]
+            id = "gpio2"
+            name = "red:power"
+            "#,
+        )
+        .unwrap();
+
+        let exp = Config {
+            sysfs_root: Some(PathBuf::from("/tmp/leds")),
+            leds: vec![
+                LedConfig {
+                    id: "gpio1".to_string(),
+                    name: "green:status".to_string(),
+                    timer: true,
+                },
+                LedConfig {
+                    id: "gpio2".to_string(),
+                    name: "red:power".to_string(),
+                    timer: false,
+                },
+            ],
+        };
+
+        assert_eq!(config, exp);
+    }
+}
@@ -1,7 +1,7 @@
 /*
  * This file is part of Edgehog.
  *
- * Copyright 2022 SECO Mind Srl
+ * Copyright 2022, 2026 SECO Mind Srl
  *
  * Licensed under the Apache License, Version 2.0 (the "License");
  * you may not use this file except in compliance with the License.
@@ -18,42 +18,97 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use tracing::{debug, info, level_filters::LevelFilter};
+use std::path::PathBuf;
+
+use clap::Parser;
+use tracing::{debug, error, info, level_filters::LevelFilter};
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
-use zbus::interface;
+use zbus::{fdo, interface};
+
+use crate::config::{Config, DEFAULT_SYSFS_ROOT};
+use crate::sysfs::{LedError, SysfsLeds};
+
+mod config;
+mod sysfs;
 
 pub const SERVICE_NAME: &str = "io.edgehog.LedManager";
 
+#[derive(Debug, Parser)]
+struct Cli {
+    /// Use the system bus instead of the session one
+    #[arg(long)]
+    system: bool,
+
+    /// TOML file mapping the LED ids to the sysfs LEDs
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Directory of the LED class devices, overrides the configuration
+    #[arg(long)]
+    sysfs_root: Option<PathBuf>,
+}
+
 #[derive(Debug)]
 struct LedManager {
-    leds: Vec<String>,
+    leds: SysfsLeds,
 }
 
 #[interface(name = "io.edgehog.LedManager1")]
 impl LedManager {
     fn list(&self) -> Vec<String> {
-        debug!("listing {} leds", self.leds.len());
+        let leds = self.leds.ids();
+
+        debug!("listing {} leds", leds.len());
 
-        self.leds.clone()
+        leds
     }
 
     fn insert(&mut self, id: String) {
         debug!("adding led {id}");
 
-        self.leds.push(id);
+        self.leds.insert(id);
     }
 
-    fn set(&self, id: String, status: bool) -> bool {
-        const RESULT: bool = true;
+    fn set(&self, id: String, status: bool) -> fdo::Result<bool> {
+        self.leds.set(&id, status).map_err(into_fdo)?;
 
-        info!("SET {id} -> {status}: result {RESULT}");
+        info!("SET {id} -> {status}");
 
-        RESULT
+        Ok(true)
+    }
+
+    /// Blinks the LED with the kernel timer trigger, until it's set again.
+    fn blink(&self, id: String, on_millis: u32, off_millis: u32) -> fdo::Result<bool> {
+        self.leds
+            .blink(&id, on_millis, off_millis)
+            .map_err(into_fdo)?;
+
+        info!("BLINK {id} -> {on_millis}ms on, {off_millis}ms off");
+
+        Ok(true)
+    }
+}
+
+fn into_fdo(err: LedError) -> fdo::Error {
+    let msg = match std::error::Error::source(&err) {
+        Some(source) => format!("{err}: {source}"),
+        None => err.to_string(),
+    };
+
+    error!("{msg}");
+
+    match err {
+        LedError::NotFound(_) | LedError::TimerNotSupported(_) => fdo::Error::InvalidArgs(msg),
+        LedError::Write { .. } | LedError::Read { .. } | LedError::MaxBrightness(_) => {
+            fdo::Error::Failed(msg)
+        }
     }
 }
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+
     color_eyre::install()?;
 
     tracing_subscriber::registry()
@@ -65,9 +120,27 @@ async fn main() -> eyre::Result<()> {
         )
         .try_init()?;
 
-    let leds = LedManager { leds: Vec::new() };
+    let config = match &cli.config {
+        Some(path) => Config::read(path).await?,
+        None => Config::default(),
+    };
+
+    let root = cli
+        .sysfs_root
+        .or(config.sysfs_root)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SYSFS_ROOT));
+
+    let leds = LedManager {
+        leds: SysfsLeds::new(root, config.leds),
+    };
+
+    let builder = if cli.system {
+        zbus::connection::Builder::system()?
+    } else {
+        zbus::connection::Builder::session()?
+    };
 
-    let _conn = zbus::connection::Builder::session()?
+    let _conn = builder
         .name(SERVICE_NAME)?
         .serve_at("/io/edgehog/LedManager", leds)?
         .build()
@@ -0,0 +1,258 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Backend that drives the LEDs through the sysfs LED class.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use tracing::{debug, warn};
+
+use crate::config::LedConfig;
+
+#[derive(Debug, thiserror::Error, displaydoc::Display)]
+pub enum LedError {
+    /// LED {0} not found
+    NotFound(String),
+    /// LED {0} doesn't support the timer trigger
+    TimerNotSupported(String),
+    /// couldn't write {path}
+    Write {
+        path: PathBuf,
+        #[source]
+        backtrace: io::Error,
+    },
+    /// couldn't read {path}
+    Read {
+        path: PathBuf,
+        #[source]
+        backtrace: io::Error,
+    },
+    /// invalid max brightness {0:?}
+    MaxBrightness(String),
+}
+
+#[derive(Debug)]
+struct Led {
+    name: String,
+    timer: bool,
+}
+
+#[derive(Debug)]
+pub struct SysfsLeds {
+    root: PathBuf,
+    leds: BTreeMap<String, Led>,
+}
+
+impl SysfsLeds {
+    pub fn new(root: PathBuf, leds: Vec<LedConfig>) -> Self {
+        let leds = leds
+            .into_iter()
+            .map(|led| {
+                (
+                    led.id,
+                    Led {
+                        name: led.name,
+                        timer: led.timer,
+                    },
+                )
+            })
+            .collect();
+
+        Self { root, leds }
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.leds.keys().cloned().collect()
+    }
+
+    /// Adds a LED with the same sysfs name of the id.
+    pub fn insert(&mut self, id: String) {
+        if self.leds.contains_key(&id) {
+            debug!("led {id} already present");
+
+            return;
+        }
+
+        if !self.root.join(&id).exists() {
+            warn!("led {id} not found in {}", self.root.display());
+        }
+
+        self.leds.insert(
+            id.clone(),
+            Led {
+                name: id,
+                timer: false,
+            },
+        );
+    }
+
+    /// Turns the LED on or off, stopping the timer trigger.
+    pub fn set(&self, id: &str, status: bool) -> Result<(), LedError> {
+        let led = self.get(id)?;
+        let dir = self.root.join(&led.name);
+
+        if led.timer {
+            write(&dir.join("trigger"), "none")?;
+        }
+
+        let brightness = if status {
+            read_max_brightness(&dir)?
+        } else {
+            0
+        };
+
+        write(&dir.join("brightness"), &brightness.to_string())
+    }
+
+    /// Blinks the LED with the kernel timer trigger.
+    pub fn blink(&self, id: &str, on_millis: u32, off_millis: u32) -> Result<(), LedError> {
+        let led = self.get(id)?;
+
+        if !led.timer {
+            return Err(LedError::TimerNotSupported(id.to_string()));
+        }
+
+        let dir = self.root.join(&led.name);
+
+        // The delay attributes are created when the trigger is set
+        write(&dir.join("trigger"), "timer")?;
+        write(&dir.join("delay_on"), &on_millis.to_string())?;
+        write(&dir.join("delay_off"), &off_millis.to_string())
+    }
+
+    fn get(&self, id: &str) -> Result<&Led, LedError> {
+        self.leds
+            .get(id)
+            .ok_or_else(|| LedError::NotFound(id.to_string()))
+    }
+}
+
+fn read_max_brightness(dir: &Path) -> Result<u32, LedError> {
+    let path = dir.join("max_brightness");
+
+    let value = std::fs::read_to_string(&path).map_err(|backtrace| LedError::Read {
+        path: path.clone(),
+        backtrace,
+    })?;
+
+    let value = value.trim();
+
+    value
+        .parse()
+        .map_err(|_| LedError::MaxBrightness(value.to_string()))
+}
+
+fn write(path: &Path, value: &str) -> Result<(), LedError> {
+    debug!("writing {value} to {}", path.display());
+
+    std::fs::write(path, value).map_err(|backtrace| LedError::Write {
+        path: path.to_path_buf(),
+        backtrace,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn mock_leds() -> (TempDir, SysfsLeds) {
+        let dir = TempDir::new().unwrap();
+
+        for name in ["green:status", "red:power"] {
+            let led = dir.path().join(name);
+
+            std::fs::create_dir(&led).unwrap();
+            std::fs::write(led.join("max_brightness"), "255\n").unwrap();
+            std::fs::write(led.join("brightness"), "0\n").unwrap();
+            std::fs::write(led.join("trigger"), "[none] timer\n").unwrap();
+        }
+
+        let leds = SysfsLeds::new(
+            dir.path().to_path_buf(),
+            vec![
+                LedConfig {
+                    id: "status".to_string(),
+                    name: "green:status".to_string(),
+                    timer: true,
+                },
+                LedConfig {
+                    id: "power".to_string(),
+                    name: "red:power".to_string(),
+                    timer: false,
+                },
+            ],
+        );
+
+        (dir, leds)
+    }
+
+    fn read(dir: &TempDir, name: &str, attr: &str) -> String {
+        std::fs::read_to_string(dir.path().join(name).join(attr)).unwrap()
+    }
+
+    #[test]
+    fn should_set_brightness() {
+        let (dir, leds) = mock_leds();
+
+        leds.set("power", true).unwrap();
+        assert_eq!(read(&dir, "red:power", "brightness"), "255");
+
+        leds.set("power", false).unwrap();
+        assert_eq!(read(&dir, "red:power", "brightness"), "0");
+    }
+
+    #[test]
+    fn should_blink_with_timer() {
+        let (dir, leds) = mock_leds();
+
+        leds.blink("status", 300, 200).unwrap();
+        assert_eq!(read(&dir, "green:status", "trigger"), "timer");
+        assert_eq!(read(&dir, "green:status", "delay_on"), "300");
+        assert_eq!(read(&dir, "green:status", "delay_off"), "200");
+
+        leds.set("status", true).unwrap();
+        assert_eq!(read(&dir, "green:status", "trigger"), "none");
+        assert_eq!(read(&dir, "green:status", "brightness"), "255");
+    }
+
+    #[test]
+    fn should_return_errors() {
+        let (_dir, mut leds) = mock_leds();
+
+        assert!(matches!(
+            leds.set("missing", true),
+            Err(LedError::NotFound(id)) if id == "missing"
+        ));
+        assert!(matches!(
+            leds.blink("power", 300, 200),
+            Err(LedError::TimerNotSupported(_))
+        ));
+
+        leds.insert("gpio1".to_string());
+
+        assert_eq!(leds.ids(), ["gpio1", "power", "status"]);
+        assert!(matches!(
+            leds.set("gpio1", true),
+            Err(LedError::Read { .. })
+        ));
+    }
+}
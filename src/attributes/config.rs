@@ -0,0 +1,94 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuration for the device attributes.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration of the custom device attributes.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AttributesConfig {
+    /// Flag to enable the device attributes.
+    #[serde(default)]
+    pub enabled: bool,
+    /// TOML or JSON file with the attributes, or a directory containing them.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// Prefix of the kernel command line parameters to read the attributes from.
+    #[serde(default = "AttributesConfig::default_cmdline_prefix")]
+    pub cmdline_prefix: String,
+    /// Seconds between the checks for changes of the attributes.
+    #[serde(default = "AttributesConfig::default_poll_interval")]
+    pub poll_interval: u64,
+}
+
+impl AttributesConfig {
+    fn default_cmdline_prefix() -> String {
+        "edgehog_attribute_".to_string()
+    }
+
+    const fn default_poll_interval() -> u64 {
+        30
+    }
+
+    pub(crate) fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval.max(1))
+    }
+}
+
+impl Default for AttributesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            cmdline_prefix: Self::default_cmdline_prefix(),
+            poll_interval: Self::default_poll_interval(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_deserialize() {
+        let string = r#"
+        enabled = true
+        path = "/etc/edgehog/attributes.d"
+        "#;
+
+        let config: AttributesConfig = toml::from_str(string).unwrap();
+
+        let exp = AttributesConfig {
+            enabled: true,
+            path: Some(PathBuf::from("/etc/edgehog/attributes.d")),
+            cmdline_prefix: "edgehog_attribute_".to_string(),
+            poll_interval: 30,
+        };
+
+        assert_eq!(config, exp);
+        assert_eq!(config.poll_interval(), Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,589 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Custom device attributes, read from a local file and the kernel command line.
+//!
+//! The attributes are published as properties and republished when they change.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::io;
+use std::path::Path;
+
+use astarte_device_sdk::AstarteData;
+use astarte_device_sdk::prelude::PropAccess;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+use crate::Client;
+
+use self::config::AttributesConfig;
+
+pub mod config;
+
+const INTERFACE: &str = "io.edgehog.devicemanager.DeviceAttributes";
+const VALUE_ENDPOINT: &str = "/value";
+
+/// Attributes by key.
+pub type Attributes = BTreeMap<String, String>;
+
+/// Scalar value of an attribute in the file.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::String(value) => write!(f, "{value}"),
+            Value::Integer(value) => write!(f, "{value}"),
+            Value::Float(value) => write!(f, "{value}"),
+            Value::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// The key is used as an endpoint segment of the interface.
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn insert_valid(attributes: &mut Attributes, key: &str, value: String) {
+    if !is_valid_key(key) {
+        warn!(
+            key,
+            "invalid attribute key, only alphanumeric and _ are allowed"
+        );
+
+        return;
+    }
+
+    attributes.insert(key.to_string(), value);
+}
+
+fn from_cmdline<'a, I>(args: I, prefix: &str) -> Attributes
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut attributes = Attributes::new();
+
+    for (key, value) in args
+        .into_iter()
+        .filter_map(|arg| arg.split_once('='))
+        .filter_map(|(key, value)| key.strip_prefix(prefix).map(|key| (key, value)))
+    {
+        insert_valid(&mut attributes, key, value.to_string());
+    }
+
+    attributes
+}
+
+fn parse_file(path: &Path, content: &str) -> eyre::Result<Attributes> {
+    let values: HashMap<String, Value> = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(content)?,
+        Some("json") => serde_json::from_str(content)?,
+        _ => bail!("unsupported attributes file {}", path.display()),
+    };
+
+    let mut attributes = Attributes::new();
+
+    for (key, value) in values {
+        insert_valid(&mut attributes, &key, value.to_string());
+    }
+
+    Ok(attributes)
+}
+
+async fn read_file(path: &Path) -> eyre::Result<Attributes> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .wrap_err_with(|| format!("couldn't read {}", path.display()))?;
+
+    parse_file(path, &content).wrap_err_with(|| format!("couldn't parse {}", path.display()))
+}
+
+/// Reads the attributes file, or all the files in the directory sorted by name.
+///
+/// A missing path has no attributes, since it can be created later by the installer.
+async fn read_path(path: &Path) -> eyre::Result<Attributes> {
+    let metadata = match tokio::fs::metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            debug!(path = %path.display(), "attributes path missing");
+
+            return Ok(Attributes::new());
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    if !metadata.is_dir() {
+        return read_file(path).await;
+    }
+
+    let mut entries = tokio::fs::read_dir(path).await?;
+    let mut files = Vec::new();
+
+    while let Some(entry) = entries.next_entry().await? {
+        let file = entry.path();
+
+        let supported = file
+            .extension()
+            .is_some_and(|ext| ext == "toml" || ext == "json");
+
+        if supported && entry.file_type().await?.is_file() {
+            files.push(file);
+        }
+    }
+
+    files.sort();
+
+    let mut attributes = Attributes::new();
+
+    for file in files {
+        attributes.extend(read_file(&file).await?);
+    }
+
+    Ok(attributes)
+}
+
+/// Reads the attributes from the kernel command line, overridden by the ones in the files.
+pub(crate) async fn read_attributes(config: &AttributesConfig) -> eyre::Result<Attributes> {
+    #[cfg_attr(
+        not(any(target_os = "linux", target_os = "android")),
+        expect(unused_mut)
+    )]
+    let mut attributes = Attributes::new();
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if !config.cmdline_prefix.is_empty() {
+        match procfs::cmdline() {
+            Ok(cmdline) => attributes.extend(from_cmdline(
+                cmdline.iter().map(String::as_str),
+                &config.cmdline_prefix,
+            )),
+            Err(err) => {
+                error!(
+                    "couldn't read the kernel cmd line: {}",
+                    eyre::Report::new(err)
+                );
+            }
+        }
+    }
+
+    if let Some(path) = &config.path {
+        attributes.extend(read_path(path).await?);
+    }
+
+    Ok(attributes)
+}
+
+/// Publishes the device attributes and their changes.
+#[derive(Debug)]
+pub struct DeviceAttributes<C> {
+    client: C,
+    config: AttributesConfig,
+    published: Attributes,
+}
+
+impl<C> DeviceAttributes<C> {
+    pub fn new(client: C, config: AttributesConfig) -> Self {
+        Self {
+            client,
+            config,
+            published: Attributes::new(),
+        }
+    }
+
+    fn key_from_path(path: &str) -> Option<&str> {
+        path.strip_prefix('/')
+            .and_then(|path| path.strip_suffix(VALUE_ENDPOINT))
+    }
+
+    /// Loads the attributes published by the previous run, to unset the removed ones.
+    async fn load_published(&mut self) -> eyre::Result<()>
+    where
+        C: PropAccess,
+    {
+        let props = self
+            .client
+            .interface_props(INTERFACE)
+            .await
+            .wrap_err("couldn't read the published attributes")?;
+
+        self.published = props
+            .into_iter()
+            .filter_map(|prop| {
+                let key = Self::key_from_path(&prop.path)?.to_string();
+
+                match prop.value {
+                    AstarteData::String(value) => Some((key, value)),
+                    value => {
+                        warn!(key, ?value, "expecting string attribute");
+
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    async fn update(&mut self)
+    where
+        C: Client,
+    {
+        let attributes = match read_attributes(&self.config).await {
+            Ok(attributes) => attributes,
+            Err(err) => {
+                error!(
+                    error = format!("{err:#}"),
+                    "couldn't read the device attributes"
+                );
+
+                return;
+            }
+        };
+
+        if attributes == self.published {
+            return;
+        }
+
+        info!(count = attributes.len(), "publishing the device attributes");
+
+        let removed: Vec<String> = self
+            .published
+            .keys()
+            .filter(|key| !attributes.contains_key(*key))
+            .cloned()
+            .collect();
+
+        // Only the sent changes are stored, so the failed ones are retried on the next update
+        for key in removed {
+            let path = format!("/{key}{VALUE_ENDPOINT}");
+
+            debug!(key, "removing device attribute");
+
+            match self.client.unset_property(INTERFACE, &path).await {
+                Ok(()) => {
+                    self.published.remove(&key);
+                }
+                Err(err) => {
+                    error!(
+                        error = format!("{:#}", eyre::Report::new(err)),
+                        path, "failed to unset property",
+                    );
+                }
+            }
+        }
+
+        for (key, value) in attributes {
+            if self.published.get(&key) == Some(&value) {
+                continue;
+            }
+
+            let path = format!("/{key}{VALUE_ENDPOINT}");
+
+            match self
+                .client
+                .set_property(INTERFACE, &path, AstarteData::String(value.clone()))
+                .await
+            {
+                Ok(()) => {
+                    self.published.insert(key, value);
+                }
+                Err(err) => {
+                    error!(
+                        error = format!("{:#}", eyre::Report::new(err)),
+                        path, "failed to set property",
+                    );
+                }
+            }
+        }
+    }
+
+    /// Publishes the attributes, then checks periodically for changes.
+    pub async fn run(mut self, cancel: CancellationToken) -> eyre::Result<()>
+    where
+        C: Client + PropAccess,
+    {
+        if let Err(err) = self.load_published().await {
+            error!(error = format!("{err:#}"), "couldn't load the attributes");
+        }
+
+        let mut interval = tokio::time::interval(self.config.poll_interval());
+
+        while cancel.run_until_cancelled(interval.tick()).await.is_some() {
+            self.update().await;
+        }
+
+        debug!("device attributes task cancelled");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astarte_device_sdk::astarte_interfaces::schema::Ownership;
+    use astarte_device_sdk::store::{SqliteStore, StoredProp};
+    use astarte_device_sdk::transport::mqtt::Mqtt;
+    use astarte_device_sdk_mock::MockDeviceClient;
+    use mockall::{Sequence, predicate};
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn attributes<const N: usize>(values: [(&str, &str); N]) -> Attributes {
+        values
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn should_read_cmdline() {
+        let cmdline = [
+            "console=ttyS0",
+            "edgehog_attribute_site_id=42",
+            "edgehog_attribute_cabinet=A7",
+            "edgehog_attribute_bad-key=1",
+            "edgehog_system_serial_number=serial",
+        ];
+
+        let res = from_cmdline(cmdline, "edgehog_attribute_");
+
+        assert_eq!(res, attributes([("cabinet", "A7"), ("site_id", "42")]));
+    }
+
+    #[test]
+    fn should_parse_files() {
+        let toml = r#"
+        site_id = "milan-01"
+        cabinet = 7
+        "#;
+
+        let res = parse_file(Path::new("attributes.toml"), toml).unwrap();
+        assert_eq!(res, attributes([("cabinet", "7"), ("site_id", "milan-01")]));
+
+        let json = r#"{"customer": "ACME", "rack": 1.5, "managed": true}"#;
+
+        let res = parse_file(Path::new("attributes.json"), json).unwrap();
+        assert_eq!(
+            res,
+            attributes([("customer", "ACME"), ("managed", "true"), ("rack", "1.5")])
+        );
+
+        assert!(parse_file(Path::new("attributes.yaml"), "").is_err());
+    }
+
+    #[tokio::test]
+    async fn should_read_directory() {
+        let dir = TempDir::new("edgehog-attributes").unwrap();
+
+        tokio::fs::write(
+            dir.path().join("10-site.toml"),
+            "site_id = \"42\"\ncabinet = \"A1\"",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(dir.path().join("20-override.json"), r#"{"cabinet": "B2"}"#)
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("README"), "ignored")
+            .await
+            .unwrap();
+
+        let config = AttributesConfig {
+            enabled: true,
+            path: Some(dir.path().to_path_buf()),
+            cmdline_prefix: String::new(),
+            poll_interval: 30,
+        };
+
+        let res = read_attributes(&config).await.unwrap();
+
+        assert_eq!(res, attributes([("cabinet", "B2"), ("site_id", "42")]));
+    }
+
+    #[tokio::test]
+    async fn should_republish_changes() {
+        let dir = TempDir::new("edgehog-attributes").unwrap();
+        let path = dir.path().join("attributes.toml");
+
+        tokio::fs::write(&path, "site_id = \"42\"\ncabinet = \"A1\"")
+            .await
+            .unwrap();
+
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut seq = Sequence::new();
+
+        client
+            .expect_interface_props()
+            .with(predicate::eq(INTERFACE))
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| {
+                Ok(vec![StoredProp {
+                    interface: INTERFACE.to_string(),
+                    path: "/customer/value".to_string(),
+                    value: AstarteData::String("ACME".to_string()),
+                    interface_major: 0,
+                    ownership: Ownership::Device,
+                }])
+            });
+
+        client
+            .expect_unset_property()
+            .with(predicate::eq(INTERFACE), predicate::eq("/customer/value"))
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+
+        client
+            .expect_set_property()
+            .with(
+                predicate::eq(INTERFACE),
+                predicate::eq("/cabinet/value"),
+                predicate::eq(AstarteData::from("A1")),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
+        client
+            .expect_set_property()
+            .with(
+                predicate::eq(INTERFACE),
+                predicate::eq("/site_id/value"),
+                predicate::eq(AstarteData::from("42")),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
+        client
+            .expect_set_property()
+            .with(
+                predicate::eq(INTERFACE),
+                predicate::eq("/cabinet/value"),
+                predicate::eq(AstarteData::from("B2")),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
+        let mut attributes = DeviceAttributes::new(
+            client,
+            AttributesConfig {
+                enabled: true,
+                path: Some(path.clone()),
+                cmdline_prefix: String::new(),
+                poll_interval: 30,
+            },
+        );
+
+        attributes.load_published().await.unwrap();
+        attributes.update().await;
+
+        // Nothing changed
+        attributes.update().await;
+
+        tokio::fs::write(&path, "site_id = \"42\"\ncabinet = \"B2\"")
+            .await
+            .unwrap();
+
+        attributes.update().await;
+    }
+
+    #[tokio::test]
+    async fn should_ignore_missing_path() {
+        let dir = TempDir::new("edgehog-attributes").unwrap();
+
+        let attributes = read_attributes(&AttributesConfig {
+            enabled: true,
+            path: Some(dir.path().join("missing.toml")),
+            cmdline_prefix: String::new(),
+            poll_interval: 30,
+        })
+        .await
+        .unwrap();
+
+        assert!(attributes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_retry_failed_publish() {
+        let dir = TempDir::new("edgehog-attributes").unwrap();
+        let path = dir.path().join("attributes.toml");
+
+        tokio::fs::write(&path, "site_id = \"42\"").await.unwrap();
+
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut seq = Sequence::new();
+
+        client
+            .expect_set_property()
+            .with(
+                predicate::eq(INTERFACE),
+                predicate::eq("/site_id/value"),
+                predicate::eq(AstarteData::from("42")),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Err(astarte_device_sdk::Error::Disconnected));
+
+        client
+            .expect_set_property()
+            .with(
+                predicate::eq(INTERFACE),
+                predicate::eq("/site_id/value"),
+                predicate::eq(AstarteData::from("42")),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
+        let mut device = DeviceAttributes::new(
+            client,
+            AttributesConfig {
+                enabled: true,
+                path: Some(path),
+                cmdline_prefix: String::new(),
+                poll_interval: 30,
+            },
+        );
+
+        device.update().await;
+        assert!(device.published.is_empty());
+
+        device.update().await;
+        assert_eq!(device.published, attributes([("site_id", "42")]));
+
+        // Nothing changed
+        device.update().await;
+    }
+}
@@ -0,0 +1,241 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuration of the bandwidth limits.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use astarte_device_sdk::chrono::{Local, Timelike};
+use serde::Deserialize;
+
+use super::Scope;
+
+/// Rates of the transfers, in kbit/s.
+///
+/// A rate of 0 disables the limit.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BandwidthConfig {
+    /// Limit shared by all the transfers.
+    #[serde(default)]
+    pub global_kbps: u64,
+    /// Limit of the OTA bundle downloads.
+    #[serde(default)]
+    pub ota_kbps: u64,
+    /// Limit of the file transfers.
+    #[serde(default)]
+    pub file_transfer_kbps: u64,
+    /// Windows of the day with a different limit.
+    ///
+    /// If more windows of the same scope overlap, the last one is used.
+    #[serde(default)]
+    pub schedule: Vec<BandwidthWindow>,
+}
+
+impl BandwidthConfig {
+    /// Returns the rate of the scope at the given time of the day.
+    pub(crate) fn rate_at(&self, scope: Scope, time: TimeOfDay) -> u64 {
+        self.schedule
+            .iter()
+            .rev()
+            .find(|window| window.scope == scope && window.contains(time))
+            .map(|window| window.rate_kbps)
+            .unwrap_or(match scope {
+                Scope::Global => self.global_kbps,
+                Scope::Ota => self.ota_kbps,
+                Scope::FileTransfer => self.file_transfer_kbps,
+            })
+    }
+}
+
+/// Limit applied between two times of the day, in the local time zone.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BandwidthWindow {
+    /// Start of the window, included.
+    pub start: TimeOfDay,
+    /// End of the window, excluded. If it's before the start the window spans midnight.
+    pub end: TimeOfDay,
+    /// Traffic the limit applies to.
+    #[serde(default)]
+    pub scope: Scope,
+    /// Rate in kbit/s, 0 to disable the limit.
+    pub rate_kbps: u64,
+}
+
+impl BandwidthWindow {
+    fn contains(&self, time: TimeOfDay) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            self.start <= time || time < self.end
+        }
+    }
+}
+
+/// Time of the day with a minute resolution, in the `HH:MM` format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
+pub struct TimeOfDay {
+    minutes: u16,
+}
+
+impl TimeOfDay {
+    /// Creates the time, if the hour and minute are valid.
+    pub const fn new(hour: u16, minute: u16) -> Option<Self> {
+        if hour < 24 && minute < 60 {
+            Some(Self {
+                minutes: hour * 60 + minute,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Current local time.
+    pub(crate) fn now() -> Self {
+        let now = Local::now();
+
+        // The hour and minute are always valid
+        Self {
+            minutes: (now.hour() * 60 + now.minute()) as u16,
+        }
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid time of the day {s}, expected HH:MM");
+
+        let (hour, minute) = s.split_once(':').ok_or_else(invalid)?;
+        let hour = hour.parse().map_err(|_| invalid())?;
+        let minute = minute.parse().map_err(|_| invalid())?;
+
+        Self::new(hour, minute).ok_or_else(invalid)
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn time(hour: u16, minute: u16) -> TimeOfDay {
+        TimeOfDay::new(hour, minute).unwrap()
+    }
+
+    #[test]
+    fn should_deserialize() {
+        let string = r#"
+        ota_kbps = 1024
+
+        [[schedule]]
+        start = "08:00"
+        end = "18:30"
+        rate_kbps = 256
+
+        [[schedule]]
+        start = "22:00"
+        end = "06:00"
+        scope = "file-transfer"
+        rate_kbps = 0
+        "#;
+
+        let config: BandwidthConfig = toml::from_str(string).unwrap();
+
+        let exp = BandwidthConfig {
+            global_kbps: 0,
+            ota_kbps: 1024,
+            file_transfer_kbps: 0,
+            schedule: vec![
+                BandwidthWindow {
+                    start: time(8, 0),
+                    end: time(18, 30),
+                    scope: Scope::Global,
+                    rate_kbps: 256,
+                },
+                BandwidthWindow {
+                    start: time(22, 0),
+                    end: time(6, 0),
+                    scope: Scope::FileTransfer,
+                    rate_kbps: 0,
+                },
+            ],
+        };
+
+        assert_eq!(config, exp);
+    }
+
+    #[test]
+    fn should_reject_invalid_time() {
+        for time in ["24:00", "12:60", "12", "noon"] {
+            assert!(time.parse::<TimeOfDay>().is_err(), "{time}");
+        }
+
+        assert_eq!(time(7, 5).to_string(), "07:05");
+    }
+
+    #[test]
+    fn should_select_scheduled_rate() {
+        let config = BandwidthConfig {
+            global_kbps: 0,
+            ota_kbps: 0,
+            file_transfer_kbps: 512,
+            schedule: vec![
+                BandwidthWindow {
+                    start: time(8, 0),
+                    end: time(18, 0),
+                    scope: Scope::Global,
+                    rate_kbps: 256,
+                },
+                BandwidthWindow {
+                    start: time(22, 0),
+                    end: time(6, 0),
+                    scope: Scope::FileTransfer,
+                    rate_kbps: 0,
+                },
+            ],
+        };
+
+        assert_eq!(config.rate_at(Scope::Global, time(7, 59)), 0);
+        assert_eq!(config.rate_at(Scope::Global, time(8, 0)), 256);
+        assert_eq!(config.rate_at(Scope::Global, time(18, 0)), 0);
+        assert_eq!(config.rate_at(Scope::FileTransfer, time(12, 0)), 512);
+        assert_eq!(config.rate_at(Scope::FileTransfer, time(23, 0)), 0);
+        assert_eq!(config.rate_at(Scope::FileTransfer, time(5, 59)), 0);
+        assert_eq!(config.rate_at(Scope::Ota, time(12, 0)), 0);
+    }
+}
@@ -0,0 +1,152 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bandwidth limits set from Astarte.
+
+use astarte_device_sdk::{
+    AstarteData, DeviceEvent, FromEvent, event::FromEventError, types::TypeError,
+};
+
+use super::Scope;
+
+const INTERFACE: &str = "io.edgehog.devicemanager.config.Bandwidth";
+
+/// Limit of a scope set at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BandwidthEvent {
+    pub scope: Scope,
+    /// Rate in kbit/s, 0 to disable the limit. Unset to go back to the configured one.
+    pub rate_kbps: Option<u64>,
+}
+
+impl FromEvent for BandwidthEvent {
+    type Err = FromEventError;
+
+    fn from_event(event: DeviceEvent) -> Result<Self, Self::Err> {
+        let scope = scope_from_path(&event.path).ok_or_else(|| FromEventError::Path {
+            interface: INTERFACE,
+            base_path: event.path.clone(),
+        })?;
+
+        let BandwidthConfig::RateKbps(rate) = BandwidthConfig::from_event(event)?;
+
+        Ok(Self {
+            scope,
+            rate_kbps: rate.map(|rate| rate.0),
+        })
+    }
+}
+
+fn scope_from_path(path: &str) -> Option<Scope> {
+    let scope = path.strip_prefix("/request/")?.split('/').next()?;
+
+    match scope {
+        "global" => Some(Scope::Global),
+        "ota" => Some(Scope::Ota),
+        "fileTransfer" => Some(Scope::FileTransfer),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, FromEvent, PartialEq, Eq)]
+#[from_event(
+    interface = "io.edgehog.devicemanager.config.Bandwidth",
+    interface_type = "properties",
+    aggregation = "individual"
+)]
+enum BandwidthConfig {
+    #[mapping(endpoint = "/request/%{scope}/rateKbps", allow_unset = true)]
+    RateKbps(Option<RateKbps>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RateKbps(u64);
+
+impl TryFrom<AstarteData> for RateKbps {
+    type Error = TypeError;
+
+    fn try_from(value: AstarteData) -> Result<Self, Self::Error> {
+        let rate = i64::try_from(value)?;
+
+        u64::try_from(rate)
+            .map(RateKbps)
+            .map_err(|_| TypeError::Conversion {
+                ctx: format!("negative bandwidth rate {rate}"),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astarte_device_sdk::Value;
+    use pretty_assertions::assert_eq;
+
+    use crate::controller::event::RuntimeEvent;
+
+    use super::*;
+
+    fn event(path: &str, data: Option<AstarteData>) -> DeviceEvent {
+        DeviceEvent {
+            interface: INTERFACE.to_string(),
+            path: path.to_string(),
+            data: Value::Property(data),
+        }
+    }
+
+    #[test]
+    fn should_convert_bandwidth_from_event() {
+        let res = RuntimeEvent::from_event(event(
+            "/request/fileTransfer/rateKbps",
+            Some(AstarteData::LongInteger(256)),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            res,
+            RuntimeEvent::Bandwidth(BandwidthEvent {
+                scope: Scope::FileTransfer,
+                rate_kbps: Some(256),
+            })
+        );
+
+        let res = RuntimeEvent::from_event(event("/request/global/rateKbps", None)).unwrap();
+
+        assert_eq!(
+            res,
+            RuntimeEvent::Bandwidth(BandwidthEvent {
+                scope: Scope::Global,
+                rate_kbps: None,
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_bandwidth() {
+        let res = BandwidthEvent::from_event(event(
+            "/request/ota/rateKbps",
+            Some(AstarteData::LongInteger(-1)),
+        ));
+        assert!(res.is_err());
+
+        let res = BandwidthEvent::from_event(event(
+            "/request/containers/rateKbps",
+            Some(AstarteData::LongInteger(1)),
+        ));
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,225 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bandwidth limits of the downloads and uploads.
+//!
+//! The transfers of each [`Scope`] share a token bucket, and all of them share the global one.
+//! The rates are set by the [`policy::BandwidthPolicy`], from the configured schedule or the
+//! values set from Astarte at runtime.
+
+use std::fmt::Display;
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+
+use crate::rate_limit::RateLimiter;
+
+pub mod config;
+pub mod event;
+pub(crate) mod policy;
+
+/// Traffic the limit applies to.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scope {
+    /// All the transfers.
+    #[default]
+    Global,
+    /// Download of the OTA bundles.
+    Ota,
+    /// Downloads and uploads of the file transfers.
+    FileTransfer,
+}
+
+impl Scope {
+    pub(crate) const ALL: [Scope; 3] = [Scope::Global, Scope::Ota, Scope::FileTransfer];
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scope::Global => write!(f, "global"),
+            Scope::Ota => write!(f, "ota"),
+            Scope::FileTransfer => write!(f, "file-transfer"),
+        }
+    }
+}
+
+/// Shared handle to the bandwidth limits.
+///
+/// The default handle has no limits.
+#[derive(Debug, Clone, Default)]
+pub struct Bandwidth {
+    global: RateLimiter,
+    ota: RateLimiter,
+    file_transfer: RateLimiter,
+}
+
+impl Bandwidth {
+    fn limiter(&self, scope: Scope) -> &RateLimiter {
+        match scope {
+            Scope::Global => &self.global,
+            Scope::Ota => &self.ota,
+            Scope::FileTransfer => &self.file_transfer,
+        }
+    }
+
+    /// Sets the rate of the scope in kbit/s, 0 to disable the limit.
+    pub(crate) fn set_rate(&self, scope: Scope, kbps: u64) {
+        // From kbit/s to bytes per second, with a burst of one second
+        let rate = kbps as f64 * 1000.0 / 8.0;
+
+        self.limiter(scope).set_rate(rate, rate);
+    }
+
+    /// Returns the rate of the scope in kbit/s, 0 if not limited.
+    pub(crate) fn rate(&self, scope: Scope) -> u64 {
+        (self.limiter(scope).rate() * 8.0 / 1000.0).round() as u64
+    }
+
+    /// Waits till the bytes can be transferred by the scope.
+    pub(crate) async fn acquire(&self, scope: Scope, bytes: usize) {
+        let bytes = bytes as f64;
+
+        if scope != Scope::Global {
+            self.limiter(scope).acquire(bytes).await;
+        }
+
+        self.global.acquire(bytes).await;
+    }
+
+    /// Limits the rate of the chunks of a stream.
+    pub(crate) fn limit_stream<S, E>(
+        &self,
+        scope: Scope,
+        stream: S,
+    ) -> impl Stream<Item = Result<Bytes, E>> + Send + 'static
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Send + 'static,
+    {
+        let bandwidth = self.clone();
+
+        stream.then(move |chunk| {
+            let bandwidth = bandwidth.clone();
+
+            async move {
+                if let Ok(bytes) = &chunk {
+                    bandwidth.acquire(scope, bytes.len()).await;
+                }
+
+                chunk
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use tokio::time::Instant;
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn should_limit_rate() {
+        let bandwidth = Bandwidth::default();
+        // 1000 bytes per second
+        bandwidth.set_rate(Scope::Ota, 8);
+
+        let start = Instant::now();
+
+        bandwidth.acquire(Scope::Ota, 500).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // The first chunk is in debt
+        bandwidth.acquire(Scope::Ota, 500).await;
+        assert!(start.elapsed() >= Duration::from_millis(500));
+
+        bandwidth.acquire(Scope::Ota, 500).await;
+        assert!(start.elapsed() >= Duration::from_secs(1));
+
+        // Other scopes are not limited
+        let now = Instant::now();
+        bandwidth.acquire(Scope::FileTransfer, 10_000).await;
+        assert_eq!(now.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_share_global_limit() {
+        let bandwidth = Bandwidth::default();
+        bandwidth.set_rate(Scope::Global, 8);
+
+        let start = Instant::now();
+
+        bandwidth.acquire(Scope::Ota, 1000).await;
+        bandwidth.acquire(Scope::FileTransfer, 1000).await;
+        assert!(start.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_wake_on_rate_change() {
+        let bandwidth = Bandwidth::default();
+        bandwidth.set_rate(Scope::FileTransfer, 8);
+
+        let start = Instant::now();
+
+        bandwidth.acquire(Scope::FileTransfer, 100_000).await;
+
+        let waiting = tokio::spawn({
+            let bandwidth = bandwidth.clone();
+
+            async move { bandwidth.acquire(Scope::FileTransfer, 1000).await }
+        });
+
+        tokio::task::yield_now().await;
+
+        bandwidth.set_rate(Scope::FileTransfer, 0);
+
+        waiting.await.unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(bandwidth.rate(Scope::FileTransfer), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_limit_stream() {
+        let bandwidth = Bandwidth::default();
+        bandwidth.set_rate(Scope::Ota, 8);
+
+        let chunks = futures::stream::iter([
+            Ok::<_, std::io::Error>(Bytes::from_static(&[0; 1000])),
+            Ok(Bytes::from_static(&[0; 1000])),
+            Ok(Bytes::from_static(&[0; 1000])),
+        ]);
+
+        let start = Instant::now();
+
+        let received: Vec<Bytes> = bandwidth
+            .limit_stream(Scope::Ota, chunks)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(received.len(), 3);
+        assert!(start.elapsed() >= Duration::from_secs(2));
+    }
+}
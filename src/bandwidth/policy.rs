@@ -0,0 +1,168 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Task applying the scheduled bandwidth limits and the ones set at runtime.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use super::config::{BandwidthConfig, TimeOfDay};
+use super::event::BandwidthEvent;
+use super::{Bandwidth, Scope};
+
+/// Period to check the schedule, it has a minute resolution.
+const SCHEDULE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Updates the limits of the shared [`Bandwidth`].
+#[derive(Debug)]
+pub(crate) struct BandwidthPolicy {
+    config: BandwidthConfig,
+    bandwidth: Bandwidth,
+    /// Limits set from Astarte, they take precedence over the configured ones.
+    overrides: HashMap<Scope, u64>,
+}
+
+impl BandwidthPolicy {
+    pub(crate) fn new(config: BandwidthConfig) -> (Self, Bandwidth) {
+        let bandwidth = Bandwidth::default();
+
+        let policy = Self {
+            config,
+            bandwidth: bandwidth.clone(),
+            overrides: HashMap::new(),
+        };
+
+        policy.apply(TimeOfDay::now());
+
+        (policy, bandwidth)
+    }
+
+    pub(crate) async fn run(
+        mut self,
+        mut events: mpsc::Receiver<BandwidthEvent>,
+        cancel: CancellationToken,
+    ) -> eyre::Result<()> {
+        let mut interval = tokio::time::interval(SCHEDULE_PERIOD);
+
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = interval.tick() => self.apply(TimeOfDay::now()),
+                event = events.recv() => {
+                    let Some(event) = event else {
+                        break;
+                    };
+
+                    self.handle(event);
+                }
+            }
+        }
+
+        debug!("bandwidth policy stopped");
+
+        Ok(())
+    }
+
+    fn handle(&mut self, event: BandwidthEvent) {
+        match event.rate_kbps {
+            Some(rate) => {
+                self.overrides.insert(event.scope, rate);
+            }
+            None => {
+                self.overrides.remove(&event.scope);
+            }
+        }
+
+        self.apply(TimeOfDay::now());
+    }
+
+    fn apply(&self, time: TimeOfDay) {
+        for scope in Scope::ALL {
+            let rate = self
+                .overrides
+                .get(&scope)
+                .copied()
+                .unwrap_or_else(|| self.config.rate_at(scope, time));
+
+            if self.bandwidth.rate(scope) != rate {
+                info!(%scope, rate_kbps = rate, %time, "setting the bandwidth limit");
+
+                self.bandwidth.set_rate(scope, rate);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::bandwidth::config::BandwidthWindow;
+
+    use super::*;
+
+    #[test]
+    fn should_override_configured_rate() {
+        let config = BandwidthConfig {
+            ota_kbps: 512,
+            ..Default::default()
+        };
+
+        let (mut policy, bandwidth) = BandwidthPolicy::new(config);
+
+        assert_eq!(bandwidth.rate(Scope::Ota), 512);
+        assert_eq!(bandwidth.rate(Scope::Global), 0);
+
+        policy.handle(BandwidthEvent {
+            scope: Scope::Ota,
+            rate_kbps: Some(128),
+        });
+        assert_eq!(bandwidth.rate(Scope::Ota), 128);
+
+        policy.handle(BandwidthEvent {
+            scope: Scope::Ota,
+            rate_kbps: None,
+        });
+        assert_eq!(bandwidth.rate(Scope::Ota), 512);
+    }
+
+    #[test]
+    fn should_follow_schedule() {
+        let config = BandwidthConfig {
+            schedule: vec![BandwidthWindow {
+                start: TimeOfDay::new(8, 0).unwrap(),
+                end: TimeOfDay::new(18, 0).unwrap(),
+                scope: Scope::Global,
+                rate_kbps: 256,
+            }],
+            ..Default::default()
+        };
+
+        let (policy, bandwidth) = BandwidthPolicy::new(config);
+
+        policy.apply(TimeOfDay::new(9, 0).unwrap());
+        assert_eq!(bandwidth.rate(Scope::Global), 256);
+
+        policy.apply(TimeOfDay::new(23, 0).unwrap());
+        assert_eq!(bandwidth.rate(Scope::Global), 0);
+    }
+}
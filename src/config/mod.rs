@@ -44,13 +44,36 @@ pub struct Config {
     #[cfg(all(feature = "zbus", target_os = "linux"))]
     pub ota: Option<edgehog_device_runtime::ota::config::OtaConfig>,
 
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub network: Option<edgehog_device_runtime::network::config::NetworkConfig>,
+
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub cellular: Option<edgehog_device_runtime::telemetry::cellular::CellularConfig>,
+
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub time_sync: Option<edgehog_device_runtime::telemetry::time_sync::TimeSyncConfig>,
+
+    pub gnss: Option<edgehog_device_runtime::telemetry::gnss::GnssConfig>,
+
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub power_policy: Option<edgehog_device_runtime::power_policy::config::PowerPolicyConfig>,
+
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub led: Option<edgehog_device_runtime::led_behavior::config::LedConfig>,
+
     #[cfg(feature = "file-transfer")]
     pub file_transfer: Option<edgehog_device_runtime::file_transfer::config::FileTransferConfig>,
 
+    pub attributes: Option<edgehog_device_runtime::attributes::config::AttributesConfig>,
+
+    #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+    pub bandwidth: Option<edgehog_device_runtime::bandwidth::config::BandwidthConfig>,
+
     pub interfaces_directory: Option<PathBuf>,
     pub store_directory: Option<PathBuf>,
     pub download_directory: Option<PathBuf>,
     pub telemetry_config: Option<Vec<TelemetryInterfaceConfig<'static>>>,
+    pub telemetry_schedule: Option<edgehog_device_runtime::telemetry::schedule::ScheduleConfig>,
 }
 
 impl TryFrom<Config> for DeviceManagerOptions {
@@ -105,12 +128,27 @@ impl TryFrom<Config> for DeviceManagerOptions {
             service: value.service,
             #[cfg(all(feature = "zbus", target_os = "linux"))]
             ota,
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            network: value.network.unwrap_or_default(),
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            cellular: value.cellular.unwrap_or_default(),
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            time_sync: value.time_sync.unwrap_or_default(),
+            gnss: value.gnss.unwrap_or_default(),
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            power_policy: value.power_policy.unwrap_or_default(),
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            led: value.led.unwrap_or_default(),
             #[cfg(feature = "file-transfer")]
             file_transfer,
+            attributes: value.attributes.unwrap_or_default(),
+            #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+            bandwidth: value.bandwidth.unwrap_or_default(),
             interfaces_directory,
             store_directory,
             download_directory,
             telemetry_config: value.telemetry_config,
+            telemetry_schedule: value.telemetry_schedule.unwrap_or_default(),
         })
     }
 }
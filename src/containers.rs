@@ -39,6 +39,7 @@ use tracing::error;
 
 use crate::Client;
 use crate::controller::actor::Actor;
+use crate::telemetry::status::subsystems::{Subsystem, SubsystemStatus, send_subsystem_status};
 
 /// Maximum number of retries for the initialization of the service
 pub const MAX_INIT_RETRIES: usize = 10;
@@ -159,12 +160,15 @@ where
 }
 
 #[cfg(not(test))]
-fn spawn_listener(
+fn spawn_listener<D>(
     config: ContainersConfig,
     store: &StateStore,
     tx: tokio::sync::mpsc::UnboundedSender<edgehog_containers::service::events::ContainerEvent>,
+    mut device: D,
     tasks: &mut JoinSet<Result<(), eyre::Error>>,
-) {
+) where
+    D: Client + Send + Sync + 'static,
+{
     use tracing::warn;
 
     // Use a lazy clone since the handle will only write to the database
@@ -181,6 +185,15 @@ fn spawn_listener(
         let should_exit = retry(&config, &mut listener).await?.is_none();
         if should_exit {
             warn!("listener retry limit reached");
+
+            send_subsystem_status(
+                &mut device,
+                Subsystem::Containers,
+                &SubsystemStatus::Degraded(
+                    "the container runtime events are not received".to_string(),
+                ),
+            )
+            .await;
         }
 
         Ok(())
@@ -209,26 +222,41 @@ impl<D> ContainerService<D> {
 
         // fixes an issue with features normalization when testing with `--all-features --workspace`
         #[cfg(not(test))]
-        spawn_listener(config, &store, tx.clone(), tasks);
+        spawn_listener(config, &store, tx.clone(), device.clone(), tasks);
 
         // Use a lazy clone since the handle will only write to the database
         let store_cl = store.clone();
         let device_cl = device.clone();
+        let mut status_device = device.clone();
         let container_handle = Arc::clone(container_handle);
         tasks.spawn(async move {
-            let maybe_client = retry(&config, || Docker::connect().map_err(Into::into)).await?;
-            let Some(client) = maybe_client else {
-                return Ok(());
-            };
+            let res = async {
+                let maybe_client = retry(&config, || Docker::connect().map_err(Into::into)).await?;
+                let Some(client) = maybe_client else {
+                    return Ok(None);
+                };
+
+                container_handle
+                    .set(ContainerHandle::new(client.clone(), store_cl.clone()))
+                    .wrap_err("couldn't initialize container handle")?;
 
-            container_handle
-                .set(ContainerHandle::new(client.clone(), store_cl.clone()))
-                .wrap_err("couldn't initialize container handle")?;
+                let mut service = Service::new(client, device_cl, rx, store_cl);
+
+                let initialized = retry(&config, &mut service).await?.is_some();
+
+                Ok::<_, eyre::Report>(initialized.then_some(service))
+            }
+            .await;
+
+            let status = match &res {
+                Ok(Some(_)) => SubsystemStatus::Initialized,
+                Ok(None) => SubsystemStatus::Failed("retried too many times".to_string()),
+                Err(err) => SubsystemStatus::Failed(format!("{err:#}")),
+            };
 
-            let mut service = Service::new(client, device_cl, rx, store_cl);
+            send_subsystem_status(&mut status_device, Subsystem::Containers, &status).await;
 
-            let should_exit = retry(&config, &mut service).await?.is_none();
-            if should_exit {
+            let Some(mut service) = res? else {
                 return Ok(());
             };
 
@@ -20,12 +20,16 @@ use std::fmt::Display;
 
 use astarte_device_sdk::{DeviceEvent, FromEvent, event::FromEventError};
 
-use crate::{commands::Commands, telemetry::event::TelemetryEvent};
+use crate::commands::Commands;
+use crate::telemetry::event::{TelemetryEvent, TelemetryRefresh};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum RuntimeEvent {
     Command(Commands),
     Telemetry(TelemetryEvent),
+    TelemetryRefresh(TelemetryRefresh),
+    #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+    Bandwidth(crate::bandwidth::event::BandwidthEvent),
     #[cfg(feature = "file-transfer")]
     FileTransfer(crate::file_transfer::interface::request::FileTransferRequest),
     #[cfg(feature = "file-transfer")]
@@ -34,6 +38,8 @@ pub enum RuntimeEvent {
     Ota(crate::ota::event::OtaRequest),
     #[cfg(all(feature = "zbus", target_os = "linux"))]
     Led(crate::led_behavior::LedEvent),
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    Network(Box<crate::network::request::ConnectionRequest>),
     #[cfg(feature = "containers")]
     Container(Box<edgehog_containers::requests::ContainerRequest>),
     #[cfg(feature = "forwarder")]
@@ -49,6 +55,13 @@ impl Display for RuntimeEvent {
             RuntimeEvent::Telemetry(_telemetry_event) => {
                 write!(f, "Telemetry")
             }
+            RuntimeEvent::TelemetryRefresh(_telemetry_refresh) => {
+                write!(f, "TelemetryRefresh")
+            }
+            #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+            RuntimeEvent::Bandwidth(_bandwidth_event) => {
+                write!(f, "Bandwidth")
+            }
             #[cfg(feature = "file-transfer")]
             RuntimeEvent::FileTransfer(_file_transfer_event) => {
                 write!(f, "FileTransfer")
@@ -65,6 +78,10 @@ impl Display for RuntimeEvent {
             RuntimeEvent::Led(_led_event) => {
                 write!(f, "Led")
             }
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            RuntimeEvent::Network(_connection_request) => {
+                write!(f, "Network")
+            }
             #[cfg(feature = "containers")]
             RuntimeEvent::Container(_container_request) => {
                 write!(f, "Container")
@@ -88,6 +105,14 @@ impl FromEvent for RuntimeEvent {
             "io.edgehog.devicemanager.config.Telemetry" => {
                 TelemetryEvent::from_event(event).map(RuntimeEvent::Telemetry)
             }
+            "io.edgehog.devicemanager.TelemetryRefresh" => {
+                TelemetryRefresh::from_event(event).map(RuntimeEvent::TelemetryRefresh)
+            }
+            #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+            "io.edgehog.devicemanager.config.Bandwidth" => {
+                crate::bandwidth::event::BandwidthEvent::from_event(event)
+                    .map(RuntimeEvent::Bandwidth)
+            }
             #[cfg(feature = "file-transfer")]
             interface if interface.starts_with("io.edgehog.devicemanager.fileTransfer") => {
                 crate::file_transfer::interface::request::FileTransferRequest::from_event(event)
@@ -102,9 +127,22 @@ impl FromEvent for RuntimeEvent {
                 crate::led_behavior::LedEvent::from_event(event).map(RuntimeEvent::Led)
             }
             #[cfg(all(feature = "zbus", target_os = "linux"))]
+            "io.edgehog.devicemanager.LedBlinkRequest" => {
+                crate::led_behavior::LedBlinkRequest::from_event(event)
+                    .and_then(|request| {
+                        crate::led_behavior::LedEvent::try_from(request).map_err(Into::into)
+                    })
+                    .map(RuntimeEvent::Led)
+            }
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
             "io.edgehog.devicemanager.OTARequest" => {
                 crate::ota::event::OtaRequest::from_event(event).map(RuntimeEvent::Ota)
             }
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            "io.edgehog.devicemanager.network.ConnectionRequest" => {
+                crate::network::request::ConnectionRequest::from_event(event)
+                    .map(|event| RuntimeEvent::Network(Box::new(event)))
+            }
             #[cfg(feature = "containers")]
             interface if interface.starts_with("io.edgehog.devicemanager.apps") => {
                 edgehog_containers::requests::ContainerRequest::from_event(event)
@@ -25,9 +25,18 @@ use tokio::{sync::mpsc, task::JoinSet};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, instrument, trace};
 
+use crate::attributes::DeviceAttributes;
 use crate::commands::execute_command;
-use crate::telemetry::Telemetry;
-use crate::telemetry::event::TelemetryEvent;
+use crate::telemetry::event::TelemetryMsg;
+#[cfg(any(
+    feature = "containers",
+    feature = "file-transfer",
+    feature = "forwarder",
+    feature = "service",
+    all(feature = "zbus", target_os = "linux")
+))]
+use crate::telemetry::status::subsystems::{Subsystem, SubsystemStatus, send_subsystem_status};
+use crate::telemetry::{SourcesConfig, Telemetry};
 use crate::{Client, DeviceManagerOptions};
 
 #[cfg(all(feature = "zbus", target_os = "linux"))]
@@ -46,7 +55,9 @@ const EVENT_BUFFER: usize = 8;
 pub struct Runtime<T> {
     client: T,
     cancel: CancellationToken,
-    telemetry_tx: mpsc::Sender<TelemetryEvent>,
+    telemetry_tx: mpsc::Sender<TelemetryMsg>,
+    #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+    bandwidth_tx: mpsc::Sender<crate::bandwidth::event::BandwidthEvent>,
     #[cfg(feature = "file-transfer")]
     file_transfer:
         Option<mpsc::Sender<crate::file_transfer::interface::request::FileTransferRequest>>,
@@ -59,7 +70,9 @@ pub struct Runtime<T> {
     #[cfg(all(feature = "zbus", target_os = "linux"))]
     led_tx: mpsc::Sender<LedEvent>,
     #[cfg(all(feature = "zbus", target_os = "linux"))]
-    ota_handler: OtaHandler,
+    network_tx: Option<mpsc::Sender<crate::network::request::ConnectionRequest>>,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    ota_handler: Option<OtaHandler>,
 }
 
 impl<C> Runtime<C> {
@@ -77,7 +90,12 @@ impl<C> Runtime<C> {
 
         info!("Initializing");
 
-        #[cfg(any(feature = "file-transfer", feature = "containers"))]
+        #[cfg(any(
+            feature = "file-transfer",
+            feature = "containers",
+            feature = "service",
+            all(feature = "zbus", target_os = "linux")
+        ))]
         let store = Self::store(&opts.store_directory)
             .await
             .wrap_err("couldn't connect to container store")?;
@@ -97,29 +115,126 @@ impl<C> Runtime<C> {
         let container_handle = std::sync::Arc::new(tokio::sync::OnceCell::new());
 
         #[cfg(all(feature = "zbus", target_os = "linux"))]
-        let ota_handler = OtaHandler::start(tasks, cancel.child_token(), client.clone(), &opts)
-            .await
-            .wrap_err("couldn't initialize ota handler")?;
+        let power = Self::setup_power_policy(
+            client.clone(),
+            opts.power_policy,
+            tasks,
+            cancel.child_token(),
+        )
+        .wrap_err("couldn't setup the power policy")?;
+        #[cfg(all(feature = "zbus", target_os = "linux"))]
+        send_subsystem_status(
+            &mut client.clone(),
+            Subsystem::PowerPolicy,
+            &SubsystemStatus::from_enabled(opts.power_policy.enabled),
+        )
+        .await;
+        #[cfg(not(all(feature = "zbus", target_os = "linux")))]
+        let power = crate::power_policy::PowerState::default();
+
+        #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+        let (bandwidth, bandwidth_tx) = {
+            use crate::bandwidth::policy::BandwidthPolicy;
+
+            let (bandwidth_tx, bandwidth_rx) = mpsc::channel(EVENT_BUFFER);
+            let (policy, bandwidth) = BandwidthPolicy::new(opts.bandwidth.clone());
+
+            tasks.spawn(policy.run(bandwidth_rx, cancel.child_token()));
+
+            (bandwidth, bandwidth_tx)
+        };
+
+        // The runtime keeps running without the OTA updates
+        #[cfg(all(feature = "zbus", target_os = "linux"))]
+        let (ota_handler, ota_status) = match OtaHandler::start(
+            tasks,
+            cancel.child_token(),
+            client.clone(),
+            &opts,
+            power.clone(),
+            bandwidth.clone(),
+            store.clone(),
+            #[cfg(feature = "containers")]
+            std::sync::Arc::clone(&container_handle),
+        )
+        .await
+        {
+            Ok(ota_handler) => (Some(ota_handler), SubsystemStatus::Initialized),
+            Err(err) => {
+                let reason = format!("{err:#}");
+
+                error!(error = reason, "couldn't initialize ota handler");
+
+                (None, SubsystemStatus::Failed(reason))
+            }
+        };
+        #[cfg(all(feature = "zbus", target_os = "linux"))]
+        send_subsystem_status(&mut client.clone(), Subsystem::Ota, &ota_status).await;
 
         #[cfg(all(feature = "zbus", target_os = "linux"))]
         let led_tx = {
             let (led_tx, led_rx) = mpsc::channel(EVENT_BUFFER);
-            tasks.spawn(LedBlink.run(led_rx, cancel.child_token()));
+            tasks.spawn(
+                LedBlink::new(
+                    opts.led.clone(),
+                    &opts.store_directory,
+                    cancel.child_token(),
+                )
+                .run(led_rx, cancel.child_token()),
+            );
             led_tx
         };
 
+        #[cfg(all(feature = "zbus", target_os = "linux"))]
+        let network_tx = {
+            let (network_tx, status) = Self::setup_network(
+                client.clone(),
+                opts.network,
+                opts.astarte_device_sdk
+                    .as_ref()
+                    .map(|sdk| sdk.pairing_url.clone()),
+                tasks,
+                cancel.child_token(),
+            )
+            .await;
+
+            send_subsystem_status(&mut client.clone(), Subsystem::Network, &status).await;
+
+            network_tx
+        };
+
         let (telemetry_tx, telemetry_rx) = mpsc::channel(EVENT_BUFFER);
 
         let telemetry = Telemetry::from_config(
             client.clone(),
             &opts.telemetry_config.unwrap_or_default(),
             opts.store_directory.clone(),
+            power.clone(),
+            SourcesConfig {
+                #[cfg(all(feature = "zbus", target_os = "linux"))]
+                cellular: opts.cellular,
+                #[cfg(all(feature = "zbus", target_os = "linux"))]
+                time_sync: opts.time_sync,
+                gnss: opts.gnss,
+            },
+            opts.telemetry_schedule,
             #[cfg(feature = "containers")]
             std::sync::Arc::clone(&container_handle),
         )
         .await;
 
         tasks.spawn(telemetry.run(telemetry_rx, cancel.child_token()));
+        tasks.spawn(crate::telemetry::forward_power_profile(
+            power.clone(),
+            telemetry_tx.clone(),
+            cancel.child_token(),
+        ));
+
+        if opts.attributes.enabled {
+            let attributes = DeviceAttributes::new(client.clone(), opts.attributes);
+
+            tasks.spawn(attributes.run(cancel.child_token()));
+        }
 
         #[cfg(feature = "file-transfer")]
         let (storage_manager, file_transfer) = {
@@ -140,12 +255,21 @@ impl<C> Runtime<C> {
                 tasks,
                 jobs,
                 notify_cleanup,
+                power,
+                bandwidth,
                 cancel.child_token(),
             )
             .wrap_err("could't initialize file transfer")?;
 
             (storage_manager, file_transfer)
         };
+        #[cfg(feature = "file-transfer")]
+        send_subsystem_status(
+            &mut client.clone(),
+            Subsystem::FileTransfer,
+            &SubsystemStatus::from_enabled(file_transfer.is_some()),
+        )
+        .await;
 
         #[cfg(feature = "containers")]
         let containers_tx = Self::setup_containers(
@@ -160,24 +284,38 @@ impl<C> Runtime<C> {
         .wrap_err("couldn't setup the container task")?;
 
         #[cfg(feature = "service")]
-        Self::setup_service(
-            opts.service.unwrap_or_default(),
-            #[cfg(feature = "containers")]
-            &container_handle,
-            tasks,
-            cancel.child_token(),
-        );
+        {
+            let status = Self::setup_service(
+                opts.service.unwrap_or_default(),
+                store.clone(),
+                #[cfg(feature = "containers")]
+                &container_handle,
+                tasks,
+                cancel.child_token(),
+            );
+
+            send_subsystem_status(&mut client.clone(), Subsystem::Service, &status).await;
+        }
 
         #[cfg(feature = "forwarder")]
         // Initialize the forwarder instance
         let forwarder = crate::forwarder::Forwarder::init(client.clone())
             .await
             .wrap_err("couldn't initialize the forwarder")?;
+        #[cfg(feature = "forwarder")]
+        send_subsystem_status(
+            &mut client.clone(),
+            Subsystem::Forwarder,
+            &SubsystemStatus::Initialized,
+        )
+        .await;
 
         Ok(Self {
             client,
             cancel,
             telemetry_tx,
+            #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+            bandwidth_tx,
             #[cfg(feature = "file-transfer")]
             file_transfer,
             #[cfg(feature = "file-transfer")]
@@ -189,10 +327,84 @@ impl<C> Runtime<C> {
             #[cfg(all(feature = "zbus", target_os = "linux"))]
             led_tx,
             #[cfg(all(feature = "zbus", target_os = "linux"))]
+            network_tx,
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
             ota_handler,
         })
     }
 
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    async fn setup_network(
+        client: C,
+        config: crate::network::config::NetworkConfig,
+        pairing_url: Option<url::Url>,
+        tasks: &mut JoinSet<eyre::Result<()>>,
+        cancel: CancellationToken,
+    ) -> (
+        Option<mpsc::Sender<crate::network::request::ConnectionRequest>>,
+        SubsystemStatus,
+    )
+    where
+        C: Client + Send + Sync + 'static,
+    {
+        use crate::network::NetworkProvisioning;
+        use crate::network::manager::NetworkManager;
+
+        if !config.enabled {
+            tracing::info!("network provisioning not enabled");
+
+            return (None, SubsystemStatus::Disabled);
+        }
+
+        // The runtime keeps running without the network provisioning
+        let backend = match NetworkManager::connect().await {
+            Ok(backend) => backend,
+            Err(err) => {
+                let reason = format!("{err:#}");
+
+                error!(error = reason, "couldn't connect to NetworkManager");
+
+                return (None, SubsystemStatus::Failed(reason));
+            }
+        };
+
+        let (network_tx, network_rx) = mpsc::channel(EVENT_BUFFER);
+
+        tasks.spawn(
+            NetworkProvisioning::new(client, backend, config, pairing_url).run(network_rx, cancel),
+        );
+
+        (Some(network_tx), SubsystemStatus::Initialized)
+    }
+
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    fn setup_power_policy(
+        client: C,
+        config: crate::power_policy::config::PowerPolicyConfig,
+        tasks: &mut JoinSet<eyre::Result<()>>,
+        cancel: CancellationToken,
+    ) -> eyre::Result<crate::power_policy::PowerState>
+    where
+        C: Client + Send + Sync + 'static,
+    {
+        use crate::power_policy::PowerState;
+        use crate::power_policy::monitor::PowerPolicy;
+
+        if !config.enabled {
+            tracing::info!("power policy not enabled");
+
+            return Ok(PowerState::default());
+        }
+
+        config.validate()?;
+
+        let (policy, power) = PowerPolicy::new(client, config);
+
+        tasks.spawn(policy.run(cancel));
+
+        Ok(power)
+    }
+
     #[cfg(feature = "file-transfer")]
     fn storage_manager(
         device: C,
@@ -232,12 +444,15 @@ impl<C> Runtime<C> {
     }
 
     #[cfg(feature = "file-transfer")]
+    #[allow(clippy::too_many_arguments)]
     fn file_transfer(
         device: C,
         config: crate::file_transfer::config::FileTransferArgs,
         tasks: &mut JoinSet<eyre::Result<()>>,
         jobs: crate::jobs::Queue,
         notify_cleanup: std::sync::Arc<tokio::sync::Notify>,
+        power: crate::power_policy::PowerState,
+        bandwidth: crate::bandwidth::Bandwidth,
         cancel: CancellationToken,
     ) -> eyre::Result<
         Option<mpsc::Sender<crate::file_transfer::interface::request::FileTransferRequest>>,
@@ -272,6 +487,8 @@ impl<C> Runtime<C> {
                 device.clone(),
                 progress_tx,
                 notify_cleanup,
+                power,
+                bandwidth,
             )?
             .run(Arc::clone(&job_notify), cancel.clone()),
         );
@@ -298,11 +515,25 @@ impl<C> Runtime<C> {
         if !config.enabled {
             tracing::info!("container service not enabled");
 
+            send_subsystem_status(
+                &mut client.clone(),
+                Subsystem::Containers,
+                &SubsystemStatus::Disabled,
+            )
+            .await;
+
             return Ok(None);
         }
 
         let (container_tx, container_rx) = mpsc::channel(EVENT_BUFFER);
 
+        send_subsystem_status(
+            &mut client.clone(),
+            Subsystem::Containers,
+            &SubsystemStatus::Enabled,
+        )
+        .await;
+
         let containers = crate::containers::ContainerService::new(
             client,
             config,
@@ -321,31 +552,36 @@ impl<C> Runtime<C> {
     #[cfg(feature = "service")]
     fn setup_service(
         config: edgehog_service::config::Config,
+        store: edgehog_store::db::Handle,
         #[cfg(feature = "containers")] container_handle: &std::sync::Arc<
             tokio::sync::OnceCell<edgehog_containers::local::ContainerHandle>,
         >,
         tasks: &mut JoinSet<eyre::Result<()>>,
         cancel: CancellationToken,
-    ) where
+    ) -> SubsystemStatus
+    where
         C: Client + Clone + Send + Sync + 'static,
     {
         if !config.enabled {
             tracing::debug!("local service not enabled");
 
-            return;
+            return SubsystemStatus::Disabled;
         }
 
         let options = match edgehog_service::service::ServiceOptions::try_from(config) {
             Ok(opt) => opt,
             Err(err) => {
-                error!(error = format!("{err:#}"), "invalid service options");
+                let reason = format!("{err:#}");
 
-                return;
+                error!(error = reason, "invalid service options");
+
+                return SubsystemStatus::Failed(reason);
             }
         };
 
         let service = edgehog_service::service::EdgehogService::new(
             options,
+            store,
             #[cfg(feature = "containers")]
             std::sync::Arc::clone(container_handle),
         );
@@ -357,6 +593,8 @@ impl<C> Runtime<C> {
 
             Ok(())
         });
+
+        SubsystemStatus::Initialized
     }
 
     #[instrument(skip(self))]
@@ -371,19 +609,34 @@ impl<C> Runtime<C> {
 
         info!("Running");
 
-        while let Some(res) = self.cancel.run_until_cancelled(self.client.recv()).await {
-            let event = match res {
-                Ok(event) => RuntimeEvent::from_event(event).wrap_err("couldn't convert event")?,
-                Err(RecvError::Disconnected) => {
-                    error!("the Runtime was disconnected");
-
-                    return Ok(());
+        loop {
+            // Bundles dropped in the local directory are handled like the requests from Astarte
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            let local = async {
+                match &mut self.ota_handler {
+                    Some(ota_handler) => RuntimeEvent::Ota(ota_handler.next_local().await),
+                    None => std::future::pending().await,
                 }
-                Err(err) => {
-                    error!(error = %Report::new(err), "error received");
+            };
+            #[cfg(not(all(feature = "zbus", target_os = "linux")))]
+            let local = std::future::pending::<RuntimeEvent>();
+
+            let event = tokio::select! {
+                _ = self.cancel.cancelled() => break,
+                event = local => event,
+                res = self.client.recv() => match res {
+                    Ok(event) => RuntimeEvent::from_event(event).wrap_err("couldn't convert event")?,
+                    Err(RecvError::Disconnected) => {
+                        error!("the Runtime was disconnected");
+
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        error!(error = %Report::new(err), "error received");
 
-                    continue;
-                }
+                        continue;
+                    }
+                },
             };
 
             self.handle_event(event).await;
@@ -402,7 +655,12 @@ impl<C> Runtime<C> {
         match event {
             RuntimeEvent::Command(cmd) => {
                 #[cfg(all(feature = "zbus", target_os = "linux"))]
-                if cmd.is_reboot() && self.ota_handler.in_progress() {
+                if cmd.is_reboot()
+                    && self
+                        .ota_handler
+                        .as_ref()
+                        .is_some_and(|ota_handler| ota_handler.in_progress())
+                {
                     error!("cannot reboot during OTA update");
 
                     return;
@@ -413,10 +671,21 @@ impl<C> Runtime<C> {
                 }
             }
             RuntimeEvent::Telemetry(event) => {
-                if self.telemetry_tx.send(event).await.is_err() {
+                if self.telemetry_tx.send(event.into()).await.is_err() {
                     error!("couldn't send the telemetry event");
                 }
             }
+            RuntimeEvent::TelemetryRefresh(refresh) => {
+                if self.telemetry_tx.send(refresh.into()).await.is_err() {
+                    error!("couldn't send the telemetry refresh");
+                }
+            }
+            #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+            RuntimeEvent::Bandwidth(event) => {
+                if self.bandwidth_tx.send(event).await.is_err() {
+                    error!("couldn't send the bandwidth event");
+                }
+            }
             #[cfg(feature = "file-transfer")]
             RuntimeEvent::FileTransfer(event) => {
                 if let Some(file_transfer) = &self.file_transfer {
@@ -446,12 +715,26 @@ impl<C> Runtime<C> {
                 }
             }
             #[cfg(all(feature = "zbus", target_os = "linux"))]
+            RuntimeEvent::Network(event) => {
+                if let Some(network_tx) = &self.network_tx {
+                    if network_tx.send(*event).await.is_err() {
+                        error!("couldn't send the network event");
+                    }
+                } else {
+                    error!("received event on network interface, but the service is disabled");
+                }
+            }
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
             RuntimeEvent::Ota(ota) => {
-                if let Err(err) = self.ota_handler.handle_event(ota).await {
-                    error!(
-                        error = %eyre::Report::new(err),
-                        "error while processing ota event",
-                    );
+                if let Some(ota_handler) = &mut self.ota_handler {
+                    if let Err(err) = ota_handler.handle_event(ota).await {
+                        error!(
+                            error = %eyre::Report::new(err),
+                            "error while processing ota event",
+                        );
+                    }
+                } else {
+                    error!("received ota event, but the service is not initialized");
                 }
             }
             #[cfg(feature = "containers")]
@@ -471,7 +754,12 @@ impl<C> Runtime<C> {
         }
     }
 
-    #[cfg(any(feature = "file-transfer", feature = "containers"))]
+    #[cfg(any(
+        feature = "file-transfer",
+        feature = "containers",
+        feature = "service",
+        all(feature = "zbus", target_os = "linux")
+    ))]
     async fn store(
         store_dir: &std::path::Path,
     ) -> Result<edgehog_store::db::Handle, edgehog_store::db::HandleError> {
@@ -72,6 +72,30 @@ pub(crate) async fn send_object_with_timestamp<C, T>(
     }
 }
 
+/// Publishes the individual value to Astarte and logs if an error happens.
+///
+/// This is used to send telemetry data without returning an error.
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub(crate) async fn send_individual_with_timestamp<C>(
+    client: &mut C,
+    interface: &str,
+    path: &str,
+    data: impl Into<AstarteData>,
+    timestamp: DateTime<Utc>,
+) where
+    C: Client,
+{
+    if let Err(err) = client
+        .send_individual_with_timestamp(interface, path, data.into(), timestamp)
+        .await
+    {
+        error!(
+            error = format!("{:#}", eyre::Report::new(err)),
+            interface, path, "failed to publish",
+        )
+    }
+}
+
 /// Sets the property and logs if an error happens.
 ///
 /// This is used to send telemetry data without returning an error.
@@ -91,6 +115,26 @@ pub(crate) async fn set_property<C>(
     }
 }
 
+/// Unsets the property and logs if an error happens.
+#[cfg(any(
+    feature = "containers",
+    feature = "file-transfer",
+    feature = "forwarder",
+    feature = "service",
+    all(feature = "zbus", target_os = "linux")
+))]
+pub(crate) async fn unset_property<C>(client: &mut C, interface: &str, path: &str)
+where
+    C: Client,
+{
+    if let Err(err) = client.unset_property(interface, path).await {
+        error!(
+            error = format!("{:#}", eyre::Report::new(err)),
+            interface, path, "failed to unset property",
+        )
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -50,6 +50,14 @@ pub enum DeviceManagerError {
     #[error(transparent)]
     Ota(#[from] crate::ota::OtaError),
 
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    #[error(transparent)]
+    SwUpdate(#[from] crate::ota::swupdate::SwUpdateError),
+
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    #[error(transparent)]
+    Script(#[from] crate::ota::script::ScriptError),
+
     #[error("configuration file error")]
     ConfigFile(#[from] toml::de::Error),
 
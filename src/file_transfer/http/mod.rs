@@ -22,13 +22,14 @@ use std::{fmt::Debug, ops::RangeInclusive};
 
 use bytes::Bytes;
 use eyre::{Context, OptionExt, eyre};
-use futures::TryStream;
+use futures::{TryStream, TryStreamExt};
 use reqwest::StatusCode;
 use reqwest::header::{GetAll, HeaderMap, HeaderValue};
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tracing::{debug, error, instrument, trace, warn};
 use url::Url;
 
+use crate::bandwidth::{Bandwidth, Scope};
 use crate::http::default_http_client_builder;
 
 #[derive(Debug)]
@@ -46,16 +47,17 @@ pub(crate) enum FileTransferRange {
 #[derive(Debug)]
 pub(crate) struct FtHttpClient {
     client: reqwest::Client,
+    bandwidth: Bandwidth,
 }
 
 impl FtHttpClient {
-    pub(crate) fn create() -> eyre::Result<Self> {
+    pub(crate) fn create(bandwidth: Bandwidth) -> eyre::Result<Self> {
         let client = default_http_client_builder()
             .wrap_err("failed to build TLS config")?
             .build()
             .wrap_err("failed to build HTTP client")?;
 
-        Ok(Self { client })
+        Ok(Self { client, bandwidth })
     }
 
     #[instrument(skip(self, headers, start, total_len))]
@@ -107,6 +109,7 @@ impl FtHttpClient {
                     response,
                     start: range_start,
                     total_length,
+                    bandwidth: self.bandwidth.clone(),
                 })
             }
             FileTransferRange::RangeNotSatisfiable { range_total } => {
@@ -131,10 +134,17 @@ impl FtHttpClient {
     {
         headers.insert(reqwest::header::CONTENT_LENGTH, bytes.into());
 
+        let body = self.limited_body(
+            body_stream
+                .into_stream()
+                .map_ok(Bytes::from)
+                .map_err(Into::into),
+        );
+
         self.client
             .put(url.as_str())
             .headers(headers)
-            .body(reqwest::Body::wrap_stream(body_stream))
+            .body(body)
             .send()
             .await
             .wrap_err("error while sending put upload request")?
@@ -144,6 +154,16 @@ impl FtHttpClient {
         Ok(())
     }
 
+    /// Body of the upload, limited by the file transfer bandwidth.
+    fn limited_body<S>(&self, stream: S) -> reqwest::Body
+    where
+        S: futures::Stream<Item = Result<Bytes, Box<dyn std::error::Error + Send + Sync>>>
+            + Send
+            + 'static,
+    {
+        reqwest::Body::wrap_stream(self.bandwidth.limit_stream(Scope::FileTransfer, stream))
+    }
+
     async fn full_request(
         &self,
         url: &Url,
@@ -169,6 +189,7 @@ impl FtHttpClient {
             response,
             start: 0,
             total_length,
+            bandwidth: self.bandwidth.clone(),
         })
     }
 
@@ -312,6 +333,7 @@ pub(crate) struct FileDownloadResponse {
     response: reqwest::Response,
     start: u64,
     total_length: Option<u64>,
+    bandwidth: Bandwidth,
 }
 
 impl FileDownloadResponse {
@@ -328,6 +350,10 @@ impl FileDownloadResponse {
         W: AsyncWrite + Unpin,
     {
         while let Some(chunk) = self.response.chunk().await? {
+            self.bandwidth
+                .acquire(Scope::FileTransfer, chunk.len())
+                .await;
+
             writer.write_all(&chunk).await?;
             writer.flush().await?;
         }
@@ -349,6 +375,7 @@ mod tests {
     use tokio_stream::once;
     use url::Url;
 
+    use crate::bandwidth::Bandwidth;
     use crate::file_transfer::http::FtHttpClient;
 
     #[tokio::test]
@@ -371,7 +398,7 @@ mod tests {
             })
             .await;
 
-        let client = FtHttpClient::create().unwrap();
+        let client = FtHttpClient::create(Bandwidth::default()).unwrap();
         let mut response = client
             .download(
                 &file_url,
@@ -401,7 +428,7 @@ mod tests {
         let binary_content = [2u8; 8192];
         let binary_size = u64::try_from(binary_content.len()).unwrap();
 
-        let client = FtHttpClient::create().unwrap();
+        let client = FtHttpClient::create(Bandwidth::default()).unwrap();
         // Mocking a different range
         let range_mock = server
             .mock_async(|when, then| {
@@ -448,7 +475,7 @@ mod tests {
             })
             .await;
 
-        let client = FtHttpClient::create().unwrap();
+        let client = FtHttpClient::create(Bandwidth::default()).unwrap();
         let mut response = client
             .download(&file_url, HeaderMap::new(), 0, Some(binary_size))
             .await
@@ -525,7 +552,7 @@ mod tests {
 
         let body_stream: tokio_stream::Once<eyre::Result<_>> = once(Ok(binary_content));
 
-        let client = FtHttpClient::create().unwrap();
+        let client = FtHttpClient::create(Bandwidth::default()).unwrap();
         client
             .upload_sized(
                 &file_url,
@@ -564,7 +591,7 @@ mod tests {
         let body_stream: tokio_stream::Iter<_> =
             tokio_stream::iter(iter::repeat_n(CHUNK, needed_chunks).map(eyre::Ok));
 
-        let client = FtHttpClient::create().unwrap();
+        let client = FtHttpClient::create(Bandwidth::default()).unwrap();
         client
             .upload_sized(
                 &file_url,
@@ -41,6 +41,7 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument, trace, warn};
 use uuid::Uuid;
 
+use crate::bandwidth::Bandwidth;
 use crate::controller::actor::Persisted;
 use crate::file_transfer::encoding::tar_gz::TarGzEncoding;
 use crate::file_transfer::encoding::{EncoderBuilder, TarEncoding};
@@ -53,6 +54,7 @@ use crate::io::limit::Limit;
 use crate::io::progress::{Progress, ProgressHandle};
 use crate::jobs::Queue;
 use crate::jobs::timestamp::Unix;
+use crate::power_policy::PowerState;
 use crate::storage::request::CleanUp;
 use crate::{file_transfer::interface::status::FileTransferResponse, io::digest::Digest};
 
@@ -175,6 +177,7 @@ pub(crate) struct FileTransfer<F, S, C> {
     client: FtHttpClient,
     device: C,
     tracker: watch::Sender<Option<FileTransferProgress>>,
+    power: PowerState,
 }
 
 impl<C> FileTransfer<Fs, SysPipe, C> {
@@ -184,13 +187,16 @@ impl<C> FileTransfer<Fs, SysPipe, C> {
         device: C,
         tracker: watch::Sender<Option<FileTransferProgress>>,
         cleanup: Arc<Notify>,
+        power: PowerState,
+        bandwidth: Bandwidth,
     ) -> eyre::Result<Self>
     where
         C: astarte_device_sdk::Client + Send + Sync + 'static,
     {
         debug_assert!(args.enabled);
 
-        let client = FtHttpClient::create().wrap_err("can't construct file transfer client")?;
+        let client =
+            FtHttpClient::create(bandwidth).wrap_err("can't construct file transfer client")?;
 
         Ok(Self {
             queue,
@@ -200,6 +206,7 @@ impl<C> FileTransfer<Fs, SysPipe, C> {
             device,
             tracker,
             cleanup,
+            power,
         })
     }
 }
@@ -254,9 +261,29 @@ impl<F, S, C> FileTransfer<F, S, C> {
         S: Pipe,
         C: astarte_device_sdk::Client + Send + Sync + 'static,
     {
-        while let Some(job) = self.next_job().await?
-            && !cancel.is_cancelled()
-        {
+        loop {
+            if self.power.current().pauses_transfers() {
+                info!(profile = %self.power.current(), "file transfers paused by the power profile");
+
+                let resumed = cancel
+                    .run_until_cancelled(self.power.wait_for(|profile| !profile.pauses_transfers()))
+                    .await;
+
+                if resumed.is_none() {
+                    break;
+                }
+
+                info!("resuming file transfers");
+            }
+
+            let Some(job) = self.next_job().await? else {
+                break;
+            };
+
+            if cancel.is_cancelled() {
+                break;
+            }
+
             if let Err(error) = self.handle(job).await {
                 error!(error = format!("{error:#}"), "couldn't handle job");
             }
@@ -927,7 +954,16 @@ mod tests {
             storage_reserved: TEST_RESERVED_PERCENTAGE,
         };
         (
-            FileTransfer::create(queue, args, device, tracker, Arc::new(Notify::new())).unwrap(),
+            FileTransfer::create(
+                queue,
+                args,
+                device,
+                tracker,
+                Arc::new(Notify::new()),
+                PowerState::default(),
+                Bandwidth::default(),
+            )
+            .unwrap(),
             dir,
         )
     }
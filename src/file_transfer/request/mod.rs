@@ -28,6 +28,8 @@ use crate::file_transfer::interface::status::FileTransferId;
 use self::download::Download;
 use self::upload::Upload;
 
+pub use crate::io::digest::FileDigest;
+
 use super::FileOptions;
 
 pub(crate) mod download;
@@ -162,39 +164,6 @@ impl FilePermissions {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, minicbor::Encode, minicbor::Decode)]
-#[cbor(index_only)]
-#[repr(u8)]
-pub enum FileDigest {
-    #[n(0)]
-    Sha256 = 0,
-}
-
-impl From<FileDigest> for u8 {
-    fn from(value: FileDigest) -> Self {
-        value as u8
-    }
-}
-
-impl From<FileDigest> for aws_lc_rs::digest::Context {
-    fn from(value: FileDigest) -> Self {
-        match value {
-            FileDigest::Sha256 => aws_lc_rs::digest::Context::new(&aws_lc_rs::digest::SHA256),
-        }
-    }
-}
-
-impl FromStr for FileDigest {
-    type Err = eyre::Report;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "sha256" => Ok(FileDigest::Sha256),
-            _ => Err(eyre!("unrecognize file digest: {s}")),
-        }
-    }
-}
-
 fn conv_or_default<T, U>(value: T, default: T) -> Result<Option<U>, U::Error>
 where
     T: PartialEq,
@@ -31,3 +31,26 @@ pub(crate) fn default_http_client_builder() -> Result<reqwest::ClientBuilder, ru
 
     Ok(client)
 }
+
+/// Checks that the Astarte pairing API can be reached.
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub(crate) async fn astarte_health(pairing_url: &url::Url) -> eyre::Result<()> {
+    use eyre::WrapErr;
+
+    let mut url = pairing_url.clone();
+
+    url.path_segments_mut()
+        .map_err(|()| eyre::eyre!("couldn't get path for pairing url"))?
+        .pop_if_empty()
+        .push("health");
+
+    default_http_client_builder()?
+        .build()?
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .wrap_err("couldn't reach astarte")?;
+
+    Ok(())
+}
@@ -18,13 +18,51 @@
 
 use std::fmt::Debug;
 use std::io::{self};
+use std::str::FromStr;
 use std::task::{Poll, ready};
 
+use eyre::eyre;
 use pin_project::pin_project;
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, BufReader};
 use tracing::instrument;
 
-use crate::file_transfer::request::FileDigest;
+/// Algorithm of the digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, minicbor::Encode, minicbor::Decode)]
+#[cbor(index_only)]
+#[repr(u8)]
+pub enum FileDigest {
+    #[n(0)]
+    Sha256 = 0,
+    #[n(1)]
+    Sha512 = 1,
+}
+
+impl From<FileDigest> for u8 {
+    fn from(value: FileDigest) -> Self {
+        value as u8
+    }
+}
+
+impl From<FileDigest> for aws_lc_rs::digest::Context {
+    fn from(value: FileDigest) -> Self {
+        match value {
+            FileDigest::Sha256 => aws_lc_rs::digest::Context::new(&aws_lc_rs::digest::SHA256),
+            FileDigest::Sha512 => aws_lc_rs::digest::Context::new(&aws_lc_rs::digest::SHA512),
+        }
+    }
+}
+
+impl FromStr for FileDigest {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sha256" => Ok(FileDigest::Sha256),
+            "sha512" => Ok(FileDigest::Sha512),
+            _ => Err(eyre!("unrecognize file digest: {s}")),
+        }
+    }
+}
 
 /// Wrapper to a [`AsyncRead`] or [`AsyncWrite`] that calculates the digest.
 #[pin_project]
@@ -17,5 +17,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod digest;
+#[cfg(feature = "file-transfer")]
 pub mod limit;
+#[cfg(feature = "file-transfer")]
 pub mod progress;
+pub mod space;
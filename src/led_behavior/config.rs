@@ -0,0 +1,157 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuration for the LED behaviors.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Configuration of the LED behaviors.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LedConfig {
+    /// Named blink patterns, that can be requested as LED behaviors.
+    #[serde(default)]
+    pub patterns: HashMap<String, BlinkPattern>,
+}
+
+/// Timings of a blink.
+///
+/// The LED is turned on and off for the number of repetitions, then it pauses before the next
+/// cycle, until the duration expires.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BlinkPattern {
+    /// Total duration of the blink in seconds.
+    #[serde(default = "BlinkPattern::default_duration")]
+    pub duration_secs: u64,
+    /// Blinks in a single cycle.
+    #[serde(default = "BlinkPattern::default_repetitions")]
+    pub repetitions: u64,
+    /// Milliseconds the LED stays on.
+    pub on_millis: u64,
+    /// Milliseconds the LED stays off.
+    pub off_millis: u64,
+    /// Milliseconds to wait at the end of a cycle.
+    #[serde(default)]
+    pub pause_millis: u64,
+}
+
+impl BlinkPattern {
+    const fn default_duration() -> u64 {
+        60
+    }
+
+    const fn default_repetitions() -> u64 {
+        1
+    }
+
+    /// Checks that the pattern doesn't busy loop.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.repetitions == 0 {
+            return Err("the repetitions must be greater than 0".to_string());
+        }
+
+        if self.on_millis == 0 && self.off_millis == 0 && self.pause_millis == 0 {
+            return Err("the on, off and pause times cannot all be 0".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_deserialize() {
+        let string = r#"
+        [patterns.find-me]
+        duration_secs = 300
+        on_millis = 100
+        off_millis = 100
+
+        [patterns.error]
+        repetitions = 3
+        on_millis = 200
+        off_millis = 200
+        pause_millis = 1000
+        "#;
+
+        let config: LedConfig = toml::from_str(string).unwrap();
+
+        let exp = LedConfig {
+            patterns: HashMap::from([
+                (
+                    "find-me".to_string(),
+                    BlinkPattern {
+                        duration_secs: 300,
+                        repetitions: 1,
+                        on_millis: 100,
+                        off_millis: 100,
+                        pause_millis: 0,
+                    },
+                ),
+                (
+                    "error".to_string(),
+                    BlinkPattern {
+                        duration_secs: 60,
+                        repetitions: 3,
+                        on_millis: 200,
+                        off_millis: 200,
+                        pause_millis: 1000,
+                    },
+                ),
+            ]),
+        };
+
+        assert_eq!(config, exp);
+    }
+
+    #[test]
+    fn should_validate_pattern() {
+        let pattern = BlinkPattern {
+            duration_secs: 60,
+            repetitions: 1,
+            on_millis: 0,
+            off_millis: 0,
+            pause_millis: 0,
+        };
+
+        assert!(pattern.validate().is_err());
+
+        let pattern = BlinkPattern {
+            repetitions: 0,
+            on_millis: 100,
+            ..pattern
+        };
+
+        assert!(pattern.validate().is_err());
+
+        let pattern = BlinkPattern {
+            repetitions: 1,
+            ..pattern
+        };
+
+        assert!(pattern.validate().is_ok());
+    }
+}
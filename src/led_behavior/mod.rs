@@ -0,0 +1,819 @@
+/*
+ * This file is part of Edgehog.
+ *
+ * Copyright 2022 - 2026 SECO Mind Srl
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Blink and status of the device LEDs, through the LED manager service.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+
+use astarte_device_sdk::{
+    AstarteData, DeviceEvent, FromEvent, event::FromEventError, types::TypeError,
+};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant, sleep};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info};
+use zbus::proxy;
+
+use crate::controller::actor::Actor;
+use crate::repository::StateRepository;
+use crate::repository::file_state_repository::FileStateRepository;
+
+use self::config::{BlinkPattern, LedConfig};
+
+pub mod config;
+
+const STATUS_PATH: &str = "led_status.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedEvent {
+    pub led_id: String,
+    pub behavior: LedBehavior,
+}
+
+impl FromEvent for LedEvent {
+    type Err = FromEventError;
+
+    fn from_event(event: DeviceEvent) -> Result<Self, Self::Err> {
+        let led_id =
+            LedBehavior::led_id_from_path(&event.path).ok_or_else(|| FromEventError::Path {
+                interface: "io.edgehog.devicemanager.LedBehavior",
+                base_path: event.path.clone(),
+            })?;
+
+        LedBehavior::from_event(event).map(|behavior| LedEvent { led_id, behavior })
+    }
+}
+
+#[derive(Debug, Clone, FromEvent, PartialEq, Eq)]
+#[from_event(
+    interface = "io.edgehog.devicemanager.LedBehavior",
+    aggregation = "individual"
+)]
+pub enum LedBehavior {
+    #[mapping(endpoint = "/%{led_id}/behavior")]
+    Behavior(Blink),
+    /// Persistent status of the LED, restored after a blink and on restart.
+    #[mapping(endpoint = "/%{led_id}/status")]
+    Status(LedStatus),
+    /// Cancels the running blink.
+    #[mapping(endpoint = "/%{led_id}/cancel")]
+    Cancel(bool),
+}
+
+impl LedBehavior {
+    fn led_id_from_path(path: &str) -> Option<String> {
+        path.strip_prefix('/')
+            .and_then(|path| path.split_once('/').map(|(led_id, _)| led_id))
+            .map(str::to_string)
+    }
+}
+
+/// Request to blink a LED with custom timings.
+#[derive(Debug, Clone, FromEvent, PartialEq, Eq)]
+#[from_event(
+    interface = "io.edgehog.devicemanager.LedBlinkRequest",
+    path = "/request",
+    aggregation = "object",
+    rename_all = "camelCase"
+)]
+pub struct LedBlinkRequest {
+    pub(crate) led_id: String,
+    pub(crate) duration_seconds: i32,
+    pub(crate) repetitions: i32,
+    pub(crate) on_millis: i32,
+    pub(crate) off_millis: i32,
+    pub(crate) pause_millis: i32,
+}
+
+impl TryFrom<LedBlinkRequest> for LedEvent {
+    type Error = TypeError;
+
+    fn try_from(value: LedBlinkRequest) -> Result<Self, Self::Error> {
+        let positive = |name: &str, value: i32| {
+            u64::try_from(value).map_err(|_| TypeError::Conversion {
+                ctx: format!("negative led blink {name} {value}"),
+            })
+        };
+
+        let pattern = BlinkPattern {
+            duration_secs: positive("duration", value.duration_seconds)?,
+            repetitions: positive("repetitions", value.repetitions)?,
+            on_millis: positive("on time", value.on_millis)?,
+            off_millis: positive("off time", value.off_millis)?,
+            pause_millis: positive("pause time", value.pause_millis)?,
+        };
+
+        pattern
+            .validate()
+            .map_err(|ctx| TypeError::Conversion { ctx })?;
+
+        Ok(LedEvent {
+            led_id: value.led_id,
+            behavior: LedBehavior::Behavior(Blink::Custom(pattern)),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Blink {
+    Single,
+    Double,
+    Slow,
+    /// Named pattern from the configuration.
+    Pattern(String),
+    Custom(BlinkPattern),
+}
+
+impl Blink {
+    /// Returns the timings of the blink, if the pattern is configured.
+    fn pattern(&self, config: &LedConfig) -> Option<BlinkPattern> {
+        match self {
+            Blink::Single => Some(BlinkPattern {
+                duration_secs: 60,
+                repetitions: 1,
+                on_millis: 1000,
+                off_millis: 1000,
+                pause_millis: 0,
+            }),
+            Blink::Double => Some(BlinkPattern {
+                duration_secs: 60,
+                repetitions: 2,
+                on_millis: 300,
+                off_millis: 200,
+                pause_millis: 800,
+            }),
+            Blink::Slow => Some(BlinkPattern {
+                duration_secs: 60,
+                repetitions: 1,
+                on_millis: 2000,
+                off_millis: 2000,
+                pause_millis: 0,
+            }),
+            Blink::Pattern(name) => config.patterns.get(name).copied(),
+            Blink::Custom(pattern) => Some(*pattern),
+        }
+    }
+}
+
+impl Display for Blink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Blink::Single => write!(f, "Blink60Seconds"),
+            Blink::Double => write!(f, "DoubleBlink60Seconds"),
+            Blink::Slow => write!(f, "SlowBlink60Seconds"),
+            Blink::Pattern(name) => write!(f, "{name}"),
+            Blink::Custom(_) => write!(f, "custom"),
+        }
+    }
+}
+
+impl TryFrom<AstarteData> for Blink {
+    type Error = TypeError;
+
+    fn try_from(value: AstarteData) -> Result<Self, Self::Error> {
+        let value = String::try_from(value)?;
+
+        match value.as_str() {
+            "Blink60Seconds" => Ok(Self::Single),
+            "DoubleBlink60Seconds" => Ok(Self::Double),
+            "SlowBlink60Seconds" => Ok(Self::Slow),
+            "" => {
+                error!("empty LedBehavior behavior value");
+
+                Err(TypeError::Conversion {
+                    ctx: "empty LedBehavior behavior value".to_string(),
+                })
+            }
+            _ => Ok(Self::Pattern(value)),
+        }
+    }
+}
+
+/// Status of the LED when it's not blinking.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LedStatus {
+    #[default]
+    Off,
+    Solid,
+}
+
+impl TryFrom<AstarteData> for LedStatus {
+    type Error = TypeError;
+
+    fn try_from(value: AstarteData) -> Result<Self, Self::Error> {
+        let value = String::try_from(value)?;
+
+        match value.as_str() {
+            "Off" => Ok(Self::Off),
+            "Solid" => Ok(Self::Solid),
+            _ => {
+                error!(value, "unrecognized LedBehavior status value");
+
+                Err(TypeError::Conversion {
+                    ctx: format!("unrecognized LedBehavior status value {value}"),
+                })
+            }
+        }
+    }
+}
+
+#[proxy(
+    interface = "io.edgehog.LedManager1",
+    default_service = "io.edgehog.LedManager",
+    default_path = "/io/edgehog/LedManager"
+)]
+trait LedManager {
+    async fn set(&self, id: String, status: bool) -> zbus::Result<bool>;
+
+    /// Blinks the LED with the kernel timer trigger, until it's set again.
+    async fn blink(&self, id: String, on_millis: u32, off_millis: u32) -> zbus::Result<bool>;
+}
+
+#[cfg(not(test))]
+async fn set_led(led_id: &str, status: bool) -> zbus::Result<bool> {
+    let connection = zbus::Connection::system().await?;
+    let led_manager = LedManagerProxy::new(&connection).await?;
+
+    led_manager.set(led_id.to_string(), status).await
+}
+
+#[cfg(test)]
+async fn set_led(_led_id: &str, _status: bool) -> zbus::Result<bool> {
+    Ok(true)
+}
+
+#[cfg(not(test))]
+async fn run_blink(pattern: BlinkPattern, led_id: &str) -> zbus::Result<()> {
+    let connection = zbus::Connection::system().await?;
+    let led_manager = LedManagerProxy::new(&connection).await?;
+
+    // The kernel timer blinks with a fixed period, so it can't pause between the repetitions
+    let timer = (pattern.pause_millis == 0)
+        .then(|| {
+            let on = u32::try_from(pattern.on_millis).ok()?;
+            let off = u32::try_from(pattern.off_millis).ok()?;
+
+            Some((on, off))
+        })
+        .flatten();
+
+    if let Some((on_millis, off_millis)) = timer {
+        match led_manager
+            .blink(led_id.to_string(), on_millis, off_millis)
+            .await
+        {
+            Ok(true) => {
+                sleep(Duration::from_secs(pattern.duration_secs)).await;
+
+                return Ok(());
+            }
+            Ok(false) => return Ok(()),
+            Err(err) => {
+                debug!(led_id, error = %err, "kernel timer not available, toggling the led");
+            }
+        }
+    }
+
+    toggle_blink(&led_manager, pattern, led_id).await
+}
+
+/// Blinks the LED by setting it on and off.
+#[cfg(not(test))]
+async fn toggle_blink(
+    led_manager: &LedManagerProxy<'_>,
+    pattern: BlinkPattern,
+    led_id: &str,
+) -> zbus::Result<()> {
+    let start = Instant::now();
+    while (Instant::now() - start).as_secs() < pattern.duration_secs {
+        for _i in 0..pattern.repetitions {
+            debug!("Turning led on");
+            if !led_manager.set(led_id.to_string(), true).await? {
+                return Ok(());
+            }
+            sleep(Duration::from_millis(pattern.on_millis)).await;
+            debug!("Turning led off");
+            if !led_manager.set(led_id.to_string(), false).await? {
+                return Ok(());
+            }
+            sleep(Duration::from_millis(pattern.off_millis)).await;
+        }
+        sleep(Duration::from_millis(pattern.pause_millis)).await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+async fn run_blink(pattern: BlinkPattern, _led_id: &str) -> zbus::Result<()> {
+    let start = Instant::now();
+    while (Instant::now() - start).as_secs() < pattern.duration_secs / 10 {
+        for _i in 0..pattern.repetitions {
+            print!("█");
+            sleep(Duration::from_millis(pattern.on_millis)).await;
+            print!("\r \r");
+            sleep(Duration::from_millis(pattern.off_millis)).await;
+        }
+        sleep(Duration::from_millis(pattern.pause_millis)).await;
+    }
+    println!();
+
+    Ok(())
+}
+
+async fn apply_status(led_id: &str, status: LedStatus) {
+    match set_led(led_id, status == LedStatus::Solid).await {
+        Ok(true) => {}
+        Ok(false) => error!(led_id, ?status, "the led manager couldn't set the led"),
+        Err(err) => error!(led_id, ?status, error = %err, "couldn't set the led"),
+    }
+}
+
+#[derive(Debug)]
+struct Running {
+    cancel: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+pub struct LedBlink {
+    config: LedConfig,
+    /// Persistent status of the LEDs.
+    status: HashMap<String, LedStatus>,
+    state: FileStateRepository<HashMap<String, LedStatus>>,
+    running: HashMap<String, Running>,
+    cancel: CancellationToken,
+}
+
+impl LedBlink {
+    pub fn new(config: LedConfig, store_directory: &Path, cancel: CancellationToken) -> Self {
+        Self {
+            config,
+            status: HashMap::new(),
+            state: FileStateRepository::new(store_directory, STATUS_PATH),
+            running: HashMap::new(),
+            cancel,
+        }
+    }
+
+    fn status(&self, led_id: &str) -> LedStatus {
+        self.status.get(led_id).copied().unwrap_or_default()
+    }
+
+    /// Cancels the running blink and waits for it to stop.
+    ///
+    /// Returns true if a blink was running, a finished one already restored the status.
+    async fn stop(&mut self, led_id: &str) -> bool {
+        let Some(running) = self.running.remove(led_id) else {
+            return false;
+        };
+
+        let finished = running.handle.is_finished();
+
+        running.cancel.cancel();
+
+        if let Err(err) = running.handle.await {
+            error!(led_id, error = %err, "led blink task panicked");
+        }
+
+        !finished
+    }
+
+    async fn start(&mut self, led_id: String, blink: Blink) {
+        let Some(pattern) = blink.pattern(&self.config) else {
+            error!(led_id, %blink, "unrecognized LedBehavior behavior value");
+
+            return;
+        };
+
+        if let Err(err) = pattern.validate() {
+            error!(led_id, %blink, error = err, "invalid led blink pattern");
+
+            return;
+        }
+
+        self.stop(&led_id).await;
+        self.running
+            .retain(|_, running| !running.handle.is_finished());
+
+        info!(led_id, %blink, "blinking led");
+
+        let cancel = self.cancel.child_token();
+        let status = self.status(&led_id);
+        let task_led_id = led_id.clone();
+        let task_cancel = cancel.clone();
+
+        let handle = tokio::spawn(async move {
+            let res = tokio::select! {
+                _ = task_cancel.cancelled() => return,
+                res = run_blink(pattern, &task_led_id) => res,
+            };
+
+            if let Err(err) = res {
+                error!(led_id = task_led_id, error = %err, "couldn't blink the led");
+            }
+
+            apply_status(&task_led_id, status).await;
+        });
+
+        self.running.insert(led_id, Running { cancel, handle });
+    }
+
+    async fn set_status(&mut self, led_id: String, status: LedStatus) {
+        self.stop(&led_id).await;
+
+        info!(led_id, ?status, "setting led status");
+
+        apply_status(&led_id, status).await;
+
+        self.status.insert(led_id, status);
+
+        if let Err(err) = self.state.write(&self.status).await {
+            error!(
+                error = format!("{:#}", eyre::Report::new(err)),
+                "couldn't persist the led status"
+            );
+        }
+    }
+}
+
+impl Actor for LedBlink {
+    type Msg = LedEvent;
+
+    fn task() -> &'static str {
+        "led-behavior"
+    }
+
+    async fn init(&mut self) -> eyre::Result<()> {
+        if !self.state.exists().await {
+            return Ok(());
+        }
+
+        match self.state.read().await {
+            Ok(status) => self.status = status,
+            Err(err) => {
+                error!(
+                    error = format!("{:#}", eyre::Report::new(err)),
+                    "couldn't read the led status"
+                );
+
+                return Ok(());
+            }
+        }
+
+        for (led_id, status) in &self.status {
+            debug!(led_id, ?status, "restoring led status");
+
+            apply_status(led_id, *status).await;
+        }
+
+        Ok(())
+    }
+
+    async fn handle(&mut self, msg: Self::Msg) -> eyre::Result<()> {
+        match msg.behavior {
+            LedBehavior::Behavior(blink) => {
+                self.start(msg.led_id, blink).await;
+            }
+            LedBehavior::Status(status) => {
+                self.set_status(msg.led_id, status).await;
+            }
+            LedBehavior::Cancel(true) => {
+                if self.stop(&msg.led_id).await {
+                    info!(led_id = msg.led_id, "led blink cancelled");
+
+                    apply_status(&msg.led_id, self.status(&msg.led_id)).await;
+                } else {
+                    debug!(led_id = msg.led_id, "no led blink to cancel");
+                }
+            }
+            LedBehavior::Cancel(false) => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::event::RuntimeEvent;
+
+    use super::*;
+
+    use astarte_device_sdk::Value;
+    use astarte_device_sdk::chrono::Utc;
+    use tempdir::TempDir;
+
+    fn mock_led(dir: &TempDir) -> LedBlink {
+        let config = LedConfig {
+            patterns: HashMap::from([(
+                "find-me".to_string(),
+                BlinkPattern {
+                    duration_secs: 300,
+                    repetitions: 1,
+                    on_millis: 100,
+                    off_millis: 100,
+                    pause_millis: 0,
+                },
+            )]),
+        };
+
+        LedBlink::new(config, dir.path(), CancellationToken::new())
+    }
+
+    #[tokio::test]
+    async fn set_behavior_test() {
+        let dir = TempDir::new("edgehog-led").unwrap();
+        let steps = [
+            Blink::Single,
+            Blink::Double,
+            Blink::Slow,
+            Blink::Pattern("find-me".to_string()),
+        ];
+
+        let mut led = mock_led(&dir);
+
+        tokio::time::pause();
+        for step in steps {
+            led.handle(LedEvent {
+                led_id: "led_1".to_string(),
+                behavior: LedBehavior::Behavior(step),
+            })
+            .await
+            .unwrap();
+
+            assert_eq!(led.running.len(), 1);
+
+            tokio::time::advance(Duration::from_secs(2)).await;
+        }
+
+        led.handle(LedEvent {
+            led_id: "led_1".to_string(),
+            behavior: LedBehavior::Cancel(true),
+        })
+        .await
+        .unwrap();
+
+        assert!(led.running.is_empty());
+
+        tokio::time::resume();
+    }
+
+    #[tokio::test]
+    async fn should_ignore_unknown_pattern() {
+        let dir = TempDir::new("edgehog-led").unwrap();
+
+        let mut led = mock_led(&dir);
+
+        led.handle(LedEvent {
+            led_id: "led_1".to_string(),
+            behavior: LedBehavior::Behavior(Blink::Pattern("missing".to_string())),
+        })
+        .await
+        .unwrap();
+
+        assert!(led.running.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_not_stop_finished_blink() {
+        let dir = TempDir::new("edgehog-led").unwrap();
+
+        let mut led = mock_led(&dir);
+
+        let handle = tokio::spawn(async {});
+        while !handle.is_finished() {
+            tokio::task::yield_now().await;
+        }
+        led.running.insert(
+            "led_1".to_string(),
+            Running {
+                cancel: CancellationToken::new(),
+                handle,
+            },
+        );
+
+        assert!(!led.stop("led_1").await);
+
+        let cancel = CancellationToken::new();
+        let task_cancel = cancel.clone();
+        led.running.insert(
+            "led_1".to_string(),
+            Running {
+                cancel,
+                handle: tokio::spawn(async move { task_cancel.cancelled().await }),
+            },
+        );
+
+        assert!(led.stop("led_1").await);
+        assert!(led.running.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_persist_status() {
+        let dir = TempDir::new("edgehog-led").unwrap();
+
+        let mut led = mock_led(&dir);
+
+        led.handle(LedEvent {
+            led_id: "led_1".to_string(),
+            behavior: LedBehavior::Status(LedStatus::Solid),
+        })
+        .await
+        .unwrap();
+
+        let mut led = mock_led(&dir);
+
+        led.init().await.unwrap();
+
+        assert_eq!(led.status("led_1"), LedStatus::Solid);
+        assert_eq!(led.status("led_2"), LedStatus::Off);
+    }
+
+    #[test]
+    fn should_convert_blink_request() {
+        let fields = [
+            ("ledId", AstarteData::from("42")),
+            ("durationSeconds", AstarteData::Integer(300)),
+            ("repetitions", AstarteData::Integer(2)),
+            ("onMillis", AstarteData::Integer(100)),
+            ("offMillis", AstarteData::Integer(150)),
+            ("pauseMillis", AstarteData::Integer(1000)),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        let event = DeviceEvent {
+            interface: "io.edgehog.devicemanager.LedBlinkRequest".to_string(),
+            path: "/request".to_string(),
+            data: Value::Object {
+                data: fields,
+                timestamp: Utc::now(),
+            },
+        };
+
+        let res = RuntimeEvent::from_event(event).unwrap();
+
+        assert_eq!(
+            res,
+            RuntimeEvent::Led(LedEvent {
+                led_id: "42".into(),
+                behavior: LedBehavior::Behavior(Blink::Custom(BlinkPattern {
+                    duration_secs: 300,
+                    repetitions: 2,
+                    on_millis: 100,
+                    off_millis: 150,
+                    pause_millis: 1000,
+                }))
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_blink_request() {
+        let request = LedBlinkRequest {
+            led_id: "42".to_string(),
+            duration_seconds: 60,
+            repetitions: 1,
+            on_millis: -100,
+            off_millis: 100,
+            pause_millis: 0,
+        };
+
+        assert!(LedEvent::try_from(request.clone()).is_err());
+
+        let request = LedBlinkRequest {
+            on_millis: 0,
+            off_millis: 0,
+            ..request
+        };
+
+        assert!(LedEvent::try_from(request).is_err());
+    }
+
+    #[test]
+    fn should_convert_status_and_cancel() {
+        let cases = [
+            (
+                "/42/status",
+                AstarteData::from("Solid"),
+                LedBehavior::Status(LedStatus::Solid),
+            ),
+            (
+                "/42/status",
+                AstarteData::from("Off"),
+                LedBehavior::Status(LedStatus::Off),
+            ),
+            (
+                "/42/cancel",
+                AstarteData::Boolean(true),
+                LedBehavior::Cancel(true),
+            ),
+            (
+                "/42/behavior",
+                AstarteData::from("find-me"),
+                LedBehavior::Behavior(Blink::Pattern("find-me".to_string())),
+            ),
+        ];
+
+        for (path, data, exp) in cases {
+            let event = DeviceEvent {
+                interface: "io.edgehog.devicemanager.LedBehavior".to_string(),
+                path: path.to_string(),
+                data: Value::Individual {
+                    data,
+                    timestamp: Utc::now(),
+                },
+            };
+
+            let res = RuntimeEvent::from_event(event).unwrap();
+
+            assert_eq!(
+                res,
+                RuntimeEvent::Led(LedEvent {
+                    led_id: "42".into(),
+                    behavior: exp
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn should_convert_led_from_event() {
+        let event = DeviceEvent {
+            interface: "io.edgehog.devicemanager.LedBehavior".to_string(),
+            path: "/42/behavior".to_string(),
+            data: Value::Individual {
+                data: "Blink60Seconds".into(),
+                timestamp: Utc::now(),
+            },
+        };
+
+        let res = RuntimeEvent::from_event(event).unwrap();
+
+        assert_eq!(
+            res,
+            RuntimeEvent::Led(LedEvent {
+                led_id: "42".into(),
+                behavior: LedBehavior::Behavior(Blink::Single)
+            })
+        );
+
+        let event = DeviceEvent {
+            interface: "io.edgehog.devicemanager.LedBehavior".to_string(),
+            path: "/42/behavior".to_string(),
+            data: Value::Individual {
+                data: "DoubleBlink60Seconds".into(),
+                timestamp: Utc::now(),
+            },
+        };
+
+        let res = RuntimeEvent::from_event(event).unwrap();
+
+        assert_eq!(
+            res,
+            RuntimeEvent::Led(LedEvent {
+                led_id: "42".into(),
+                behavior: LedBehavior::Behavior(Blink::Double)
+            })
+        );
+
+        let event = DeviceEvent {
+            interface: "io.edgehog.devicemanager.LedBehavior".to_string(),
+            path: "/42/behavior".to_string(),
+            data: Value::Individual {
+                data: "SlowBlink60Seconds".into(),
+                timestamp: Utc::now(),
+            },
+        };
+
+        let res = RuntimeEvent::from_event(event).unwrap();
+
+        assert_eq!(
+            res,
+            RuntimeEvent::Led(LedEvent {
+                led_id: "42".into(),
+                behavior: LedBehavior::Behavior(Blink::Slow)
+            })
+        );
+    }
+}
@@ -25,6 +25,9 @@ use self::data::astarte_device_sdk_lib::AstarteDeviceSdkConfigOptions;
 use self::telemetry::TelemetryInterfaceConfig;
 pub use astarte_device_sdk::Client;
 
+pub mod attributes;
+#[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+pub mod bandwidth;
 mod commands;
 #[cfg(feature = "containers")]
 pub mod containers;
@@ -38,15 +41,19 @@ pub mod file_transfer;
 #[cfg(feature = "forwarder")]
 mod forwarder;
 pub(crate) mod http;
-#[cfg(feature = "file-transfer")]
+#[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
 pub mod io;
 #[cfg(feature = "file-transfer")]
 pub(crate) mod jobs;
 #[cfg(all(feature = "zbus", target_os = "linux"))]
-mod led_behavior;
+pub mod led_behavior;
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub mod network;
 #[cfg(all(feature = "zbus", target_os = "linux"))]
 pub mod ota;
 mod power_management;
+pub mod power_policy;
+pub(crate) mod rate_limit;
 pub mod repository;
 #[cfg(feature = "file-transfer")]
 pub mod storage;
@@ -77,10 +84,25 @@ pub struct DeviceManagerOptions {
     pub file_transfer: self::file_transfer::config::FileTransferArgs,
     #[cfg(all(feature = "zbus", target_os = "linux"))]
     pub ota: self::ota::config::OtaConfig,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub network: self::network::config::NetworkConfig,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub cellular: self::telemetry::cellular::CellularConfig,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub time_sync: self::telemetry::time_sync::TimeSyncConfig,
+    pub gnss: self::telemetry::gnss::GnssConfig,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub power_policy: self::power_policy::config::PowerPolicyConfig,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub led: self::led_behavior::config::LedConfig,
+    pub attributes: self::attributes::config::AttributesConfig,
+    #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+    pub bandwidth: self::bandwidth::config::BandwidthConfig,
     pub interfaces_directory: PathBuf,
     pub store_directory: PathBuf,
     pub download_directory: PathBuf,
     pub telemetry_config: Option<Vec<TelemetryInterfaceConfig<'static>>>,
+    pub telemetry_schedule: self::telemetry::schedule::ScheduleConfig,
 }
 
 #[cfg(test)]
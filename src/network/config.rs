@@ -0,0 +1,128 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuration for the network provisioning.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for the remote network provisioning through NetworkManager.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Flag to enable the network provisioning.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds to wait for the confirmation of a change before rolling it back.
+    ///
+    /// A value of 0 disables the rollback.
+    #[serde(default = "NetworkConfig::default_rollback_timeout")]
+    pub rollback_timeout: u64,
+    /// Confirms the change automatically once Astarte can be reached.
+    ///
+    /// The change can still be confirmed with a request before Astarte is reached.
+    #[serde(default = "NetworkConfig::default_auto_confirm")]
+    pub auto_confirm: bool,
+    /// Seconds between the checks of the connection to Astarte.
+    #[serde(default = "NetworkConfig::default_confirm_interval")]
+    pub confirm_interval: u64,
+}
+
+impl NetworkConfig {
+    const fn default_rollback_timeout() -> u64 {
+        120
+    }
+
+    const fn default_auto_confirm() -> bool {
+        true
+    }
+
+    const fn default_confirm_interval() -> u64 {
+        10
+    }
+
+    /// Returns the interval between the checks of the connection to Astarte.
+    pub(crate) fn confirm_interval(&self) -> Duration {
+        Duration::from_secs(self.confirm_interval.max(1))
+    }
+
+    /// Returns the rollback timeout, if enabled.
+    pub(crate) fn rollback_timeout(&self) -> Option<Duration> {
+        (self.rollback_timeout > 0).then(|| Duration::from_secs(self.rollback_timeout))
+    }
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rollback_timeout: Self::default_rollback_timeout(),
+            auto_confirm: Self::default_auto_confirm(),
+            confirm_interval: Self::default_confirm_interval(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_deserialize() {
+        let string = r#"
+        enabled = true
+        rollback_timeout = 30
+        auto_confirm = false
+        confirm_interval = 5
+        "#;
+
+        let config: NetworkConfig = toml::from_str(string).unwrap();
+
+        let exp = NetworkConfig {
+            enabled: true,
+            rollback_timeout: 30,
+            auto_confirm: false,
+            confirm_interval: 5,
+        };
+
+        assert_eq!(config, exp);
+        assert_eq!(config.rollback_timeout(), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn should_deserialize_default() {
+        let config: NetworkConfig = toml::from_str("").unwrap();
+
+        assert_eq!(config, NetworkConfig::default());
+        assert_eq!(config.rollback_timeout(), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn rollback_disabled() {
+        let config = NetworkConfig {
+            enabled: true,
+            rollback_timeout: 0,
+            ..Default::default()
+        };
+
+        assert_eq!(config.rollback_timeout(), None);
+    }
+}
@@ -0,0 +1,327 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Communication with NetworkManager over D-Bus.
+
+use std::collections::HashMap;
+
+use tracing::{debug, instrument, warn};
+use zbus::proxy;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
+
+#[cfg(test)]
+use mockall::automock;
+
+use crate::error::DeviceManagerError;
+
+/// Connection settings, as a map of setting names to their properties.
+pub type Settings = HashMap<String, HashMap<String, OwnedValue>>;
+
+/// Settings that could contain secrets, which are not returned by `GetSettings`.
+const SECRET_SETTINGS: &[&str] = &["802-11-wireless-security", "802-1x", "gsm"];
+
+/// Roll back the new connections created after the checkpoint.
+const CHECKPOINT_DELETE_NEW_CONNECTIONS: u32 = 0x02;
+/// Disconnect the devices activated after the checkpoint.
+const CHECKPOINT_DISCONNECT_NEW_DEVICES: u32 = 0x04;
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager"
+)]
+trait NetworkManager {
+    /// Activate a connection using the supplied device.
+    fn activate_connection(
+        &self,
+        connection: &ObjectPath<'_>,
+        device: &ObjectPath<'_>,
+        specific_object: &ObjectPath<'_>,
+    ) -> zbus::Result<OwnedObjectPath>;
+
+    /// Create a checkpoint of the current networking configuration for the given devices.
+    fn checkpoint_create(
+        &self,
+        devices: &[ObjectPath<'_>],
+        rollback_timeout: u32,
+        flags: u32,
+    ) -> zbus::Result<OwnedObjectPath>;
+
+    /// Destroy a previously created checkpoint.
+    fn checkpoint_destroy(&self, checkpoint: &ObjectPath<'_>) -> zbus::Result<()>;
+
+    /// Rollback a checkpoint before the timeout is reached.
+    fn checkpoint_rollback(
+        &self,
+        checkpoint: &ObjectPath<'_>,
+    ) -> zbus::Result<HashMap<String, u32>>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.Settings",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager/Settings"
+)]
+trait NmSettings {
+    /// List the saved network connections known to NetworkManager.
+    fn list_connections(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+
+    /// Add new connection and save it to disk.
+    fn add_connection(&self, connection: Settings) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.Settings.Connection",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait NmConnection {
+    /// Get the settings maps describing this network configuration, without the secrets.
+    fn get_settings(&self) -> zbus::Result<Settings>;
+
+    /// Get the secrets belonging to this network configuration.
+    fn get_secrets(&self, setting_name: &str) -> zbus::Result<Settings>;
+
+    /// Update the connection with new settings and properties, replacing all previous settings.
+    fn update(&self, properties: Settings) -> zbus::Result<()>;
+
+    /// Delete the connection.
+    fn delete(&self) -> zbus::Result<()>;
+}
+
+/// Operations on the network configuration of the device.
+#[cfg_attr(test, automock)]
+pub trait NetworkBackend: Send + Sync {
+    /// Returns the path of the connection with the given id.
+    fn find_connection(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<Option<OwnedObjectPath>, DeviceManagerError>> + Send;
+    /// Returns the settings of a connection, including the secrets.
+    fn get_settings(
+        &self,
+        connection: &OwnedObjectPath,
+    ) -> impl Future<Output = Result<Settings, DeviceManagerError>> + Send;
+    fn add_connection(
+        &self,
+        settings: Settings,
+    ) -> impl Future<Output = Result<OwnedObjectPath, DeviceManagerError>> + Send;
+    fn update_connection(
+        &self,
+        connection: &OwnedObjectPath,
+        settings: Settings,
+    ) -> impl Future<Output = Result<(), DeviceManagerError>> + Send;
+    fn delete_connection(
+        &self,
+        connection: &OwnedObjectPath,
+    ) -> impl Future<Output = Result<(), DeviceManagerError>> + Send;
+    fn activate_connection(
+        &self,
+        connection: &OwnedObjectPath,
+    ) -> impl Future<Output = Result<(), DeviceManagerError>> + Send;
+    /// Creates a checkpoint that will be rolled back automatically after the timeout in seconds.
+    fn checkpoint_create(
+        &self,
+        rollback_timeout: u32,
+    ) -> impl Future<Output = Result<OwnedObjectPath, DeviceManagerError>> + Send;
+    fn checkpoint_destroy(
+        &self,
+        checkpoint: &OwnedObjectPath,
+    ) -> impl Future<Output = Result<(), DeviceManagerError>> + Send;
+    fn checkpoint_rollback(
+        &self,
+        checkpoint: &OwnedObjectPath,
+    ) -> impl Future<Output = Result<(), DeviceManagerError>> + Send;
+}
+
+/// NetworkManager connected on the system bus.
+#[derive(Debug, Clone)]
+pub struct NetworkManager {
+    connection: zbus::Connection,
+    manager: NetworkManagerProxy<'static>,
+    settings: NmSettingsProxy<'static>,
+}
+
+impl NetworkManager {
+    #[instrument]
+    pub async fn connect() -> Result<Self, DeviceManagerError> {
+        let connection = zbus::Connection::system().await?;
+
+        let manager = NetworkManagerProxy::new(&connection).await?;
+        let settings = NmSettingsProxy::new(&connection).await?;
+
+        debug!("connected to NetworkManager");
+
+        Ok(Self {
+            connection,
+            manager,
+            settings,
+        })
+    }
+
+    async fn connection_proxy(
+        &self,
+        path: &OwnedObjectPath,
+    ) -> Result<NmConnectionProxy<'static>, DeviceManagerError> {
+        let proxy = NmConnectionProxy::builder(&self.connection)
+            .path(path.clone())?
+            .build()
+            .await?;
+
+        Ok(proxy)
+    }
+}
+
+impl NetworkBackend for NetworkManager {
+    async fn find_connection(
+        &self,
+        id: &str,
+    ) -> Result<Option<OwnedObjectPath>, DeviceManagerError> {
+        for path in self.settings.list_connections().await? {
+            let settings = self.connection_proxy(&path).await?.get_settings().await?;
+
+            let connection_id = settings
+                .get("connection")
+                .and_then(|connection| connection.get("id"))
+                .and_then(|value| value.downcast_ref::<&str>().ok());
+
+            if connection_id == Some(id) {
+                return Ok(Some(path));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn get_settings(
+        &self,
+        connection: &OwnedObjectPath,
+    ) -> Result<Settings, DeviceManagerError> {
+        let proxy = self.connection_proxy(connection).await?;
+
+        let mut settings = proxy.get_settings().await?;
+
+        for name in SECRET_SETTINGS {
+            if !settings.contains_key(*name) {
+                continue;
+            }
+
+            let secrets = match proxy.get_secrets(name).await {
+                Ok(secrets) => secrets,
+                Err(err) => {
+                    warn!(
+                        setting = name,
+                        error = format!("{:#}", eyre::Report::new(err)),
+                        "couldn't get the connection secrets"
+                    );
+
+                    continue;
+                }
+            };
+
+            for (name, values) in secrets {
+                settings.entry(name).or_default().extend(values);
+            }
+        }
+
+        Ok(settings)
+    }
+
+    async fn add_connection(
+        &self,
+        settings: Settings,
+    ) -> Result<OwnedObjectPath, DeviceManagerError> {
+        self.settings
+            .add_connection(settings)
+            .await
+            .map_err(DeviceManagerError::Zbus)
+    }
+
+    async fn update_connection(
+        &self,
+        connection: &OwnedObjectPath,
+        settings: Settings,
+    ) -> Result<(), DeviceManagerError> {
+        self.connection_proxy(connection)
+            .await?
+            .update(settings)
+            .await
+            .map_err(DeviceManagerError::Zbus)
+    }
+
+    async fn delete_connection(
+        &self,
+        connection: &OwnedObjectPath,
+    ) -> Result<(), DeviceManagerError> {
+        self.connection_proxy(connection)
+            .await?
+            .delete()
+            .await
+            .map_err(DeviceManagerError::Zbus)
+    }
+
+    async fn activate_connection(
+        &self,
+        connection: &OwnedObjectPath,
+    ) -> Result<(), DeviceManagerError> {
+        let any = ObjectPath::from_static_str_unchecked("/");
+
+        let active = self
+            .manager
+            .activate_connection(connection, &any, &any)
+            .await?;
+
+        debug!(%active, "connection activated");
+
+        Ok(())
+    }
+
+    async fn checkpoint_create(
+        &self,
+        rollback_timeout: u32,
+    ) -> Result<OwnedObjectPath, DeviceManagerError> {
+        self.manager
+            .checkpoint_create(
+                &[],
+                rollback_timeout,
+                CHECKPOINT_DELETE_NEW_CONNECTIONS | CHECKPOINT_DISCONNECT_NEW_DEVICES,
+            )
+            .await
+            .map_err(DeviceManagerError::Zbus)
+    }
+
+    async fn checkpoint_destroy(
+        &self,
+        checkpoint: &OwnedObjectPath,
+    ) -> Result<(), DeviceManagerError> {
+        self.manager
+            .checkpoint_destroy(checkpoint)
+            .await
+            .map_err(DeviceManagerError::Zbus)
+    }
+
+    async fn checkpoint_rollback(
+        &self,
+        checkpoint: &OwnedObjectPath,
+    ) -> Result<(), DeviceManagerError> {
+        let devices = self.manager.checkpoint_rollback(checkpoint).await?;
+
+        debug!(?devices, "checkpoint rolled back");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,854 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Remote provisioning of the network connections through NetworkManager.
+//!
+//! Every change is applied after creating a NetworkManager checkpoint. The change is confirmed
+//! once the device can still reach Astarte, or with a new request, otherwise it's rolled back after
+//! the configured timeout.
+
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::Duration;
+
+use astarte_device_sdk::chrono::Utc;
+use astarte_device_sdk::{AstarteData, IntoAstarteObject};
+use eyre::{OptionExt, WrapErr};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, instrument, warn};
+use url::Url;
+use zbus::zvariant::OwnedObjectPath;
+
+use crate::Client;
+use crate::controller::actor::Actor;
+use crate::data::send_object_with_timestamp;
+use crate::http::astarte_health;
+
+use self::config::NetworkConfig;
+use self::manager::NetworkBackend;
+use self::request::{ConnectionOperation, ConnectionRequest};
+
+pub mod config;
+pub(crate) mod manager;
+pub mod request;
+
+const EVENT_INTERFACE: &str = "io.edgehog.devicemanager.network.ConnectionEvent";
+
+/// Extra time given to NetworkManager before rolling back the checkpoint by itself.
+///
+/// This is used to restore the configuration even if the runtime is stopped.
+const CHECKPOINT_GRACE: Duration = Duration::from_secs(30);
+
+/// Status of a connection request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConnectionStatus {
+    /// The change was applied without a rollback.
+    Applied,
+    /// The change was applied and waits for a confirmation.
+    PendingConfirmation,
+    /// The pending change was confirmed.
+    Confirmed,
+    /// The pending change wasn't confirmed in time and was rolled back.
+    RolledBack,
+    /// The request failed.
+    Failure,
+}
+
+impl Display for ConnectionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionStatus::Applied => write!(f, "Applied"),
+            ConnectionStatus::PendingConfirmation => write!(f, "PendingConfirmation"),
+            ConnectionStatus::Confirmed => write!(f, "Confirmed"),
+            ConnectionStatus::RolledBack => write!(f, "RolledBack"),
+            ConnectionStatus::Failure => write!(f, "Failure"),
+        }
+    }
+}
+
+impl From<ConnectionStatus> for AstarteData {
+    fn from(value: ConnectionStatus) -> Self {
+        AstarteData::String(value.to_string())
+    }
+}
+
+/// Event published for each connection request.
+#[derive(Debug, Clone, PartialEq, IntoAstarteObject)]
+#[astarte_object(rename_all = "camelCase")]
+pub(crate) struct ConnectionEvent {
+    request_id: String,
+    connection_id: String,
+    status: ConnectionStatus,
+    message: String,
+}
+
+impl ConnectionEvent {
+    fn new(request: &ConnectionRequest, status: ConnectionStatus, message: String) -> Self {
+        Self {
+            request_id: request.id.clone(),
+            connection_id: request.connection_id.clone(),
+            status,
+            message,
+        }
+    }
+
+    async fn send<C>(self, client: &mut C)
+    where
+        C: Client,
+    {
+        debug!(status = %self.status, "publishing connection event");
+
+        send_object_with_timestamp(client, EVENT_INTERFACE, "/event", self, Utc::now()).await;
+    }
+}
+
+/// How the wait for the confirmation of a change ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeOutcome {
+    /// Stopped by the confirmation request.
+    Cancelled,
+    /// Confirmed once Astarte was reached.
+    Confirmed,
+    /// Rolled back after the timeout.
+    RolledBack,
+}
+
+/// Change waiting for the confirmation.
+#[derive(Debug)]
+struct PendingChange {
+    request: ConnectionRequest,
+    checkpoint: OwnedObjectPath,
+    cancel: CancellationToken,
+    confirmation: JoinHandle<ChangeOutcome>,
+}
+
+/// Handles the network connection requests.
+#[derive(Debug)]
+pub struct NetworkProvisioning<C, N> {
+    client: C,
+    backend: Arc<N>,
+    config: NetworkConfig,
+    /// Used to check the connection to Astarte after a change
+    pairing_url: Option<Url>,
+    pending: Option<PendingChange>,
+}
+
+impl<C, N> NetworkProvisioning<C, N>
+where
+    C: Client + Clone + Send + Sync + 'static,
+    N: NetworkBackend + 'static,
+{
+    pub fn new(client: C, backend: N, config: NetworkConfig, pairing_url: Option<Url>) -> Self {
+        Self {
+            client,
+            backend: Arc::new(backend),
+            config,
+            pairing_url,
+            pending: None,
+        }
+    }
+
+    /// Removes the pending change if it was already confirmed or rolled back.
+    fn take_finished(&mut self) {
+        if self
+            .pending
+            .as_ref()
+            .is_some_and(|pending| pending.confirmation.is_finished())
+        {
+            debug!("removing finished change");
+
+            self.pending = None;
+        }
+    }
+
+    async fn apply(&self, request: &ConnectionRequest) -> eyre::Result<()> {
+        match request.operation {
+            ConnectionOperation::Create => {
+                let settings = request.create_settings()?;
+
+                let path = self
+                    .backend
+                    .add_connection(settings)
+                    .await
+                    .wrap_err("couldn't add the connection")?;
+
+                info!(%path, "connection created");
+            }
+            ConnectionOperation::Modify => {
+                let path = self.find(&request.connection_id).await?;
+
+                let current = self
+                    .backend
+                    .get_settings(&path)
+                    .await
+                    .wrap_err("couldn't get the connection settings")?;
+
+                let settings = request.merge_settings(current)?;
+
+                self.backend
+                    .update_connection(&path, settings)
+                    .await
+                    .wrap_err("couldn't update the connection")?;
+
+                info!(%path, "connection modified");
+            }
+            ConnectionOperation::Activate => {
+                let path = self.find(&request.connection_id).await?;
+
+                self.backend
+                    .activate_connection(&path)
+                    .await
+                    .wrap_err("couldn't activate the connection")?;
+
+                info!(%path, "connection activated");
+            }
+            ConnectionOperation::Delete => {
+                let path = self.find(&request.connection_id).await?;
+
+                self.backend
+                    .delete_connection(&path)
+                    .await
+                    .wrap_err("couldn't delete the connection")?;
+
+                info!(%path, "connection deleted");
+            }
+            ConnectionOperation::Confirm => unreachable!("confirm is handled separately"),
+        }
+
+        Ok(())
+    }
+
+    async fn find(&self, connection_id: &str) -> eyre::Result<OwnedObjectPath> {
+        self.backend
+            .find_connection(connection_id)
+            .await
+            .wrap_err("couldn't list the connections")?
+            .ok_or_eyre(format!("connection {connection_id} not found"))
+    }
+
+    #[instrument(skip_all, fields(id = request.id, connection_id = request.connection_id))]
+    async fn change(&mut self, request: ConnectionRequest) {
+        self.take_finished();
+
+        if let Some(pending) = &self.pending {
+            let message = format!(
+                "change {} is waiting for the confirmation",
+                pending.request.id
+            );
+
+            warn!("{message}");
+
+            ConnectionEvent::new(&request, ConnectionStatus::Failure, message)
+                .send(&mut self.client)
+                .await;
+
+            return;
+        }
+
+        let Some(timeout) = self.config.rollback_timeout() else {
+            let event = match self.apply(&request).await {
+                Ok(()) => ConnectionEvent::new(&request, ConnectionStatus::Applied, String::new()),
+                Err(err) => {
+                    error!(error = format!("{err:#}"), "couldn't apply the change");
+
+                    ConnectionEvent::new(&request, ConnectionStatus::Failure, format!("{err:#}"))
+                }
+            };
+
+            event.send(&mut self.client).await;
+
+            return;
+        };
+
+        let nm_timeout = u32::try_from((timeout + CHECKPOINT_GRACE).as_secs()).unwrap_or(u32::MAX);
+
+        let checkpoint = match self.backend.checkpoint_create(nm_timeout).await {
+            Ok(checkpoint) => checkpoint,
+            Err(err) => {
+                let err = eyre::Report::new(err).wrap_err("couldn't create the checkpoint");
+
+                error!(error = format!("{err:#}"), "couldn't apply the change");
+
+                ConnectionEvent::new(&request, ConnectionStatus::Failure, format!("{err:#}"))
+                    .send(&mut self.client)
+                    .await;
+
+                return;
+            }
+        };
+
+        debug!(%checkpoint, "checkpoint created");
+
+        if let Err(err) = self.apply(&request).await {
+            error!(error = format!("{err:#}"), "couldn't apply the change");
+
+            if let Err(err) = self.backend.checkpoint_rollback(&checkpoint).await {
+                error!(
+                    error = format!("{:#}", eyre::Report::new(err)),
+                    "couldn't rollback the checkpoint"
+                );
+            }
+
+            ConnectionEvent::new(&request, ConnectionStatus::Failure, format!("{err:#}"))
+                .send(&mut self.client)
+                .await;
+
+            return;
+        }
+
+        ConnectionEvent::new(
+            &request,
+            ConnectionStatus::PendingConfirmation,
+            format!("the change will be rolled back in {}s", timeout.as_secs()),
+        )
+        .send(&mut self.client)
+        .await;
+
+        let probe = self
+            .pairing_url
+            .clone()
+            .filter(|_| self.config.auto_confirm)
+            .map(|url| (url, self.config.confirm_interval()));
+
+        let cancel = CancellationToken::new();
+        let confirmation = tokio::spawn(Self::wait_confirmation(
+            self.client.clone(),
+            Arc::clone(&self.backend),
+            request.clone(),
+            checkpoint.clone(),
+            timeout,
+            probe,
+            cancel.clone(),
+        ));
+
+        self.pending = Some(PendingChange {
+            request,
+            checkpoint,
+            cancel,
+            confirmation,
+        });
+    }
+
+    /// Waits for Astarte to be reachable to confirm the change, or rolls back the checkpoint
+    /// after the timeout.
+    ///
+    /// Without the probe the change is only confirmed by a request, which cancels the wait.
+    async fn wait_confirmation(
+        mut client: C,
+        backend: Arc<N>,
+        request: ConnectionRequest,
+        checkpoint: OwnedObjectPath,
+        timeout: Duration,
+        probe: Option<(Url, Duration)>,
+        cancel: CancellationToken,
+    ) -> ChangeOutcome {
+        let reached = async {
+            let Some((url, interval)) = probe else {
+                return std::future::pending().await;
+            };
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                match astarte_health(&url).await {
+                    Ok(()) => break,
+                    Err(err) => debug!(error = format!("{err:#}"), "astarte not reachable yet"),
+                }
+            }
+        };
+
+        tokio::select! {
+            _ = cancel.cancelled() => return ChangeOutcome::Cancelled,
+            () = reached => {
+                info!(id = request.id, %checkpoint, "astarte reached, confirming the change");
+
+                if let Err(err) = backend.checkpoint_destroy(&checkpoint).await {
+                    error!(
+                        error = format!("{:#}", eyre::Report::new(err)),
+                        "couldn't destroy the checkpoint"
+                    );
+                }
+
+                ConnectionEvent::new(
+                    &request,
+                    ConnectionStatus::Confirmed,
+                    "the change was confirmed once astarte was reached".to_string(),
+                )
+                .send(&mut client)
+                .await;
+
+                return ChangeOutcome::Confirmed;
+            }
+            _ = tokio::time::sleep(timeout) => {}
+        }
+
+        warn!(id = request.id, %checkpoint, "change not confirmed, rolling back");
+
+        let message = match backend.checkpoint_rollback(&checkpoint).await {
+            Ok(()) => "the change was not confirmed in time".to_string(),
+            Err(err) => {
+                // NetworkManager will rollback it by itself after the grace period
+                error!(
+                    error = format!("{:#}", eyre::Report::new(err)),
+                    "couldn't rollback the checkpoint"
+                );
+
+                "the change was not confirmed in time, rollback delegated to NetworkManager"
+                    .to_string()
+            }
+        };
+
+        ConnectionEvent::new(&request, ConnectionStatus::RolledBack, message)
+            .send(&mut client)
+            .await;
+
+        ChangeOutcome::RolledBack
+    }
+
+    #[instrument(skip_all, fields(id = request.id, connection_id = request.connection_id))]
+    async fn confirm(&mut self, request: ConnectionRequest) {
+        let Some(pending) = self.pending.take() else {
+            ConnectionEvent::new(
+                &request,
+                ConnectionStatus::Failure,
+                "no change is waiting for the confirmation".to_string(),
+            )
+            .send(&mut self.client)
+            .await;
+
+            return;
+        };
+
+        pending.cancel.cancel();
+
+        let outcome = pending.confirmation.await.unwrap_or_else(|err| {
+            error!(
+                error = format!("{:#}", eyre::Report::new(err)),
+                "confirmation task failed"
+            );
+
+            ChangeOutcome::RolledBack
+        });
+
+        match outcome {
+            ChangeOutcome::Cancelled => {
+                if let Err(err) = self.backend.checkpoint_destroy(&pending.checkpoint).await {
+                    error!(
+                        error = format!("{:#}", eyre::Report::new(err)),
+                        "couldn't destroy the checkpoint"
+                    );
+                }
+
+                info!(change = pending.request.id, "change confirmed");
+
+                ConnectionEvent::new(&request, ConnectionStatus::Confirmed, String::new())
+                    .send(&mut self.client)
+                    .await;
+            }
+            ChangeOutcome::Confirmed => {
+                ConnectionEvent::new(
+                    &request,
+                    ConnectionStatus::Confirmed,
+                    "the change was already confirmed".to_string(),
+                )
+                .send(&mut self.client)
+                .await;
+            }
+            ChangeOutcome::RolledBack => {
+                ConnectionEvent::new(
+                    &request,
+                    ConnectionStatus::Failure,
+                    "the change was already rolled back".to_string(),
+                )
+                .send(&mut self.client)
+                .await;
+            }
+        }
+    }
+}
+
+impl<C, N> Actor for NetworkProvisioning<C, N>
+where
+    C: Client + Clone + Send + Sync + 'static,
+    N: NetworkBackend + 'static,
+{
+    type Msg = ConnectionRequest;
+
+    fn task() -> &'static str {
+        "network"
+    }
+
+    async fn init(&mut self) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    async fn handle(&mut self, msg: Self::Msg) -> eyre::Result<()> {
+        match msg.operation {
+            ConnectionOperation::Confirm => self.confirm(msg).await,
+            ConnectionOperation::Create
+            | ConnectionOperation::Modify
+            | ConnectionOperation::Activate
+            | ConnectionOperation::Delete => self.change(msg).await,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astarte_device_sdk::aggregate::AstarteObject;
+    use astarte_device_sdk::store::SqliteStore;
+    use astarte_device_sdk::transport::mqtt::Mqtt;
+    use astarte_device_sdk_mock::MockDeviceClient;
+    use futures::FutureExt;
+    use mockall::{Sequence, predicate};
+    use zbus::zvariant::ObjectPath;
+
+    use super::manager::MockNetworkBackend;
+    use super::request::tests::connection_request;
+    use super::*;
+
+    fn expect_event(
+        client: &mut MockDeviceClient<Mqtt<SqliteStore>>,
+        seq: &mut Sequence,
+        status: ConnectionStatus,
+    ) {
+        client
+            .expect_send_object_with_timestamp()
+            .once()
+            .in_sequence(seq)
+            .withf(move |interface, path, data, _| {
+                interface == EVENT_INTERFACE
+                    && path == "/event"
+                    && data.get("status") == Some(&AstarteData::from(status))
+            })
+            .returning(|_, _, _, _| Ok(()));
+    }
+
+    fn path(path: &'static str) -> OwnedObjectPath {
+        ObjectPath::from_static_str_unchecked(path).into()
+    }
+
+    fn config(rollback_timeout: u64) -> NetworkConfig {
+        NetworkConfig {
+            enabled: true,
+            rollback_timeout,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn event_into_object() {
+        let request = connection_request(ConnectionOperation::Create);
+
+        let event = ConnectionEvent::new(
+            &request,
+            ConnectionStatus::PendingConfirmation,
+            "message".to_string(),
+        );
+
+        let exp = AstarteObject::from_iter([
+            (
+                "requestId".to_string(),
+                AstarteData::from(request.id.clone()),
+            ),
+            ("connectionId".to_string(), AstarteData::from("office")),
+            (
+                "status".to_string(),
+                AstarteData::from("PendingConfirmation"),
+            ),
+            ("message".to_string(), AstarteData::from("message")),
+        ]);
+
+        assert_eq!(AstarteObject::try_from(event).unwrap(), exp);
+    }
+
+    #[tokio::test]
+    async fn create_without_rollback() {
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut backend = MockNetworkBackend::new();
+        let mut seq = Sequence::new();
+
+        backend
+            .expect_add_connection()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(path("/conn/1"))).boxed());
+
+        expect_event(&mut client, &mut seq, ConnectionStatus::Applied);
+
+        let mut network = NetworkProvisioning::new(client, backend, config(0), None);
+
+        network
+            .handle(connection_request(ConnectionOperation::Create))
+            .await
+            .unwrap();
+
+        assert!(network.pending.is_none());
+    }
+
+    #[tokio::test]
+    async fn confirm_pending_change() {
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut backend = MockNetworkBackend::new();
+        let mut seq = Sequence::new();
+
+        backend
+            .expect_checkpoint_create()
+            .once()
+            .in_sequence(&mut seq)
+            .with(predicate::eq(150))
+            .returning(|_| futures::future::ready(Ok(path("/checkpoint/1"))).boxed());
+        backend
+            .expect_find_connection()
+            .once()
+            .in_sequence(&mut seq)
+            .with(predicate::eq("office"))
+            .returning(|_| futures::future::ready(Ok(Some(path("/conn/1")))).boxed());
+        backend
+            .expect_activate_connection()
+            .once()
+            .in_sequence(&mut seq)
+            .with(predicate::eq(path("/conn/1")))
+            .returning(|_| futures::future::ready(Ok(())).boxed());
+
+        expect_event(&mut client, &mut seq, ConnectionStatus::PendingConfirmation);
+
+        client
+            .expect_clone()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(MockDeviceClient::<Mqtt<SqliteStore>>::new);
+
+        backend
+            .expect_checkpoint_destroy()
+            .once()
+            .in_sequence(&mut seq)
+            .with(predicate::eq(path("/checkpoint/1")))
+            .returning(|_| futures::future::ready(Ok(())).boxed());
+
+        expect_event(&mut client, &mut seq, ConnectionStatus::Confirmed);
+
+        let mut network = NetworkProvisioning::new(client, backend, config(120), None);
+
+        network
+            .handle(connection_request(ConnectionOperation::Activate))
+            .await
+            .unwrap();
+
+        assert!(network.pending.is_some());
+
+        network
+            .handle(connection_request(ConnectionOperation::Confirm))
+            .await
+            .unwrap();
+
+        assert!(network.pending.is_none());
+    }
+
+    #[tokio::test]
+    async fn confirm_once_astarte_reached() {
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut backend = MockNetworkBackend::new();
+        let mut seq = Sequence::new();
+
+        let server = httpmock::MockServer::start_async().await;
+        let health = server
+            .mock_async(|when, then| {
+                when.method(httpmock::Method::GET).path("/pairing/health");
+                then.status(200);
+            })
+            .await;
+
+        backend
+            .expect_checkpoint_create()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(path("/checkpoint/1"))).boxed());
+        backend
+            .expect_find_connection()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(Some(path("/conn/1")))).boxed());
+        backend
+            .expect_activate_connection()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(())).boxed());
+
+        expect_event(&mut client, &mut seq, ConnectionStatus::PendingConfirmation);
+
+        client
+            .expect_clone()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|| {
+                let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+
+                expect_event(
+                    &mut client,
+                    &mut Sequence::new(),
+                    ConnectionStatus::Confirmed,
+                );
+
+                client
+            });
+
+        backend
+            .expect_checkpoint_destroy()
+            .once()
+            .in_sequence(&mut seq)
+            .with(predicate::eq(path("/checkpoint/1")))
+            .returning(|_| futures::future::ready(Ok(())).boxed());
+
+        // The manual confirmation is still accepted
+        expect_event(&mut client, &mut seq, ConnectionStatus::Confirmed);
+
+        let config = NetworkConfig {
+            confirm_interval: 1,
+            ..config(120)
+        };
+        let pairing_url = Url::parse(&server.url("/pairing")).unwrap();
+        let mut network = NetworkProvisioning::new(client, backend, config, Some(pairing_url));
+
+        network
+            .handle(connection_request(ConnectionOperation::Activate))
+            .await
+            .unwrap();
+
+        let confirmation = &network.pending.as_ref().unwrap().confirmation;
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !confirmation.is_finished() {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        health.assert_async().await;
+
+        network
+            .handle(connection_request(ConnectionOperation::Confirm))
+            .await
+            .unwrap();
+
+        assert!(network.pending.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rollback_unconfirmed_change() {
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut backend = MockNetworkBackend::new();
+        let mut seq = Sequence::new();
+
+        backend
+            .expect_checkpoint_create()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(path("/checkpoint/1"))).boxed());
+        backend
+            .expect_find_connection()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(Some(path("/conn/1")))).boxed());
+        backend
+            .expect_delete_connection()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(())).boxed());
+
+        expect_event(&mut client, &mut seq, ConnectionStatus::PendingConfirmation);
+
+        client
+            .expect_clone()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|| {
+                let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+
+                expect_event(
+                    &mut client,
+                    &mut Sequence::new(),
+                    ConnectionStatus::RolledBack,
+                );
+
+                client
+            });
+
+        backend
+            .expect_checkpoint_rollback()
+            .once()
+            .in_sequence(&mut seq)
+            .with(predicate::eq(path("/checkpoint/1")))
+            .returning(|_| futures::future::ready(Ok(())).boxed());
+
+        expect_event(&mut client, &mut seq, ConnectionStatus::Failure);
+
+        let mut network = NetworkProvisioning::new(client, backend, config(10), None);
+
+        network
+            .handle(connection_request(ConnectionOperation::Delete))
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_secs(11)).await;
+
+        network
+            .handle(connection_request(ConnectionOperation::Confirm))
+            .await
+            .unwrap();
+
+        assert!(network.pending.is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_change_is_rolled_back() {
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut backend = MockNetworkBackend::new();
+        let mut seq = Sequence::new();
+
+        backend
+            .expect_checkpoint_create()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(path("/checkpoint/1"))).boxed());
+        backend
+            .expect_find_connection()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(None)).boxed());
+        backend
+            .expect_checkpoint_rollback()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| futures::future::ready(Ok(())).boxed());
+
+        expect_event(&mut client, &mut seq, ConnectionStatus::Failure);
+
+        let mut network = NetworkProvisioning::new(client, backend, config(120), None);
+
+        network
+            .handle(connection_request(ConnectionOperation::Modify))
+            .await
+            .unwrap();
+
+        assert!(network.pending.is_none());
+    }
+}
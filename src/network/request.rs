@@ -0,0 +1,860 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Network connection request received from Astarte.
+
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+use astarte_device_sdk::event::FromEventError;
+use astarte_device_sdk::{
+    AstarteData, DeviceEvent, FromEvent, Value as AstarteValue, types::TypeError,
+};
+use eyre::{OptionExt, WrapErr, bail};
+use tracing::{debug, error};
+use uuid::Uuid;
+use zbus::zvariant::{OwnedValue, Value};
+
+use super::manager::Settings;
+
+const WIFI_SECURITY: &str = "802-11-wireless-security";
+const DOT1X: &str = "802-1x";
+
+/// Sections replaced as a whole when the value of the key changes.
+const MODE_KEYS: [(&str, &str); 2] = [("ipv4", "method"), (WIFI_SECURITY, "key-mgmt")];
+
+/// Request to provision a network connection.
+///
+/// All the string fields are optional, an empty string means the value is not set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionRequest {
+    pub(crate) id: String,
+    pub(crate) operation: ConnectionOperation,
+    pub(crate) connection_id: String,
+    pub(crate) kind: OptString,
+    pub(crate) interface_name: OptString,
+    /// Optional, the connection keeps its value if not set
+    pub(crate) autoconnect: Option<bool>,
+    pub(crate) ssid: OptString,
+    pub(crate) security: OptString,
+    pub(crate) psk: OptString,
+    pub(crate) eap_method: OptString,
+    pub(crate) identity: OptString,
+    pub(crate) password: OptString,
+    pub(crate) ipv4_method: OptString,
+    pub(crate) ipv4_addresses: Vec<String>,
+    pub(crate) ipv4_gateway: OptString,
+    pub(crate) ipv4_dns: Vec<String>,
+    pub(crate) apn: OptString,
+}
+
+/// Mandatory fields of the [`ConnectionRequest`]
+#[derive(Debug, Clone, FromEvent, PartialEq, Eq)]
+#[from_event(
+    interface = "io.edgehog.devicemanager.network.ConnectionRequest",
+    path = "/request",
+    aggregation = "object",
+    rename_all = "camelCase"
+)]
+struct RequestFields {
+    id: String,
+    operation: ConnectionOperation,
+    connection_id: String,
+    kind: OptString,
+    interface_name: OptString,
+    ssid: OptString,
+    security: OptString,
+    psk: OptString,
+    eap_method: OptString,
+    identity: OptString,
+    password: OptString,
+    ipv4_method: OptString,
+    ipv4_addresses: Vec<String>,
+    ipv4_gateway: OptString,
+    ipv4_dns: Vec<String>,
+    apn: OptString,
+}
+
+impl FromEvent for ConnectionRequest {
+    type Err = FromEventError;
+
+    fn from_event(mut event: DeviceEvent) -> Result<Self, Self::Err> {
+        let autoconnect = match &mut event.data {
+            AstarteValue::Object { data, .. } => data.remove("autoconnect"),
+            AstarteValue::Individual { .. } | AstarteValue::Property(_) => None,
+        };
+
+        let RequestFields {
+            id,
+            operation,
+            connection_id,
+            kind,
+            interface_name,
+            ssid,
+            security,
+            psk,
+            eap_method,
+            identity,
+            password,
+            ipv4_method,
+            ipv4_addresses,
+            ipv4_gateway,
+            ipv4_dns,
+            apn,
+        } = RequestFields::from_event(event)?;
+
+        let autoconnect = autoconnect.map(bool::try_from).transpose()?;
+
+        Ok(Self {
+            id,
+            operation,
+            connection_id,
+            kind,
+            interface_name,
+            autoconnect,
+            ssid,
+            security,
+            psk,
+            eap_method,
+            identity,
+            password,
+            ipv4_method,
+            ipv4_addresses,
+            ipv4_gateway,
+            ipv4_dns,
+            apn,
+        })
+    }
+}
+
+/// Operation to perform on the connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionOperation {
+    Create,
+    Modify,
+    Activate,
+    Delete,
+    /// Confirms the pending change, preventing the rollback.
+    Confirm,
+}
+
+impl TryFrom<AstarteData> for ConnectionOperation {
+    type Error = TypeError;
+
+    fn try_from(value: AstarteData) -> Result<Self, Self::Error> {
+        let value = String::try_from(value)?;
+
+        match value.as_str() {
+            "Create" => Ok(Self::Create),
+            "Modify" => Ok(Self::Modify),
+            "Activate" => Ok(Self::Activate),
+            "Delete" => Ok(Self::Delete),
+            "Confirm" => Ok(Self::Confirm),
+            _ => {
+                error!(value, "unrecognized network connection operation");
+
+                Err(TypeError::Conversion {
+                    ctx: format!("unrecognized network connection operation {value}"),
+                })
+            }
+        }
+    }
+}
+
+/// String that is [`None`] when empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OptString(Option<String>);
+
+impl OptString {
+    fn as_deref(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+}
+
+impl TryFrom<AstarteData> for OptString {
+    type Error = TypeError;
+
+    fn try_from(value: AstarteData) -> Result<Self, Self::Error> {
+        let value = String::try_from(value)?;
+
+        Ok(Self::from(value))
+    }
+}
+
+impl From<String> for OptString {
+    fn from(value: String) -> Self {
+        if value.is_empty() {
+            OptString(None)
+        } else {
+            OptString(Some(value))
+        }
+    }
+}
+
+impl From<&str> for OptString {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+fn owned<'a>(value: impl Into<Value<'a>>) -> eyre::Result<OwnedValue> {
+    value
+        .into()
+        .try_into_owned()
+        .wrap_err("couldn't convert setting value")
+}
+
+impl ConnectionRequest {
+    /// Returns the NetworkManager settings for a new connection.
+    pub(crate) fn create_settings(&self) -> eyre::Result<Settings> {
+        let kind = self
+            .kind
+            .as_deref()
+            .ok_or_eyre("missing kind for the new connection")?;
+
+        let ty = match kind {
+            "wifi" => "802-11-wireless",
+            "gsm" => "gsm",
+            "ethernet" => "802-3-ethernet",
+            _ => bail!("unsupported connection kind {kind}"),
+        };
+
+        if self.security.as_deref() == Some("wpa-psk") && self.psk.as_deref().is_none() {
+            bail!("missing psk for wpa-psk");
+        }
+
+        let mut settings = self.settings()?;
+
+        let connection = settings.entry("connection".to_string()).or_default();
+        connection.insert("id".to_string(), owned(self.connection_id.as_str())?);
+        connection.insert("type".to_string(), owned(ty)?);
+        connection.insert("uuid".to_string(), owned(Uuid::new_v4().to_string())?);
+
+        // The section must be present for the connection to be valid
+        settings.entry(ty.to_string()).or_default();
+
+        settings
+            .entry("ipv6".to_string())
+            .or_default()
+            .insert("method".to_string(), owned("auto")?);
+
+        Ok(settings)
+    }
+
+    /// Merge the values set in the request into the existing settings of a connection.
+    ///
+    /// The current settings must include the secrets, since the update replaces all of them. The
+    /// values not in the request, like the psk when only the addresses change, are kept. When the
+    /// ipv4 method or the Wi-Fi key management changes, the whole section is replaced instead, so
+    /// the values of the previous mode are dropped.
+    pub(crate) fn merge_settings(&self, mut current: Settings) -> eyre::Result<Settings> {
+        match self.security.as_deref() {
+            Some("none") => {
+                current.remove(WIFI_SECURITY);
+                current.remove(DOT1X);
+            }
+            // The 802.1X settings are only used by the wpa-eap key management
+            Some("wpa-psk") => {
+                current.remove(DOT1X);
+            }
+            _ => {}
+        }
+
+        for (name, values) in self.settings()? {
+            let mode_changed = MODE_KEYS
+                .iter()
+                .find(|(section, _)| *section == name)
+                .is_some_and(|(_, key)| {
+                    string_value(current.get(&name), key) != string_value(Some(&values), key)
+                });
+
+            if mode_changed {
+                debug!(name, "replacing the settings for the changed mode");
+
+                current.insert(name, values);
+            } else {
+                current.entry(name).or_default().extend(values);
+            }
+        }
+
+        Ok(current)
+    }
+
+    /// Settings for the values set in the request.
+    fn settings(&self) -> eyre::Result<Settings> {
+        let mut settings = Settings::new();
+
+        let mut connection = HashMap::new();
+        if let Some(autoconnect) = self.autoconnect {
+            connection.insert("autoconnect".to_string(), owned(autoconnect)?);
+        }
+        if let Some(interface_name) = self.interface_name.as_deref() {
+            connection.insert("interface-name".to_string(), owned(interface_name)?);
+        }
+        if !connection.is_empty() {
+            settings.insert("connection".to_string(), connection);
+        }
+
+        if let Some(ssid) = self.ssid.as_deref() {
+            settings.insert(
+                "802-11-wireless".to_string(),
+                HashMap::from([
+                    ("ssid".to_string(), owned(ssid.as_bytes())?),
+                    ("mode".to_string(), owned("infrastructure")?),
+                ]),
+            );
+        }
+
+        self.wifi_security(&mut settings)?;
+
+        if let Some(apn) = self.apn.as_deref() {
+            let mut gsm = HashMap::from([("apn".to_string(), owned(apn)?)]);
+            if let Some(identity) = self.identity.as_deref() {
+                gsm.insert("username".to_string(), owned(identity)?);
+            }
+            if let Some(password) = self.password.as_deref() {
+                gsm.insert("password".to_string(), owned(password)?);
+            }
+
+            settings.insert("gsm".to_string(), gsm);
+        }
+
+        if let Some(ipv4) = self.ipv4()? {
+            settings.insert("ipv4".to_string(), ipv4);
+        }
+
+        Ok(settings)
+    }
+
+    /// Wi-Fi security settings.
+    ///
+    /// Without a psk in the request, the one of the current connection is kept.
+    fn wifi_security(&self, settings: &mut Settings) -> eyre::Result<()> {
+        match self.security.as_deref() {
+            None | Some("none") => {}
+            Some("wpa-psk") => {
+                let mut security = HashMap::from([("key-mgmt".to_string(), owned("wpa-psk")?)]);
+                if let Some(psk) = self.psk.as_deref() {
+                    security.insert("psk".to_string(), owned(psk)?);
+                }
+
+                settings.insert(WIFI_SECURITY.to_string(), security);
+            }
+            Some("wpa-eap") => {
+                let eap = self
+                    .eap_method
+                    .as_deref()
+                    .ok_or_eyre("missing eap method for wpa-eap")?;
+
+                settings.insert(
+                    WIFI_SECURITY.to_string(),
+                    HashMap::from([("key-mgmt".to_string(), owned("wpa-eap")?)]),
+                );
+
+                let mut dot1x = HashMap::from([("eap".to_string(), owned(vec![eap])?)]);
+                if let Some(identity) = self.identity.as_deref() {
+                    dot1x.insert("identity".to_string(), owned(identity)?);
+                }
+                if let Some(password) = self.password.as_deref() {
+                    dot1x.insert("password".to_string(), owned(password)?);
+                }
+                if matches!(eap, "peap" | "ttls") {
+                    dot1x.insert("phase2-auth".to_string(), owned("mschapv2")?);
+                }
+
+                settings.insert(DOT1X.to_string(), dot1x);
+            }
+            Some(security) => bail!("unsupported wifi security {security}"),
+        }
+
+        Ok(())
+    }
+
+    fn ipv4(&self) -> eyre::Result<Option<HashMap<String, OwnedValue>>> {
+        let ipv4 = match self.ipv4_method.as_deref() {
+            None => return Ok(None),
+            Some("auto") => HashMap::from([("method".to_string(), owned("auto")?)]),
+            Some("manual") => {
+                let addresses = self
+                    .ipv4_addresses
+                    .iter()
+                    .map(|address| parse_address(address))
+                    .collect::<eyre::Result<Vec<_>>>()?;
+
+                if addresses.is_empty() {
+                    bail!("missing addresses for manual ipv4 method");
+                }
+
+                // DNS servers are u32 in network byte order
+                let dns = self
+                    .ipv4_dns
+                    .iter()
+                    .map(|dns| {
+                        dns.parse::<Ipv4Addr>()
+                            .map(|ip| u32::from_ne_bytes(ip.octets()))
+                            .wrap_err_with(|| format!("invalid dns address {dns}"))
+                    })
+                    .collect::<eyre::Result<Vec<u32>>>()?;
+
+                let mut ipv4 = HashMap::from([
+                    ("method".to_string(), owned("manual")?),
+                    ("address-data".to_string(), owned(addresses)?),
+                    ("dns".to_string(), owned(dns)?),
+                ]);
+
+                if let Some(gateway) = self.ipv4_gateway.as_deref() {
+                    let gateway = gateway
+                        .parse::<Ipv4Addr>()
+                        .wrap_err_with(|| format!("invalid gateway {gateway}"))?;
+
+                    ipv4.insert("gateway".to_string(), owned(gateway.to_string())?);
+                }
+
+                ipv4
+            }
+            Some(method) => bail!("unsupported ipv4 method {method}"),
+        };
+
+        Ok(Some(ipv4))
+    }
+}
+
+/// Value of a string setting.
+fn string_value(values: Option<&HashMap<String, OwnedValue>>, key: &str) -> Option<String> {
+    values?.get(key)?.downcast_ref::<String>().ok()
+}
+
+/// Parse an address in the CIDR notation into the `address-data` format.
+fn parse_address(address: &str) -> eyre::Result<HashMap<String, Value<'static>>> {
+    let (ip, prefix) = address
+        .split_once('/')
+        .ok_or_else(|| eyre::eyre!("missing prefix in address {address}"))?;
+
+    let ip = ip
+        .parse::<Ipv4Addr>()
+        .wrap_err_with(|| format!("invalid ip in address {address}"))?;
+    let prefix = prefix
+        .parse::<u32>()
+        .ok()
+        .filter(|prefix| *prefix <= 32)
+        .ok_or_else(|| eyre::eyre!("invalid prefix in address {address}"))?;
+
+    Ok(HashMap::from([
+        ("address".to_string(), Value::from(ip.to_string())),
+        ("prefix".to_string(), Value::from(prefix)),
+    ]))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use astarte_device_sdk::chrono::Utc;
+    use astarte_device_sdk::{DeviceEvent, Value as AstarteValue};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    pub(crate) fn connection_request(operation: ConnectionOperation) -> ConnectionRequest {
+        ConnectionRequest {
+            id: Uuid::from_u128(1).to_string(),
+            operation,
+            connection_id: "office".to_string(),
+            kind: "wifi".into(),
+            interface_name: OptString::default(),
+            autoconnect: Some(true),
+            ssid: "Office".into(),
+            security: "wpa-psk".into(),
+            psk: "secret-password".into(),
+            eap_method: OptString::default(),
+            identity: OptString::default(),
+            password: OptString::default(),
+            ipv4_method: "auto".into(),
+            ipv4_addresses: Vec::new(),
+            ipv4_gateway: OptString::default(),
+            ipv4_dns: Vec::new(),
+            apn: OptString::default(),
+        }
+    }
+
+    fn string_setting(settings: &Settings, name: &str, key: &str) -> Option<String> {
+        settings
+            .get(name)
+            .and_then(|values| values.get(key))
+            .and_then(|value| value.downcast_ref::<String>().ok())
+    }
+
+    #[test]
+    fn connection_request_from_event() {
+        let fields = [
+            ("id", AstarteData::from(Uuid::from_u128(1).to_string())),
+            ("operation", AstarteData::from("Create")),
+            ("connectionId", AstarteData::from("office")),
+            ("kind", AstarteData::from("wifi")),
+            ("interfaceName", AstarteData::from("")),
+            ("autoconnect", AstarteData::Boolean(true)),
+            ("ssid", AstarteData::from("Office")),
+            ("security", AstarteData::from("wpa-psk")),
+            ("psk", AstarteData::from("secret-password")),
+            ("eapMethod", AstarteData::from("")),
+            ("identity", AstarteData::from("")),
+            ("password", AstarteData::from("")),
+            ("ipv4Method", AstarteData::from("auto")),
+            ("ipv4Addresses", AstarteData::StringArray(Vec::new())),
+            ("ipv4Gateway", AstarteData::from("")),
+            ("ipv4Dns", AstarteData::StringArray(Vec::new())),
+            ("apn", AstarteData::from("")),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        let event = DeviceEvent {
+            interface: "io.edgehog.devicemanager.network.ConnectionRequest".to_string(),
+            path: "/request".to_string(),
+            data: AstarteValue::Object {
+                data: fields,
+                timestamp: Utc::now(),
+            },
+        };
+
+        let request = ConnectionRequest::from_event(event).unwrap();
+
+        assert_eq!(request, connection_request(ConnectionOperation::Create));
+    }
+
+    #[test]
+    fn connection_request_without_autoconnect() {
+        let fields = [
+            ("id", AstarteData::from(Uuid::from_u128(1).to_string())),
+            ("operation", AstarteData::from("Modify")),
+            ("connectionId", AstarteData::from("office")),
+            ("kind", AstarteData::from("")),
+            ("interfaceName", AstarteData::from("")),
+            ("ssid", AstarteData::from("")),
+            ("security", AstarteData::from("")),
+            ("psk", AstarteData::from("")),
+            ("eapMethod", AstarteData::from("")),
+            ("identity", AstarteData::from("")),
+            ("password", AstarteData::from("")),
+            ("ipv4Method", AstarteData::from("auto")),
+            ("ipv4Addresses", AstarteData::StringArray(Vec::new())),
+            ("ipv4Gateway", AstarteData::from("")),
+            ("ipv4Dns", AstarteData::StringArray(Vec::new())),
+            ("apn", AstarteData::from("")),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        let event = DeviceEvent {
+            interface: "io.edgehog.devicemanager.network.ConnectionRequest".to_string(),
+            path: "/request".to_string(),
+            data: AstarteValue::Object {
+                data: fields,
+                timestamp: Utc::now(),
+            },
+        };
+
+        let request = ConnectionRequest::from_event(event).unwrap();
+
+        assert_eq!(request.autoconnect, None);
+        assert!(!request.settings().unwrap().contains_key("connection"));
+    }
+
+    #[test]
+    fn operation_from_data() {
+        let cases = [
+            ("Create", ConnectionOperation::Create),
+            ("Modify", ConnectionOperation::Modify),
+            ("Activate", ConnectionOperation::Activate),
+            ("Delete", ConnectionOperation::Delete),
+            ("Confirm", ConnectionOperation::Confirm),
+        ];
+
+        for (case, exp) in cases {
+            let res = ConnectionOperation::try_from(AstarteData::from(case)).unwrap();
+
+            assert_eq!(res, exp);
+        }
+
+        ConnectionOperation::try_from(AstarteData::from("Reboot")).unwrap_err();
+    }
+
+    #[test]
+    fn create_wifi_psk_settings() {
+        let request = connection_request(ConnectionOperation::Create);
+
+        let settings = request.create_settings().unwrap();
+
+        assert_eq!(
+            string_setting(&settings, "connection", "type").as_deref(),
+            Some("802-11-wireless")
+        );
+        assert_eq!(
+            string_setting(&settings, "connection", "id").as_deref(),
+            Some("office")
+        );
+        assert!(string_setting(&settings, "connection", "uuid").is_some());
+
+        let ssid: Vec<u8> = settings["802-11-wireless"]["ssid"]
+            .try_clone()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(ssid, b"Office");
+
+        assert_eq!(
+            string_setting(&settings, "802-11-wireless-security", "key-mgmt").as_deref(),
+            Some("wpa-psk")
+        );
+        assert_eq!(
+            string_setting(&settings, "802-11-wireless-security", "psk").as_deref(),
+            Some("secret-password")
+        );
+        assert_eq!(
+            string_setting(&settings, "ipv4", "method").as_deref(),
+            Some("auto")
+        );
+    }
+
+    #[test]
+    fn create_wifi_eap_settings() {
+        let mut request = connection_request(ConnectionOperation::Create);
+        request.security = "wpa-eap".into();
+        request.psk = OptString::default();
+        request.eap_method = "peap".into();
+        request.identity = "user".into();
+        request.password = "pass".into();
+
+        let settings = request.create_settings().unwrap();
+
+        assert_eq!(
+            string_setting(&settings, "802-11-wireless-security", "key-mgmt").as_deref(),
+            Some("wpa-eap")
+        );
+        let eap: Vec<String> = settings["802-1x"]["eap"]
+            .try_clone()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(eap, ["peap"]);
+        assert_eq!(
+            string_setting(&settings, "802-1x", "identity").as_deref(),
+            Some("user")
+        );
+        assert_eq!(
+            string_setting(&settings, "802-1x", "phase2-auth").as_deref(),
+            Some("mschapv2")
+        );
+    }
+
+    #[test]
+    fn create_gsm_static_settings() {
+        let mut request = connection_request(ConnectionOperation::Create);
+        request.kind = "gsm".into();
+        request.ssid = OptString::default();
+        request.security = OptString::default();
+        request.apn = "internet.example".into();
+        request.ipv4_method = "manual".into();
+        request.ipv4_addresses = vec!["192.168.1.10/24".to_string()];
+        request.ipv4_gateway = "192.168.1.1".into();
+        request.ipv4_dns = vec!["1.1.1.1".to_string()];
+
+        let settings = request.create_settings().unwrap();
+
+        assert_eq!(
+            string_setting(&settings, "connection", "type").as_deref(),
+            Some("gsm")
+        );
+        assert_eq!(
+            string_setting(&settings, "gsm", "apn").as_deref(),
+            Some("internet.example")
+        );
+        assert!(!settings.contains_key("802-11-wireless-security"));
+        assert_eq!(
+            string_setting(&settings, "ipv4", "gateway").as_deref(),
+            Some("192.168.1.1")
+        );
+
+        let dns: Vec<u32> = settings["ipv4"]["dns"]
+            .try_clone()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(dns, [u32::from_ne_bytes([1, 1, 1, 1])]);
+
+        let addresses: Vec<HashMap<String, OwnedValue>> = settings["ipv4"]["address-data"]
+            .try_clone()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(addresses.len(), 1);
+        assert_eq!(
+            addresses[0]["address"].downcast_ref::<String>().unwrap(),
+            "192.168.1.10"
+        );
+        assert_eq!(addresses[0]["prefix"].downcast_ref::<u32>().unwrap(), 24);
+    }
+
+    #[test]
+    fn invalid_settings() {
+        let mut missing_kind = connection_request(ConnectionOperation::Create);
+        missing_kind.kind = OptString::default();
+        assert!(missing_kind.create_settings().is_err());
+
+        let mut missing_psk = connection_request(ConnectionOperation::Create);
+        missing_psk.psk = OptString::default();
+        assert!(missing_psk.create_settings().is_err());
+
+        let mut invalid_address = connection_request(ConnectionOperation::Create);
+        invalid_address.ipv4_method = "manual".into();
+        invalid_address.ipv4_addresses = vec!["192.168.1.10".to_string()];
+        assert!(invalid_address.create_settings().is_err());
+
+        let mut invalid_prefix = connection_request(ConnectionOperation::Create);
+        invalid_prefix.ipv4_method = "manual".into();
+        invalid_prefix.ipv4_addresses = vec!["192.168.1.10/33".to_string()];
+        assert!(invalid_prefix.create_settings().is_err());
+    }
+
+    #[test]
+    fn merge_keeps_existing_settings() {
+        let mut request = connection_request(ConnectionOperation::Modify);
+        request.kind = OptString::default();
+        request.ssid = OptString::default();
+        request.security = "wpa-psk".into();
+        request.psk = "new-password".into();
+        request.ipv4_method = OptString::default();
+
+        let current = request
+            .clone()
+            .merge_settings(
+                connection_request(ConnectionOperation::Create)
+                    .create_settings()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            string_setting(&current, "802-11-wireless-security", "psk").as_deref(),
+            Some("new-password")
+        );
+        assert_eq!(
+            string_setting(&current, "connection", "type").as_deref(),
+            Some("802-11-wireless")
+        );
+        assert!(current["802-11-wireless"].contains_key("ssid"));
+    }
+
+    #[test]
+    fn partial_modify_keeps_secrets() {
+        let mut request = connection_request(ConnectionOperation::Modify);
+        request.kind = OptString::default();
+        request.autoconnect = None;
+        request.ssid = OptString::default();
+        request.psk = OptString::default();
+        request.ipv4_method = "manual".into();
+        request.ipv4_addresses = vec!["192.168.1.10/24".to_string()];
+
+        let mut existing = connection_request(ConnectionOperation::Create)
+            .create_settings()
+            .unwrap();
+        existing
+            .get_mut("connection")
+            .unwrap()
+            .insert("autoconnect".to_string(), owned(false).unwrap());
+
+        let current = request.merge_settings(existing).unwrap();
+
+        assert_eq!(
+            string_setting(&current, "802-11-wireless-security", "psk").as_deref(),
+            Some("secret-password")
+        );
+        assert_eq!(
+            string_setting(&current, "802-11-wireless-security", "key-mgmt").as_deref(),
+            Some("wpa-psk")
+        );
+        assert!(
+            !current["connection"]["autoconnect"]
+                .downcast_ref::<bool>()
+                .unwrap()
+        );
+        assert_eq!(
+            string_setting(&current, "ipv4", "method").as_deref(),
+            Some("manual")
+        );
+    }
+
+    #[test]
+    fn merge_replaces_changed_ipv4_method() {
+        let mut existing = connection_request(ConnectionOperation::Create);
+        existing.ipv4_method = "manual".into();
+        existing.ipv4_addresses = vec!["192.168.1.10/24".to_string()];
+        existing.ipv4_gateway = "192.168.1.1".into();
+
+        let mut request = connection_request(ConnectionOperation::Modify);
+        request.psk = OptString::default();
+
+        let current = request
+            .merge_settings(existing.create_settings().unwrap())
+            .unwrap();
+
+        assert_eq!(
+            string_setting(&current, "ipv4", "method").as_deref(),
+            Some("auto")
+        );
+        assert!(!current["ipv4"].contains_key("address-data"));
+        assert!(!current["ipv4"].contains_key("gateway"));
+    }
+
+    #[test]
+    fn merge_replaces_changed_key_mgmt() {
+        let mut request = connection_request(ConnectionOperation::Modify);
+        request.security = "wpa-eap".into();
+        request.psk = OptString::default();
+        request.eap_method = "peap".into();
+        request.identity = "user".into();
+        request.password = "password".into();
+
+        let current = request
+            .merge_settings(
+                connection_request(ConnectionOperation::Create)
+                    .create_settings()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            string_setting(&current, WIFI_SECURITY, "key-mgmt").as_deref(),
+            Some("wpa-eap")
+        );
+        assert!(!current[WIFI_SECURITY].contains_key("psk"));
+        assert!(current.contains_key(DOT1X));
+
+        // Back to wpa-psk drops the 802.1X settings
+        let mut request = connection_request(ConnectionOperation::Modify);
+        request.psk = "new-password".into();
+
+        let current = request.merge_settings(current).unwrap();
+
+        assert_eq!(
+            string_setting(&current, WIFI_SECURITY, "psk").as_deref(),
+            Some("new-password")
+        );
+        assert!(!current.contains_key(DOT1X));
+    }
+}
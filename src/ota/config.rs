@@ -17,18 +17,67 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::fmt::Display;
+use std::path::PathBuf;
 
 use serde::Deserialize;
+use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+use crate::io::space::Percentage;
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
 pub struct OtaConfig {
+    /// System used to install the bundle
+    #[serde(default)]
+    pub backend: OtaBackend,
     #[serde(default)]
     pub reboot: Reboot,
     #[serde(default)]
     pub streaming: bool,
+    /// Authentication and TLS options of the streamed bundles
+    #[serde(default)]
+    pub stream: StreamConfig,
     /// RAUC configuration for the OTA
     #[serde(default)]
     pub rauc: RaucConfig,
+    /// SWUpdate configuration for the OTA
+    #[serde(default)]
+    pub swupdate: SwUpdateConfig,
+    /// Hooks configuration for the script backend
+    #[serde(default)]
+    pub script: ScriptConfig,
+    /// Retry policy for the bundle download
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// Checks to pass after the reboot before marking the slot good
+    #[serde(default)]
+    pub health: HealthConfig,
+    /// Verification of the bundle signature
+    #[serde(default)]
+    pub verification: VerificationConfig,
+    /// Percentage of the download filesystem reserved for the system
+    ///
+    /// The download fails before starting if the bundle would leave less free space.
+    #[serde(default)]
+    pub storage_reserved: Option<Percentage>,
+    /// Installation of the bundles copied on the device, like from a USB stick
+    #[serde(default)]
+    pub local: LocalConfig,
+    /// Retention of the updates history
+    #[serde(default)]
+    pub history: HistoryConfig,
+}
+
+/// System used to install the bundle
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OtaBackend {
+    /// Installs the bundle through the RAUC DBUS service
+    #[default]
+    Rauc,
+    /// Installs the bundle through the SWUpdate IPC sockets
+    SwUpdate,
+    /// Installs the bundle through custom hook executables
+    Script,
 }
 
 #[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
@@ -58,6 +107,107 @@ pub enum RaucDbus {
     Session,
 }
 
+/// Options passed to the backend to stream the bundle.
+///
+/// They are used only by the RAUC backend, that requests the bundle itself.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+pub struct StreamConfig {
+    /// HTTP headers sent with the requests, in the `Name: value` format
+    #[serde(default)]
+    pub http_headers: Vec<String>,
+    /// TLS client certificate, as a PEM file or a PKCS#11 URI
+    #[serde(default)]
+    pub tls_cert: Option<PathBuf>,
+    /// TLS client private key, as a PEM file or a PKCS#11 URI
+    #[serde(default)]
+    pub tls_key: Option<PathBuf>,
+    /// CA to verify the server certificate, defaults to the CA file the runtime loads its roots from
+    #[serde(default)]
+    pub tls_ca: Option<PathBuf>,
+    /// Disables the verification of the server certificate
+    #[serde(default)]
+    pub tls_no_verify: bool,
+}
+
+/// Configuration for SWUpdate
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct SwUpdateConfig {
+    /// Socket to send the install requests and the bundle
+    #[serde(default = "SwUpdateConfig::default_control_socket")]
+    pub control_socket: PathBuf,
+    /// Socket the installation progress is received from
+    #[serde(default = "SwUpdateConfig::default_progress_socket")]
+    pub progress_socket: PathBuf,
+    /// File with the board name and revision, checked against the bundle hardware compatibility
+    #[serde(default = "SwUpdateConfig::default_hwrevision")]
+    pub hwrevision: PathBuf,
+}
+
+impl SwUpdateConfig {
+    fn default_control_socket() -> PathBuf {
+        PathBuf::from("/tmp/sockinstctrl")
+    }
+
+    fn default_progress_socket() -> PathBuf {
+        PathBuf::from("/tmp/swupdateprog")
+    }
+
+    fn default_hwrevision() -> PathBuf {
+        PathBuf::from("/etc/hwrevision")
+    }
+}
+
+impl Default for SwUpdateConfig {
+    fn default() -> Self {
+        Self {
+            control_socket: Self::default_control_socket(),
+            progress_socket: Self::default_progress_socket(),
+            hwrevision: Self::default_hwrevision(),
+        }
+    }
+}
+
+/// Hook executables of the script backend
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScriptConfig {
+    /// Prints the compatible and version of the bundle
+    pub info: Option<PathBuf>,
+    /// Prints the compatible of the system
+    pub compatible: Option<PathBuf>,
+    /// Installs the bundle, printing the progress
+    pub install: Option<PathBuf>,
+    /// Prints the booted slot
+    pub boot_slot: Option<PathBuf>,
+    /// Prints the primary slot
+    pub get_primary: Option<PathBuf>,
+    /// Marks the state of a slot
+    pub mark: Option<PathBuf>,
+    /// Seconds to wait for the hooks, except the install one
+    #[serde(default = "ScriptConfig::default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl ScriptConfig {
+    const fn default_timeout_secs() -> u64 {
+        60
+    }
+}
+
+impl Default for ScriptConfig {
+    fn default() -> Self {
+        Self {
+            info: None,
+            compatible: None,
+            install: None,
+            boot_slot: None,
+            get_primary: None,
+            mark: None,
+            timeout_secs: Self::default_timeout_secs(),
+        }
+    }
+}
+
 impl Display for RaucDbus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -67,6 +217,233 @@ impl Display for RaucDbus {
     }
 }
 
+/// Retry policy for the download of the bundle.
+///
+/// Only the retryable errors are retried, like timeouts or server errors.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Maximum number of download attempts, including the first one.
+    #[serde(default = "RetryConfig::default_max_attempts")]
+    pub max_attempts: u32,
+    /// Maximum seconds spent retrying the download, 0 to disable the limit.
+    #[serde(default)]
+    pub max_elapsed_secs: u64,
+    /// How the delay grows between the attempts.
+    #[serde(default)]
+    pub backoff: Backoff,
+    /// Seconds to wait before the first retry.
+    #[serde(default = "RetryConfig::default_initial_delay_secs")]
+    pub initial_delay_secs: u64,
+    /// Maximum seconds to wait between two attempts.
+    #[serde(default = "RetryConfig::default_max_delay_secs")]
+    pub max_delay_secs: u64,
+    /// Random variation of the delay, in percent of the delay.
+    #[serde(default = "RetryConfig::default_jitter_percent")]
+    pub jitter_percent: u8,
+    /// Seconds a partial download is kept to be resumed, after it's removed.
+    #[serde(default = "RetryConfig::default_resume_max_age_secs")]
+    pub resume_max_age_secs: u64,
+}
+
+impl RetryConfig {
+    const fn default_max_attempts() -> u32 {
+        5
+    }
+
+    const fn default_initial_delay_secs() -> u64 {
+        2
+    }
+
+    const fn default_max_delay_secs() -> u64 {
+        60
+    }
+
+    const fn default_jitter_percent() -> u8 {
+        10
+    }
+
+    const fn default_resume_max_age_secs() -> u64 {
+        24 * 60 * 60
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: Self::default_max_attempts(),
+            max_elapsed_secs: 0,
+            backoff: Backoff::default(),
+            initial_delay_secs: Self::default_initial_delay_secs(),
+            max_delay_secs: Self::default_max_delay_secs(),
+            jitter_percent: Self::default_jitter_percent(),
+            resume_max_age_secs: Self::default_resume_max_age_secs(),
+        }
+    }
+}
+
+/// Verification of the bundle signature.
+///
+/// When the public key is configured, only the requests with a digest signed by the key are
+/// accepted.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+pub struct VerificationConfig {
+    /// Public key in DER format, or the raw key bytes
+    #[serde(default)]
+    pub public_key: Option<PathBuf>,
+    #[serde(default)]
+    pub algorithm: SignatureAlgorithm,
+}
+
+/// Algorithm of the bundle signature
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureAlgorithm {
+    #[default]
+    Ed25519,
+    /// ECDSA on the P-256 curve with SHA-256, ASN.1 DER encoded
+    EcdsaP256Sha256,
+    /// RSA PKCS#1 v1.5 with SHA-256
+    RsaPkcs1Sha256,
+}
+
+/// Health checks run after booting the updated slot.
+///
+/// If they don't pass within the timeout, the slot is marked bad and the device reboots into the
+/// previous one.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct HealthConfig {
+    /// Seconds the checks have to pass within
+    #[serde(default = "HealthConfig::default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Seconds between two runs of the failed checks
+    #[serde(default = "HealthConfig::default_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default)]
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthConfig {
+    const fn default_timeout_secs() -> u64 {
+        300
+    }
+
+    const fn default_interval_secs() -> u64 {
+        5
+    }
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: Self::default_timeout_secs(),
+            interval_secs: Self::default_interval_secs(),
+            checks: Vec::new(),
+        }
+    }
+}
+
+/// Check on the health of the updated system
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HealthCheck {
+    /// Astarte can be reached
+    Astarte,
+    /// The systemd unit is active
+    Systemd { unit: String },
+    /// All the containers of the deployment are running
+    Containers { deployment: Uuid },
+    /// The script exits successfully
+    Script { path: PathBuf },
+}
+
+impl Display for HealthCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HealthCheck::Astarte => write!(f, "astarte"),
+            HealthCheck::Systemd { unit } => write!(f, "systemd unit {unit}"),
+            HealthCheck::Containers { deployment } => write!(f, "deployment {deployment}"),
+            HealthCheck::Script { path } => write!(f, "script {}", path.display()),
+        }
+    }
+}
+
+/// Installation of the bundles dropped in a local directory.
+///
+/// The bundles are copied out of the directory, and removed from it only once installed, so the
+/// directory must be writable. A bundle that couldn't be installed is renamed to
+/// `<bundle>.failed`, to not install it again. The signature of the bundle is read from the `<bundle>.sig` file,
+/// hex encoded, and checked with the configured public key like for the remote requests.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct LocalConfig {
+    /// Directory watched for the bundles, disabled if not set
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    /// Seconds between two scans of the directory
+    #[serde(default = "LocalConfig::default_interval_secs")]
+    pub interval_secs: u64,
+    /// Extensions of the files installed as bundles, the other files are ignored
+    #[serde(default = "LocalConfig::default_extensions")]
+    pub extensions: Vec<String>,
+}
+
+impl LocalConfig {
+    const fn default_interval_secs() -> u64 {
+        10
+    }
+
+    fn default_extensions() -> Vec<String> {
+        vec!["raucb".to_string(), "swu".to_string()]
+    }
+}
+
+impl Default for LocalConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            interval_secs: Self::default_interval_secs(),
+            extensions: Self::default_extensions(),
+        }
+    }
+}
+
+/// Retention of the updates history.
+///
+/// The oldest updates are removed from the store, and unset from Astarte, once there are more
+/// than the configured entries.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+pub struct HistoryConfig {
+    /// Number of updates kept in the history
+    #[serde(default = "HistoryConfig::default_max_entries")]
+    pub max_entries: usize,
+}
+
+impl HistoryConfig {
+    const fn default_max_entries() -> usize {
+        10
+    }
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: Self::default_max_entries(),
+        }
+    }
+}
+
+/// Curve of the delay between the download attempts
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backoff {
+    /// Always waits the initial delay
+    Constant,
+    /// The delay grows by the initial delay at every attempt
+    Linear,
+    /// The delay doubles at every attempt
+    #[default]
+    Exponential,
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -83,11 +460,21 @@ mod tests {
         let config: OtaConfig = toml::from_str(string).unwrap();
 
         let exp = OtaConfig {
+            backend: OtaBackend::Rauc,
             reboot: Reboot::External,
             streaming: true,
+            stream: StreamConfig::default(),
             rauc: RaucConfig {
                 dbus_socket: RaucDbus::System,
             },
+            swupdate: SwUpdateConfig::default(),
+            script: ScriptConfig::default(),
+            retry: RetryConfig::default(),
+            health: HealthConfig::default(),
+            verification: VerificationConfig::default(),
+            storage_reserved: None,
+            local: LocalConfig::default(),
+            history: HistoryConfig::default(),
         };
 
         assert_eq!(config, exp);
@@ -101,11 +488,21 @@ mod tests {
         let config: OtaConfig = toml::from_str(string).unwrap();
 
         let exp = OtaConfig {
+            backend: OtaBackend::Rauc,
             reboot: Reboot::Default,
             streaming: false,
+            stream: StreamConfig::default(),
             rauc: RaucConfig {
                 dbus_socket: RaucDbus::System,
             },
+            swupdate: SwUpdateConfig::default(),
+            script: ScriptConfig::default(),
+            retry: RetryConfig::default(),
+            health: HealthConfig::default(),
+            verification: VerificationConfig::default(),
+            storage_reserved: None,
+            local: LocalConfig::default(),
+            history: HistoryConfig::default(),
         };
 
         assert_eq!(config, exp);
@@ -121,13 +518,229 @@ mod tests {
         let config: OtaConfig = toml::from_str(string).unwrap();
 
         let exp = OtaConfig {
+            backend: OtaBackend::Rauc,
             reboot: Reboot::Default,
             streaming: false,
+            stream: StreamConfig::default(),
             rauc: RaucConfig {
                 dbus_socket: RaucDbus::Session,
             },
+            swupdate: SwUpdateConfig::default(),
+            script: ScriptConfig::default(),
+            retry: RetryConfig::default(),
+            health: HealthConfig::default(),
+            verification: VerificationConfig::default(),
+            storage_reserved: None,
+            local: LocalConfig::default(),
+            history: HistoryConfig::default(),
         };
 
         assert_eq!(config, exp);
     }
+
+    #[test]
+    fn should_deserialize_storage_reserved() {
+        let string = r#"
+        storage_reserved = 10
+        "#;
+
+        let config: OtaConfig = toml::from_str(string).unwrap();
+
+        assert_eq!(config.storage_reserved, Percentage::new(10));
+
+        let string = r#"
+        storage_reserved = 101
+        "#;
+
+        assert!(toml::from_str::<OtaConfig>(string).is_err());
+    }
+
+    #[test]
+    fn should_deserialize_stream() {
+        let string = r#"
+        streaming = true
+
+        [stream]
+        http_headers = ["Authorization: Bearer token"]
+        tls_cert = "/etc/edgehog/client.pem"
+        tls_key = "pkcs11:token=edgehog;object=client"
+        tls_ca = "/etc/edgehog/ca.pem"
+        "#;
+
+        let config: OtaConfig = toml::from_str(string).unwrap();
+
+        let exp = StreamConfig {
+            http_headers: vec!["Authorization: Bearer token".to_string()],
+            tls_cert: Some(PathBuf::from("/etc/edgehog/client.pem")),
+            tls_key: Some(PathBuf::from("pkcs11:token=edgehog;object=client")),
+            tls_ca: Some(PathBuf::from("/etc/edgehog/ca.pem")),
+            tls_no_verify: false,
+        };
+
+        assert_eq!(config.stream, exp);
+    }
+
+    #[test]
+    fn should_deserialize_local() {
+        let string = r#"
+        [local]
+        directory = "/run/media/ota"
+        extensions = ["swu"]
+        "#;
+
+        let config: OtaConfig = toml::from_str(string).unwrap();
+
+        let exp = LocalConfig {
+            directory: Some(PathBuf::from("/run/media/ota")),
+            interval_secs: 10,
+            extensions: vec!["swu".to_string()],
+        };
+
+        assert_eq!(config.local, exp);
+    }
+
+    #[test]
+    fn should_deserialize_history() {
+        let string = r#"
+        [history]
+        max_entries = 3
+        "#;
+
+        let config: OtaConfig = toml::from_str(string).unwrap();
+
+        assert_eq!(config.history, HistoryConfig { max_entries: 3 });
+    }
+
+    #[test]
+    fn should_deserialize_retry() {
+        let string = r#"
+        [retry]
+        max_attempts = 3
+        max_elapsed_secs = 600
+        backoff = "linear"
+        jitter_percent = 0
+        resume_max_age_secs = 3600
+        "#;
+
+        let config: OtaConfig = toml::from_str(string).unwrap();
+
+        let exp = RetryConfig {
+            max_attempts: 3,
+            max_elapsed_secs: 600,
+            backoff: Backoff::Linear,
+            initial_delay_secs: 2,
+            max_delay_secs: 60,
+            jitter_percent: 0,
+            resume_max_age_secs: 3600,
+        };
+
+        assert_eq!(config.retry, exp);
+    }
+
+    #[test]
+    fn should_deserialize_swupdate() {
+        let string = r#"
+        backend = "swupdate"
+
+        [swupdate]
+        control_socket = "/run/swupdate/sockinstctrl"
+        "#;
+
+        let config: OtaConfig = toml::from_str(string).unwrap();
+
+        assert_eq!(config.backend, OtaBackend::SwUpdate);
+        assert_eq!(
+            config.swupdate,
+            SwUpdateConfig {
+                control_socket: PathBuf::from("/run/swupdate/sockinstctrl"),
+                progress_socket: PathBuf::from("/tmp/swupdateprog"),
+                hwrevision: PathBuf::from("/etc/hwrevision"),
+            }
+        );
+    }
+
+    #[test]
+    fn should_deserialize_script() {
+        let string = r#"
+        backend = "script"
+
+        [script]
+        install = "/usr/libexec/ota/install"
+        boot-slot = "/usr/libexec/ota/boot-slot"
+        "#;
+
+        let config: OtaConfig = toml::from_str(string).unwrap();
+
+        assert_eq!(config.backend, OtaBackend::Script);
+        assert_eq!(
+            config.script,
+            ScriptConfig {
+                install: Some(PathBuf::from("/usr/libexec/ota/install")),
+                boot_slot: Some(PathBuf::from("/usr/libexec/ota/boot-slot")),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn should_deserialize_health() {
+        let string = r#"
+        [health]
+        timeout_secs = 120
+
+        [[health.checks]]
+        type = "astarte"
+
+        [[health.checks]]
+        type = "systemd"
+        unit = "app.service"
+
+        [[health.checks]]
+        type = "containers"
+        deployment = "6e4ba38d-1e52-4e84-a1ec-4b5b6f5e7f45"
+
+        [[health.checks]]
+        type = "script"
+        path = "/usr/libexec/health"
+        "#;
+
+        let config: OtaConfig = toml::from_str(string).unwrap();
+
+        let exp = HealthConfig {
+            timeout_secs: 120,
+            interval_secs: 5,
+            checks: vec![
+                HealthCheck::Astarte,
+                HealthCheck::Systemd {
+                    unit: "app.service".to_string(),
+                },
+                HealthCheck::Containers {
+                    deployment: Uuid::parse_str("6e4ba38d-1e52-4e84-a1ec-4b5b6f5e7f45").unwrap(),
+                },
+                HealthCheck::Script {
+                    path: PathBuf::from("/usr/libexec/health"),
+                },
+            ],
+        };
+
+        assert_eq!(config.health, exp);
+    }
+
+    #[test]
+    fn should_deserialize_verification() {
+        let string = r#"
+        [verification]
+        public_key = "/etc/edgehog/ota.der"
+        algorithm = "ecdsa-p256-sha256"
+        "#;
+
+        let config: OtaConfig = toml::from_str(string).unwrap();
+
+        let exp = VerificationConfig {
+            public_key: Some(PathBuf::from("/etc/edgehog/ota.der")),
+            algorithm: SignatureAlgorithm::EcdsaP256Sha256,
+        };
+
+        assert_eq!(config.verification, exp);
+    }
 }
@@ -0,0 +1,307 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resumption of the interrupted OTA bundle downloads.
+//!
+//! The ETag of the bundle is persisted next to the partial file, and used to request only the
+//! missing bytes with the `Range` and `If-Range` headers.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_RANGE, ETAG, HeaderValue, IF_RANGE, RANGE};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info, warn};
+
+use crate::repository::StateRepository;
+use crate::repository::file_state_repository::FileStateRepository;
+
+use super::{OtaError, OtaId};
+
+/// State of a partial download, needed to resume it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ResumeState {
+    pub(crate) url: String,
+    /// Strong ETag of the bundle.
+    pub(crate) etag: String,
+    /// Size of the bundle in bytes.
+    pub(crate) total: u64,
+}
+
+impl ResumeState {
+    /// Repository of the state, stored next to the downloaded file.
+    pub(crate) fn repository(file_path: &Path) -> FileStateRepository<Self> {
+        let dir = file_path.parent().unwrap_or(Path::new("."));
+        let mut name = file_path
+            .file_name()
+            .map(|name| name.to_os_string())
+            .unwrap_or_default();
+        name.push(".resume.json");
+
+        FileStateRepository::new(dir, PathBuf::from(name))
+    }
+
+    /// Creates the state from the response of a full download.
+    ///
+    /// Returns [`None`] if the server doesn't provide a strong ETag.
+    pub(crate) fn from_response(
+        req: &OtaId,
+        response: &reqwest::Response,
+        total: u64,
+    ) -> Option<Self> {
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .filter(|etag| !etag.starts_with("W/"))?;
+
+        Some(Self {
+            url: req.url.clone(),
+            etag: etag.to_string(),
+            total,
+        })
+    }
+}
+
+/// Removes the resume state, if present.
+pub(crate) async fn clear_resume_state(repository: &FileStateRepository<ResumeState>) {
+    if repository.exists().await
+        && let Err(err) = repository.clear().await
+    {
+        error!(
+            error = format!("{:#}", eyre::Report::new(err)),
+            "couldn't remove the download resume state"
+        );
+    }
+}
+
+/// Removes the partial download and its resume state if they weren't written for longer than
+/// the max age.
+///
+/// Returns `true` if the partial download is still resumable.
+pub(crate) async fn remove_expired_download(file_path: &Path, max_age: Duration) -> bool {
+    let repository = ResumeState::repository(file_path);
+
+    if !repository.exists().await {
+        return false;
+    }
+
+    let age = tokio::fs::metadata(file_path)
+        .await
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok());
+
+    if age.is_some_and(|age| age < max_age) {
+        return true;
+    }
+
+    info!(path = %file_path.display(), "removing the expired partial download");
+
+    clear_resume_state(&repository).await;
+
+    if let Err(err) = tokio::fs::remove_file(file_path).await
+        && err.kind() != std::io::ErrorKind::NotFound
+    {
+        error!(
+            path = %file_path.display(),
+            error = format!("{:#}", eyre::Report::new(err)),
+            "couldn't remove the expired partial download"
+        );
+    }
+
+    false
+}
+
+/// Returns the state and the bytes already downloaded, if the download can be resumed.
+pub(crate) async fn resume_offset(
+    repository: &FileStateRepository<ResumeState>,
+    req: &OtaId,
+    file_path: &Path,
+) -> Option<(ResumeState, u64)> {
+    if !repository.exists().await {
+        return None;
+    }
+
+    let state = match repository.read().await {
+        Ok(state) => state,
+        Err(err) => {
+            warn!(
+                error = format!("{:#}", eyre::Report::new(err)),
+                "couldn't read the download resume state"
+            );
+
+            return None;
+        }
+    };
+
+    if state.url != req.url {
+        debug!("partial download of a different bundle");
+
+        return None;
+    }
+
+    let downloaded = tokio::fs::metadata(file_path).await.ok()?.len();
+
+    (downloaded > 0 && downloaded < state.total).then_some((state, downloaded))
+}
+
+/// Response to a resume request.
+#[derive(Debug)]
+pub(crate) enum Resumed {
+    /// The server sent the missing bytes.
+    Partial(reqwest::Response),
+    /// The server sent the full bundle, since it changed or ranges are not supported.
+    Full(reqwest::Response),
+    /// The partial file cannot be resumed and the download needs to be restarted.
+    Restart,
+}
+
+/// Requests the missing bytes of the bundle.
+pub(crate) async fn resume_request(
+    client: &reqwest::Client,
+    state: &ResumeState,
+    offset: u64,
+) -> Result<Resumed, OtaError> {
+    info!(offset, total = state.total, "resuming download");
+
+    let mut request = client
+        .get(&state.url)
+        .header(RANGE, format!("bytes={offset}-"));
+
+    match HeaderValue::from_str(&state.etag) {
+        Ok(etag) => request = request.header(IF_RANGE, etag),
+        Err(err) => {
+            error!(error = %err, "invalid ETag in the resume state");
+
+            return Ok(Resumed::Restart);
+        }
+    }
+
+    let response = request.send().await.map_err(|err| {
+        let message = "Error downloading update".to_string();
+        error!("{message}: {err:?}");
+        OtaError::Network(message)
+    })?;
+
+    match response.status() {
+        StatusCode::PARTIAL_CONTENT => {
+            let range = response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_content_range);
+
+            match range {
+                Some((start, total)) if start == offset && total == state.total => {
+                    Ok(Resumed::Partial(response))
+                }
+                range => {
+                    warn!(
+                        ?range,
+                        offset, "unexpected content range, restarting download"
+                    );
+
+                    Ok(Resumed::Restart)
+                }
+            }
+        }
+        StatusCode::OK => {
+            info!("bundle changed or range not supported, restarting download");
+
+            Ok(Resumed::Full(response))
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            info!("range not satisfiable, restarting download");
+
+            Ok(Resumed::Restart)
+        }
+        status => {
+            error!(%status, "unexpected status resuming the download");
+
+            Err(OtaError::Http {
+                status: status.as_u16(),
+                message: "Unexpected status resuming the download".to_string(),
+            })
+        }
+    }
+}
+
+/// Parses the start and total length from a `bytes start-end/total` content range.
+fn parse_content_range(value: &str) -> Option<(u64, u64)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, end) = range.split_once('-')?;
+
+    let start = start.parse().ok()?;
+    let end: u64 = end.parse().ok()?;
+    let total = total.parse().ok()?;
+
+    // The range must end with the file
+    (end.checked_add(1) == Some(total)).then_some((start, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_content_range() {
+        assert_eq!(
+            parse_content_range("bytes 512-1023/1024"),
+            Some((512, 1024))
+        );
+        assert_eq!(parse_content_range("bytes 512-1000/1024"), None);
+        assert_eq!(parse_content_range("bytes 512-1023/*"), None);
+        assert_eq!(parse_content_range("512-1023/1024"), None);
+    }
+
+    #[test]
+    fn should_store_state_next_to_file() {
+        let repository = ResumeState::repository(Path::new("/var/tmp/edgehog/update.bin"));
+
+        assert_eq!(
+            repository.path,
+            PathBuf::from("/var/tmp/edgehog/update.bin.resume.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn should_remove_expired_download() {
+        let dir = tempdir::TempDir::new("should_remove_expired_download").unwrap();
+        let path = dir.path().join("update.bin");
+        let repository = ResumeState::repository(&path);
+
+        tokio::fs::write(&path, b"ab").await.unwrap();
+        repository
+            .write(&ResumeState {
+                url: "https://example.com/ota.bin".to_string(),
+                etag: "\"bundle-v1\"".to_string(),
+                total: 5,
+            })
+            .await
+            .unwrap();
+
+        assert!(remove_expired_download(&path, Duration::from_secs(3600)).await);
+        assert!(path.exists());
+
+        assert!(!remove_expired_download(&path, Duration::ZERO).await);
+        assert!(!path.exists());
+        assert!(!repository.exists().await);
+    }
+}
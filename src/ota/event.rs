@@ -20,20 +20,82 @@
 
 use std::ops::Deref;
 
-use astarte_device_sdk::{AstarteData, FromEvent, types::TypeError};
+use astarte_device_sdk::event::FromEventError;
+use astarte_device_sdk::{AstarteData, DeviceEvent, FromEvent, Value, types::TypeError};
 use tracing::error;
 use uuid::Uuid;
 
+use super::verify::BundleCheck;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtaRequest {
+    pub operation: OtaOperation,
+    pub url: String,
+    pub uuid: OtaUuid,
+    /// Optional digest and signature of the bundle
+    pub check: BundleCheck,
+    /// Optional HTTP headers to stream the bundle, in the `Name: value` format
+    pub http_headers: Vec<String>,
+}
+
+/// Mandatory fields of the [`OtaRequest`]
 #[derive(Debug, Clone, FromEvent, PartialEq, Eq)]
 #[from_event(
     interface = "io.edgehog.devicemanager.OTARequest",
     path = "/request",
     aggregation = "object"
 )]
-pub struct OtaRequest {
-    pub operation: OtaOperation,
-    pub url: String,
-    pub uuid: OtaUuid,
+struct RequestFields {
+    operation: OtaOperation,
+    url: String,
+    uuid: OtaUuid,
+}
+
+impl FromEvent for OtaRequest {
+    type Err = FromEventError;
+
+    fn from_event(mut event: DeviceEvent) -> Result<Self, Self::Err> {
+        let (digest, signature, http_headers) = match &mut event.data {
+            Value::Object { data, .. } => (
+                data.remove("digest"),
+                data.remove("signature"),
+                data.remove("httpHeaders"),
+            ),
+            Value::Individual { .. } | Value::Property(_) => (None, None, None),
+        };
+
+        let RequestFields {
+            operation,
+            url,
+            uuid,
+        } = RequestFields::from_event(event)?;
+
+        let digest = digest.map(String::try_from).transpose()?;
+        let signature = signature.map(String::try_from).transpose()?;
+        let http_headers = http_headers
+            .map(Vec::<String>::try_from)
+            .transpose()?
+            .unwrap_or_default();
+
+        let check = BundleCheck::from_fields(digest, signature).map_err(|err| {
+            error!(
+                error = format!("{err:#}"),
+                "couldn't parse the bundle check"
+            );
+
+            TypeError::Conversion {
+                ctx: format!("couldn't parse the bundle check: {err:#}"),
+            }
+        })?;
+
+        Ok(Self {
+            operation,
+            url,
+            uuid,
+            check,
+            http_headers,
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -75,8 +137,20 @@ impl TryFrom<AstarteData> for OtaUuid {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OtaOperation {
+    /// Download, install and reboot
     Update,
+    /// Cancel the update in progress or the staged one
     Cancel,
+    /// Download the bundle and keep it staged until activated
+    Stage,
+    /// Install the staged bundle and reboot
+    Activate,
+    /// Install a bundle copied in the local drop directory and reboot
+    ///
+    /// It's never received from Astarte, the requests are created by the [`LocalWatcher`].
+    ///
+    /// [`LocalWatcher`]: super::local::LocalWatcher
+    Local,
 }
 
 impl TryFrom<AstarteData> for OtaOperation {
@@ -88,6 +162,8 @@ impl TryFrom<AstarteData> for OtaOperation {
         match value.as_str() {
             "Update" => Ok(Self::Update),
             "Cancel" => Ok(Self::Cancel),
+            "Stage" => Ok(Self::Stage),
+            "Activate" => Ok(Self::Activate),
             _ => {
                 error!(value, "unrecognize Ota operation value");
 
@@ -138,10 +214,54 @@ mod tests {
                 operation: OtaOperation::Update,
                 url: url.to_string(),
                 uuid: uuid.into(),
+                check: BundleCheck::default(),
+                http_headers: Vec::new(),
             })
         );
     }
 
+    #[test]
+    fn should_convert_request_headers() {
+        let uuid = Uuid::try_parse("04bf491c-af94-4e9d-813f-ebeebfb856a6").unwrap();
+
+        let data = AstarteObject::from_iter([
+            ("operation".to_string(), "Update".into()),
+            ("url".to_string(), "http://example.com".into()),
+            ("uuid".to_string(), uuid.to_string().into()),
+            (
+                "httpHeaders".to_string(),
+                AstarteData::StringArray(vec!["Authorization: Bearer token".to_string()]),
+            ),
+        ]);
+
+        let res = OtaRequest::from_event(DeviceEvent {
+            interface: "io.edgehog.devicemanager.OTARequest".to_string(),
+            path: "/request".to_string(),
+            data: Value::Object {
+                data,
+                timestamp: Utc::now(),
+            },
+        })
+        .unwrap();
+
+        assert_eq!(
+            res.http_headers,
+            vec!["Authorization: Bearer token".to_string()]
+        );
+    }
+
+    #[test]
+    fn should_convert_staged_operations() {
+        assert_eq!(
+            OtaOperation::try_from(AstarteData::from("Stage")).unwrap(),
+            OtaOperation::Stage
+        );
+        assert_eq!(
+            OtaOperation::try_from(AstarteData::from("Activate")).unwrap(),
+            OtaOperation::Activate
+        );
+    }
+
     #[test]
     fn telemetry_missing_uuid() {
         let data = AstarteObject::from_iter([(
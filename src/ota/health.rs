@@ -0,0 +1,333 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Health checks run after booting the updated slot, before marking it good.
+
+use std::fmt::{Debug, Display};
+use std::process::Stdio;
+use std::time::Duration;
+
+use astarte_device_sdk::DeviceClient;
+use astarte_device_sdk::chrono::Utc;
+use astarte_device_sdk::store::SqliteStore;
+use astarte_device_sdk::transport::mqtt::Mqtt;
+use eyre::{OptionExt, WrapErr, bail, ensure};
+use tokio::process::Command;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+use url::Url;
+use zbus::proxy;
+use zbus::zvariant::OwnedObjectPath;
+
+use crate::http::astarte_health;
+
+use super::config::{HealthCheck, HealthConfig};
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait Systemd {
+    /// Loads the unit, returning its object path.
+    fn load_unit(&self, name: &str) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Unit",
+    default_service = "org.freedesktop.systemd1"
+)]
+trait Unit {
+    #[zbus(property)]
+    fn active_state(&self) -> zbus::Result<String>;
+}
+
+/// Check that didn't pass within the timeout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthFailure {
+    pub check: HealthCheck,
+    pub reason: String,
+}
+
+impl Display for HealthFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} failed: {}", self.check, self.reason)
+    }
+}
+
+/// Runs the configured health checks.
+#[derive(Default)]
+pub struct HealthGate {
+    config: HealthConfig,
+    pairing_url: Option<Url>,
+    /// Client connected directly to the Astarte MQTT broker.
+    mqtt: Option<DeviceClient<Mqtt<SqliteStore>>>,
+    #[cfg(feature = "containers")]
+    containers:
+        Option<std::sync::Arc<tokio::sync::OnceCell<edgehog_containers::local::ContainerHandle>>>,
+}
+
+impl Debug for HealthGate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HealthGate")
+            .field("config", &self.config)
+            .field("pairing_url", &self.pairing_url)
+            .field("mqtt", &self.mqtt.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl HealthGate {
+    pub fn new(
+        config: HealthConfig,
+        pairing_url: Option<Url>,
+        mqtt: Option<DeviceClient<Mqtt<SqliteStore>>>,
+        #[cfg(feature = "containers")] containers: std::sync::Arc<
+            tokio::sync::OnceCell<edgehog_containers::local::ContainerHandle>,
+        >,
+    ) -> Self {
+        Self {
+            config,
+            pairing_url,
+            mqtt,
+            #[cfg(feature = "containers")]
+            containers: Some(containers),
+        }
+    }
+
+    /// Waits for all the checks to pass.
+    ///
+    /// The failed checks are run again every interval, until the timeout expires.
+    pub async fn wait_healthy(&self) -> Result<(), HealthFailure> {
+        if self.config.checks.is_empty() {
+            return Ok(());
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(self.config.timeout_secs);
+        let interval = Duration::from_secs(self.config.interval_secs);
+
+        let mut pending: Vec<&HealthCheck> = self.config.checks.iter().collect();
+
+        loop {
+            let mut failures = Vec::new();
+
+            for check in pending {
+                match tokio::time::timeout_at(deadline, self.run(check)).await {
+                    Ok(Ok(())) => {
+                        info!(%check, "health check passed");
+                    }
+                    Ok(Err(err)) => {
+                        let reason = format!("{err:#}");
+
+                        debug!(%check, reason, "health check failed");
+
+                        failures.push(HealthFailure {
+                            check: check.clone(),
+                            reason,
+                        });
+                    }
+                    Err(_) => {
+                        return Err(HealthFailure {
+                            check: check.clone(),
+                            reason: "timed out".to_string(),
+                        });
+                    }
+                }
+            }
+
+            let Some(first) = failures.first() else {
+                return Ok(());
+            };
+
+            if Instant::now() + interval >= deadline {
+                warn!(failed = failures.len(), "health checks timed out");
+
+                return Err(first.clone());
+            }
+
+            tokio::time::sleep(interval).await;
+
+            pending = self
+                .config
+                .checks
+                .iter()
+                .filter(|check| failures.iter().any(|failure| failure.check == **check))
+                .collect();
+        }
+    }
+
+    async fn run(&self, check: &HealthCheck) -> eyre::Result<()> {
+        match check {
+            HealthCheck::Astarte => self.astarte().await,
+            HealthCheck::Systemd { unit } => systemd(unit).await,
+            HealthCheck::Containers { deployment } => self.containers(*deployment).await,
+            HealthCheck::Script { path } => {
+                let status = Command::new(path)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .kill_on_drop(true)
+                    .status()
+                    .await
+                    .wrap_err("couldn't run the script")?;
+
+                ensure!(status.success(), "script exited with {status}");
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks that the device connected to Astarte.
+    ///
+    /// With the MQTT client, the certificate is fetched only when the connection to the broker is
+    /// created, so the check passes once the device connected with valid credentials. Otherwise,
+    /// it checks that the Astarte pairing API can be reached.
+    async fn astarte(&self) -> eyre::Result<()> {
+        if let Some(client) = &self.mqtt {
+            let valid = client
+                .is_valid_at(Utc::now())
+                .await
+                .ok_or_eyre("not connected to the MQTT broker")?;
+
+            ensure!(valid, "the MQTT certificate is expired");
+
+            return Ok(());
+        }
+
+        let url = self
+            .pairing_url
+            .as_ref()
+            .ok_or_eyre("the pairing url is not configured")?;
+
+        astarte_health(url).await
+    }
+
+    #[cfg(feature = "containers")]
+    async fn containers(&self, deployment: uuid::Uuid) -> eyre::Result<()> {
+        let handle = self
+            .containers
+            .as_ref()
+            .and_then(|containers| containers.get())
+            .ok_or_eyre("the container service is not available")?;
+
+        match handle.deployment_running(deployment).await? {
+            Some(true) => Ok(()),
+            Some(false) => bail!("not all the containers are running"),
+            None => bail!("deployment not found"),
+        }
+    }
+
+    #[cfg(not(feature = "containers"))]
+    async fn containers(&self, _deployment: uuid::Uuid) -> eyre::Result<()> {
+        bail!("the containers feature is not enabled")
+    }
+}
+
+/// Checks that the systemd unit is active.
+async fn systemd(unit: &str) -> eyre::Result<()> {
+    let connection = zbus::Connection::system().await?;
+    let manager = SystemdProxy::new(&connection).await?;
+
+    let path = manager
+        .load_unit(unit)
+        .await
+        .wrap_err("couldn't load the unit")?;
+
+    let state = UnitProxy::builder(&connection)
+        .path(path)?
+        .build()
+        .await?
+        .active_state()
+        .await
+        .wrap_err("couldn't read the unit state")?;
+
+    ensure!(state == "active", "unit is {state}");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn script(dir: &Path, code: i32) -> PathBuf {
+        let path = dir.join(format!("health-{code}.sh"));
+
+        std::fs::write(&path, format!("#!/bin/sh\nexit {code}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        path
+    }
+
+    fn gate(checks: Vec<HealthCheck>) -> HealthGate {
+        HealthGate {
+            config: HealthConfig {
+                timeout_secs: 1,
+                interval_secs: 1,
+                checks,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_pass_without_checks() {
+        gate(Vec::new()).wait_healthy().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_pass_script_check() {
+        let dir = TempDir::new("edgehog-health").unwrap();
+
+        let path = script(dir.path(), 0);
+
+        gate(vec![HealthCheck::Script { path }])
+            .wait_healthy()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_fail_after_timeout() {
+        let dir = TempDir::new("edgehog-health").unwrap();
+
+        let ok = script(dir.path(), 0);
+        let failing = script(dir.path(), 3);
+
+        let err = gate(vec![
+            HealthCheck::Script { path: ok },
+            HealthCheck::Script {
+                path: failing.clone(),
+            },
+            HealthCheck::Astarte,
+        ])
+        .wait_healthy()
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.check, HealthCheck::Script { path: failing });
+        assert_eq!(err.reason, "script exited with exit status: 3");
+    }
+}
@@ -0,0 +1,649 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! History of the OTA updates, persisted in the store and published as a property.
+
+use astarte_device_sdk::AstarteData;
+use astarte_device_sdk::chrono::{DateTime, Utc};
+use diesel::{delete, insert_into, prelude::*};
+use edgehog_store::conversions::SqlUuid;
+use edgehog_store::db::{self, Handle};
+use edgehog_store::models::ota::OtaUpdate;
+use edgehog_store::models::ota::outcome::OtaOutcome;
+use edgehog_store::schema::runtime::ota_history;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, instrument};
+use uuid::Uuid;
+
+use crate::Client;
+use crate::data::set_property;
+use crate::ota::config::HistoryConfig;
+use crate::ota::rauc::BundleInfo;
+use crate::ota::{OtaError, OtaStatus};
+
+const INTERFACE: &str = "io.edgehog.devicemanager.OTAHistory";
+
+/// Change of the history sent to the [`HistoryPublisher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryChange {
+    /// The update was recorded or modified
+    Updated(OtaUpdate),
+    /// The update was removed from the history
+    Removed(OtaUpdate),
+}
+
+/// Records the updates in the store.
+///
+/// Only the last [`HistoryConfig::max_entries`] updates are kept. The history is disabled when
+/// created with [`Default`].
+#[derive(Debug, Clone, Default)]
+pub struct OtaHistory {
+    db: Option<Handle>,
+    publisher_tx: Option<mpsc::Sender<HistoryChange>>,
+    config: HistoryConfig,
+}
+
+impl OtaHistory {
+    pub fn new(
+        db: Handle,
+        publisher_tx: mpsc::Sender<HistoryChange>,
+        config: HistoryConfig,
+    ) -> Self {
+        Self {
+            db: Some(db),
+            publisher_tx: Some(publisher_tx),
+            config,
+        }
+    }
+
+    /// Removes the oldest updates exceeding the configured entries.
+    ///
+    /// This is called at startup, since the configuration could have changed.
+    pub async fn prune(&self) {
+        let Some(db) = &self.db else {
+            return;
+        };
+
+        let max_entries = self.config.max_entries;
+
+        let res = db
+            .for_write(move |writer| remove_oldest(writer, max_entries))
+            .await;
+
+        match res {
+            Ok(removed) => {
+                self.publish(removed.into_iter().map(HistoryChange::Removed))
+                    .await
+            }
+            Err(error) => error!(
+                error = format!("{:#}", eyre::Report::new(error)),
+                "couldn't prune the ota history"
+            ),
+        }
+    }
+
+    /// Records the time the update entered the status, and the outcome.
+    pub async fn record_status(&self, status: &OtaStatus) {
+        let Some(ota_id) = status.ota_id() else {
+            return;
+        };
+
+        // The transient errors are published as events only
+        if matches!(status, OtaStatus::Init(_) | OtaStatus::Error(..)) {
+            return;
+        }
+
+        let status = status.clone();
+        let now = Utc::now().timestamp();
+
+        self.modify(ota_id.uuid, ota_id.url.clone(), move |update| {
+            apply_status(update, &status, now)
+        })
+        .await;
+    }
+
+    /// Records the version and compatible of the bundle.
+    pub async fn record_bundle(&self, uuid: Uuid, info: &BundleInfo) {
+        let version = info.version.clone();
+        let compatible = info.compatible.clone();
+
+        self.modify(uuid, String::new(), move |update| {
+            update.bundle_version = Some(version);
+            update.bundle_compatible = Some(compatible);
+        })
+        .await;
+    }
+
+    /// Records the slot booted before the update.
+    pub async fn record_from_slot(&self, uuid: Uuid, slot: &str) {
+        let slot = slot.to_string();
+
+        self.modify(uuid, String::new(), move |update| {
+            update.from_slot = Some(slot)
+        })
+        .await;
+    }
+
+    /// Records the slot booted after the update.
+    pub async fn record_to_slot(&self, uuid: Uuid, slot: &str) {
+        let slot = slot.to_string();
+
+        self.modify(uuid, String::new(), move |update| {
+            update.to_slot = Some(slot)
+        })
+        .await;
+    }
+
+    /// Creates or updates the record and sends it to the publisher, with the updates removed to
+    /// keep the history in the configured size.
+    ///
+    /// The errors are only logged, since the history must not block the update.
+    #[instrument(skip(self, f))]
+    async fn modify<F>(&self, uuid: Uuid, url: String, f: F)
+    where
+        F: FnOnce(&mut OtaUpdate) + Send + 'static,
+    {
+        let Some(db) = &self.db else {
+            return;
+        };
+
+        let max_entries = self.config.max_entries;
+
+        let res = db
+            .for_write(move |writer| {
+                let id = SqlUuid::new(uuid);
+
+                let mut update = OtaUpdate::query()
+                    .filter(ota_history::id.eq(id))
+                    .first(writer)
+                    .optional()?
+                    .unwrap_or_else(|| OtaUpdate::new(id, url, Utc::now().timestamp()));
+
+                (f)(&mut update);
+
+                insert_into(ota_history::table)
+                    .values(&update)
+                    .on_conflict(ota_history::id)
+                    .do_update()
+                    .set(&update)
+                    .execute(writer)?;
+
+                let removed = remove_oldest(writer, max_entries)?;
+
+                Ok((update, removed))
+            })
+            .await;
+
+        let (update, removed) = match res {
+            Ok(res) => res,
+            Err(error) => {
+                error!(
+                    error = format!("{:#}", eyre::Report::new(error)),
+                    "couldn't record the ota history"
+                );
+
+                return;
+            }
+        };
+
+        debug!(outcome = %update.outcome, removed = removed.len(), "ota history recorded");
+
+        let changes = std::iter::once(HistoryChange::Updated(update))
+            .chain(removed.into_iter().map(HistoryChange::Removed));
+
+        self.publish(changes).await;
+    }
+
+    async fn publish(&self, changes: impl IntoIterator<Item = HistoryChange>) {
+        let Some(publisher_tx) = &self.publisher_tx else {
+            return;
+        };
+
+        for change in changes {
+            if publisher_tx.send(change).await.is_err() {
+                error!("ota history publisher dropped");
+
+                return;
+            }
+        }
+    }
+}
+
+/// Deletes the updates older than the last `max_entries`, and returns them.
+fn remove_oldest(writer: &mut SqliteConnection, max_entries: usize) -> db::Result<Vec<OtaUpdate>> {
+    let offset = i64::try_from(max_entries).unwrap_or(i64::MAX);
+
+    let removed: Vec<OtaUpdate> = OtaUpdate::query()
+        .order_by(ota_history::acknowledged_at.desc())
+        .offset(offset)
+        .load(writer)?;
+
+    if removed.is_empty() {
+        return Ok(removed);
+    }
+
+    let ids: Vec<SqlUuid> = removed.iter().map(|update| update.id).collect();
+
+    delete(ota_history::table)
+        .filter(ota_history::id.eq_any(ids))
+        .execute(writer)?;
+
+    Ok(removed)
+}
+
+/// Sets the timestamp of the status and the outcome of the completed update.
+fn apply_status(update: &mut OtaUpdate, status: &OtaStatus, now: i64) {
+    match status {
+        OtaStatus::Downloading(..) => {
+            update.downloading_at.get_or_insert(now);
+        }
+        OtaStatus::Deploying(..) => {
+            update.deploying_at.get_or_insert(now);
+        }
+        OtaStatus::Deployed(_) => update.deployed_at = Some(now),
+        OtaStatus::Staged(_) => {
+            update.staged_at.get_or_insert(now);
+        }
+        OtaStatus::Rebooting(_) => update.rebooting_at = Some(now),
+        OtaStatus::Success(_) => {
+            update.outcome = OtaOutcome::Success;
+            update.completed_at = Some(now);
+        }
+        OtaStatus::Failure(error, _) => {
+            update.outcome = match error {
+                OtaError::Canceled => OtaOutcome::Canceled,
+                OtaError::SystemRollback(_) => OtaOutcome::RolledBack,
+                _ => OtaOutcome::Failure,
+            };
+            update.message = Some(error.to_string());
+            update.completed_at = Some(now);
+        }
+        OtaStatus::Idle
+        | OtaStatus::Init(_)
+        | OtaStatus::NoPendingOta
+        | OtaStatus::Acknowledged(_)
+        | OtaStatus::Rebooted
+        | OtaStatus::Error(..) => {}
+    }
+}
+
+/// Publishes the history on the `io.edgehog.devicemanager.OTAHistory` property interface.
+#[derive(Debug)]
+pub struct HistoryPublisher<C> {
+    client: C,
+}
+
+impl<C> HistoryPublisher<C> {
+    pub fn new(client: C) -> Self {
+        Self { client }
+    }
+
+    /// Publishes the changes of the history as they are recorded.
+    ///
+    /// The stored updates were already published when recorded, and the properties are kept by
+    /// Astarte, so they are not sent again at startup.
+    pub async fn run(
+        mut self,
+        mut rx: mpsc::Receiver<HistoryChange>,
+        cancel: CancellationToken,
+    ) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        while let Some(Some(change)) = cancel.run_until_cancelled(rx.recv()).await {
+            match change {
+                HistoryChange::Updated(update) => self.publish(&update).await,
+                HistoryChange::Removed(update) => self.unset(&update).await,
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn publish(&mut self, update: &OtaUpdate)
+    where
+        C: Client + Send + Sync,
+    {
+        for (path, data) in properties(update) {
+            set_property(&mut self.client, INTERFACE, &path, data).await;
+        }
+    }
+
+    async fn unset(&mut self, update: &OtaUpdate)
+    where
+        C: Client + Send + Sync,
+    {
+        for (path, _) in properties(update) {
+            if let Err(err) = self.client.unset_property(INTERFACE, &path).await {
+                error!(
+                    error = format!("{:#}", eyre::Report::new(err)),
+                    path, "failed to unset property",
+                )
+            }
+        }
+    }
+}
+
+/// Properties of the update, the missing values are not set.
+fn properties(update: &OtaUpdate) -> Vec<(String, AstarteData)> {
+    let texts = [
+        ("url", Some(&update.url)),
+        ("bundleVersion", update.bundle_version.as_ref()),
+        ("bundleCompatible", update.bundle_compatible.as_ref()),
+        ("fromSlot", update.from_slot.as_ref()),
+        ("toSlot", update.to_slot.as_ref()),
+        ("message", update.message.as_ref()),
+    ];
+
+    let timestamps = [
+        ("acknowledgedAt", Some(update.acknowledged_at)),
+        ("downloadingAt", update.downloading_at),
+        ("deployingAt", update.deploying_at),
+        ("deployedAt", update.deployed_at),
+        ("stagedAt", update.staged_at),
+        ("rebootingAt", update.rebooting_at),
+        ("completedAt", update.completed_at),
+    ];
+
+    let id = update.id;
+
+    let texts = texts
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, AstarteData::from(value.as_str()))));
+
+    let timestamps = timestamps.into_iter().filter_map(|(name, value)| {
+        value
+            .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+            .map(|value| (name, AstarteData::from(value)))
+    });
+
+    std::iter::once(("outcome", AstarteData::from(update.outcome.to_string())))
+        .chain(texts)
+        .chain(timestamps)
+        .map(|(name, data)| (format!("/{id}/{name}"), data))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use astarte_device_sdk::store::SqliteStore;
+    use astarte_device_sdk::transport::mqtt::Mqtt;
+    use astarte_device_sdk_mock::MockDeviceClient;
+    use mockall::predicate;
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use crate::ota::{DeployProgress, OtaId};
+
+    use super::*;
+
+    async fn mock_history(
+        max_entries: usize,
+    ) -> (OtaHistory, mpsc::Receiver<HistoryChange>, TempDir) {
+        let dir = TempDir::new("edgehog-ota-history").unwrap();
+        let db = Handle::open(dir.path().join("state.db")).await.unwrap();
+
+        let (tx, rx) = mpsc::channel(16);
+
+        (
+            OtaHistory::new(db, tx, HistoryConfig { max_entries }),
+            rx,
+            dir,
+        )
+    }
+
+    async fn insert(history: &OtaHistory, update: OtaUpdate) {
+        history
+            .db
+            .as_ref()
+            .unwrap()
+            .for_write(move |writer| {
+                insert_into(ota_history::table)
+                    .values(&update)
+                    .execute(writer)?;
+
+                Ok(())
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn read(history: &OtaHistory, uuid: Uuid) -> OtaUpdate {
+        let id = SqlUuid::new(uuid);
+
+        history
+            .db
+            .as_ref()
+            .unwrap()
+            .for_read(move |reader| {
+                Ok(OtaUpdate::query()
+                    .filter(ota_history::id.eq(id))
+                    .first(reader)?)
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_record_update() {
+        let (history, mut rx, _dir) = mock_history(10).await;
+
+        let ota_id = OtaId {
+            uuid: Uuid::new_v4(),
+            url: "https://example.com/ota.bin".to_string(),
+        };
+
+        history
+            .record_status(&OtaStatus::Acknowledged(ota_id.clone()))
+            .await;
+        history
+            .record_status(&OtaStatus::Downloading(ota_id.clone(), 0))
+            .await;
+        history
+            .record_bundle(
+                ota_id.uuid,
+                &BundleInfo {
+                    compatible: "rauc-demo-x86".to_string(),
+                    version: "2".to_string(),
+                },
+            )
+            .await;
+        history
+            .record_status(&OtaStatus::Deploying(
+                ota_id.clone(),
+                DeployProgress::default(),
+            ))
+            .await;
+        history.record_from_slot(ota_id.uuid, "A").await;
+        history
+            .record_status(&OtaStatus::Deployed(ota_id.clone()))
+            .await;
+        history
+            .record_status(&OtaStatus::Rebooting(ota_id.clone()))
+            .await;
+
+        // The request URL is not persisted across the reboot
+        let rebooted = OtaId {
+            uuid: ota_id.uuid,
+            url: String::new(),
+        };
+
+        history.record_to_slot(ota_id.uuid, "B").await;
+        history.record_status(&OtaStatus::Success(rebooted)).await;
+
+        let update = read(&history, ota_id.uuid).await;
+
+        assert_eq!(update.url, ota_id.url);
+        assert_eq!(update.bundle_version.as_deref(), Some("2"));
+        assert_eq!(update.bundle_compatible.as_deref(), Some("rauc-demo-x86"));
+        assert_eq!(update.from_slot.as_deref(), Some("A"));
+        assert_eq!(update.to_slot.as_deref(), Some("B"));
+        assert_eq!(update.outcome, OtaOutcome::Success);
+        assert!(update.downloading_at.is_some());
+        assert!(update.deploying_at.is_some());
+        assert!(update.deployed_at.is_some());
+        assert!(update.rebooting_at.is_some());
+        assert!(update.completed_at.is_some());
+        assert_eq!(update.staged_at, None);
+        assert_eq!(update.message, None);
+
+        let mut last = None;
+        while let Ok(update) = rx.try_recv() {
+            last = Some(update);
+        }
+        assert_eq!(last, Some(HistoryChange::Updated(update)));
+    }
+
+    #[tokio::test]
+    async fn should_remove_oldest() {
+        let (history, mut rx, _dir) = mock_history(2).await;
+
+        let updates: Vec<OtaUpdate> = (1..=3)
+            .map(|secs| OtaUpdate::new(Uuid::new_v4(), "https://example.com/ota.bin", secs))
+            .collect();
+
+        for update in &updates {
+            insert(&history, update.clone()).await;
+        }
+
+        history.prune().await;
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            HistoryChange::Removed(updates[0].clone())
+        );
+        assert!(rx.try_recv().is_err());
+
+        let kept = history
+            .db
+            .as_ref()
+            .unwrap()
+            .for_read(|reader| Ok(ota_history::table.count().get_result::<i64>(reader)?))
+            .await
+            .unwrap();
+
+        assert_eq!(kept, 2);
+
+        // A new update removes the oldest one
+        history
+            .record_status(&OtaStatus::Acknowledged(OtaId {
+                uuid: Uuid::new_v4(),
+                url: "https://example.com/ota.bin".to_string(),
+            }))
+            .await;
+
+        assert!(matches!(rx.try_recv().unwrap(), HistoryChange::Updated(_)));
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            HistoryChange::Removed(updates[1].clone())
+        );
+    }
+
+    #[tokio::test]
+    async fn should_unset_removed() {
+        let uuid = Uuid::new_v4();
+        let update = OtaUpdate::new(uuid, "https://example.com/ota.bin", 60);
+
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+
+        for name in ["outcome", "url", "acknowledgedAt"] {
+            client
+                .expect_unset_property()
+                .with(
+                    predicate::eq(INTERFACE),
+                    predicate::eq(format!("/{uuid}/{name}")),
+                )
+                .once()
+                .returning(|_, _| Ok(()));
+        }
+
+        let (tx, rx) = mpsc::channel(1);
+        tx.send(HistoryChange::Removed(update)).await.unwrap();
+        drop(tx);
+
+        HistoryPublisher::new(client)
+            .run(rx, CancellationToken::new())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_record_rollback() {
+        let (history, _rx, _dir) = mock_history(10).await;
+
+        let ota_id = OtaId {
+            uuid: Uuid::new_v4(),
+            url: "https://example.com/ota.bin".to_string(),
+        };
+
+        history
+            .record_status(&OtaStatus::Acknowledged(ota_id.clone()))
+            .await;
+        history
+            .record_status(&OtaStatus::Error(
+                OtaError::Network("timeout".to_string()),
+                ota_id.clone(),
+            ))
+            .await;
+        history
+            .record_status(&OtaStatus::Failure(
+                OtaError::SystemRollback("Unable to switch slot".to_string()),
+                Some(ota_id.clone()),
+            ))
+            .await;
+
+        let update = read(&history, ota_id.uuid).await;
+
+        assert_eq!(update.outcome, OtaOutcome::RolledBack);
+        assert_eq!(
+            update.message,
+            Some(OtaError::SystemRollback("Unable to switch slot".to_string()).to_string())
+        );
+        assert!(update.completed_at.is_some());
+    }
+
+    #[test]
+    fn should_map_properties() {
+        let uuid = Uuid::new_v4();
+
+        let mut update = OtaUpdate::new(uuid, "https://example.com/ota.bin", 60);
+        update.from_slot = Some("A".to_string());
+        update.outcome = OtaOutcome::Failure;
+        update.completed_at = Some(120);
+
+        let exp = vec![
+            (format!("/{uuid}/outcome"), AstarteData::from("Failure")),
+            (
+                format!("/{uuid}/url"),
+                AstarteData::from("https://example.com/ota.bin"),
+            ),
+            (format!("/{uuid}/fromSlot"), AstarteData::from("A")),
+            (
+                format!("/{uuid}/acknowledgedAt"),
+                AstarteData::from(DateTime::<Utc>::from_timestamp(60, 0).unwrap()),
+            ),
+            (
+                format!("/{uuid}/completedAt"),
+                AstarteData::from(DateTime::<Utc>::from_timestamp(120, 0).unwrap()),
+            ),
+        ];
+
+        assert_eq!(properties(&update), exp);
+    }
+}
@@ -0,0 +1,371 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Watches a local directory for bundles to install without a request from Astarte.
+//!
+//! Used on sites without connectivity, where the bundles are copied from a USB stick.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use eyre::Context;
+use rustix::fs::Access;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::io::digest::FileDigest;
+
+use super::event::{OtaOperation, OtaRequest, OtaUuid};
+use super::ota_handler::OtaInProgress;
+use super::verify::{BundleCheck, BundleDigest};
+
+/// Size and modification time of a file, they don't change once it's completely copied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    len: u64,
+    modified: SystemTime,
+}
+
+/// Submits the bundles dropped in the directory to the [`OtaHandler`](super::ota_handler::OtaHandler).
+#[derive(Debug)]
+pub(crate) struct LocalWatcher {
+    directory: PathBuf,
+    interval: Duration,
+    extensions: Vec<String>,
+    local_tx: mpsc::Sender<OtaRequest>,
+    flag: OtaInProgress,
+    /// Files found by the last scan
+    seen: HashMap<PathBuf, Stamp>,
+    /// Bundles already submitted, they are removed from the directory only once installed
+    submitted: HashMap<PathBuf, Stamp>,
+}
+
+impl LocalWatcher {
+    pub(crate) fn new(
+        directory: PathBuf,
+        interval: Duration,
+        extensions: Vec<String>,
+        local_tx: mpsc::Sender<OtaRequest>,
+        flag: OtaInProgress,
+    ) -> Self {
+        Self {
+            directory,
+            interval,
+            extensions,
+            local_tx,
+            flag,
+            seen: HashMap::new(),
+            submitted: HashMap::new(),
+        }
+    }
+
+    pub(crate) async fn run(mut self, cancel: CancellationToken) -> eyre::Result<()> {
+        info!(directory = %self.directory.display(), "watching for local bundles");
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            // The bundle is picked up after the update in progress
+            if self.flag.in_progress() {
+                continue;
+            }
+
+            match self.scan().await {
+                Ok(Some((bundle, stamp))) => self.submit(bundle, stamp).await?,
+                Ok(None) => {}
+                // The directory is missing while the USB stick is not mounted
+                Err(error) => {
+                    debug!(%error, directory = %self.directory.display(), "couldn't scan the directory")
+                }
+            }
+        }
+
+        debug!("local bundle watcher stopped");
+
+        Ok(())
+    }
+
+    /// Checks if the file has one of the configured bundle extensions.
+    fn is_bundle(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.extensions.iter().any(|exp| exp == ext))
+    }
+
+    /// Returns the first bundle unchanged since the previous scan.
+    ///
+    /// Hidden and empty files are ignored, since they are still being copied.
+    async fn scan(&mut self) -> io::Result<Option<(PathBuf, Stamp)>> {
+        let mut entries = tokio::fs::read_dir(&self.directory).await?;
+        let mut current = HashMap::new();
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+
+            if entry.file_name().to_string_lossy().starts_with('.') || !self.is_bundle(&path) {
+                continue;
+            }
+
+            let meta = entry.metadata().await?;
+
+            if !meta.is_file() || meta.len() == 0 {
+                continue;
+            }
+
+            let stamp = Stamp {
+                len: meta.len(),
+                modified: meta.modified()?,
+            };
+
+            current.insert(path, stamp);
+        }
+
+        let previous = std::mem::replace(&mut self.seen, current);
+
+        // A bundle copied again is a new one
+        self.submitted
+            .retain(|path, stamp| self.seen.get(path) == Some(stamp));
+
+        let bundle = self
+            .seen
+            .iter()
+            .filter(|(path, stamp)| {
+                previous.get(*path) == Some(*stamp) && self.submitted.get(*path) != Some(*stamp)
+            })
+            .min_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(path, stamp)| (path.clone(), *stamp));
+
+        Ok(bundle)
+    }
+
+    async fn submit(&mut self, bundle: PathBuf, stamp: Stamp) -> eyre::Result<()> {
+        self.submitted.insert(bundle.clone(), stamp);
+
+        let Some(url) = bundle.to_str() else {
+            warn!(bundle = %bundle.display(), "ignoring local bundle with a non UTF-8 path");
+
+            return Ok(());
+        };
+
+        // Otherwise the bundle would be installed again at every boot
+        if let Err(error) = rustix::fs::access(&self.directory, Access::WRITE_OK) {
+            warn!(
+                %error,
+                bundle = %bundle.display(),
+                "ignoring local bundle, it couldn't be removed from the directory once installed"
+            );
+
+            return Ok(());
+        }
+
+        let check = match bundle_check(&bundle).await {
+            Ok(check) => check,
+            Err(error) => {
+                warn!(%error, bundle = %bundle.display(), "ignoring local bundle, couldn't read it");
+
+                return Ok(());
+            }
+        };
+
+        let request = OtaRequest {
+            operation: OtaOperation::Local,
+            url: url.to_string(),
+            uuid: OtaUuid(Uuid::new_v4()),
+            check,
+            http_headers: Vec::new(),
+        };
+
+        info!(uuid = %request.uuid.0, bundle = %bundle.display(), "local bundle found");
+
+        self.local_tx
+            .send(request)
+            .await
+            .wrap_err("couldn't send the local ota request")
+    }
+}
+
+/// Path of the detached signature of a local bundle.
+pub(crate) fn signature_path(bundle: &Path) -> PathBuf {
+    let mut path = OsString::from(bundle);
+    path.push(".sig");
+
+    PathBuf::from(path)
+}
+
+/// Computes the digest of the bundle and reads the signature file, if present.
+///
+/// The digest is checked again on the copy of the bundle, before installing it.
+async fn bundle_check(bundle: &Path) -> eyre::Result<BundleCheck> {
+    let mut file = tokio::fs::File::open(bundle)
+        .await
+        .wrap_err("couldn't open the bundle")?;
+
+    let mut ctx = aws_lc_rs::digest::Context::from(FileDigest::Sha256);
+    let mut buf = vec![0; 64 * 1024];
+
+    loop {
+        let read = file
+            .read(&mut buf)
+            .await
+            .wrap_err("couldn't read the bundle")?;
+
+        if read == 0 {
+            break;
+        }
+
+        ctx.update(&buf[..read]);
+    }
+
+    let digest = BundleDigest {
+        algorithm: FileDigest::Sha256,
+        value: ctx.finish().as_ref().to_vec(),
+    };
+
+    let signature = match tokio::fs::read_to_string(signature_path(bundle)).await {
+        Ok(signature) => {
+            Some(hex::decode(signature.trim()).wrap_err("couldn't decode hex signature")?)
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => return Err(error).wrap_err("couldn't read the signature"),
+    };
+
+    Ok(BundleCheck {
+        digest: Some(digest),
+        signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn mock_watcher(dir: &TempDir) -> (LocalWatcher, mpsc::Receiver<OtaRequest>) {
+        let (local_tx, local_rx) = mpsc::channel(2);
+
+        let watcher = LocalWatcher::new(
+            dir.path().to_owned(),
+            Duration::from_secs(10),
+            vec!["raucb".to_string(), "swu".to_string()],
+            local_tx,
+            OtaInProgress::default(),
+        );
+
+        (watcher, local_rx)
+    }
+
+    #[tokio::test]
+    async fn should_submit_stable_bundle() {
+        let dir = TempDir::new("edgehog-local").unwrap();
+        let (mut watcher, mut local_rx) = mock_watcher(&dir);
+
+        let bundle = dir.path().join("update.raucb");
+        tokio::fs::write(&bundle, b"bundle").await.unwrap();
+
+        // Could still be copying
+        assert!(watcher.scan().await.unwrap().is_none());
+
+        let (path, stamp) = watcher.scan().await.unwrap().unwrap();
+        assert_eq!(path, bundle);
+
+        watcher.submit(path, stamp).await.unwrap();
+
+        let req = local_rx.recv().await.unwrap();
+        assert_eq!(req.operation, OtaOperation::Local);
+        assert_eq!(req.url, bundle.to_str().unwrap());
+        assert_eq!(
+            req.check,
+            BundleCheck {
+                digest: Some(
+                    "sha256:1e6ed65d77d6364eeaed5a745ba5c4985ae2b700dd85d7cf7f027bdf294a33fc"
+                        .parse()
+                        .unwrap()
+                ),
+                signature: None,
+            }
+        );
+
+        // Not removed, but already submitted
+        assert!(bundle.exists());
+        assert!(watcher.scan().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_read_signature() {
+        let dir = TempDir::new("edgehog-local").unwrap();
+
+        let bundle = dir.path().join("update.swu");
+        tokio::fs::write(&bundle, b"bundle").await.unwrap();
+        tokio::fs::write(signature_path(&bundle), "deadbeef\n")
+            .await
+            .unwrap();
+
+        let check = bundle_check(&bundle).await.unwrap();
+
+        assert_eq!(check.signature, Some(vec![0xde, 0xad, 0xbe, 0xef]));
+    }
+
+    #[tokio::test]
+    async fn should_ignore_other_files() {
+        let dir = TempDir::new("edgehog-local").unwrap();
+        let (mut watcher, _local_rx) = mock_watcher(&dir);
+
+        tokio::fs::write(dir.path().join(".update.raucb"), b"bundle")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("empty.raucb"), b"")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("notes.txt"), b"notes")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("update.raucb.sig"), b"deadbeef")
+            .await
+            .unwrap();
+        tokio::fs::create_dir(dir.path().join("bundles.raucb"))
+            .await
+            .unwrap();
+
+        assert!(watcher.scan().await.unwrap().is_none());
+        assert!(watcher.scan().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_fail_missing_directory() {
+        let dir = TempDir::new("edgehog-local").unwrap();
+        let (mut watcher, _local_rx) = mock_watcher(&dir);
+        watcher.directory = dir.path().join("usb");
+
+        assert!(watcher.scan().await.is_err());
+    }
+}
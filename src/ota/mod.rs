@@ -17,13 +17,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use std::{
     fmt::Display,
     path::{Path, PathBuf},
 };
 
-use event::OtaRequest;
+use event::{OtaOperation, OtaRequest};
 use futures::TryStreamExt;
 use futures::stream::BoxStream;
 #[cfg(all(feature = "zbus", target_os = "linux"))]
@@ -39,21 +40,42 @@ use uuid::Uuid;
 use mockall::automock;
 
 use crate::DeviceManagerOptions;
+use crate::bandwidth::{Bandwidth, Scope};
 use crate::controller::actor::Actor;
 use crate::error::DeviceManagerError;
 use crate::http::default_http_client_builder;
+use crate::io::digest::Digest;
+use crate::io::space::Percentage;
 use crate::ota::rauc::BundleInfo;
+use crate::power_policy::PowerState;
 use crate::repository::StateRepository;
 
 use self::config::{OtaConfig, Reboot};
+use self::download::{
+    ResumeState, Resumed, clear_resume_state, remove_expired_download, resume_offset,
+    resume_request,
+};
+use self::health::HealthGate;
+use self::history::OtaHistory;
+use self::retry::{ErrorClass, RetryPolicy};
+use self::verify::{BundleCheck, BundleDigest, VerifyError};
 
 pub mod config;
+mod download;
 pub mod event;
+pub mod health;
+pub mod history;
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub(crate) mod local;
 #[cfg(all(feature = "zbus", target_os = "linux"))]
 pub(crate) mod ota_handler;
 #[cfg(test)]
 mod ota_handler_test;
 pub mod rauc;
+pub mod retry;
+pub mod script;
+pub mod swupdate;
+pub mod verify;
 
 /// Provides deploying progress information.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -78,12 +100,31 @@ pub enum DeployStatus {
 /// Stream of the [`DeployStatus`] events
 pub type ProgressStream = BoxStream<'static, Result<DeployStatus, DeviceManagerError>>;
 
+/// Options passed with the bundle to install.
+///
+/// They are set only when the bundle is streamed from the URL, backends that don't stream the
+/// bundle ignore them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstallOptions {
+    /// HTTP headers sent with the requests for the bundle, in the `Name: value` format
+    pub http_headers: Vec<String>,
+    /// TLS client certificate
+    pub tls_cert: Option<PathBuf>,
+    /// TLS client private key
+    pub tls_key: Option<PathBuf>,
+    /// CA used to verify the server certificate
+    pub tls_ca: Option<PathBuf>,
+    /// Skip the verification of the server certificate
+    pub tls_no_verify: bool,
+}
+
 /// A **trait** required for all SystemUpdate handlers that want to update a system.
 #[cfg_attr(test, automock)]
 pub trait SystemUpdate: Send + Sync {
     fn install_bundle(
         &self,
         source: &str,
+        options: &InstallOptions,
     ) -> impl Future<Output = Result<(), DeviceManagerError>> + Send;
     fn last_error(&self) -> impl Future<Output = Result<String, DeviceManagerError>> + Send;
     fn info(
@@ -118,6 +159,9 @@ pub enum OtaError {
     #[error("NetworkError: {0}")]
     /// A generic network error occurred
     Network(String),
+    #[error("HttpError: status {status}: {message}")]
+    /// The server replied with an error status
+    Http { status: u16, message: String },
     #[error("IOError: {0}")]
     /// A filesystem error occurred
     Io(String),
@@ -128,8 +172,8 @@ pub enum OtaError {
     /// Invalid OTA image received
     InvalidBaseImage(String),
     #[error("SystemRollback: {0}")]
-    /// The OTA procedure boot on the wrong partition
-    SystemRollback(&'static str),
+    /// The OTA procedure boot on the wrong partition or the updated system isn't healthy
+    SystemRollback(String),
     /// OTA update aborted by Edgehog half way during the procedure
     #[error("Canceled")]
     Canceled,
@@ -138,6 +182,24 @@ pub enum OtaError {
     InconsistentState,
 }
 
+impl OtaError {
+    /// Tells if the operation could succeed by retrying it.
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            OtaError::Network(_) => ErrorClass::Retryable,
+            OtaError::Http { status, .. } => ErrorClass::from_status(*status),
+            OtaError::Request(_)
+            | OtaError::UpdateAlreadyInProgress
+            | OtaError::Io(_)
+            | OtaError::Internal(_)
+            | OtaError::InvalidBaseImage(_)
+            | OtaError::SystemRollback(_)
+            | OtaError::Canceled
+            | OtaError::InconsistentState => ErrorClass::Fatal,
+        }
+    }
+}
+
 impl Default for DeployStatus {
     fn default() -> Self {
         DeployStatus::Progress(DeployProgress::default())
@@ -151,6 +213,9 @@ const DEPLOY_PERC_ROUNDING_STEP: i32 = 10;
 pub struct PersistentState {
     pub uuid: Uuid,
     pub slot: String,
+    /// The bundle was downloaded but not installed yet, the slot is empty.
+    #[serde(default)]
+    pub staged: bool,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -169,6 +234,8 @@ pub enum OtaStatus {
     Deploying(OtaId, DeployProgress),
     /// The device deployed the update
     Deployed(OtaId),
+    /// The bundle was downloaded and is waiting to be activated
+    Staged(OtaId),
     /// The device is in the process of rebooting
     Rebooting(OtaId),
     /// The device was rebooted
@@ -190,6 +257,7 @@ impl OtaStatus {
             | OtaStatus::NoPendingOta
             | OtaStatus::Deploying(_, _)
             | OtaStatus::Deployed(_)
+            | OtaStatus::Staged(_)
             | OtaStatus::Rebooting(_)
             | OtaStatus::Rebooted
             | OtaStatus::Success(_)
@@ -206,6 +274,7 @@ impl OtaStatus {
             | OtaStatus::Downloading(id, _)
             | OtaStatus::Deploying(id, _)
             | OtaStatus::Deployed(id)
+            | OtaStatus::Staged(id)
             | OtaStatus::Rebooting(id)
             | OtaStatus::Success(id)
             | OtaStatus::Error(_, id) => Some(id.clone()),
@@ -243,6 +312,10 @@ impl OtaStatus {
                 ota_event.requestUUID = ota_request.uuid.to_string();
                 ota_event.status = "Deployed".to_string();
             }
+            OtaStatus::Staged(ota_request) => {
+                ota_event.requestUUID = ota_request.uuid.to_string();
+                ota_event.status = "Staged".to_string();
+            }
             OtaStatus::Rebooting(ota_request) => {
                 ota_event.requestUUID = ota_request.uuid.to_string();
                 ota_event.status = "Rebooting".to_string()
@@ -293,6 +366,7 @@ impl Display for OtaStatus {
             }
             OtaStatus::Deploying(req, progress) => write!(f, "Deploying {req} {progress}"),
             OtaStatus::Deployed(req) => write!(f, "Deployed {req}"),
+            OtaStatus::Staged(req) => write!(f, "Staged {req}"),
             OtaStatus::Rebooting(req) => write!(f, "Rebooting {req}"),
             OtaStatus::Rebooted => write!(f, "Rebooted"),
             OtaStatus::Success(req) => write!(f, "Success {req}"),
@@ -344,6 +418,24 @@ where
     pub ota_status: OtaStatus,
     pub flag: OtaInProgress,
     pub publisher_tx: mpsc::Sender<OtaStatus>,
+    pub power: PowerState,
+    pub health: HealthGate,
+    /// Set when the updated slot was marked bad, to reboot into the previous one
+    pub rollback: AtomicBool,
+    /// Operation of the current request
+    pub operation: OtaOperation,
+    /// Bundle downloaded and waiting to be activated
+    pub staged: Option<OtaId>,
+    /// Digest and signature of the current request bundle
+    pub check: BundleCheck,
+    /// HTTP headers of the current request, to stream the bundle
+    pub http_headers: Vec<String>,
+    /// CA file the runtime verifies the servers with, used to stream the bundle
+    pub runtime_ca: Option<PathBuf>,
+    /// Limits the download rate of the bundle
+    pub bandwidth: Bandwidth,
+    /// Records the updates in the store
+    pub history: OtaHistory,
 }
 
 impl<T, U> Actor for Ota<T, U>
@@ -368,6 +460,11 @@ where
         // Always run the publish and cleanup
         self.handle_ota_update(cancel).await;
 
+        // The requests don't survive a restart, the bundles left by previous OTAs are stale
+        if self.staged.is_none() {
+            self.remove_stale_bundle().await;
+        }
+
         Ok(())
     }
 
@@ -378,7 +475,76 @@ where
             return Err(OtaError::InconsistentState.into());
         }
 
-        self.ota_status = OtaStatus::Init(msg.ota_id);
+        match msg.operation {
+            OtaOperation::Local if self.staged.is_some() => {
+                warn!(ota_id = %msg.ota_id, "rejecting the local bundle, a bundle is staged");
+
+                self.publish_status(OtaStatus::Failure(
+                    OtaError::Request("Unable to install the local bundle, a bundle is staged"),
+                    Some(msg.ota_id),
+                ))
+                .await;
+
+                // Reset the in progress flag set by the handler
+                self.is_ota_in_progress();
+
+                return Ok(());
+            }
+            OtaOperation::Update | OtaOperation::Stage | OtaOperation::Local => {
+                if self.staged.is_some() {
+                    info!("discarding the staged bundle for the new request");
+
+                    self.discard_staged().await;
+                }
+
+                self.ota_status = OtaStatus::Init(msg.ota_id);
+                self.check = msg.check;
+                self.http_headers = msg.http_headers;
+            }
+            OtaOperation::Activate => {
+                let Some(staged) = self.staged.take_if(|staged| staged.uuid == msg.ota_id.uuid)
+                else {
+                    self.publish_status(OtaStatus::Failure(
+                        OtaError::Request("Unable to activate OTA request, no staged bundle"),
+                        Some(msg.ota_id),
+                    ))
+                    .await;
+
+                    return Ok(());
+                };
+
+                info!(%staged, "activating the staged bundle");
+
+                // Goes through the power profile check before deploying
+                self.ota_status = OtaStatus::Acknowledged(msg.ota_id);
+            }
+            OtaOperation::Cancel => {
+                if self
+                    .staged
+                    .as_ref()
+                    .is_some_and(|staged| staged.uuid == msg.ota_id.uuid)
+                {
+                    info!(ota_id = %msg.ota_id, "staged bundle cancelled");
+
+                    self.ota_status = OtaStatus::Failure(OtaError::Canceled, Some(msg.ota_id));
+                    self.publish_status(self.ota_status.clone()).await;
+
+                    self.discard_staged().await;
+                } else {
+                    self.publish_status(OtaStatus::Failure(
+                        OtaError::Internal(
+                            "Unable to cancel OTA request, internal request is empty",
+                        ),
+                        Some(msg.ota_id),
+                    ))
+                    .await;
+                }
+
+                return Ok(());
+            }
+        }
+
+        self.operation = msg.operation;
         self.handle_ota_update(msg.cancel).await;
 
         Ok(())
@@ -390,21 +556,36 @@ where
     T: SystemUpdate,
     U: StateRepository<PersistentState>,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         opts: &DeviceManagerOptions,
         tx_publisher: mpsc::Sender<OtaStatus>,
         flag: OtaInProgress,
         system_update: T,
         state_repository: U,
+        power: PowerState,
+        health: HealthGate,
+        bandwidth: Bandwidth,
+        history: OtaHistory,
     ) -> Self {
         Ota {
             system_update,
-            config: opts.ota,
+            config: opts.ota.clone(),
             state_repository,
             download_file_path: opts.download_directory.clone(),
             ota_status: OtaStatus::Idle,
             flag,
             publisher_tx: tx_publisher,
+            power,
+            health,
+            rollback: AtomicBool::new(false),
+            operation: OtaOperation::Update,
+            staged: None,
+            check: BundleCheck::default(),
+            http_headers: Vec::new(),
+            runtime_ca: edgehog_tls::ca_file(),
+            bandwidth,
+            history,
         }
     }
 
@@ -412,23 +593,84 @@ where
         self.system_update.last_error().await
     }
 
+    /// Checks if the bundle is streamed instead of downloaded.
+    ///
+    /// Staged and verified bundles are always downloaded.
+    fn is_streaming(&self) -> bool {
+        self.config.streaming
+            && self.operation == OtaOperation::Update
+            && self.check.digest.is_none()
+            && self.config.verification.public_key.is_none()
+    }
+
     /// Path or URL to the bundle that should be installed
     fn get_install_uri(&self, req: &OtaId) -> String {
-        if self.config.streaming {
+        if self.is_streaming() {
             req.url.clone()
         } else {
             self.get_update_file_path().to_string_lossy().to_string()
         }
     }
 
+    /// Headers and TLS options to stream the bundle.
+    ///
+    /// The request id is sent like in the download, followed by the configured headers and the
+    /// ones of the request. Without a configured CA, RAUC verifies the server with the same CA
+    /// file the runtime loads its roots from.
+    fn get_install_options(&self, req: &OtaId) -> InstallOptions {
+        if !self.is_streaming() {
+            return InstallOptions::default();
+        }
+
+        let stream = &self.config.stream;
+
+        let http_headers = std::iter::once(format!("x-edgehog-ota-id: {}", req.uuid))
+            .chain(stream.http_headers.iter().cloned())
+            .chain(self.http_headers.iter().cloned())
+            .collect();
+
+        let tls_ca = stream.tls_ca.clone().or_else(|| self.runtime_ca.clone());
+
+        InstallOptions {
+            http_headers,
+            tls_cert: stream.tls_cert.clone(),
+            tls_key: stream.tls_key.clone(),
+            tls_ca,
+            tls_no_verify: stream.tls_no_verify,
+        }
+    }
+
     /// Returns the path of the downloaded image.
     fn get_update_file_path(&self) -> PathBuf {
         self.download_file_path.join("update.bin")
     }
 
     /// Called after the ota request has been Acknowledged.
-    fn start_update(&self, ota_request: OtaId) -> OtaStatus {
-        if self.config.streaming {
+    ///
+    /// Waits for the power profile to allow the update before starting it, also when activating a
+    /// staged bundle.
+    async fn start_update(&mut self, ota_request: OtaId) -> OtaStatus {
+        if self.power.current().defers_ota() {
+            info!(profile = %self.power.current(), "ota deferred by the power profile");
+
+            self.power.wait_for(|profile| !profile.defers_ota()).await;
+
+            info!("resuming deferred ota");
+        }
+
+        // The staged bundle was already downloaded and verified
+        if self.operation == OtaOperation::Activate {
+            return OtaStatus::Deploying(ota_request, DeployProgress::default());
+        }
+
+        if let Err(err) = self.check.verify_signature(&self.config.verification).await {
+            let message = format!("Unable to verify the bundle signature: {err}");
+            error!("{message}: {:#}", eyre::Report::new(err));
+
+            return OtaStatus::Failure(OtaError::InvalidBaseImage(message), Some(ota_request));
+        }
+
+        if self.is_streaming() {
             debug!("streaming image directly to disk");
 
             OtaStatus::Deploying(ota_request, DeployProgress::default())
@@ -439,7 +681,9 @@ where
         }
     }
 
-    // Retries the download 5 times
+    /// Retries the download following the configured policy.
+    ///
+    /// Fatal errors, like a missing bundle, are not retried.
     async fn retry_download(
         &self,
         req: &OtaId,
@@ -447,38 +691,161 @@ where
         ota_file: &str,
     ) -> Result<String, OtaError> {
         let client = create_http_client(req)?;
+        let policy = RetryPolicy::new(self.config.retry);
+        let start = tokio::time::Instant::now();
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            debug!(attempt, "downloading ota image");
+
+            let res = wget(
+                &client,
+                req,
+                ota_path,
+                self.check.digest.as_ref(),
+                self.config.storage_reserved,
+                &self.bandwidth,
+                &self.publisher_tx,
+            )
+            .await;
 
-        for i in 1..=5 {
-            debug!(time = i, "downloading ota image");
+            let err = match res {
+                Ok(()) => return Ok(ota_file.to_string()),
+                Err(err) => err,
+            };
 
-            let res = wget(&client, req, ota_path, &self.publisher_tx).await;
+            let class = err.class();
 
-            match res {
-                Ok(()) => return Ok(ota_file.to_string()),
-                Err(err) => {
-                    error!(error = format!("{err:#}"), "couldn't downloading the image");
-
-                    if self
-                        .publisher_tx
-                        .send(OtaStatus::Error(err, req.clone()))
-                        .await
-                        .is_err()
-                    {
-                        warn!("ota_status_publisher dropped before send error_status")
-                    }
-                }
+            error!(
+                error = format!("{err:#}"),
+                %class,
+                "couldn't download the image"
+            );
+
+            if self
+                .publisher_tx
+                .send(OtaStatus::Error(err.clone(), req.clone()))
+                .await
+                .is_err()
+            {
+                warn!("ota_status_publisher dropped before send error_status")
+            }
+
+            if class == ErrorClass::Fatal {
+                return Err(err);
             }
 
-            let wait = u64::pow(2, i);
+            let Some(wait) = policy.next_delay(attempt, start.elapsed()) else {
+                error!(attempt, "too many attempts to download the OTA file");
+
+                return Err(err);
+            };
 
-            error!("Next attempt in {wait}s",);
+            error!("Next attempt in {wait:?}");
 
-            tokio::time::sleep(tokio::time::Duration::from_secs(wait)).await;
+            tokio::time::sleep(wait).await;
         }
+    }
 
-        Err(OtaError::Internal(
-            "Too many attempts to download the OTA file",
-        ))
+    /// Copies the bundle from the local directory, checking its digest.
+    ///
+    /// The bundle is removed from the directory only after it's installed.
+    async fn copy_local_bundle(&self, req: &OtaId, ota_path: &Path) -> Result<(), OtaError> {
+        let bundle = Path::new(&req.url);
+
+        info!(bundle = %bundle.display(), "installing local bundle");
+
+        let size = tokio::fs::metadata(bundle)
+            .await
+            .map_err(|error| {
+                let message = format!("Unable to read the local bundle {}", bundle.display());
+                error!("{message} : {error}");
+                OtaError::Io(message)
+            })?
+            .len();
+
+        check_free_space(ota_path, size, self.config.storage_reserved).await?;
+
+        tokio::fs::copy(bundle, ota_path).await.map_err(|error| {
+            let message = format!("Unable to copy the local bundle {}", bundle.display());
+            error!("{message} : {error}");
+            OtaError::Io(message)
+        })?;
+
+        let Some(digest) = &self.check.digest else {
+            return Ok(());
+        };
+
+        let file = tokio::fs::File::open(ota_path).await.map_err(|error| {
+            let message = format!("Unable to read ota_file in {ota_path:?}");
+            error!("{message} : {error:?}");
+            OtaError::Io(message)
+        })?;
+
+        let file = Digest::from_read(file, digest.algorithm, size)
+            .await
+            .map_err(|error| {
+                let message = format!("Unable to read ota_file in {ota_path:?}");
+                error!("{message} : {error:?}");
+                OtaError::Io(message)
+            })?;
+
+        if file.check_digest(&digest.value).is_err() {
+            let err = VerifyError::DigestMismatch;
+            error!("{err}");
+
+            return Err(OtaError::InvalidBaseImage(err.to_string()));
+        }
+
+        debug!("local bundle digest verified");
+
+        Ok(())
+    }
+
+    /// Removes the installed bundle and its signature from the local directory.
+    async fn remove_local_bundle(&self, req: &OtaId) {
+        let bundle = Path::new(&req.url);
+
+        if let Err(error) = tokio::fs::remove_file(bundle).await {
+            error!(%error, bundle = %bundle.display(), "couldn't remove the local bundle");
+        }
+
+        let signature = local::signature_path(bundle);
+
+        if signature.exists()
+            && let Err(error) = tokio::fs::remove_file(&signature).await
+        {
+            error!(%error, signature = %signature.display(), "couldn't remove the local bundle signature");
+        }
+    }
+
+    /// Renames the bundle that couldn't be installed to `*.failed`, so it's not submitted again.
+    ///
+    /// The signature is renamed with it, to keep them together.
+    async fn mark_local_bundle_failed(&self, req: &OtaId) {
+        let bundle = Path::new(&req.url);
+
+        let mut failed = bundle.as_os_str().to_owned();
+        failed.push(".failed");
+        let failed = PathBuf::from(failed);
+
+        if let Err(error) = tokio::fs::rename(bundle, &failed).await {
+            error!(%error, bundle = %bundle.display(), "couldn't rename the failed local bundle");
+
+            return;
+        }
+
+        warn!(bundle = %failed.display(), "local bundle couldn't be installed");
+
+        let signature = local::signature_path(bundle);
+
+        if signature.exists()
+            && let Err(error) = tokio::fs::rename(&signature, local::signature_path(&failed)).await
+        {
+            error!(%error, signature = %signature.display(), "couldn't rename the failed local bundle signature");
+        }
     }
 
     /// Handle the transition to the deploying status.
@@ -492,9 +859,14 @@ where
             );
         };
 
-        let download_res = self
-            .retry_download(&ota_request, &download_file_path, download_file_str)
-            .await;
+        let download_res = if self.operation == OtaOperation::Local {
+            self.copy_local_bundle(&ota_request, &download_file_path)
+                .await
+                .map(|()| download_file_str.to_string())
+        } else {
+            self.retry_download(&ota_request, &download_file_path, download_file_str)
+                .await
+        };
 
         let ota_file = match download_res {
             Ok(ota_file) => ota_file,
@@ -518,6 +890,10 @@ where
 
         debug!("bundle info: {:?}", bundle_info);
 
+        self.history
+            .record_bundle(ota_request.uuid, &bundle_info)
+            .await;
+
         let system_image_info = match self.system_update.compatible().await {
             Ok(info) => info,
             Err(err) => {
@@ -541,9 +917,32 @@ where
             );
         }
 
+        if self.operation == OtaOperation::Stage {
+            return self.stage(ota_request).await;
+        }
+
         OtaStatus::Deploying(ota_request.clone(), DeployProgress::default())
     }
 
+    /// Persists the downloaded bundle to activate it later.
+    async fn stage(&self, ota_request: OtaId) -> OtaStatus {
+        let state = PersistentState {
+            uuid: ota_request.uuid,
+            slot: String::new(),
+            staged: true,
+        };
+
+        if let Err(error) = self.state_repository.write(&state).await {
+            let message = "Unable to persist ota state".to_string();
+            error!("{message} : {error}");
+            return OtaStatus::Failure(OtaError::Io(message), Some(ota_request));
+        };
+
+        info!(%ota_request, "bundle staged");
+
+        OtaStatus::Staged(ota_request)
+    }
+
     /// Handle the transition to the deployed status.
     pub async fn deploy(&self, ota_request: OtaId) -> OtaStatus {
         let booted_slot = match self.system_update.boot_slot().await {
@@ -557,9 +956,14 @@ where
             }
         };
 
+        self.history
+            .record_from_slot(ota_request.uuid, &booted_slot)
+            .await;
+
         let state = PersistentState {
             uuid: ota_request.uuid,
             slot: booted_slot,
+            staged: false,
         };
 
         if let Err(error) = self.state_repository.write(&state).await {
@@ -570,7 +974,10 @@ where
 
         if let Err(error) = self
             .system_update
-            .install_bundle(&self.get_install_uri(&ota_request))
+            .install_bundle(
+                &self.get_install_uri(&ota_request),
+                &self.get_install_options(&ota_request),
+            )
             .await
         {
             let message = "Unable to install ota image".to_string();
@@ -651,6 +1058,10 @@ where
             0 => {
                 info!("Update successful");
 
+                if self.operation == OtaOperation::Local {
+                    self.remove_local_bundle(&ota_request).await;
+                }
+
                 OtaStatus::Deployed(ota_request)
             }
             _ => {
@@ -740,6 +1151,12 @@ where
             url: "".to_string(),
         };
 
+        if ota_state.staged {
+            info!(%ota_request, "found staged bundle");
+
+            return OtaStatus::Staged(ota_request);
+        }
+
         if let Err(error) = self.do_pending_ota(&ota_state).await {
             return OtaStatus::Failure(error, Some(ota_request));
         }
@@ -749,6 +1166,7 @@ where
 
     pub async fn do_pending_ota(&self, state: &PersistentState) -> Result<(), OtaError> {
         const GOOD_STATE: &str = "good";
+        const BAD_STATE: &str = "bad";
 
         let booted_slot = self.system_update.boot_slot().await.map_err(|error| {
             let message = "Unable to identify the booted slot";
@@ -756,10 +1174,12 @@ where
             OtaError::Internal(message)
         })?;
 
+        self.history.record_to_slot(state.uuid, &booted_slot).await;
+
         if state.slot == booted_slot {
             let message = "Unable to switch slot";
             error!("{message}");
-            return Err(OtaError::SystemRollback(message));
+            return Err(OtaError::SystemRollback(message.to_string()));
         }
 
         let primary_slot = self.system_update.get_primary().await.map_err(|error| {
@@ -768,6 +1188,18 @@ where
             OtaError::Internal(message)
         })?;
 
+        if let Err(failure) = self.health.wait_healthy().await {
+            error!(%failure, "updated system is not healthy, rolling back");
+
+            if let Err(error) = self.system_update.mark(BAD_STATE, &primary_slot).await {
+                error!("Unable to mark the slot {primary_slot} bad: {error}");
+            }
+
+            self.rollback.store(true, Ordering::Release);
+
+            return Err(OtaError::SystemRollback(format!("health check {failure}")));
+        }
+
         let (marked_slot, _) = self
             .system_update
             .mark(GOOD_STATE, &primary_slot)
@@ -789,19 +1221,31 @@ where
     pub async fn next(&mut self) {
         self.ota_status = match self.ota_status.clone() {
             OtaStatus::Init(req) => OtaStatus::Acknowledged(req),
-            OtaStatus::Acknowledged(ota_request) => self.start_update(ota_request),
+            OtaStatus::Acknowledged(ota_request) => self.start_update(ota_request).await,
             OtaStatus::Downloading(ota_request, _) => self.download(ota_request).await,
             OtaStatus::Deploying(ota_request, _) => self.deploy(ota_request).await,
             OtaStatus::Deployed(ota_request) => self.reboot(ota_request).await,
+            OtaStatus::Staged(ota_request) => {
+                self.staged = Some(ota_request);
+
+                OtaStatus::Idle
+            }
             OtaStatus::Rebooted => self.check_reboot().await,
             OtaStatus::Rebooting(ota_request) => self.wait_reboot(ota_request).await,
             OtaStatus::Error(ota_error, ota_request) => {
                 OtaStatus::Failure(ota_error, Some(ota_request))
             }
-            OtaStatus::Idle
-            | OtaStatus::NoPendingOta
-            | OtaStatus::Success(_)
-            | OtaStatus::Failure(_, _) => OtaStatus::Idle,
+            OtaStatus::Failure(err, ota_request) => {
+                if self.operation == OtaOperation::Local
+                    && err != OtaError::Canceled
+                    && let Some(ota_request) = ota_request
+                {
+                    self.mark_local_bundle_failed(&ota_request).await;
+                }
+
+                OtaStatus::Idle
+            }
+            OtaStatus::Idle | OtaStatus::NoPendingOta | OtaStatus::Success(_) => OtaStatus::Idle,
         };
     }
 
@@ -812,6 +1256,7 @@ where
             info!(status = %self.ota_status, "ota progress");
 
             self.publish_status(self.ota_status.clone()).await;
+            self.history.record_status(&self.ota_status).await;
 
             if self.ota_status.is_cancellable() {
                 if cancel.run_until_cancelled(self.next()).await.is_none() {
@@ -838,23 +1283,127 @@ where
         self.publish_status(self.ota_status.clone()).await;
 
         self.clear().await;
+
+        if self.rollback.swap(false, Ordering::AcqRel) {
+            self.reboot_rollback().await;
+        }
+    }
+
+    /// Reboots into the previous slot after the updated one was marked bad.
+    async fn reboot_rollback(&self) {
+        info!("Rebooting the device to roll back the update");
+
+        if cfg!(test) {
+            return;
+        }
+
+        match self.config.reboot {
+            Reboot::Default => {
+                if let Err(error) = crate::power_management::reboot().await {
+                    error!("Unable to run reboot command : {error}");
+                }
+            }
+            Reboot::External => {
+                info!("waiting for next reboot");
+            }
+        }
     }
 
     async fn clear(&mut self) {
+        // Keep the bundle till it's activated or cancelled
+        if self.staged.is_some() {
+            self.ota_status = OtaStatus::Idle;
+
+            return;
+        }
+
+        if self.state_repository.exists().await {
+            let _ = self.state_repository.clear().await.map_err(|error| {
+                error!("Error clearing the state repository: {:?}", error);
+            });
+        }
+
+        let path = self.get_update_file_path();
+        let resume = ResumeState::repository(&path);
+
+        // Keep the partial download to resume it on the next request
+        if resume.exists().await
+            && !matches!(self.ota_status, OtaStatus::Failure(OtaError::Canceled, _))
+        {
+            info!("keeping the partial download to resume it");
+
+            self.expire_partial_download(path);
+
+            self.ota_status = OtaStatus::Idle;
+
+            return;
+        }
+
+        self.remove_bundle().await;
+
+        self.ota_status = OtaStatus::Idle;
+    }
+
+    /// Removes the staged bundle and its state.
+    async fn discard_staged(&mut self) {
+        self.staged = None;
+
         if self.state_repository.exists().await {
             let _ = self.state_repository.clear().await.map_err(|error| {
                 error!("Error clearing the state repository: {:?}", error);
             });
         }
 
+        self.remove_bundle().await;
+    }
+
+    /// Removes the bundle left by a previous run.
+    ///
+    /// A partial download with its resume state is kept to resume it on the next request, till
+    /// it expires.
+    async fn remove_stale_bundle(&self) {
+        let path = self.get_update_file_path();
+
+        if path.exists() && remove_expired_download(&path, self.resume_max_age()).await {
+            info!("keeping the partial download to resume it");
+
+            self.expire_partial_download(path);
+
+            return;
+        }
+
+        self.remove_bundle().await;
+    }
+
+    fn resume_max_age(&self) -> Duration {
+        Duration::from_secs(self.config.retry.resume_max_age_secs)
+    }
+
+    /// Removes the kept partial download once it expires, if no update is in progress.
+    fn expire_partial_download(&self, path: PathBuf) {
+        let max_age = self.resume_max_age();
+        let flag = self.flag.clone();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(max_age).await;
+
+            if !flag.in_progress() {
+                remove_expired_download(&path, max_age).await;
+            }
+        });
+    }
+
+    /// Removes the downloaded bundle and its resume state.
+    async fn remove_bundle(&self) {
         let path = self.get_update_file_path();
+
+        clear_resume_state(&ResumeState::repository(&path)).await;
+
         if path.exists()
             && let Err(e) = tokio::fs::remove_file(&path).await
         {
             error!("Unable to remove {}: {}", path.display(), e);
         }
-
-        self.ota_status = OtaStatus::Idle;
     }
 
     async fn publish_status(&self, status: OtaStatus) {
@@ -905,111 +1454,364 @@ fn create_http_client(req: &OtaId) -> Result<reqwest::Client, OtaError> {
     Ok(client)
 }
 
+/// Sends the request for the full bundle.
+async fn full_request(
+    client: &reqwest::Client,
+    req: &OtaId,
+) -> Result<reqwest::Response, OtaError> {
+    let response = client.get(&req.url).send().await.map_err(|err| {
+        if err.is_builder() {
+            error!("invalid bundle url: {err:?}");
+
+            return OtaError::Request("Invalid bundle url");
+        }
+
+        let message = "Error downloading update".to_string();
+        error!("{message}: {err:?}");
+        OtaError::Network(message)
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        error!(%status, url = req.url, "couldn't download the update");
+
+        return Err(OtaError::Http {
+            status: status.as_u16(),
+            message: format!("Error downloading update from {}", req.url),
+        });
+    }
+
+    Ok(response)
+}
+
+/// Downloads the bundle, resuming the partial file of a previous attempt if possible.
 pub async fn wget(
     client: &reqwest::Client,
     req: &OtaId,
     file_path: &Path,
+    digest: Option<&BundleDigest>,
+    reserved: Option<Percentage>,
+    bandwidth: &Bandwidth,
     ota_status_publisher: &mpsc::Sender<OtaStatus>,
 ) -> Result<(), OtaError> {
-    use tokio_stream::StreamExt;
-
-    if file_path.exists() {
-        tokio::fs::remove_file(file_path).await.map_err(|err| {
-            error!(
-                "failed to remove old file '{}': {}",
-                file_path.display(),
-                err
-            );
-
-            OtaError::Internal("failed to remove old file")
-        })?;
-    }
+    let resume = ResumeState::repository(file_path);
 
     info!(url = req.url, "Downloading");
 
-    let result_response = client.get(&req.url).send().await;
-
-    match result_response {
-        Err(err) => {
-            let message = "Error downloading update".to_string();
-            error!("{message}: {err:?}");
-            Err(OtaError::Network(message))
-        }
-        Ok(response) => {
-            debug!("Writing {}", file_path.display());
-
-            let total_size = response
-                .content_length()
-                .and_then(|size| if size == 0 { None } else { Some(size) })
-                .ok_or_else(|| {
-                    OtaError::Network(format!("Unable to get content length from: {}", req.url))
-                })? as f64;
-
-            let mut downloaded: f64 = 0.0;
-            let mut last_percentage_sent = 0.0;
-            let mut stream = response.bytes_stream();
-
-            let mut os_file = tokio::fs::File::create(file_path).await.map_err(|error| {
-                let message = format!("Unable to create ota_file in {file_path:?}");
-                error!("{message} : {error:?}");
-                OtaError::Io(message)
-            })?;
+    let resumed = match resume_offset(&resume, req, file_path).await {
+        Some((state, offset)) => match resume_request(client, &state, offset).await? {
+            Resumed::Partial(response) => Some((response, offset, state.total)),
+            Resumed::Full(response) => {
+                clear_resume_state(&resume).await;
+
+                return download_full(
+                    response,
+                    req,
+                    file_path,
+                    digest,
+                    reserved,
+                    bandwidth,
+                    ota_status_publisher,
+                )
+                .await;
+            }
+            Resumed::Restart => None,
+        },
+        None => None,
+    };
 
-            while let Some(chunk_result) = stream.next().await {
-                let chunk = chunk_result.map_err(|error| {
-                    let message = "Unable to parse response".to_string();
-                    error!("{message} : {error:?}");
-                    OtaError::Network(message)
-                })?;
+    let Some((response, offset, total)) = resumed else {
+        clear_resume_state(&resume).await;
 
-                if chunk.is_empty() {
-                    continue;
-                }
+        let response = full_request(client, req).await?;
 
-                let mut content = std::io::Cursor::new(&chunk);
+        return download_full(
+            response,
+            req,
+            file_path,
+            digest,
+            reserved,
+            bandwidth,
+            ota_status_publisher,
+        )
+        .await;
+    };
 
-                tokio::io::copy(&mut content, &mut os_file)
-                    .await
-                    .map_err(|error| {
-                        let message = format!("Unable to write chunk to ota_file in {file_path:?}");
-                        error!("{message} : {error:?}");
-                        OtaError::Io(message)
-                    })?;
+    check_free_space(file_path, total, reserved).await?;
 
-                downloaded += chunk.len() as f64;
-                let progress_percentage = (downloaded / total_size) * 100.0;
-                if progress_percentage == 100.0
-                    || (progress_percentage - last_percentage_sent) >= DOWNLOAD_PERC_ROUNDING_STEP
-                {
-                    last_percentage_sent = progress_percentage;
-                    if ota_status_publisher
-                        .send(OtaStatus::Downloading(
-                            req.clone(),
-                            progress_percentage as i32,
-                        ))
-                        .await
-                        .is_err()
-                    {
-                        warn!("ota_status_publisher dropped before send downloading_status")
-                    }
-                }
+    // Readable to compute the digest of the downloaded part
+    let file = tokio::fs::OpenOptions::new()
+        .read(true)
+        .append(true)
+        .open(file_path)
+        .await
+        .map_err(|error| {
+            let message = format!("Unable to open ota_file in {file_path:?}");
+            error!("{message} : {error:?}");
+            OtaError::Io(message)
+        })?;
+
+    write_checked(
+        Box::pin(bandwidth.limit_stream(Scope::Ota, response.bytes_stream())),
+        file,
+        offset,
+        total,
+        req,
+        file_path,
+        digest,
+        ota_status_publisher,
+    )
+    .await?;
+
+    clear_resume_state(&resume).await;
+
+    Ok(())
+}
+
+/// Writes the bundle from the start, storing the state to resume it if interrupted.
+async fn download_full(
+    response: reqwest::Response,
+    req: &OtaId,
+    file_path: &Path,
+    digest: Option<&BundleDigest>,
+    reserved: Option<Percentage>,
+    bandwidth: &Bandwidth,
+    ota_status_publisher: &mpsc::Sender<OtaStatus>,
+) -> Result<(), OtaError> {
+    debug!("Writing {}", file_path.display());
+
+    let total_size = response
+        .content_length()
+        .and_then(|size| if size == 0 { None } else { Some(size) })
+        .ok_or_else(|| {
+            OtaError::Network(format!("Unable to get content length from: {}", req.url))
+        })?;
+
+    check_free_space(file_path, total_size, reserved).await?;
+
+    let resume = ResumeState::repository(file_path);
+
+    match ResumeState::from_response(req, &response, total_size) {
+        Some(state) => {
+            if let Err(err) = resume.write(&state).await {
+                warn!(
+                    error = format!("{:#}", eyre::Report::new(err)),
+                    "couldn't store the download resume state"
+                );
             }
+        }
+        None => debug!("missing strong ETag, the download cannot be resumed"),
+    }
 
-            if total_size == downloaded {
-                Ok(())
-            } else {
-                let message = "Unable to download file".to_string();
-                error!("{message}");
-                Err(OtaError::Network(message))
+    let os_file = tokio::fs::File::create(file_path).await.map_err(|error| {
+        let message = format!("Unable to create ota_file in {file_path:?}");
+        error!("{message} : {error:?}");
+        OtaError::Io(message)
+    })?;
+
+    write_checked(
+        Box::pin(bandwidth.limit_stream(Scope::Ota, response.bytes_stream())),
+        os_file,
+        0,
+        total_size,
+        req,
+        file_path,
+        digest,
+        ota_status_publisher,
+    )
+    .await?;
+
+    clear_resume_state(&resume).await;
+
+    Ok(())
+}
+
+/// Checks the bundle fits in the free space of the download filesystem.
+///
+/// The bytes already in the file are not counted, since they are resumed or overwritten.
+async fn check_free_space(
+    file_path: &Path,
+    total_size: u64,
+    reserved: Option<Percentage>,
+) -> Result<(), OtaError> {
+    let dir = file_path.parent().unwrap_or(Path::new("."));
+
+    let stat = rustix::fs::statvfs(dir).map_err(|error| {
+        let message = format!("Unable to read the free space of {}", dir.display());
+        error!("{message} : {error:?}");
+        OtaError::Io(message)
+    })?;
+
+    let existing = tokio::fs::metadata(file_path)
+        .await
+        .map(|meta| meta.len())
+        .unwrap_or(0);
+    let required = total_size.saturating_sub(existing);
+
+    let available = stat.f_bavail.saturating_mul(stat.f_frsize);
+    let reserved = reserved
+        .map(|reserved| reserved.calculate(stat.f_blocks.saturating_mul(stat.f_frsize)))
+        .unwrap_or(0);
+
+    if required.saturating_add(reserved) > available {
+        let message = format!(
+            "Not enough free space in {} to download the bundle: {required} bytes required, {available} available with {reserved} reserved",
+            dir.display()
+        );
+        error!("{message}");
+
+        return Err(OtaError::Io(message));
+    }
+
+    debug!(
+        required,
+        available, reserved, "enough free space for the bundle"
+    );
+
+    Ok(())
+}
+
+/// Writes the bundle, checking the digest when one is expected.
+///
+/// On a mismatch the bundle is removed, so the next attempt doesn't resume it.
+#[allow(clippy::too_many_arguments)]
+async fn write_checked<S>(
+    stream: S,
+    os_file: tokio::fs::File,
+    offset: u64,
+    total_size: u64,
+    req: &OtaId,
+    file_path: &Path,
+    digest: Option<&BundleDigest>,
+    ota_status_publisher: &mpsc::Sender<OtaStatus>,
+) -> Result<(), OtaError>
+where
+    S: futures::Stream<Item = reqwest::Result<bytes::Bytes>> + Unpin,
+{
+    let Some(digest) = digest else {
+        return write_bundle(
+            stream,
+            os_file,
+            offset,
+            total_size,
+            req,
+            file_path,
+            ota_status_publisher,
+        )
+        .await;
+    };
+
+    let mut writer = Digest::from_read(os_file, digest.algorithm, offset)
+        .await
+        .map_err(|error| {
+            let message = format!("Unable to read ota_file in {file_path:?}");
+            error!("{message} : {error:?}");
+            OtaError::Io(message)
+        })?;
+
+    write_bundle(
+        stream,
+        &mut writer,
+        offset,
+        total_size,
+        req,
+        file_path,
+        ota_status_publisher,
+    )
+    .await?;
+
+    if writer.check_digest(&digest.value).is_err() {
+        let err = VerifyError::DigestMismatch;
+        error!("{err}");
+
+        clear_resume_state(&ResumeState::repository(file_path)).await;
+
+        if let Err(error) = tokio::fs::remove_file(file_path).await {
+            error!("Unable to remove {}: {}", file_path.display(), error);
+        }
+
+        return Err(OtaError::InvalidBaseImage(err.to_string()));
+    }
+
+    debug!("bundle digest verified");
+
+    Ok(())
+}
+
+async fn write_bundle<S, W>(
+    mut stream: S,
+    mut os_file: W,
+    offset: u64,
+    total_size: u64,
+    req: &OtaId,
+    file_path: &Path,
+    ota_status_publisher: &mpsc::Sender<OtaStatus>,
+) -> Result<(), OtaError>
+where
+    S: futures::Stream<Item = reqwest::Result<bytes::Bytes>> + Unpin,
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    use tokio_stream::StreamExt;
+
+    let total_size = total_size as f64;
+    let mut downloaded = offset as f64;
+    let mut last_percentage_sent = (downloaded / total_size) * 100.0;
+
+    while let Some(chunk_result) = stream.next().await {
+        let chunk = chunk_result.map_err(|error| {
+            let message = "Unable to parse response".to_string();
+            error!("{message} : {error:?}");
+            OtaError::Network(message)
+        })?;
+
+        if chunk.is_empty() {
+            continue;
+        }
+
+        let mut content = std::io::Cursor::new(&chunk);
+
+        tokio::io::copy(&mut content, &mut os_file)
+            .await
+            .map_err(|error| {
+                let message = format!("Unable to write chunk to ota_file in {file_path:?}");
+                error!("{message} : {error:?}");
+                OtaError::Io(message)
+            })?;
+
+        downloaded += chunk.len() as f64;
+        let progress_percentage = (downloaded / total_size) * 100.0;
+        if progress_percentage == 100.0
+            || (progress_percentage - last_percentage_sent) >= DOWNLOAD_PERC_ROUNDING_STEP
+        {
+            last_percentage_sent = progress_percentage;
+            if ota_status_publisher
+                .send(OtaStatus::Downloading(
+                    req.clone(),
+                    progress_percentage as i32,
+                ))
+                .await
+                .is_err()
+            {
+                warn!("ota_status_publisher dropped before send downloading_status")
             }
         }
     }
+
+    if total_size == downloaded {
+        Ok(())
+    } else {
+        let message = "Unable to download file".to_string();
+        error!("{message}");
+        Err(OtaError::Network(message))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::io;
+    use std::os::unix::fs::PermissionsExt;
     use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, Ordering};
     use std::time::Duration;
 
     use futures::{FutureExt, StreamExt, future};
@@ -1021,16 +1823,28 @@ mod tests {
     use tokio_util::sync::CancellationToken;
     use uuid::Uuid;
 
+    use crate::bandwidth::Bandwidth;
+    use crate::controller::actor::Actor;
     use crate::error::DeviceManagerError;
+    use crate::io::digest::FileDigest;
+    use crate::io::space::Percentage;
+    use crate::ota::download::ResumeState;
+    use crate::ota::event::OtaOperation;
+    use crate::ota::health::HealthGate;
+    use crate::ota::history::OtaHistory;
     use crate::ota::ota_handler_test::deploy_status_stream;
     use crate::ota::rauc::BundleInfo;
-    use crate::ota::{DeployProgress, DeployStatus, MockSystemUpdate, OtaError, SystemUpdate};
+    use crate::ota::verify::{BundleCheck, BundleDigest, VerifyError};
+    use crate::ota::{
+        DeployProgress, DeployStatus, InstallOptions, MockSystemUpdate, OtaError, SystemUpdate,
+    };
     use crate::ota::{Ota, OtaId, OtaStatus, PersistentState, create_http_client, wget};
+    use crate::power_policy::{PeriodMultipliers, PowerProfile, PowerState};
     use crate::repository::file_state_repository::FileStateError;
     use crate::repository::{MockStateRepository, StateRepository};
 
-    use super::config::OtaConfig;
-    use super::ota_handler::OtaInProgress;
+    use super::config::{HealthCheck, HealthConfig, OtaConfig, StreamConfig};
+    use super::ota_handler::{OtaInProgress, OtaMessage};
 
     /// Creates a temporary directory that will be deleted when the returned TempDir is dropped.
     fn temp_dir(prefix: &str) -> (TempDir, PathBuf) {
@@ -1059,6 +1873,16 @@ mod tests {
                 publisher_tx,
                 flag: OtaInProgress::default(),
                 config: OtaConfig::default(),
+                power: PowerState::default(),
+                health: HealthGate::default(),
+                rollback: AtomicBool::new(false),
+                operation: OtaOperation::Update,
+                staged: None,
+                check: BundleCheck::default(),
+                http_headers: Vec::new(),
+                runtime_ca: None,
+                bandwidth: Bandwidth::default(),
+                history: OtaHistory::default(),
             }
         }
 
@@ -1078,6 +1902,16 @@ mod tests {
                 publisher_tx,
                 flag: OtaInProgress::default(),
                 config: OtaConfig::default(),
+                power: PowerState::default(),
+                health: HealthGate::default(),
+                rollback: AtomicBool::new(false),
+                operation: OtaOperation::Update,
+                staged: None,
+                check: BundleCheck::default(),
+                http_headers: Vec::new(),
+                runtime_ca: None,
+                bandwidth: Bandwidth::default(),
+                history: OtaHistory::default(),
             };
 
             (mock, dir)
@@ -1146,6 +1980,35 @@ mod tests {
         assert_eq!(ota.ota_status, OtaStatus::Downloading(ota_id.clone(), 0));
     }
 
+    #[test]
+    fn should_default_to_runtime_ca() {
+        let (publisher_tx, _publisher_rx) = mpsc::channel(8);
+        let mut ota = Ota::mock_new(
+            MockSystemUpdate::new(),
+            MockStateRepository::<PersistentState>::new(),
+            publisher_tx,
+        );
+        ota.config.streaming = true;
+        ota.runtime_ca = Some(PathBuf::from("/etc/ssl/certs/ca-certificates.crt"));
+        let req = OtaId {
+            uuid: Uuid::new_v4(),
+            url: "http://example.com/ota.bin".to_string(),
+        };
+
+        let options = ota.get_install_options(&req);
+
+        assert_eq!(
+            options.tls_ca,
+            Some(PathBuf::from("/etc/ssl/certs/ca-certificates.crt"))
+        );
+
+        ota.config.stream.tls_ca = Some(PathBuf::from("/etc/edgehog/ca.pem"));
+
+        let options = ota.get_install_options(&req);
+
+        assert_eq!(options.tls_ca, Some(PathBuf::from("/etc/edgehog/ca.pem")));
+    }
+
     #[tokio::test]
     async fn ota_streaming_success() {
         let slot = "A";
@@ -1156,6 +2019,7 @@ mod tests {
         let state = PersistentState {
             uuid: req.uuid,
             slot: slot.to_string(),
+            staged: false,
         };
 
         let mut state_mock = MockStateRepository::<PersistentState>::new();
@@ -1178,8 +2042,19 @@ mod tests {
             .expect_install_bundle()
             .once()
             .in_sequence(&mut seq)
-            .with(predicate::eq(req.url.clone()))
-            .returning(|_| future::ready(Ok(())).boxed());
+            .with(
+                predicate::eq(req.url.clone()),
+                predicate::eq(InstallOptions {
+                    http_headers: vec![
+                        format!("x-edgehog-ota-id: {}", req.uuid),
+                        "Authorization: Bearer token".to_string(),
+                        "X-Bundle-Token: secret".to_string(),
+                    ],
+                    tls_ca: Some(PathBuf::from("/etc/edgehog/ca.pem")),
+                    ..Default::default()
+                }),
+            )
+            .returning(|_, _| future::ready(Ok(())).boxed());
 
         system_update
             .expect_operation()
@@ -1256,6 +2131,12 @@ mod tests {
         let (tx, mut rx) = mpsc::channel(10);
         let mut ota = Ota::mock_new(system_update, state_mock, tx);
         ota.config.streaming = true;
+        ota.config.stream = StreamConfig {
+            http_headers: vec!["Authorization: Bearer token".to_string()],
+            tls_ca: Some(PathBuf::from("/etc/edgehog/ca.pem")),
+            ..Default::default()
+        };
+        ota.http_headers = vec!["X-Bundle-Token: secret".to_string()];
         ota.ota_status = OtaStatus::Acknowledged(req.clone());
 
         tokio::time::timeout(
@@ -1374,7 +2255,7 @@ mod tests {
         let mock_ota_file_request = server
             .mock_async(|when, then| {
                 when.method(GET).path("/ota.bin");
-                then.status(404);
+                then.status(503);
             })
             .await;
 
@@ -1394,27 +2275,17 @@ mod tests {
 
         let status = ota.download(ota_id.clone()).await;
 
+        let unavailable = OtaError::Http {
+            status: 503,
+            message: format!("Error downloading update from {ota_url}"),
+        };
+
         let exp = [
-            OtaStatus::Error(
-                OtaError::Network(format!("Unable to get content length from: {ota_url}")),
-                ota_id.clone(),
-            ),
-            OtaStatus::Error(
-                OtaError::Network(format!("Unable to get content length from: {ota_url}")),
-                ota_id.clone(),
-            ),
-            OtaStatus::Error(
-                OtaError::Network(format!("Unable to get content length from: {ota_url}")),
-                ota_id.clone(),
-            ),
-            OtaStatus::Error(
-                OtaError::Network(format!("Unable to get content length from: {ota_url}")),
-                ota_id.clone(),
-            ),
-            OtaStatus::Error(
-                OtaError::Network(format!("Unable to get content length from: {ota_url}")),
-                ota_id.clone(),
-            ),
+            OtaStatus::Error(unavailable.clone(), ota_id.clone()),
+            OtaStatus::Error(unavailable.clone(), ota_id.clone()),
+            OtaStatus::Error(unavailable.clone(), ota_id.clone()),
+            OtaStatus::Error(unavailable.clone(), ota_id.clone()),
+            OtaStatus::Error(unavailable.clone(), ota_id.clone()),
         ];
 
         // for the timeout to work
@@ -1429,15 +2300,54 @@ mod tests {
             assert_eq!(val, status);
         }
 
+        assert_eq!(status, OtaStatus::Failure(unavailable, Some(ota_id)));
+
+        mock_ota_file_request.assert_calls_async(5).await;
+    }
+
+    #[tokio::test]
+    async fn try_to_deploying_fail_wget_not_found() {
+        let state_mock = MockStateRepository::<PersistentState>::new();
+        let system_update = MockSystemUpdate::new();
+
+        let server = MockServer::start_async().await;
+        let mock_ota_file_request = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/ota.bin");
+                then.status(404);
+            })
+            .await;
+
+        let ota_url = server.url("/ota.bin");
+        let ota_id = OtaId {
+            uuid: Uuid::new_v4(),
+            url: ota_url.clone(),
+        };
+
+        let (publisher_tx, mut publisher_rx) = mpsc::channel(10);
+        let (ota, _dir) = Ota::mock_new_with_path(
+            system_update,
+            state_mock,
+            "fail_wget_not_found",
+            publisher_tx,
+        );
+
+        let status = ota.download(ota_id.clone()).await;
+
+        let not_found = OtaError::Http {
+            status: 404,
+            message: format!("Error downloading update from {ota_url}"),
+        };
+
+        // Not retried
         assert_eq!(
-            status,
-            OtaStatus::Failure(
-                OtaError::Internal("Too many attempts to download the OTA file"),
-                Some(ota_id)
-            )
+            publisher_rx.try_recv().unwrap(),
+            OtaStatus::Error(not_found.clone(), ota_id.clone())
         );
+        assert!(publisher_rx.try_recv().is_err());
+        assert_eq!(status, OtaStatus::Failure(not_found, Some(ota_id)));
 
-        mock_ota_file_request.assert_calls_async(5).await;
+        mock_ota_file_request.assert_calls_async(1).await;
     }
 
     #[tokio::test]
@@ -1920,7 +2830,7 @@ mod tests {
             .expect_install_bundle()
             .once()
             .in_sequence(&mut seq)
-            .returning(|_| {
+            .returning(|_, _| {
                 future::ready(Err(DeviceManagerError::Fatal("install fail".to_string()))).boxed()
             });
 
@@ -1969,7 +2879,7 @@ mod tests {
             .expect_install_bundle()
             .once()
             .in_sequence(&mut seq)
-            .returning(|_| future::ready(Ok(())).boxed());
+            .returning(|_, _| future::ready(Ok(())).boxed());
         system_update
             .expect_operation()
             .once()
@@ -2025,7 +2935,7 @@ mod tests {
             .expect_install_bundle()
             .once()
             .in_sequence(&mut seq)
-            .returning(|_| future::ready(Ok(())).boxed());
+            .returning(|_, _| future::ready(Ok(())).boxed());
         system_update
             .expect_operation()
             .once()
@@ -2087,7 +2997,7 @@ mod tests {
             .expect_install_bundle()
             .once()
             .in_sequence(&mut seq)
-            .returning(|_| future::ready(Ok(())).boxed());
+            .returning(|_, _| future::ready(Ok(())).boxed());
         system_update
             .expect_operation()
             .once()
@@ -2151,7 +3061,7 @@ mod tests {
             .expect_install_bundle()
             .once()
             .in_sequence(&mut seq)
-            .returning(|_| future::ready(Ok(())).boxed());
+            .returning(|_, _| future::ready(Ok(())).boxed());
         system_update
             .expect_operation()
             .once()
@@ -2235,85 +3145,588 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn try_to_success_no_pending_update() {
+    async fn init_removes_stale_bundle() {
+        let (publisher_tx, _publisher_rx) = mpsc::channel(2);
         let mut state_mock = MockStateRepository::<PersistentState>::new();
-        let system_update = MockSystemUpdate::new();
-
         state_mock
             .expect_exists()
             .returning(|| future::ready(false).boxed());
 
-        let (ota_status_publisher, _ota_status_receiver) = mpsc::channel(1);
-        let ota = Ota::mock_new(system_update, state_mock, ota_status_publisher);
-        let ota_status = ota.check_reboot().await;
+        let (mut ota, _dir) = Ota::mock_new_with_path(
+            MockSystemUpdate::new(),
+            state_mock,
+            "init_removes_stale_bundle",
+            publisher_tx,
+        );
 
-        assert!(matches!(ota_status, OtaStatus::NoPendingOta));
+        let bundle = ota.get_update_file_path();
+        tokio::fs::write(&bundle, b"abcde").await.unwrap();
+
+        ota.init().await.unwrap();
+
+        assert!(!bundle.exists());
     }
 
     #[tokio::test]
-    async fn try_to_success_fail_read_state() {
+    async fn init_removes_expired_partial_download() {
+        let (publisher_tx, _publisher_rx) = mpsc::channel(2);
         let mut state_mock = MockStateRepository::<PersistentState>::new();
-        let system_update = MockSystemUpdate::new();
-
         state_mock
             .expect_exists()
-            .returning(|| future::ready(true).boxed());
-        state_mock.expect_read().returning(move || {
-            future::ready(Err(FileStateError::Write {
-                path: "/ota.bin".into(),
-                backtrace: io::Error::new(io::ErrorKind::PermissionDenied, "permission denied"),
-            }))
-            .boxed()
-        });
+            .returning(|| future::ready(false).boxed());
 
-        let (ota_status_publisher, _ota_status_receiver) = mpsc::channel(1);
-        let ota = Ota::mock_new(system_update, state_mock, ota_status_publisher);
-        let ota_status = ota.check_reboot().await;
+        let (mut ota, _dir) = Ota::mock_new_with_path(
+            MockSystemUpdate::new(),
+            state_mock,
+            "init_removes_expired_partial_download",
+            publisher_tx,
+        );
+        ota.config.retry.resume_max_age_secs = 0;
 
-        assert!(matches!(ota_status, OtaStatus::Failure(OtaError::Io(_), _)));
+        let bundle = ota.get_update_file_path();
+        partial_download(&bundle, "https://example.com/ota.bin", b"ab", 5).await;
+
+        ota.init().await.unwrap();
+
+        assert!(!bundle.exists());
+        assert!(!ResumeState::repository(&bundle).exists().await);
     }
 
     #[tokio::test]
-    async fn try_to_success_fail_pending_ota() {
+    async fn init_keeps_partial_download() {
+        let (publisher_tx, mut publisher_rx) = mpsc::channel(2);
         let mut state_mock = MockStateRepository::<PersistentState>::new();
-        let mut system_update = MockSystemUpdate::new();
-        let uuid = Uuid::new_v4();
-        let slot = "A";
-
         state_mock
             .expect_exists()
-            .returning(|| future::ready(true).boxed());
-        state_mock.expect_read().returning(move || {
-            future::ready(Ok(PersistentState {
-                uuid,
-                slot: slot.to_owned(),
-            }))
-            .boxed()
-        });
-        state_mock
-            .expect_clear()
-            .returning(|| future::ready(Ok(())).boxed());
+            .returning(|| future::ready(false).boxed());
 
-        system_update
-            .expect_boot_slot()
-            .returning(|| future::ready(Ok("A".to_owned())).boxed());
+        let (mut ota, _dir) = Ota::mock_new_with_path(
+            MockSystemUpdate::new(),
+            state_mock,
+            "init_keeps_partial_download",
+            publisher_tx,
+        );
 
-        let (ota_status_publisher, _ota_status_receiver) = mpsc::channel(1);
-        let ota = Ota::mock_new(system_update, state_mock, ota_status_publisher);
-        let ota_status = ota.check_reboot().await;
+        let server = MockServer::start_async().await;
+        let ota_url = server.url("/ota.bin");
+        let mock_range_request = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/ota.bin")
+                    .header("range", "bytes=2-")
+                    .header("if-range", "\"bundle-v1\"");
+                then.status(206)
+                    .header("Content-Range", "bytes 2-4/5")
+                    .header("content-Length", 3.to_string())
+                    .body(b"cde");
+            })
+            .await;
+
+        let bundle = ota.get_update_file_path();
+        partial_download(&bundle, &ota_url, b"ab", 5).await;
+
+        ota.init().await.unwrap();
+
+        assert!(bundle.exists());
+        assert!(ResumeState::repository(&bundle).exists().await);
+
+        let req = OtaId {
+            uuid: Uuid::new_v4(),
+            url: ota_url,
+        };
+
+        ota.retry_download(&req, &bundle, bundle.to_str().unwrap())
+            .await
+            .unwrap();
+
+        mock_range_request.assert_async().await;
 
+        assert_eq!(tokio::fs::read(&bundle).await.unwrap(), b"abcde");
+
+        // Final status of the init
+        assert_eq!(publisher_rx.try_recv().unwrap(), OtaStatus::Idle);
         assert!(matches!(
-            ota_status,
-            OtaStatus::Failure(OtaError::SystemRollback(_), _)
+            publisher_rx.try_recv().unwrap(),
+            OtaStatus::Downloading(_, 100)
         ));
     }
 
     #[tokio::test]
-    async fn try_to_success() {
+    async fn try_to_downloading_fail_unsigned_bundle() {
+        let (publisher_tx, _publisher_rx) = mpsc::channel(2);
+        let mut ota = Ota::mock_new(
+            MockSystemUpdate::new(),
+            MockStateRepository::<PersistentState>::new(),
+            publisher_tx,
+        );
+        ota.config.verification.public_key = Some(PathBuf::from("/etc/edgehog/ota.der"));
+        ota.check = BundleCheck {
+            digest: Some(BundleDigest {
+                algorithm: FileDigest::Sha256,
+                value: vec![0; 32],
+            }),
+            signature: None,
+        };
+
+        let ota_id = OtaId::default();
+
+        let status = ota.start_update(ota_id.clone()).await;
+
+        assert_eq!(
+            status,
+            OtaStatus::Failure(
+                OtaError::InvalidBaseImage(
+                    "Unable to verify the bundle signature: the request is missing the bundle signature"
+                        .to_string()
+                ),
+                Some(ota_id)
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn try_to_stage_download_success() {
+        let uuid = Uuid::new_v4();
+
         let mut state_mock = MockStateRepository::<PersistentState>::new();
         let mut system_update = MockSystemUpdate::new();
-        let uuid = Uuid::new_v4();
-        let slot = "A";
+
+        system_update.expect_info().once().returning(|_: &str| {
+            future::ready(Ok(BundleInfo {
+                compatible: "rauc-demo-x86".to_string(),
+                version: "1".to_string(),
+            }))
+            .boxed()
+        });
+        system_update
+            .expect_compatible()
+            .once()
+            .returning(|| future::ready(Ok("rauc-demo-x86".to_string())).boxed());
+
+        state_mock
+            .expect_write()
+            .once()
+            .with(predicate::eq(PersistentState {
+                uuid,
+                slot: String::new(),
+                staged: true,
+            }))
+            .returning(|_| future::ready(Ok(())).boxed());
+
+        let server = MockServer::start_async().await;
+        let ota_url = server.url("/ota.bin");
+
+        let mock_ota_file_request = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/ota.bin");
+                then.status(200).body(b"\x80\x02\x03");
+            })
+            .await;
+
+        let (publisher_tx, _publisher_rx) = mpsc::channel(2);
+        let (mut ota, _dir) =
+            Ota::mock_new_with_path(system_update, state_mock, "stage_success", publisher_tx);
+        ota.operation = OtaOperation::Stage;
+
+        let ota_id = OtaId { uuid, url: ota_url };
+
+        let status = ota.download(ota_id.clone()).await;
+
+        assert_eq!(status, OtaStatus::Staged(ota_id));
+        assert!(ota.get_update_file_path().exists());
+
+        mock_ota_file_request.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn try_to_download_local_bundle() {
+        let mut system_update = MockSystemUpdate::new();
+
+        system_update.expect_info().once().returning(|_: &str| {
+            future::ready(Ok(BundleInfo {
+                compatible: "rauc-demo-x86".to_string(),
+                version: "1".to_string(),
+            }))
+            .boxed()
+        });
+        system_update
+            .expect_compatible()
+            .once()
+            .returning(|| future::ready(Ok("rauc-demo-x86".to_string())).boxed());
+
+        let (_drop_dir, drop_path) = temp_dir("local_drop");
+        let bundle = drop_path.join("update.raucb");
+        tokio::fs::write(&bundle, b"\x80\x02\x03").await.unwrap();
+
+        let (publisher_tx, _publisher_rx) = mpsc::channel(2);
+        let (mut ota, _dir) = Ota::mock_new_with_path(
+            system_update,
+            MockStateRepository::<PersistentState>::new(),
+            "local_success",
+            publisher_tx,
+        );
+        ota.operation = OtaOperation::Local;
+        ota.check = BundleCheck {
+            digest: Some(
+                "sha256:79401198e510225591e7d5e6446c488a31cf208fdfd32253d8fc309125942802"
+                    .parse()
+                    .unwrap(),
+            ),
+            signature: None,
+        };
+
+        let ota_id = OtaId {
+            uuid: Uuid::new_v4(),
+            url: bundle.to_str().unwrap().to_string(),
+        };
+
+        let status = ota.download(ota_id.clone()).await;
+
+        assert_eq!(
+            status,
+            OtaStatus::Deploying(ota_id, DeployProgress::default())
+        );
+        // Removed only once installed
+        assert!(bundle.exists());
+        assert_eq!(
+            tokio::fs::read(ota.get_update_file_path()).await.unwrap(),
+            b"\x80\x02\x03"
+        );
+    }
+
+    #[tokio::test]
+    async fn try_to_download_changed_local_bundle() {
+        let (_drop_dir, drop_path) = temp_dir("local_drop_changed");
+        let bundle = drop_path.join("update.raucb");
+        tokio::fs::write(&bundle, b"\x80\x02\x04").await.unwrap();
+
+        let (publisher_tx, _publisher_rx) = mpsc::channel(2);
+        let (mut ota, _dir) = Ota::mock_new_with_path(
+            MockSystemUpdate::new(),
+            MockStateRepository::<PersistentState>::new(),
+            "local_changed",
+            publisher_tx,
+        );
+        ota.operation = OtaOperation::Local;
+        ota.check = BundleCheck {
+            digest: Some(
+                "sha256:79401198e510225591e7d5e6446c488a31cf208fdfd32253d8fc309125942802"
+                    .parse()
+                    .unwrap(),
+            ),
+            signature: None,
+        };
+
+        let ota_id = OtaId {
+            uuid: Uuid::new_v4(),
+            url: bundle.to_str().unwrap().to_string(),
+        };
+
+        let status = ota.download(ota_id.clone()).await;
+
+        assert_eq!(
+            status,
+            OtaStatus::Failure(
+                OtaError::InvalidBaseImage(VerifyError::DigestMismatch.to_string()),
+                Some(ota_id)
+            )
+        );
+        assert!(bundle.exists());
+
+        ota.ota_status = status;
+        ota.next().await;
+
+        assert_eq!(ota.ota_status, OtaStatus::Idle);
+        // Not submitted again by the watcher
+        assert!(!bundle.exists());
+        assert!(drop_path.join("update.raucb.failed").exists());
+    }
+
+    #[tokio::test]
+    async fn reject_local_bundle_while_staged() {
+        let staged = OtaId {
+            uuid: Uuid::new_v4(),
+            url: String::new(),
+        };
+        let ota_id = OtaId {
+            uuid: Uuid::new_v4(),
+            url: "/run/media/ota/update.raucb".to_string(),
+        };
+
+        let (publisher_tx, mut publisher_rx) = mpsc::channel(2);
+        let mut ota = Ota::mock_new(
+            MockSystemUpdate::new(),
+            MockStateRepository::<PersistentState>::new(),
+            publisher_tx,
+        );
+        ota.staged = Some(staged.clone());
+        ota.flag.set_in_progress(true);
+
+        ota.handle(OtaMessage {
+            ota_id: ota_id.clone(),
+            operation: OtaOperation::Local,
+            check: BundleCheck::default(),
+            http_headers: Vec::new(),
+            cancel: CancellationToken::new(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            publisher_rx.recv().await.unwrap(),
+            OtaStatus::Failure(
+                OtaError::Request("Unable to install the local bundle, a bundle is staged"),
+                Some(ota_id)
+            )
+        );
+        assert_eq!(ota.staged, Some(staged));
+        assert!(!ota.flag.in_progress());
+    }
+
+    #[tokio::test]
+    async fn try_to_success_staged_bundle() {
+        let uuid = Uuid::new_v4();
+
+        let mut state_mock = MockStateRepository::<PersistentState>::new();
+        let system_update = MockSystemUpdate::new();
+
+        state_mock
+            .expect_exists()
+            .returning(|| future::ready(true).boxed());
+        state_mock.expect_read().returning(move || {
+            future::ready(Ok(PersistentState {
+                uuid,
+                slot: String::new(),
+                staged: true,
+            }))
+            .boxed()
+        });
+
+        let (ota_status_publisher, _ota_status_receiver) = mpsc::channel(1);
+        let ota = Ota::mock_new(system_update, state_mock, ota_status_publisher);
+        let ota_status = ota.check_reboot().await;
+
+        assert_eq!(
+            ota_status,
+            OtaStatus::Staged(OtaId {
+                uuid,
+                url: String::new()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn cancel_staged_bundle() {
+        let ota_id = OtaId {
+            uuid: Uuid::new_v4(),
+            url: String::new(),
+        };
+
+        let mut state_mock = MockStateRepository::<PersistentState>::new();
+        state_mock
+            .expect_exists()
+            .returning(|| future::ready(true).boxed());
+        state_mock
+            .expect_clear()
+            .once()
+            .returning(|| future::ready(Ok(())).boxed());
+
+        let (publisher_tx, mut publisher_rx) = mpsc::channel(2);
+        let (mut ota, _dir) = Ota::mock_new_with_path(
+            MockSystemUpdate::new(),
+            state_mock,
+            "cancel_staged",
+            publisher_tx,
+        );
+        ota.staged = Some(ota_id.clone());
+
+        let path = ota.get_update_file_path();
+        tokio::fs::write(&path, b"bundle").await.unwrap();
+
+        ota.handle(OtaMessage {
+            ota_id: ota_id.clone(),
+            operation: OtaOperation::Cancel,
+            check: BundleCheck::default(),
+            http_headers: Vec::new(),
+            cancel: CancellationToken::new(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            publisher_rx.recv().await.unwrap(),
+            OtaStatus::Failure(OtaError::Canceled, Some(ota_id))
+        );
+        assert!(ota.staged.is_none());
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn activate_without_staged_bundle() {
+        let ota_id = OtaId {
+            uuid: Uuid::new_v4(),
+            url: String::new(),
+        };
+
+        let (publisher_tx, mut publisher_rx) = mpsc::channel(2);
+        let mut ota = Ota::mock_new(
+            MockSystemUpdate::new(),
+            MockStateRepository::<PersistentState>::new(),
+            publisher_tx,
+        );
+
+        ota.handle(OtaMessage {
+            ota_id: ota_id.clone(),
+            operation: OtaOperation::Activate,
+            check: BundleCheck::default(),
+            http_headers: Vec::new(),
+            cancel: CancellationToken::new(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            publisher_rx.recv().await.unwrap(),
+            OtaStatus::Failure(
+                OtaError::Request("Unable to activate OTA request, no staged bundle"),
+                Some(ota_id)
+            )
+        );
+        assert_eq!(ota.ota_status, OtaStatus::Idle);
+    }
+
+    #[tokio::test]
+    async fn activate_deferred_by_power_profile() {
+        let ota_id = OtaId {
+            uuid: Uuid::new_v4(),
+            url: String::new(),
+        };
+
+        let mut state_mock = MockStateRepository::<PersistentState>::new();
+        state_mock
+            .expect_exists()
+            .returning(|| future::ready(true).boxed());
+        state_mock
+            .expect_clear()
+            .returning(|| future::ready(Ok(())).boxed());
+
+        let (publisher_tx, mut publisher_rx) = mpsc::channel(4);
+        let (mut ota, _dir) = Ota::mock_new_with_path(
+            MockSystemUpdate::new(),
+            state_mock,
+            "activate_deferred",
+            publisher_tx,
+        );
+        ota.staged = Some(ota_id.clone());
+
+        let (_power_tx, power_rx) = tokio::sync::watch::channel(PowerProfile::LowPower);
+        ota.power = PowerState::new(power_rx, PeriodMultipliers::default());
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        // The deploy is never reached, since the system update has no expectations
+        ota.handle(OtaMessage {
+            ota_id: ota_id.clone(),
+            operation: OtaOperation::Activate,
+            check: BundleCheck::default(),
+            http_headers: Vec::new(),
+            cancel,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            publisher_rx.recv().await.unwrap(),
+            OtaStatus::Acknowledged(ota_id.clone())
+        );
+        assert_eq!(
+            publisher_rx.recv().await.unwrap(),
+            OtaStatus::Failure(OtaError::Canceled, Some(ota_id))
+        );
+    }
+
+    #[tokio::test]
+    async fn try_to_success_no_pending_update() {
+        let mut state_mock = MockStateRepository::<PersistentState>::new();
+        let system_update = MockSystemUpdate::new();
+
+        state_mock
+            .expect_exists()
+            .returning(|| future::ready(false).boxed());
+
+        let (ota_status_publisher, _ota_status_receiver) = mpsc::channel(1);
+        let ota = Ota::mock_new(system_update, state_mock, ota_status_publisher);
+        let ota_status = ota.check_reboot().await;
+
+        assert!(matches!(ota_status, OtaStatus::NoPendingOta));
+    }
+
+    #[tokio::test]
+    async fn try_to_success_fail_read_state() {
+        let mut state_mock = MockStateRepository::<PersistentState>::new();
+        let system_update = MockSystemUpdate::new();
+
+        state_mock
+            .expect_exists()
+            .returning(|| future::ready(true).boxed());
+        state_mock.expect_read().returning(move || {
+            future::ready(Err(FileStateError::Write {
+                path: "/ota.bin".into(),
+                backtrace: io::Error::new(io::ErrorKind::PermissionDenied, "permission denied"),
+            }))
+            .boxed()
+        });
+
+        let (ota_status_publisher, _ota_status_receiver) = mpsc::channel(1);
+        let ota = Ota::mock_new(system_update, state_mock, ota_status_publisher);
+        let ota_status = ota.check_reboot().await;
+
+        assert!(matches!(ota_status, OtaStatus::Failure(OtaError::Io(_), _)));
+    }
+
+    #[tokio::test]
+    async fn try_to_success_fail_pending_ota() {
+        let mut state_mock = MockStateRepository::<PersistentState>::new();
+        let mut system_update = MockSystemUpdate::new();
+        let uuid = Uuid::new_v4();
+        let slot = "A";
+
+        state_mock
+            .expect_exists()
+            .returning(|| future::ready(true).boxed());
+        state_mock.expect_read().returning(move || {
+            future::ready(Ok(PersistentState {
+                uuid,
+                slot: slot.to_owned(),
+                staged: false,
+            }))
+            .boxed()
+        });
+        state_mock
+            .expect_clear()
+            .returning(|| future::ready(Ok(())).boxed());
+
+        system_update
+            .expect_boot_slot()
+            .returning(|| future::ready(Ok("A".to_owned())).boxed());
+
+        let (ota_status_publisher, _ota_status_receiver) = mpsc::channel(1);
+        let ota = Ota::mock_new(system_update, state_mock, ota_status_publisher);
+        let ota_status = ota.check_reboot().await;
+
+        assert!(matches!(
+            ota_status,
+            OtaStatus::Failure(OtaError::SystemRollback(_), _)
+        ));
+    }
+
+    #[tokio::test]
+    async fn try_to_success() {
+        let mut state_mock = MockStateRepository::<PersistentState>::new();
+        let mut system_update = MockSystemUpdate::new();
+        let uuid = Uuid::new_v4();
+        let slot = "A";
 
         state_mock
             .expect_exists()
@@ -2322,6 +3735,7 @@ mod tests {
             future::ready(Ok(PersistentState {
                 uuid,
                 slot: slot.to_owned(),
+                staged: false,
             }))
             .boxed()
         });
@@ -2360,6 +3774,7 @@ mod tests {
             future::ready(Ok(PersistentState {
                 uuid,
                 slot: slot.to_owned(),
+                staged: false,
             }))
             .boxed()
         });
@@ -2392,6 +3807,7 @@ mod tests {
             future::ready(Ok(PersistentState {
                 uuid,
                 slot: slot.to_owned(),
+                staged: false,
             }))
             .boxed()
         });
@@ -2421,6 +3837,7 @@ mod tests {
             future::ready(Ok(PersistentState {
                 uuid,
                 slot: slot.to_owned(),
+                staged: false,
             }))
             .boxed()
         });
@@ -2458,6 +3875,7 @@ mod tests {
             future::ready(Ok(PersistentState {
                 uuid,
                 slot: slot.to_owned(),
+                staged: false,
             }))
             .boxed()
         });
@@ -2521,6 +3939,7 @@ mod tests {
         let state = PersistentState {
             uuid: Uuid::new_v4(),
             slot: "A".to_string(),
+            staged: false,
         };
 
         let err = ota.do_pending_ota(&state).await.unwrap_err();
@@ -2541,6 +3960,7 @@ mod tests {
             future::ready(Ok(PersistentState {
                 uuid,
                 slot: slot.to_owned(),
+                staged: false,
             }))
             .boxed()
         });
@@ -2572,6 +3992,71 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn do_pending_ota_fail_health_check() {
+        let (_dir, t_dir) = temp_dir("do_pending_ota_fail_health_check");
+
+        let script = t_dir.join("health.sh");
+        std::fs::write(&script, "#!/bin/sh\nexit 1\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let state_mock = MockStateRepository::<PersistentState>::new();
+
+        let mut system_update = MockSystemUpdate::new();
+        system_update
+            .expect_boot_slot()
+            .returning(|| future::ready(Ok("B".to_owned())).boxed());
+        system_update
+            .expect_get_primary()
+            .returning(|| future::ready(Ok("rootfs.0".to_owned())).boxed());
+        system_update
+            .expect_mark()
+            .once()
+            .with(predicate::eq("bad"), predicate::eq("rootfs.0"))
+            .returning(|_: &str, _: &str| {
+                future::ready(Ok((
+                    "rootfs.0".to_owned(),
+                    "marked slot rootfs.0 as bad".to_owned(),
+                )))
+                .boxed()
+            });
+
+        let (ota_status_publisher, _ota_status_receiver) = mpsc::channel(1);
+        let mut ota = Ota::mock_new(system_update, state_mock, ota_status_publisher);
+        ota.health = HealthGate::new(
+            HealthConfig {
+                timeout_secs: 1,
+                interval_secs: 1,
+                checks: vec![HealthCheck::Script {
+                    path: script.clone(),
+                }],
+            },
+            None,
+            None,
+            #[cfg(feature = "containers")]
+            Default::default(),
+        );
+
+        let state = PersistentState {
+            uuid: Uuid::new_v4(),
+            slot: "A".to_owned(),
+            staged: false,
+        };
+        let result = ota.do_pending_ota(&state).await;
+
+        let Err(OtaError::SystemRollback(message)) = result else {
+            panic!("expected a system rollback, got {result:?}");
+        };
+        assert_eq!(
+            message,
+            format!(
+                "health check script {} failed: script exited with exit status: 1",
+                script.display()
+            )
+        );
+        assert!(ota.rollback.load(Ordering::Acquire));
+    }
+
     #[tokio::test]
     async fn wget_failed() {
         let (_dir, t_dir) = temp_dir("wget_failed");
@@ -2594,11 +4079,23 @@ mod tests {
         };
 
         let client = create_http_client(&req).unwrap();
-        let result = wget(&client, &req, &ota_file, &ota_status_publisher).await;
+        let result = wget(
+            &client,
+            &req,
+            &ota_file,
+            None,
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await;
 
         hello_mock.assert_async().await;
         assert!(result.is_err());
-        assert!(matches!(result.err().unwrap(), OtaError::Network(_),));
+        assert!(matches!(
+            result.err().unwrap(),
+            OtaError::Http { status: 500, .. }
+        ));
     }
 
     #[tokio::test]
@@ -2627,7 +4124,16 @@ mod tests {
         let (ota_status_publisher, _) = mpsc::channel(1);
         let client = create_http_client(&req).unwrap();
 
-        let result = wget(&client, &req, &ota_file, &ota_status_publisher).await;
+        let result = wget(
+            &client,
+            &req,
+            &ota_file,
+            None,
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await;
 
         mock_ota_file_request.assert_async().await;
         assert!(result.is_err());
@@ -2657,7 +4163,16 @@ mod tests {
 
         let client = create_http_client(&req).unwrap();
 
-        let result = wget(&client, &req, &ota_file, &ota_status_publisher).await;
+        let result = wget(
+            &client,
+            &req,
+            &ota_file,
+            None,
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await;
 
         mock_ota_file_request.assert_async().await;
         assert!(result.is_err());
@@ -2692,7 +4207,16 @@ mod tests {
         };
 
         let client = create_http_client(&req).unwrap();
-        let result = wget(&client, &req, &ota_file, &ota_status_publisher).await;
+        let result = wget(
+            &client,
+            &req,
+            &ota_file,
+            None,
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await;
         mock_ota_file_request.assert_async().await;
 
         let receive_result = ota_status_receiver.try_recv();
@@ -2708,4 +4232,352 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    /// Writes the partial download of a previous attempt.
+    async fn partial_download(file: &std::path::Path, url: &str, content: &[u8], total: u64) {
+        tokio::fs::write(file, content).await.unwrap();
+
+        ResumeState::repository(file)
+            .write(&ResumeState {
+                url: url.to_string(),
+                etag: "\"bundle-v1\"".to_string(),
+                total,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn wget_clears_resume_state() {
+        let (_dir, t_dir) = temp_dir("wget_clears_resume_state");
+
+        let server = MockServer::start_async().await;
+        let ota_url = server.url("/ota.bin");
+        let mock_ota_file_request = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/ota.bin");
+                then.status(200)
+                    .header("ETag", "\"bundle-v1\"")
+                    .header("content-Length", 3.to_string())
+                    .body(b"\x80\x02\x03");
+            })
+            .await;
+
+        let ota_file = t_dir.join("ota.bin");
+        let req = OtaId {
+            uuid: Uuid::new_v4(),
+            url: ota_url,
+        };
+
+        let (ota_status_publisher, _rx) = mpsc::channel(2);
+        let client = create_http_client(&req).unwrap();
+
+        wget(
+            &client,
+            &req,
+            &ota_file,
+            None,
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await
+        .unwrap();
+
+        mock_ota_file_request.assert_async().await;
+
+        // Removed after the download is completed
+        assert!(!ResumeState::repository(&ota_file).exists().await);
+    }
+
+    #[tokio::test]
+    async fn wget_resumes_partial_download() {
+        let (_dir, t_dir) = temp_dir("wget_resumes_partial_download");
+
+        let server = MockServer::start_async().await;
+        let ota_url = server.url("/ota.bin");
+        let mock_range_request = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/ota.bin")
+                    .header("range", "bytes=2-")
+                    .header("if-range", "\"bundle-v1\"");
+                then.status(206)
+                    .header("Content-Range", "bytes 2-4/5")
+                    .header("content-Length", 3.to_string())
+                    .body(b"cde");
+            })
+            .await;
+
+        let ota_file = t_dir.join("ota.bin");
+        partial_download(&ota_file, &ota_url, b"ab", 5).await;
+
+        let req = OtaId {
+            uuid: Uuid::new_v4(),
+            url: ota_url,
+        };
+
+        let (ota_status_publisher, mut ota_status_receiver) = mpsc::channel(2);
+        let client = create_http_client(&req).unwrap();
+
+        wget(
+            &client,
+            &req,
+            &ota_file,
+            None,
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await
+        .unwrap();
+
+        mock_range_request.assert_async().await;
+
+        assert_eq!(tokio::fs::read(&ota_file).await.unwrap(), b"abcde");
+        assert!(matches!(
+            ota_status_receiver.try_recv().unwrap(),
+            OtaStatus::Downloading(_, 100)
+        ));
+        assert!(!ResumeState::repository(&ota_file).exists().await);
+    }
+
+    #[tokio::test]
+    async fn wget_checks_resumed_download_digest() {
+        let (_dir, t_dir) = temp_dir("wget_checks_resumed_download_digest");
+
+        let server = MockServer::start_async().await;
+        let ota_url = server.url("/ota.bin");
+        let mock_range_request = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/ota.bin")
+                    .header("range", "bytes=2-");
+                then.status(206)
+                    .header("Content-Range", "bytes 2-4/5")
+                    .header("content-Length", 3.to_string())
+                    .body(b"cde");
+            })
+            .await;
+
+        let ota_file = t_dir.join("ota.bin");
+        partial_download(&ota_file, &ota_url, b"ab", 5).await;
+
+        let req = OtaId {
+            uuid: Uuid::new_v4(),
+            url: ota_url,
+        };
+        let digest = BundleDigest {
+            algorithm: FileDigest::Sha512,
+            value: aws_lc_rs::digest::digest(&aws_lc_rs::digest::SHA512, b"abcde")
+                .as_ref()
+                .to_vec(),
+        };
+
+        let (ota_status_publisher, _rx) = mpsc::channel(2);
+        let client = create_http_client(&req).unwrap();
+
+        wget(
+            &client,
+            &req,
+            &ota_file,
+            Some(&digest),
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await
+        .unwrap();
+
+        mock_range_request.assert_async().await;
+
+        assert_eq!(tokio::fs::read(&ota_file).await.unwrap(), b"abcde");
+    }
+
+    #[tokio::test]
+    async fn wget_rejects_digest_mismatch() {
+        let (_dir, t_dir) = temp_dir("wget_rejects_digest_mismatch");
+
+        let server = MockServer::start_async().await;
+        let ota_url = server.url("/ota.bin");
+        let mock_ota_file_request = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/ota.bin");
+                then.status(200)
+                    .header("ETag", "\"bundle-v1\"")
+                    .header("content-Length", 3.to_string())
+                    .body(b"\x80\x02\x03");
+            })
+            .await;
+
+        let ota_file = t_dir.join("ota.bin");
+        let req = OtaId {
+            uuid: Uuid::new_v4(),
+            url: ota_url,
+        };
+        let digest = BundleDigest {
+            algorithm: FileDigest::Sha256,
+            value: vec![0; 32],
+        };
+
+        let (ota_status_publisher, _rx) = mpsc::channel(2);
+        let client = create_http_client(&req).unwrap();
+
+        let err = wget(
+            &client,
+            &req,
+            &ota_file,
+            Some(&digest),
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await
+        .unwrap_err();
+
+        mock_ota_file_request.assert_async().await;
+
+        assert!(matches!(err, OtaError::InvalidBaseImage(_)), "got {err:?}");
+        assert!(!ota_file.exists());
+        assert!(!ResumeState::repository(&ota_file).exists().await);
+    }
+
+    #[tokio::test]
+    async fn wget_fails_without_free_space() {
+        let (_dir, t_dir) = temp_dir("wget_fails_without_free_space");
+
+        let server = MockServer::start_async().await;
+        let ota_url = server.url("/ota.bin");
+        let mock_ota_file_request = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/ota.bin");
+                then.status(200)
+                    .header("content-Length", 3.to_string())
+                    .body(b"\x80\x02\x03");
+            })
+            .await;
+
+        let ota_file = t_dir.join("ota.bin");
+        let req = OtaId {
+            uuid: Uuid::new_v4(),
+            url: ota_url,
+        };
+
+        let (ota_status_publisher, _rx) = mpsc::channel(2);
+        let client = create_http_client(&req).unwrap();
+
+        // The whole filesystem is reserved
+        let err = wget(
+            &client,
+            &req,
+            &ota_file,
+            None,
+            Percentage::new(100),
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await
+        .unwrap_err();
+
+        mock_ota_file_request.assert_async().await;
+
+        assert!(matches!(err, OtaError::Io(_)), "got {err:?}");
+        assert!(!ota_file.exists());
+    }
+
+    #[tokio::test]
+    async fn wget_restarts_on_range_not_satisfiable() {
+        let (_dir, t_dir) = temp_dir("wget_restarts_on_range_not_satisfiable");
+
+        let server = MockServer::start_async().await;
+        let ota_url = server.url("/ota.bin");
+        let mock_range_request = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/ota.bin").header_exists("range");
+                then.status(416).header("Content-Range", "bytes */4");
+            })
+            .await;
+        let mock_full_request = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/ota.bin").header_missing("range");
+                then.status(200)
+                    .header("content-Length", 4.to_string())
+                    .body(b"wxyz");
+            })
+            .await;
+
+        let ota_file = t_dir.join("ota.bin");
+        partial_download(&ota_file, &ota_url, b"ab", 5).await;
+
+        let req = OtaId {
+            uuid: Uuid::new_v4(),
+            url: ota_url,
+        };
+
+        let (ota_status_publisher, _rx) = mpsc::channel(2);
+        let client = create_http_client(&req).unwrap();
+
+        wget(
+            &client,
+            &req,
+            &ota_file,
+            None,
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await
+        .unwrap();
+
+        mock_range_request.assert_async().await;
+        mock_full_request.assert_async().await;
+
+        assert_eq!(tokio::fs::read(&ota_file).await.unwrap(), b"wxyz");
+    }
+
+    #[tokio::test]
+    async fn wget_restarts_when_bundle_changed() {
+        let (_dir, t_dir) = temp_dir("wget_restarts_when_bundle_changed");
+
+        let server = MockServer::start_async().await;
+        let ota_url = server.url("/ota.bin");
+        let mock_ota_file_request = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/ota.bin")
+                    .header("range", "bytes=2-");
+                then.status(200)
+                    .header("ETag", "\"bundle-v2\"")
+                    .header("content-Length", 4.to_string())
+                    .body(b"wxyz");
+            })
+            .await;
+
+        let ota_file = t_dir.join("ota.bin");
+        partial_download(&ota_file, &ota_url, b"ab", 5).await;
+
+        let req = OtaId {
+            uuid: Uuid::new_v4(),
+            url: ota_url,
+        };
+
+        let (ota_status_publisher, _rx) = mpsc::channel(2);
+        let client = create_http_client(&req).unwrap();
+
+        wget(
+            &client,
+            &req,
+            &ota_file,
+            None,
+            None,
+            &Bandwidth::default(),
+            &ota_status_publisher,
+        )
+        .await
+        .unwrap();
+
+        mock_ota_file_request.assert_async().await;
+
+        assert_eq!(tokio::fs::read(&ota_file).await.unwrap(), b"wxyz");
+    }
 }
@@ -16,24 +16,38 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use std::any::Any;
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::time::Duration;
 
 use astarte_device_sdk::aggregate::AstarteObject;
 use astarte_device_sdk::chrono::Utc;
-use astarte_device_sdk::{Client, IntoAstarteObject};
+use astarte_device_sdk::store::SqliteStore;
+use astarte_device_sdk::transport::mqtt::Mqtt;
+use astarte_device_sdk::{Client, DeviceClient, IntoAstarteObject};
+use edgehog_store::db::Handle;
 use eyre::Context;
 use tokio::sync::mpsc;
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace};
 
+use crate::bandwidth::Bandwidth;
 use crate::controller::actor::Actor;
 use crate::error::DeviceManagerError;
 use crate::ota::OtaError;
+use crate::ota::config::OtaBackend;
+use crate::ota::health::HealthGate;
+use crate::ota::history::{HistoryPublisher, OtaHistory};
+use crate::ota::local::LocalWatcher;
 use crate::ota::rauc::OTARauc;
+use crate::ota::script::ScriptUpdate;
+use crate::ota::swupdate::SwUpdate;
+use crate::ota::verify::BundleCheck;
 use crate::ota::{Ota, OtaId, OtaStatus};
+use crate::power_policy::PowerState;
 use crate::repository::file_state_repository::FileStateRepository;
 
 use super::PersistentState;
@@ -60,6 +74,9 @@ pub struct OtaStatusMessage {
 #[derive(Debug, Clone)]
 pub struct OtaMessage {
     pub ota_id: OtaId,
+    pub operation: OtaOperation,
+    pub check: BundleCheck,
+    pub http_headers: Vec<String>,
     pub cancel: CancellationToken,
 }
 
@@ -84,14 +101,23 @@ pub struct OtaHandler {
     pub publisher_tx: mpsc::Sender<OtaStatus>,
     pub flag: OtaInProgress,
     pub current: Option<OtaMessage>,
+    /// Requests for the bundles dropped in the local directory
+    pub local_rx: Option<mpsc::Receiver<OtaRequest>>,
 }
 
 impl OtaHandler {
+    #[allow(clippy::too_many_arguments)]
     pub async fn start<C>(
         tasks: &mut JoinSet<eyre::Result<()>>,
         cancel: CancellationToken,
         client: C,
         opts: &crate::DeviceManagerOptions,
+        power: PowerState,
+        bandwidth: Bandwidth,
+        db: Handle,
+        #[cfg(feature = "containers")] containers: Arc<
+            tokio::sync::OnceCell<edgehog_containers::local::ContainerHandle>,
+        >,
     ) -> eyre::Result<Self>
     where
         C: Client + Send + Sync + 'static,
@@ -101,26 +127,109 @@ impl OtaHandler {
         let (publisher_tx, publisher_rx) = mpsc::channel(8);
         let (ota_tx, ota_rx) = mpsc::channel(MAX_OTA_OPERATION);
 
-        let system_update = OTARauc::connect(&opts.ota)
-            .await
-            .wrap_err("couldn't connect to RAUC")?;
-
         let state_repository = FileStateRepository::new(&opts.store_directory, "state.json");
 
+        let (history_tx, history_rx) = mpsc::channel(8);
+        let history = OtaHistory::new(db, history_tx, opts.ota.history);
+
+        let history_publisher = HistoryPublisher::new(client.clone());
+
+        history.prune().await;
+
+        // Only the MQTT client exposes the state of the connection to Astarte
+        let mqtt = (&client as &dyn Any)
+            .downcast_ref::<DeviceClient<Mqtt<SqliteStore>>>()
+            .cloned();
+
         let publisher = OtaPublisher::new(client);
 
         let flag = OtaInProgress::default();
 
-        let ota = Ota::<OTARauc, FileStateRepository<PersistentState>>::new(
-            opts,
-            publisher_tx.clone(),
-            flag.clone(),
-            system_update,
-            state_repository,
+        let health = HealthGate::new(
+            opts.ota.health.clone(),
+            opts.astarte_device_sdk
+                .as_ref()
+                .map(|sdk| sdk.pairing_url.clone()),
+            mqtt,
+            #[cfg(feature = "containers")]
+            containers,
         );
 
+        match opts.ota.backend {
+            OtaBackend::Rauc => {
+                let system_update = OTARauc::connect(&opts.ota)
+                    .await
+                    .wrap_err("couldn't connect to RAUC")?;
+
+                let ota = Ota::<OTARauc, FileStateRepository<PersistentState>>::new(
+                    opts,
+                    publisher_tx.clone(),
+                    flag.clone(),
+                    system_update,
+                    state_repository,
+                    power,
+                    health,
+                    bandwidth,
+                    history,
+                );
+
+                tasks.spawn(ota.run(ota_rx, cancel.clone()));
+            }
+            OtaBackend::SwUpdate => {
+                let system_update = SwUpdate::new(opts.ota.swupdate.clone());
+
+                let ota = Ota::<SwUpdate, FileStateRepository<PersistentState>>::new(
+                    opts,
+                    publisher_tx.clone(),
+                    flag.clone(),
+                    system_update,
+                    state_repository,
+                    power,
+                    health,
+                    bandwidth,
+                    history,
+                );
+
+                tasks.spawn(ota.run(ota_rx, cancel.clone()));
+            }
+            OtaBackend::Script => {
+                let system_update = ScriptUpdate::new(opts.ota.script.clone());
+
+                let ota = Ota::<ScriptUpdate, FileStateRepository<PersistentState>>::new(
+                    opts,
+                    publisher_tx.clone(),
+                    flag.clone(),
+                    system_update,
+                    state_repository,
+                    power,
+                    health,
+                    bandwidth,
+                    history,
+                );
+
+                tasks.spawn(ota.run(ota_rx, cancel.clone()));
+            }
+        }
+
+        // Started only once the backend is available
+        tasks.spawn(history_publisher.run(history_rx, cancel.clone()));
         tasks.spawn(publisher.run(publisher_rx, cancel.clone()));
-        tasks.spawn(ota.run(ota_rx, cancel));
+
+        let local_rx = opts.ota.local.directory.as_ref().map(|directory| {
+            let (local_tx, local_rx) = mpsc::channel(1);
+
+            let watcher = LocalWatcher::new(
+                directory.clone(),
+                Duration::from_secs(opts.ota.local.interval_secs),
+                opts.ota.local.extensions.clone(),
+                local_tx,
+                flag.clone(),
+            );
+
+            tasks.spawn(watcher.run(cancel.clone()));
+
+            local_rx
+        });
 
         trace!("ota handler started");
 
@@ -129,6 +238,7 @@ impl OtaHandler {
             publisher_tx,
             flag,
             current: None,
+            local_rx,
         })
     }
 
@@ -137,12 +247,34 @@ impl OtaHandler {
         self.flag.in_progress()
     }
 
-    pub async fn handle_event(&mut self, req: OtaRequest) -> Result<(), DeviceManagerError> {
+    /// Waits for the request of a bundle dropped in the local directory.
+    ///
+    /// The request is handled with [`OtaHandler::handle_event`] like the ones from Astarte. It
+    /// never returns if the local directory is not configured.
+    pub async fn next_local(&mut self) -> OtaRequest {
+        if let Some(local_rx) = &mut self.local_rx
+            && let Some(req) = local_rx.recv().await
+        {
+            return req;
+        }
+
+        // The watcher stopped
+        self.local_rx = None;
+
+        std::future::pending().await
+    }
+
+    pub async fn handle_event(&mut self, mut req: OtaRequest) -> Result<(), DeviceManagerError> {
         let operation = req.operation;
+        let check = std::mem::take(&mut req.check);
+        let http_headers = std::mem::take(&mut req.http_headers);
         let id = OtaId::from(req);
 
         match operation {
-            OtaOperation::Update => self.handle_update(id).await,
+            OtaOperation::Update
+            | OtaOperation::Stage
+            | OtaOperation::Activate
+            | OtaOperation::Local => self.handle_update(id, operation, check, http_headers).await,
             OtaOperation::Cancel => {
                 self.handle_cancel(id).await;
 
@@ -151,7 +283,13 @@ impl OtaHandler {
         }
     }
 
-    async fn handle_update(&mut self, id: OtaId) -> Result<(), DeviceManagerError> {
+    async fn handle_update(
+        &mut self,
+        id: OtaId,
+        operation: OtaOperation,
+        check: BundleCheck,
+        http_headers: Vec<String>,
+    ) -> Result<(), DeviceManagerError> {
         if self.check_update_already_in_progress(&id).await {
             return Ok(());
         }
@@ -160,6 +298,9 @@ impl OtaHandler {
 
         let ota_message = OtaMessage {
             ota_id: id,
+            operation,
+            check,
+            http_headers,
             cancel: CancellationToken::new(),
         };
 
@@ -212,9 +353,18 @@ impl OtaHandler {
     /// Cancel an in progress OTA.
     ///
     /// This will check only the current since after a reboot we cannot cancel an OTA. The cancel
-    /// will be handled by the OTA task.
+    /// will be handled by the OTA task. A staged bundle is discarded by the OTA task.
     async fn handle_cancel(&mut self, id: OtaId) {
         match &self.current {
+            Some(current)
+                if current.ota_id == id
+                    && current.operation == OtaOperation::Stage
+                    && !self.flag.in_progress() =>
+            {
+                self.current = None;
+
+                self.cancel_staged(id).await;
+            }
             Some(current) if current.ota_id == id => {
                 info!("Ota {id} cancelled");
 
@@ -233,17 +383,28 @@ impl OtaHandler {
                     ))
                     .await;
             }
-            None => {
-                let _ = self
-                    .publisher_tx
-                    .send(OtaStatus::Failure(
-                        OtaError::Internal(
-                            "Unable to cancel OTA request, internal request is empty",
-                        ),
-                        Some(id),
-                    ))
-                    .await;
-            }
+            None => self.cancel_staged(id).await,
+        }
+    }
+
+    /// Sends the cancel to the OTA task, that checks for a staged bundle.
+    async fn cancel_staged(&mut self, id: OtaId) {
+        let ota_message = OtaMessage {
+            ota_id: id.clone(),
+            operation: OtaOperation::Cancel,
+            check: BundleCheck::default(),
+            http_headers: Vec::new(),
+            cancel: CancellationToken::new(),
+        };
+
+        if self.ota_tx.send(ota_message).await.is_err() {
+            let _ = self
+                .publisher_tx
+                .send(OtaStatus::Failure(
+                    OtaError::Internal("Unable to cancel OTA request, internal request is empty"),
+                    Some(id),
+                ))
+                .await;
         }
     }
 }
@@ -265,7 +426,14 @@ impl From<&OtaError> for OtaStatusMessage {
             }
             OtaError::Network(message) => {
                 ota_status_message.status_code = "NetworkError".to_string();
-                ota_status_message.message = message.to_string()
+                ota_status_message.message = format!("{message} ({} error)", ota_error.class())
+            }
+            OtaError::Http { status, message } => {
+                ota_status_message.status_code = "NetworkError".to_string();
+                ota_status_message.message = format!(
+                    "{message} (HTTP status {status}, {} error)",
+                    ota_error.class()
+                )
             }
             OtaError::Io(message) => {
                 ota_status_message.status_code = "IOError".to_string();
@@ -592,7 +760,7 @@ mod tests {
             status: "Failure".to_string(),
             statusProgress: 0,
             statusCode: "NetworkError".to_string(),
-            message: "no network".to_string(),
+            message: "no network (retryable error)".to_string(),
         };
 
         let ota_event = OtaStatus::Failure(
@@ -607,6 +775,33 @@ mod tests {
         assert_eq!(expected_ota_event.requestUUID, ota_event.requestUUID);
     }
 
+    #[test]
+    #[allow(non_snake_case)]
+    fn convert_ota_status_Failure_HttpError_to_OtaStatusMessage() {
+        let ota_request = OtaId::default();
+        let expected_ota_event = OtaEvent {
+            requestUUID: ota_request.uuid.to_string(),
+            status: "Failure".to_string(),
+            statusProgress: 0,
+            statusCode: "NetworkError".to_string(),
+            message: "not found (HTTP status 404, fatal error)".to_string(),
+        };
+
+        let ota_event = OtaStatus::Failure(
+            OtaError::Http {
+                status: 404,
+                message: "not found".to_string(),
+            },
+            Some(ota_request),
+        )
+        .as_event()
+        .unwrap();
+        assert_eq!(expected_ota_event.status, ota_event.status);
+        assert_eq!(expected_ota_event.statusCode, ota_event.statusCode);
+        assert_eq!(expected_ota_event.message, ota_event.message);
+        assert_eq!(expected_ota_event.requestUUID, ota_event.requestUUID);
+    }
+
     #[test]
     #[allow(non_snake_case)]
     fn convert_ota_status_Failure_IOError_to_OtaStatusMessage() {
@@ -687,7 +882,7 @@ mod tests {
         };
 
         let ota_event = OtaStatus::Failure(
-            OtaError::SystemRollback("Unable to switch partition"),
+            OtaError::SystemRollback("Unable to switch partition".to_string()),
             Some(ota_request),
         )
         .as_event()
@@ -37,6 +37,7 @@ use uuid::Uuid;
 use crate::error::DeviceManagerError;
 use crate::ota::ota_handler::{OtaHandler, OtaMessage};
 use crate::ota::rauc::BundleInfo;
+use crate::ota::verify::BundleCheck;
 use crate::ota::{DeployProgress, DeployStatus, MockSystemUpdate, OtaError, ProgressStream};
 use crate::ota::{Ota, OtaId, OtaStatus, PersistentState};
 use crate::repository::MockStateRepository;
@@ -91,6 +92,7 @@ impl OtaHandler {
             publisher_tx,
             flag,
             current: None,
+            local_rx: None,
         }
     }
 }
@@ -155,7 +157,7 @@ async fn handle_ota_event_bundle_not_compatible() {
         .expect_install_bundle()
         .once()
         .in_sequence(&mut seq)
-        .returning(|_| {
+        .returning(|_, _| {
             future::ready(Err(DeviceManagerError::Fatal("install fail".to_string()))).boxed()
         });
 
@@ -177,6 +179,8 @@ async fn handle_ota_event_bundle_not_compatible() {
         url: ota_url.clone(),
         operation: OtaOperation::Update,
         uuid: uuid.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let (publisher_tx, mut publisher_rx) = mpsc::channel(8);
@@ -276,7 +280,7 @@ async fn handle_ota_event_bundle_install_completed_fail() {
         .expect_install_bundle()
         .once()
         .in_sequence(&mut seq)
-        .returning(|_: &str| future::ready(Ok(())).boxed());
+        .returning(|_, _| future::ready(Ok(())).boxed());
 
     system_update
         .expect_operation()
@@ -319,6 +323,8 @@ async fn handle_ota_event_bundle_install_completed_fail() {
         operation: OtaOperation::Update,
         url: ota_url.clone(),
         uuid: uuid.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let (publisher_tx, mut publisher_rx) = mpsc::channel(8);
@@ -417,7 +423,7 @@ async fn ota_event_fail_deployed() {
         .expect_install_bundle()
         .once()
         .in_sequence(&mut seq)
-        .returning(|_| {
+        .returning(|_, _| {
             future::ready(Err(DeviceManagerError::Fatal("install fail".to_string()))).boxed()
         });
 
@@ -439,6 +445,8 @@ async fn ota_event_fail_deployed() {
         url: ota_url.clone(),
         operation: OtaOperation::Update,
         uuid: uuid.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let (publisher_tx, mut publisher_rx) = mpsc::channel(8);
@@ -514,6 +522,7 @@ async fn ota_event_update_success() {
             future::ready(Ok(PersistentState {
                 uuid,
                 slot: "A".to_owned(),
+                staged: false,
             }))
             .boxed()
         });
@@ -555,7 +564,7 @@ async fn ota_event_update_success() {
         .expect_install_bundle()
         .once()
         .in_sequence(&mut seq)
-        .returning(|_: &str| future::ready(Ok(())).boxed());
+        .returning(|_, _| future::ready(Ok(())).boxed());
 
     system_update
         .expect_operation()
@@ -616,6 +625,8 @@ async fn ota_event_update_success() {
         operation: OtaOperation::Update,
         url: ota_url.clone(),
         uuid: uuid.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let (publisher_tx, mut publisher_rx) = mpsc::channel(8);
@@ -681,6 +692,8 @@ async fn ota_event_update_already_in_progress_same_uuid() {
         operation: OtaOperation::Update,
         url: ota_url.clone(),
         uuid: uuid.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let (publisher_tx, mut publisher_rx) = mpsc::channel(1);
@@ -740,12 +753,16 @@ async fn ota_event_update_already_in_progress_different_uuid() {
         operation: OtaOperation::Update,
         url: ota_url.clone(),
         uuid: uuid_1.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let req2 = OtaRequest {
         operation: OtaOperation::Update,
         url: ota_url.clone(),
         uuid: uuid_2.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let (publisher_tx, mut publisher_rx) = mpsc::channel(2);
@@ -807,6 +824,81 @@ async fn ota_event_update_already_in_progress_different_uuid() {
     assert_eq!(count, 2);
 }
 
+#[tokio::test]
+async fn ota_event_local_while_update_in_progress() {
+    let uuid_1 = Uuid::new_v4();
+    let uuid_2 = Uuid::new_v4();
+
+    let mut state_mock = MockStateRepository::<PersistentState>::new();
+    state_mock
+        .expect_exists()
+        .returning(|| future::ready(false).boxed());
+
+    let system_update = MockSystemUpdate::new();
+
+    let ota_url = "http://localhost".to_string();
+    let local_url = "/run/media/ota/update.raucb".to_string();
+
+    let req = OtaRequest {
+        operation: OtaOperation::Update,
+        url: ota_url.clone(),
+        uuid: uuid_1.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
+    };
+
+    let (publisher_tx, mut publisher_rx) = mpsc::channel(4);
+    let (local_tx, local_rx) = mpsc::channel(1);
+
+    let ota = Ota::mock_new(system_update, state_mock, publisher_tx);
+    let mut ota_handler = OtaHandler::mock_new_with_ota(ota);
+    ota_handler.local_rx = Some(local_rx);
+
+    ota_handler.handle_event(req).await.unwrap();
+
+    local_tx
+        .send(OtaRequest {
+            operation: OtaOperation::Local,
+            url: local_url.clone(),
+            uuid: uuid_2.into(),
+            check: BundleCheck::default(),
+            http_headers: Vec::new(),
+        })
+        .await
+        .unwrap();
+
+    let local = ota_handler.next_local().await;
+    ota_handler.handle_event(local).await.unwrap();
+
+    // The local bundle doesn't replace the update in progress
+    assert_eq!(
+        ota_handler
+            .current
+            .as_ref()
+            .map(|current| current.ota_id.uuid),
+        Some(uuid_1)
+    );
+
+    let failure = OtaStatus::Failure(
+        OtaError::UpdateAlreadyInProgress,
+        Some(OtaId {
+            uuid: uuid_2,
+            url: local_url,
+        }),
+    );
+
+    loop {
+        let val = tokio::time::timeout(Duration::from_secs(2), publisher_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        if val == failure {
+            break;
+        }
+    }
+}
+
 #[tokio::test]
 async fn ota_event_canceled() {
     let uuid = Uuid::new_v4();
@@ -848,6 +940,9 @@ async fn ota_event_canceled() {
             uuid,
             url: String::new(),
         },
+        operation: OtaOperation::Update,
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
         cancel: cancel_token.clone(),
     });
 
@@ -855,6 +950,8 @@ async fn ota_event_canceled() {
         operation: OtaOperation::Cancel,
         url: String::new(),
         uuid: uuid.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let res = ota_handler.handle_event(ota_req_map).await;
@@ -912,6 +1009,7 @@ async fn ota_event_success_after_canceled_event() {
             future::ready(Ok(PersistentState {
                 uuid: uuid_2,
                 slot: slot.to_owned(),
+                staged: false,
             }))
             .boxed()
         });
@@ -945,7 +1043,7 @@ async fn ota_event_success_after_canceled_event() {
         .returning(|| future::ready(Ok("B".to_owned())).boxed());
     system_update
         .expect_install_bundle()
-        .returning(|_: &str| future::ready(Ok(())).boxed());
+        .returning(|_, _| future::ready(Ok(())).boxed());
     system_update
         .expect_operation()
         .returning(|| future::ready(Ok("".to_string())).boxed());
@@ -984,6 +1082,8 @@ async fn ota_event_success_after_canceled_event() {
         operation: OtaOperation::Update,
         uuid: uuid_1.into(),
         url: ota_url.clone(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let (publisher_tx, mut publisher_rx) = mpsc::channel(1);
@@ -1021,6 +1121,8 @@ async fn ota_event_success_after_canceled_event() {
         operation: OtaOperation::Cancel,
         url: ota_url.clone(),
         uuid: uuid_1.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
     ota_handler.handle_event(ota_cancel).await.unwrap();
 
@@ -1043,6 +1145,8 @@ async fn ota_event_success_after_canceled_event() {
         operation: OtaOperation::Update,
         url: ota_url.clone(),
         uuid: uuid_2.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     ota_handler.handle_event(ota_update.clone()).await.unwrap();
@@ -1098,6 +1202,8 @@ async fn ota_event_not_canceled() {
         operation: OtaOperation::Cancel,
         url: String::new(),
         uuid: uuid.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
@@ -1151,6 +1257,8 @@ async fn ota_event_not_canceled_empty() {
         operation: OtaOperation::Cancel,
         url: String::new(),
         uuid: uuid.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
@@ -1206,6 +1314,8 @@ async fn ota_event_not_canceled_different_uuid() {
         operation: OtaOperation::Cancel,
         url: String::new(),
         uuid: uuid.into(),
+        check: BundleCheck::default(),
+        http_headers: Vec::new(),
     };
 
     let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
@@ -1267,6 +1377,7 @@ async fn ensure_pending_ota_ota_is_done_fail() {
         future::ready(Ok(PersistentState {
             uuid,
             slot: slot.to_owned(),
+            staged: false,
         }))
         .boxed()
     });
@@ -1288,7 +1399,7 @@ async fn ensure_pending_ota_ota_is_done_fail() {
     let exp = [
         OtaStatus::Rebooted,
         OtaStatus::Failure(
-            OtaError::SystemRollback("Unable to switch slot"),
+            OtaError::SystemRollback("Unable to switch slot".to_string()),
             Some(OtaId {
                 uuid,
                 url: String::new(),
@@ -1319,6 +1430,7 @@ async fn ensure_pending_ota_is_done_ota_success() {
         future::ready(Ok(PersistentState {
             uuid,
             slot: slot.to_owned(),
+            staged: false,
         }))
         .boxed()
     });
@@ -18,6 +18,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use std::collections::HashMap;
 use std::task::Poll;
 
 use futures::stream::FusedStream;
@@ -25,11 +26,11 @@ use futures::{Stream, StreamExt, TryStreamExt, future, ready};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, instrument, warn};
 use zbus::proxy;
-use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
+use zbus::zvariant::{DeserializeDict, SerializeDict, Type, Value};
 
 use crate::error::DeviceManagerError;
 use crate::ota::config::RaucDbus;
-use crate::ota::{DeployProgress, DeployStatus, SystemUpdate};
+use crate::ota::{DeployProgress, DeployStatus, InstallOptions, SystemUpdate};
 
 use super::ProgressStream;
 use super::config::OtaConfig;
@@ -69,11 +70,7 @@ pub struct BundleInfo {
 pub trait Rauc {
     /// Triggers the installation of a bundle. This method call is non-blocking.
     /// After completion, the “Completed” signal will be emitted.
-    fn install_bundle(
-        &self,
-        source: &str,
-        args: std::collections::HashMap<String, zbus::zvariant::Value<'_>>,
-    ) -> zbus::Result<()>;
+    fn install_bundle(&self, source: &str, args: HashMap<String, Value<'_>>) -> zbus::Result<()>;
 
     /// Provides bundle info.
     fn info(&self, bundle: &str) -> zbus::Result<BundleInfo>;
@@ -126,10 +123,47 @@ pub struct OTARauc<'a> {
     rauc: RaucProxy<'a>,
 }
 
+/// Arguments of the `InstallBundle` method, used by RAUC to stream the bundle.
+fn install_args(options: &InstallOptions) -> HashMap<String, Value<'_>> {
+    let mut args = HashMap::new();
+
+    if !options.http_headers.is_empty() {
+        args.insert(
+            "http-headers".to_string(),
+            Value::from(options.http_headers.clone()),
+        );
+    }
+
+    let files = [
+        ("tls-cert", &options.tls_cert),
+        ("tls-key", &options.tls_key),
+        ("tls-ca", &options.tls_ca),
+    ];
+
+    for (name, file) in files {
+        if let Some(file) = file {
+            args.insert(
+                name.to_string(),
+                Value::from(file.to_string_lossy().into_owned()),
+            );
+        }
+    }
+
+    if options.tls_no_verify {
+        args.insert("tls-no-verify".to_string(), Value::from(true));
+    }
+
+    args
+}
+
 impl SystemUpdate for OTARauc<'static> {
-    async fn install_bundle(&self, source: &str) -> Result<(), DeviceManagerError> {
+    async fn install_bundle(
+        &self,
+        source: &str,
+        options: &InstallOptions,
+    ) -> Result<(), DeviceManagerError> {
         self.rauc
-            .install_bundle(source, std::collections::HashMap::new())
+            .install_bundle(source, install_args(options))
             .await?;
         Ok(())
     }
@@ -362,3 +396,45 @@ where
         self.completed
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_map_install_args() {
+        assert!(install_args(&InstallOptions::default()).is_empty());
+
+        let options = InstallOptions {
+            http_headers: vec!["x-edgehog-ota-id: 1".to_string()],
+            tls_cert: Some(PathBuf::from("/etc/edgehog/client.pem")),
+            tls_key: None,
+            tls_ca: Some(PathBuf::from("/etc/edgehog/ca.pem")),
+            tls_no_verify: true,
+        };
+
+        let args = install_args(&options);
+
+        let exp = HashMap::from([
+            (
+                "http-headers".to_string(),
+                Value::from(vec!["x-edgehog-ota-id: 1".to_string()]),
+            ),
+            (
+                "tls-cert".to_string(),
+                Value::from("/etc/edgehog/client.pem".to_string()),
+            ),
+            (
+                "tls-ca".to_string(),
+                Value::from("/etc/edgehog/ca.pem".to_string()),
+            ),
+            ("tls-no-verify".to_string(), Value::from(true)),
+        ]);
+
+        assert_eq!(args, exp);
+    }
+}
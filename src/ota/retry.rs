@@ -0,0 +1,203 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retry policy for the download of the bundle.
+
+use std::fmt::Display;
+use std::time::Duration;
+
+use rand::Rng;
+
+use super::config::{Backoff, RetryConfig};
+
+/// Tells if an error could go away by retrying the operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Transient error, like a timeout or a server error.
+    Retryable,
+    /// The operation will fail again, the request needs to be changed.
+    Fatal,
+}
+
+impl ErrorClass {
+    /// Classifies the HTTP status of a failed request.
+    pub(crate) fn from_status(status: u16) -> Self {
+        match status {
+            // Request Timeout, Too Early and Too Many Requests
+            408 | 425 | 429 => ErrorClass::Retryable,
+            500.. => ErrorClass::Retryable,
+            _ => ErrorClass::Fatal,
+        }
+    }
+}
+
+impl Display for ErrorClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorClass::Retryable => write!(f, "retryable"),
+            ErrorClass::Fatal => write!(f, "fatal"),
+        }
+    }
+}
+
+/// Computes the delay between the download attempts.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    config: RetryConfig,
+}
+
+impl RetryPolicy {
+    pub(crate) fn new(config: RetryConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the delay before the next attempt, or [`None`] if no more attempts are allowed.
+    ///
+    /// The attempt is the number of attempts already made, starting from 1.
+    pub(crate) fn next_delay(&self, attempt: u32, elapsed: Duration) -> Option<Duration> {
+        if attempt >= self.config.max_attempts {
+            return None;
+        }
+
+        let delay = self.jitter(self.backoff(attempt));
+
+        let max_elapsed = self.config.max_elapsed_secs;
+        if max_elapsed > 0 && elapsed.saturating_add(delay) > Duration::from_secs(max_elapsed) {
+            return None;
+        }
+
+        Some(delay)
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let initial = self.config.initial_delay_secs;
+
+        let secs = match self.config.backoff {
+            Backoff::Constant => initial,
+            Backoff::Linear => initial.saturating_mul(attempt.into()),
+            Backoff::Exponential => {
+                let factor = 2u64.checked_pow(attempt - 1).unwrap_or(u64::MAX);
+
+                initial.saturating_mul(factor)
+            }
+        };
+
+        Duration::from_secs(secs.min(self.config.max_delay_secs))
+    }
+
+    /// Randomly varies the delay by the configured percentage, in both directions.
+    fn jitter(&self, delay: Duration) -> Duration {
+        let percent = u64::from(self.config.jitter_percent.min(100));
+        let millis = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX);
+        let range = millis.saturating_mul(percent) / 100;
+
+        if range == 0 {
+            return delay;
+        }
+
+        let min = millis.saturating_sub(range);
+        let max = millis.saturating_add(range);
+
+        Duration::from_millis(rand::rng().random_range(min..=max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn policy(backoff: Backoff) -> RetryPolicy {
+        RetryPolicy::new(RetryConfig {
+            max_attempts: 5,
+            max_elapsed_secs: 0,
+            backoff,
+            initial_delay_secs: 2,
+            max_delay_secs: 10,
+            jitter_percent: 0,
+            ..Default::default()
+        })
+    }
+
+    fn delays(policy: RetryPolicy) -> Vec<Option<u64>> {
+        (1..=5)
+            .map(|attempt| {
+                policy
+                    .next_delay(attempt, Duration::ZERO)
+                    .map(|delay| delay.as_secs())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_compute_backoff() {
+        assert_eq!(
+            delays(policy(Backoff::Constant)),
+            [Some(2), Some(2), Some(2), Some(2), None]
+        );
+        assert_eq!(
+            delays(policy(Backoff::Linear)),
+            [Some(2), Some(4), Some(6), Some(8), None]
+        );
+        assert_eq!(
+            delays(policy(Backoff::Exponential)),
+            [Some(2), Some(4), Some(8), Some(10), None]
+        );
+    }
+
+    #[test]
+    fn should_stop_after_max_elapsed() {
+        let policy = RetryPolicy::new(RetryConfig {
+            max_elapsed_secs: 30,
+            jitter_percent: 0,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            policy.next_delay(1, Duration::from_secs(20)),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(policy.next_delay(2, Duration::from_secs(28)), None);
+    }
+
+    #[test]
+    fn should_add_jitter() {
+        let policy = RetryPolicy::new(RetryConfig {
+            jitter_percent: 50,
+            ..Default::default()
+        });
+
+        for _ in 0..20 {
+            let delay = policy.next_delay(1, Duration::ZERO).unwrap();
+
+            assert!(
+                (Duration::from_secs(1)..=Duration::from_secs(3)).contains(&delay),
+                "{delay:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn should_classify_status() {
+        assert_eq!(ErrorClass::from_status(404), ErrorClass::Fatal);
+        assert_eq!(ErrorClass::from_status(403), ErrorClass::Fatal);
+        assert_eq!(ErrorClass::from_status(429), ErrorClass::Retryable);
+        assert_eq!(ErrorClass::from_status(503), ErrorClass::Retryable);
+    }
+}
@@ -0,0 +1,514 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! OTA backend driven by custom hook executables.
+//!
+//! Every hook prints a JSON object on the standard output:
+//!
+//! - `info <bundle>`: `{"compatible": "...", "version": "..."}`
+//! - `compatible`: `{"compatible": "..."}`
+//! - `boot-slot` and `get-primary`: `{"slot": "..."}`
+//! - `mark <state> <slot>`: `{"slot": "...", "message": "..."}`
+//! - `install <bundle>`: one `{"percentage": 0, "message": "..."}` line for each progress update,
+//!   the exit code is the result of the installation.
+
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::StreamExt;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, ChildStdout, Command};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, instrument, warn};
+
+use crate::error::DeviceManagerError;
+use crate::ota::rauc::BundleInfo;
+use crate::ota::{DeployProgress, DeployStatus, InstallOptions, ProgressStream, SystemUpdate};
+
+use super::config::ScriptConfig;
+
+/// Error returned by the script backend
+#[derive(Debug, thiserror::Error, displaydoc::Display)]
+pub enum ScriptError {
+    /// the {0} hook is not configured
+    NotConfigured(&'static str),
+    /// couldn't run the hook {path}
+    Spawn {
+        path: PathBuf,
+        #[source]
+        backtrace: std::io::Error,
+    },
+    /// the hook {path} timed out
+    Timeout { path: PathBuf },
+    /// the hook {path} exited with {status}
+    Status { path: PathBuf, status: ExitStatus },
+    /// invalid JSON output from the hook {path}
+    Json {
+        path: PathBuf,
+        #[source]
+        backtrace: serde_json::Error,
+    },
+    /// no installation in progress
+    NotInstalling,
+}
+
+#[derive(Debug, Deserialize)]
+struct Compatible {
+    compatible: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Info {
+    compatible: String,
+    #[serde(default)]
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Slot {
+    slot: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Marked {
+    slot: String,
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Progress {
+    percentage: i32,
+    #[serde(default)]
+    message: String,
+}
+
+/// Installation started by the install hook.
+#[derive(Debug)]
+struct Install {
+    path: PathBuf,
+    child: Child,
+    stdout: ChildStdout,
+    /// Task reading the standard error.
+    stderr: JoinHandle<()>,
+}
+
+/// Installs the bundles through the configured hooks.
+#[derive(Debug)]
+pub struct ScriptUpdate {
+    config: ScriptConfig,
+    install: Mutex<Option<Install>>,
+    /// Last line the install hook printed on the standard error.
+    last_error: Arc<Mutex<String>>,
+}
+
+impl ScriptUpdate {
+    pub fn new(config: ScriptConfig) -> Self {
+        Self {
+            config,
+            install: Mutex::new(None),
+            last_error: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    fn hook(hook: &Option<PathBuf>, name: &'static str) -> Result<PathBuf, ScriptError> {
+        hook.clone().ok_or(ScriptError::NotConfigured(name))
+    }
+
+    /// Runs the hook and parses its output.
+    async fn run<T>(&self, path: &Path, args: &[&str]) -> Result<T, ScriptError>
+    where
+        T: DeserializeOwned,
+    {
+        debug!(path = %path.display(), ?args, "running hook");
+
+        let output = Command::new(path)
+            .args(args)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let output = tokio::time::timeout(timeout, output)
+            .await
+            .map_err(|_| ScriptError::Timeout {
+                path: path.to_path_buf(),
+            })?
+            .map_err(|backtrace| ScriptError::Spawn {
+                path: path.to_path_buf(),
+                backtrace,
+            })?;
+
+        if !output.stderr.is_empty() {
+            debug!(
+                path = %path.display(),
+                stderr = %String::from_utf8_lossy(&output.stderr),
+                "hook standard error"
+            );
+        }
+
+        if !output.status.success() {
+            return Err(ScriptError::Status {
+                path: path.to_path_buf(),
+                status: output.status,
+            });
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|backtrace| ScriptError::Json {
+            path: path.to_path_buf(),
+            backtrace,
+        })
+    }
+
+    fn start_install(&self, source: &str) -> Result<(), ScriptError> {
+        let path = Self::hook(&self.config.install, "install")?;
+
+        let mut child = Command::new(&path)
+            .arg(source)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|backtrace| ScriptError::Spawn {
+                path: path.clone(),
+                backtrace,
+            })?;
+
+        // Both are piped
+        let stdout = child.stdout.take().ok_or(ScriptError::NotInstalling)?;
+        let stderr = child.stderr.take().ok_or(ScriptError::NotInstalling)?;
+
+        let last_error = Arc::clone(&self.last_error);
+        *last_error.lock().unwrap_or_else(|err| err.into_inner()) = String::new();
+
+        let stderr = tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                warn!(line, "install hook error output");
+
+                *last_error.lock().unwrap_or_else(|err| err.into_inner()) = line;
+            }
+        });
+
+        *self.install.lock().unwrap_or_else(|err| err.into_inner()) = Some(Install {
+            path,
+            child,
+            stdout,
+            stderr,
+        });
+
+        Ok(())
+    }
+
+    fn progress_stream(&self) -> Result<ProgressStream, ScriptError> {
+        let install = self
+            .install
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take()
+            .ok_or(ScriptError::NotInstalling)?;
+
+        let lines = BufReader::new(install.stdout).lines();
+
+        let stream = futures::stream::unfold(
+            Some((lines, install.child, install.stderr, install.path)),
+            |state| async move {
+                let (mut lines, mut child, stderr, path) = state?;
+
+                loop {
+                    match lines.next_line().await {
+                        Ok(Some(line)) => match serde_json::from_str::<Progress>(&line) {
+                            Ok(progress) => {
+                                let status = DeployStatus::Progress(DeployProgress {
+                                    percentage: progress.percentage.clamp(0, 100),
+                                    message: progress.message,
+                                });
+
+                                return Some((Ok(status), Some((lines, child, stderr, path))));
+                            }
+                            Err(err) => {
+                                debug!(line, error = %err, "ignoring install hook output");
+                            }
+                        },
+                        Ok(None) => break,
+                        Err(err) => {
+                            error!(error = %err, "couldn't read the install hook output");
+
+                            break;
+                        }
+                    }
+                }
+
+                // Wait for the last error line
+                if let Err(err) = stderr.await {
+                    error!(error = %err, "couldn't read the install hook error output");
+                }
+
+                let res = child
+                    .wait()
+                    .await
+                    .map(|status| {
+                        info!(path = %path.display(), %status, "install hook exited");
+
+                        // Killed by a signal if there is no code
+                        DeployStatus::Completed {
+                            signal: status.code().unwrap_or(-1),
+                        }
+                    })
+                    .map_err(|backtrace| {
+                        DeviceManagerError::from(ScriptError::Spawn { path, backtrace })
+                    });
+
+                Some((res, None))
+            },
+        );
+
+        Ok(stream.boxed())
+    }
+}
+
+impl SystemUpdate for ScriptUpdate {
+    #[instrument(skip(self, _options))]
+    async fn install_bundle(
+        &self,
+        source: &str,
+        _options: &InstallOptions,
+    ) -> Result<(), DeviceManagerError> {
+        self.start_install(source)?;
+
+        info!("install hook started");
+
+        Ok(())
+    }
+
+    async fn last_error(&self) -> Result<String, DeviceManagerError> {
+        Ok(self
+            .last_error
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone())
+    }
+
+    async fn info(&self, bundle: &str) -> Result<BundleInfo, DeviceManagerError> {
+        let path = Self::hook(&self.config.info, "info")?;
+
+        let info: Info = self.run(&path, &[bundle]).await?;
+
+        Ok(BundleInfo {
+            compatible: info.compatible,
+            version: info.version,
+        })
+    }
+
+    async fn operation(&self) -> Result<String, DeviceManagerError> {
+        let installing = self
+            .install
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .is_some();
+
+        let operation = if installing { "installing" } else { "idle" };
+
+        Ok(operation.to_string())
+    }
+
+    async fn compatible(&self) -> Result<String, DeviceManagerError> {
+        let path = Self::hook(&self.config.compatible, "compatible")?;
+
+        let compatible: Compatible = self.run(&path, &[]).await?;
+
+        Ok(compatible.compatible)
+    }
+
+    async fn boot_slot(&self) -> Result<String, DeviceManagerError> {
+        let path = Self::hook(&self.config.boot_slot, "boot-slot")?;
+
+        let slot: Slot = self.run(&path, &[]).await?;
+
+        Ok(slot.slot)
+    }
+
+    async fn receive_completed(&self) -> Result<ProgressStream, DeviceManagerError> {
+        self.progress_stream().map_err(Into::into)
+    }
+
+    async fn get_primary(&self) -> Result<String, DeviceManagerError> {
+        let path = Self::hook(&self.config.get_primary, "get-primary")?;
+
+        let slot: Slot = self.run(&path, &[]).await?;
+
+        Ok(slot.slot)
+    }
+
+    async fn mark(
+        &self,
+        state: &str,
+        slot_identifier: &str,
+    ) -> Result<(String, String), DeviceManagerError> {
+        let path = Self::hook(&self.config.mark, "mark")?;
+
+        let marked: Marked = self.run(&path, &[state, slot_identifier]).await?;
+
+        Ok((marked.slot, marked.message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    async fn hook(dir: &Path, name: &str, script: &str) -> Option<PathBuf> {
+        let path = dir.join(name);
+
+        tokio::fs::write(&path, format!("#!/bin/sh\n{script}\n"))
+            .await
+            .unwrap();
+        tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .await
+            .unwrap();
+
+        Some(path)
+    }
+
+    #[tokio::test]
+    async fn should_run_hooks() {
+        let dir = TempDir::new("edgehog-ota-script").unwrap();
+        let dir = dir.path();
+
+        let config = ScriptConfig {
+            info: hook(
+                dir,
+                "info",
+                r#"echo "{\"compatible\": \"board\", \"version\": \"$1\"}""#,
+            )
+            .await,
+            compatible: hook(dir, "compatible", r#"echo '{"compatible": "board"}'"#).await,
+            boot_slot: hook(dir, "boot-slot", r#"echo '{"slot": "A"}'"#).await,
+            get_primary: hook(dir, "get-primary", r#"echo '{"slot": "B"}'"#).await,
+            mark: hook(
+                dir,
+                "mark",
+                r#"echo "{\"slot\": \"$2\", \"message\": \"marked $2 as $1\"}""#,
+            )
+            .await,
+            ..Default::default()
+        };
+
+        let script = ScriptUpdate::new(config);
+
+        let info = script.info("bundle.bin").await.unwrap();
+        assert_eq!(info.compatible, "board");
+        assert_eq!(info.version, "bundle.bin");
+
+        assert_eq!(script.compatible().await.unwrap(), "board");
+        assert_eq!(script.boot_slot().await.unwrap(), "A");
+        assert_eq!(script.get_primary().await.unwrap(), "B");
+        assert_eq!(
+            script.mark("good", "B").await.unwrap(),
+            ("B".to_string(), "marked B as good".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_exit_status() {
+        let dir = TempDir::new("edgehog-ota-script").unwrap();
+
+        let config = ScriptConfig {
+            compatible: hook(dir.path(), "compatible", "exit 2").await,
+            ..Default::default()
+        };
+
+        let script = ScriptUpdate::new(config);
+
+        let err = script.compatible().await.unwrap_err();
+        assert!(matches!(
+            err,
+            DeviceManagerError::Script(ScriptError::Status { .. })
+        ));
+
+        let err = script.boot_slot().await.unwrap_err();
+        assert!(matches!(
+            err,
+            DeviceManagerError::Script(ScriptError::NotConfigured("boot-slot"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_stream_install_progress() {
+        let dir = TempDir::new("edgehog-ota-script").unwrap();
+
+        let config = ScriptConfig {
+            install: hook(
+                dir.path(),
+                "install",
+                r#"
+echo '{"percentage": 10, "message": "erasing"}'
+echo "dd of=/dev/mmcblk0p3"
+echo '{"percentage": 100, "message": "written"}'
+echo "fw_setenv failed" >&2
+exit 3
+"#,
+            )
+            .await,
+            ..Default::default()
+        };
+
+        let script = ScriptUpdate::new(config);
+
+        script
+            .install_bundle("update.bin", &InstallOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(script.operation().await.unwrap(), "installing");
+
+        let events: Vec<DeployStatus> = script
+            .receive_completed()
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        let exp = vec![
+            DeployStatus::Progress(DeployProgress {
+                percentage: 10,
+                message: "erasing".to_string(),
+            }),
+            DeployStatus::Progress(DeployProgress {
+                percentage: 100,
+                message: "written".to_string(),
+            }),
+            DeployStatus::Completed { signal: 3 },
+        ];
+
+        assert_eq!(events, exp);
+        assert_eq!(script.operation().await.unwrap(), "idle");
+        assert_eq!(script.last_error().await.unwrap(), "fw_setenv failed");
+    }
+}
@@ -0,0 +1,252 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reads the `sw-description` of a SWUpdate bundle.
+//!
+//! The bundle is a `newc` CPIO archive with the `sw-description` as the first entry.
+
+use std::path::{Path, PathBuf};
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+const CPIO_MAGIC: &[u8] = b"070701";
+const CPIO_HEADER_LEN: usize = 110;
+const SW_DESCRIPTION: &str = "sw-description";
+/// Limit the size of the description read in memory.
+const MAX_DESCRIPTION_SIZE: usize = 1024 * 1024;
+
+/// Version and compatibility of a bundle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SwDescription {
+    pub(crate) version: Option<String>,
+    /// Hardware revisions the bundle can be installed on, empty if not checked.
+    pub(crate) hardware_compatibility: Vec<String>,
+}
+
+impl SwDescription {
+    pub(crate) async fn read(bundle: &Path) -> Result<Self, DescriptionError> {
+        let mut file =
+            tokio::fs::File::open(bundle)
+                .await
+                .map_err(|backtrace| DescriptionError::Open {
+                    path: bundle.to_path_buf(),
+                    backtrace,
+                })?;
+
+        let content = read_first_entry(&mut file).await?;
+
+        Ok(Self::parse(&content))
+    }
+
+    /// Extracts the fields from the libconfig syntax of the description.
+    fn parse(content: &str) -> Self {
+        let version = find_setting(content, "version").and_then(|value| quoted(value).next());
+
+        let hardware_compatibility = find_setting(content, "hardware-compatibility")
+            .and_then(|value| {
+                let value = value.strip_prefix('[')?;
+                let end = value.find(']')?;
+
+                Some(quoted(&value[..end]).collect())
+            })
+            .unwrap_or_default();
+
+        Self {
+            version,
+            hardware_compatibility,
+        }
+    }
+}
+
+/// Returns the value after the setting name and its `=` or `:` separator.
+fn find_setting<'a>(content: &'a str, name: &str) -> Option<&'a str> {
+    content.match_indices(name).find_map(|(idx, _)| {
+        let before = content[..idx].chars().next_back();
+        if before.is_some_and(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+            return None;
+        }
+
+        let rest = content[idx + name.len()..].trim_start();
+        let rest = rest.strip_prefix('=').or_else(|| rest.strip_prefix(':'))?;
+
+        Some(rest.trim_start())
+    })
+}
+
+/// Iterates the quoted strings in the value.
+fn quoted(value: &str) -> impl Iterator<Item = String> + '_ {
+    value.split('"').skip(1).step_by(2).map(str::to_string)
+}
+
+async fn read_first_entry<R>(reader: &mut R) -> Result<String, DescriptionError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut header = [0; CPIO_HEADER_LEN];
+    reader
+        .read_exact(&mut header)
+        .await
+        .map_err(|backtrace| DescriptionError::Read { backtrace })?;
+
+    if !header.starts_with(CPIO_MAGIC) {
+        return Err(DescriptionError::Format);
+    }
+
+    // Fields of 8 hex digits after the magic: filesize is the 7th and namesize the 12th
+    let field = |idx: usize| {
+        let start = CPIO_MAGIC.len() + idx * 8;
+
+        std::str::from_utf8(&header[start..start + 8])
+            .ok()
+            .and_then(|hex| usize::from_str_radix(hex, 16).ok())
+            .ok_or(DescriptionError::Format)
+    };
+
+    let file_size = field(6)?;
+    let name_size = field(11)?;
+
+    if file_size > MAX_DESCRIPTION_SIZE || name_size > 4096 {
+        return Err(DescriptionError::Format);
+    }
+
+    // The name is NUL terminated and padded, with the header, to a multiple of 4
+    let mut name = vec![0; (CPIO_HEADER_LEN + name_size).next_multiple_of(4) - CPIO_HEADER_LEN];
+    reader
+        .read_exact(&mut name)
+        .await
+        .map_err(|backtrace| DescriptionError::Read { backtrace })?;
+
+    let name = name.get(..name_size.saturating_sub(1)).unwrap_or_default();
+    if name != SW_DESCRIPTION.as_bytes() {
+        return Err(DescriptionError::Missing);
+    }
+
+    let mut content = vec![0; file_size];
+    reader
+        .read_exact(&mut content)
+        .await
+        .map_err(|backtrace| DescriptionError::Read { backtrace })?;
+
+    String::from_utf8(content).map_err(|_| DescriptionError::Format)
+}
+
+/// Couldn't read the sw-description of the bundle
+#[derive(Debug, thiserror::Error, displaydoc::Display)]
+pub enum DescriptionError {
+    /// couldn't open the bundle {path}
+    Open {
+        path: PathBuf,
+        #[source]
+        backtrace: std::io::Error,
+    },
+    /// couldn't read the bundle
+    Read {
+        #[source]
+        backtrace: std::io::Error,
+    },
+    /// the bundle is not a valid CPIO archive
+    Format,
+    /// the sw-description is not the first file of the bundle
+    Missing,
+}
+
+/// Builds a bundle with only the `sw-description`.
+#[cfg(test)]
+pub(crate) fn bundle(description: &str) -> Vec<u8> {
+    let name = format!("{SW_DESCRIPTION}\0");
+
+    let mut buf = Vec::new();
+    buf.extend_from_slice(CPIO_MAGIC);
+    for value in [
+        0,
+        0o100644,
+        0,
+        0,
+        1,
+        0,
+        description.len(),
+        0,
+        0,
+        0,
+        0,
+        name.len(),
+        0,
+    ] {
+        buf.extend_from_slice(format!("{value:08X}").as_bytes());
+    }
+    buf.extend_from_slice(name.as_bytes());
+    buf.resize(buf.len().next_multiple_of(4), 0);
+    buf.extend_from_slice(description.as_bytes());
+    buf.resize(buf.len().next_multiple_of(4), 0);
+
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const DESCRIPTION: &str = r#"
+software =
+{
+    version = "1.2.0";
+    description = "hardware-compatibility is checked";
+
+    hardware-compatibility: [ "1.0", "1.2"];
+
+    images: (
+        {
+            filename = "rootfs.ext4";
+            device = "/dev/mmcblk0p2";
+        }
+    );
+}
+"#;
+
+    #[test]
+    fn should_parse_description() {
+        let description = SwDescription::parse(DESCRIPTION);
+
+        let exp = SwDescription {
+            version: Some("1.2.0".to_string()),
+            hardware_compatibility: vec!["1.0".to_string(), "1.2".to_string()],
+        };
+
+        assert_eq!(description, exp);
+    }
+
+    #[tokio::test]
+    async fn should_read_first_entry() {
+        let bundle = bundle(DESCRIPTION);
+
+        let content = read_first_entry(&mut bundle.as_slice()).await.unwrap();
+
+        assert_eq!(content, DESCRIPTION);
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_bundle() {
+        let err = read_first_entry(&mut b"not a cpio archive".repeat(10).as_slice())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, DescriptionError::Format));
+    }
+}
@@ -0,0 +1,536 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Wire format of the SWUpdate IPC sockets.
+//!
+//! The messages are the C structures of `network_ipc.h` and `progress_ipc.h`, in the native
+//! byte order and layout. The sizes and offsets are taken from the mirrored structures in
+//! [`layout`].
+
+use std::fmt::Display;
+use std::mem::{offset_of, size_of};
+
+/// Magic number of every control message.
+pub(crate) const IPC_MAGIC: i32 = 0x1405_2001;
+/// Version of the install request API.
+const SWUPDATE_API_VERSION: u32 = 0x1;
+
+/// Size of the `ipc_message` structure.
+pub(crate) const IPC_MESSAGE_SIZE: usize = size_of::<layout::IpcMessage>();
+/// Size of the `progress_msg` structure.
+pub(crate) const PROGRESS_MESSAGE_SIZE: usize = size_of::<layout::ProgressMsg>();
+
+const DATA_OFFSET: usize = offset_of!(layout::IpcMessage, data);
+const STATUS_CURRENT_OFFSET: usize = DATA_OFFSET + offset_of!(layout::Status, current);
+const STATUS_LAST_RESULT_OFFSET: usize = DATA_OFFSET + offset_of!(layout::Status, last_result);
+const STATUS_ERROR_OFFSET: usize = DATA_OFFSET + offset_of!(layout::Status, error);
+/// Offset of `status.desc` in the message.
+const STATUS_DESC_OFFSET: usize = DATA_OFFSET + offset_of!(layout::Status, desc);
+const STATUS_DESC_LEN: usize = layout::PRINFOSIZE;
+
+/// C structures of the SWUpdate IPC, only used to compute the sizes and the offsets.
+///
+/// They mirror `include/network_ipc.h` and `include/progress_ipc.h` of SWUpdate 2022.05 and
+/// later, which added `disable_store_swu` to the install request.
+#[allow(dead_code)]
+mod layout {
+    use std::ffi::{c_char, c_int, c_uint, c_ulonglong};
+
+    /// Size of the message and description buffers
+    pub(super) const PRINFOSIZE: usize = 2048;
+
+    /// `struct swupdate_request`
+    #[repr(C)]
+    pub(super) struct SwupdateRequest {
+        pub(super) apiversion: c_uint,
+        pub(super) source: c_uint,
+        pub(super) dry_run: c_int,
+        pub(super) len: usize,
+        pub(super) info: [c_char; 512],
+        pub(super) software_set: [c_char; 256],
+        pub(super) running_mode: [c_char; 256],
+        pub(super) disable_store_swu: bool,
+    }
+
+    /// `status` member of `msgdata`
+    #[repr(C)]
+    pub(super) struct Status {
+        pub(super) current: c_int,
+        pub(super) last_result: c_int,
+        pub(super) error: c_int,
+        pub(super) desc: [c_char; PRINFOSIZE],
+    }
+
+    /// `instmsg` member of `msgdata`, the largest one
+    #[repr(C)]
+    pub(super) struct InstMsg {
+        pub(super) req: SwupdateRequest,
+        pub(super) len: c_uint,
+        pub(super) buf: [c_char; 2048],
+    }
+
+    /// `procmsg` member of `msgdata`
+    #[repr(C)]
+    pub(super) struct ProcMsg {
+        pub(super) source: c_uint,
+        pub(super) cmd: c_int,
+        pub(super) timeout: c_int,
+        pub(super) len: c_uint,
+        pub(super) buf: [c_char; 2048],
+    }
+
+    /// `versions` member of `msgdata`
+    #[repr(C)]
+    pub(super) struct Versions {
+        pub(super) minimum_version: [c_char; 256],
+        pub(super) maximum_version: [c_char; 256],
+        pub(super) current_version: [c_char; 256],
+        pub(super) update_type: [c_char; 256],
+    }
+
+    /// `msgdata`
+    #[repr(C)]
+    pub(super) union MsgData {
+        pub(super) msg: [c_char; 128],
+        pub(super) status: std::mem::ManuallyDrop<Status>,
+        pub(super) instmsg: std::mem::ManuallyDrop<InstMsg>,
+        pub(super) procmsg: std::mem::ManuallyDrop<ProcMsg>,
+        pub(super) versions: std::mem::ManuallyDrop<Versions>,
+    }
+
+    /// `ipc_message`
+    #[repr(C)]
+    pub(super) struct IpcMessage {
+        pub(super) magic: c_int,
+        pub(super) type_: c_int,
+        pub(super) data: MsgData,
+    }
+
+    /// `struct progress_msg`
+    #[repr(C)]
+    pub(super) struct ProgressMsg {
+        pub(super) apiversion: c_uint,
+        pub(super) status: c_uint,
+        pub(super) dwl_percent: c_uint,
+        pub(super) dwl_bytes: c_ulonglong,
+        pub(super) nsteps: c_uint,
+        pub(super) cur_step: c_uint,
+        pub(super) cur_percent: c_uint,
+        pub(super) cur_image: [c_char; 256],
+        pub(super) hnd_name: [c_char; 64],
+        pub(super) source: c_uint,
+        pub(super) infolen: c_uint,
+        pub(super) info: [c_char; PRINFOSIZE],
+    }
+}
+
+/// Type of a control message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MsgType {
+    ReqInstall = 0,
+    Ack = 1,
+    Nack = 2,
+    GetStatus = 3,
+    SetUpdateState = 7,
+}
+
+impl MsgType {
+    fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(MsgType::ReqInstall),
+            1 => Some(MsgType::Ack),
+            2 => Some(MsgType::Nack),
+            3 => Some(MsgType::GetStatus),
+            7 => Some(MsgType::SetUpdateState),
+            _ => None,
+        }
+    }
+}
+
+/// Interface that triggered the update, `SOURCE_LOCAL` for the bundles we send.
+const SOURCE_LOCAL: u32 = 4;
+
+/// Update state stored in the bootloader environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateState {
+    Ok = b'0' as isize,
+    Failed = b'3' as isize,
+}
+
+/// Status of the update reported by the daemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryStatus {
+    Idle,
+    Start,
+    Run,
+    Success,
+    Failure,
+    Download,
+    Done,
+    Subprocess,
+    Progress,
+}
+
+impl RecoveryStatus {
+    fn from_raw(value: u32) -> Option<Self> {
+        let status = match value {
+            0 => RecoveryStatus::Idle,
+            1 => RecoveryStatus::Start,
+            2 => RecoveryStatus::Run,
+            3 => RecoveryStatus::Success,
+            4 => RecoveryStatus::Failure,
+            5 => RecoveryStatus::Download,
+            6 => RecoveryStatus::Done,
+            7 => RecoveryStatus::Subprocess,
+            8 => RecoveryStatus::Progress,
+            _ => return None,
+        };
+
+        Some(status)
+    }
+}
+
+impl Display for RecoveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecoveryStatus::Idle => write!(f, "idle"),
+            RecoveryStatus::Start => write!(f, "start"),
+            RecoveryStatus::Run => write!(f, "running"),
+            RecoveryStatus::Success => write!(f, "success"),
+            RecoveryStatus::Failure => write!(f, "failure"),
+            RecoveryStatus::Download => write!(f, "download"),
+            RecoveryStatus::Done => write!(f, "done"),
+            RecoveryStatus::Subprocess => write!(f, "subprocess"),
+            RecoveryStatus::Progress => write!(f, "progress"),
+        }
+    }
+}
+
+/// Control message exchanged on the install socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcMessage {
+    /// Request to install a bundle, followed by the bundle content.
+    ReqInstall,
+    Ack,
+    Nack,
+    GetStatus,
+    /// Reply to [`IpcMessage::GetStatus`].
+    Status {
+        current: RecoveryStatus,
+        last_result: RecoveryStatus,
+        error: i32,
+        desc: String,
+    },
+    SetUpdateState(UpdateState),
+}
+
+impl IpcMessage {
+    fn msg_type(&self) -> MsgType {
+        match self {
+            IpcMessage::ReqInstall => MsgType::ReqInstall,
+            IpcMessage::Ack => MsgType::Ack,
+            IpcMessage::Nack => MsgType::Nack,
+            IpcMessage::GetStatus | IpcMessage::Status { .. } => MsgType::GetStatus,
+            IpcMessage::SetUpdateState(_) => MsgType::SetUpdateState,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![0; IPC_MESSAGE_SIZE];
+
+        put_i32(&mut buf, offset_of!(layout::IpcMessage, magic), IPC_MAGIC);
+        put_i32(
+            &mut buf,
+            offset_of!(layout::IpcMessage, type_),
+            self.msg_type() as i32,
+        );
+
+        match self {
+            IpcMessage::ReqInstall => {
+                put_u32(
+                    &mut buf,
+                    DATA_OFFSET + offset_of!(layout::SwupdateRequest, apiversion),
+                    SWUPDATE_API_VERSION,
+                );
+                put_u32(
+                    &mut buf,
+                    DATA_OFFSET + offset_of!(layout::SwupdateRequest, source),
+                    SOURCE_LOCAL,
+                );
+            }
+            IpcMessage::Status {
+                current,
+                last_result,
+                error,
+                desc,
+            } => {
+                put_u32(&mut buf, STATUS_CURRENT_OFFSET, *current as u32);
+                put_u32(&mut buf, STATUS_LAST_RESULT_OFFSET, *last_result as u32);
+                put_i32(&mut buf, STATUS_ERROR_OFFSET, *error);
+                put_str(&mut buf, STATUS_DESC_OFFSET, STATUS_DESC_LEN, desc);
+            }
+            IpcMessage::SetUpdateState(state) => {
+                buf[DATA_OFFSET] = *state as u8;
+            }
+            IpcMessage::Ack | IpcMessage::Nack | IpcMessage::GetStatus => {}
+        }
+
+        buf
+    }
+
+    /// Decodes a reply of the daemon, or a request in the tests.
+    pub(crate) fn decode(buf: &[u8; IPC_MESSAGE_SIZE]) -> Result<Self, IpcError> {
+        let magic = get_i32(buf, offset_of!(layout::IpcMessage, magic));
+        if magic != IPC_MAGIC {
+            return Err(IpcError::Magic(magic));
+        }
+
+        let raw = get_i32(buf, offset_of!(layout::IpcMessage, type_));
+        let msg_type = MsgType::from_raw(raw).ok_or(IpcError::Type(raw))?;
+
+        let msg = match msg_type {
+            MsgType::ReqInstall => IpcMessage::ReqInstall,
+            MsgType::Ack => IpcMessage::Ack,
+            MsgType::Nack => IpcMessage::Nack,
+            MsgType::GetStatus => {
+                let status = |offset| {
+                    let raw = get_u32(buf, offset);
+
+                    RecoveryStatus::from_raw(raw).ok_or(IpcError::Status(raw))
+                };
+
+                let current = status(STATUS_CURRENT_OFFSET)?;
+                let last_result = status(STATUS_LAST_RESULT_OFFSET)?;
+
+                IpcMessage::Status {
+                    current,
+                    last_result,
+                    error: get_i32(buf, STATUS_ERROR_OFFSET),
+                    desc: get_str(buf, STATUS_DESC_OFFSET, STATUS_DESC_LEN),
+                }
+            }
+            MsgType::SetUpdateState => match buf[DATA_OFFSET] {
+                b'0' => IpcMessage::SetUpdateState(UpdateState::Ok),
+                b'3' => IpcMessage::SetUpdateState(UpdateState::Failed),
+                state => return Err(IpcError::Type(state.into())),
+            },
+        };
+
+        Ok(msg)
+    }
+}
+
+/// Progress of the update, sent by the daemon on the progress socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProgressMessage {
+    pub(crate) status: RecoveryStatus,
+    pub(crate) nsteps: u32,
+    pub(crate) cur_step: u32,
+    pub(crate) cur_percent: u32,
+    pub(crate) cur_image: String,
+    pub(crate) info: String,
+}
+
+impl ProgressMessage {
+    const STATUS_OFFSET: usize = offset_of!(layout::ProgressMsg, status);
+    const NSTEPS_OFFSET: usize = offset_of!(layout::ProgressMsg, nsteps);
+    const CUR_STEP_OFFSET: usize = offset_of!(layout::ProgressMsg, cur_step);
+    const CUR_PERCENT_OFFSET: usize = offset_of!(layout::ProgressMsg, cur_percent);
+    const CUR_IMAGE_OFFSET: usize = offset_of!(layout::ProgressMsg, cur_image);
+    const CUR_IMAGE_LEN: usize = 256;
+    const INFOLEN_OFFSET: usize = offset_of!(layout::ProgressMsg, infolen);
+    const INFO_OFFSET: usize = offset_of!(layout::ProgressMsg, info);
+    const INFO_LEN: usize = layout::PRINFOSIZE;
+
+    /// Overall percentage of the installation, over all the steps.
+    pub(crate) fn percentage(&self) -> i32 {
+        if self.nsteps == 0 {
+            return 0;
+        }
+
+        let done = self.cur_step.saturating_sub(1).min(self.nsteps);
+        let percentage = (done * 100 + self.cur_percent.min(100)) / self.nsteps;
+
+        i32::try_from(percentage.min(100)).unwrap_or(100)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![0; PROGRESS_MESSAGE_SIZE];
+
+        put_u32(
+            &mut buf,
+            offset_of!(layout::ProgressMsg, apiversion),
+            SWUPDATE_API_VERSION,
+        );
+        put_u32(&mut buf, Self::STATUS_OFFSET, self.status as u32);
+        put_u32(&mut buf, Self::NSTEPS_OFFSET, self.nsteps);
+        put_u32(&mut buf, Self::CUR_STEP_OFFSET, self.cur_step);
+        put_u32(&mut buf, Self::CUR_PERCENT_OFFSET, self.cur_percent);
+        put_str(
+            &mut buf,
+            Self::CUR_IMAGE_OFFSET,
+            Self::CUR_IMAGE_LEN,
+            &self.cur_image,
+        );
+
+        let info_len = self.info.len().min(Self::INFO_LEN - 1);
+        put_u32(
+            &mut buf,
+            Self::INFOLEN_OFFSET,
+            u32::try_from(info_len).unwrap_or_default(),
+        );
+        put_str(&mut buf, Self::INFO_OFFSET, Self::INFO_LEN, &self.info);
+
+        buf
+    }
+
+    pub(crate) fn decode(buf: &[u8; PROGRESS_MESSAGE_SIZE]) -> Result<Self, IpcError> {
+        let raw = get_u32(buf, Self::STATUS_OFFSET);
+        let status = RecoveryStatus::from_raw(raw).ok_or(IpcError::Status(raw))?;
+
+        let info_len = usize::try_from(get_u32(buf, Self::INFOLEN_OFFSET))
+            .unwrap_or(Self::INFO_LEN)
+            .min(Self::INFO_LEN);
+
+        Ok(Self {
+            status,
+            nsteps: get_u32(buf, Self::NSTEPS_OFFSET),
+            cur_step: get_u32(buf, Self::CUR_STEP_OFFSET),
+            cur_percent: get_u32(buf, Self::CUR_PERCENT_OFFSET),
+            cur_image: get_str(buf, Self::CUR_IMAGE_OFFSET, Self::CUR_IMAGE_LEN),
+            info: get_str(buf, Self::INFO_OFFSET, info_len),
+        })
+    }
+}
+
+/// Invalid message received from the SWUpdate daemon
+#[derive(Debug, thiserror::Error, displaydoc::Display)]
+pub enum IpcError {
+    /// invalid magic number {0:#x}
+    Magic(i32),
+    /// unknown message type {0}
+    Type(i32),
+    /// unknown update status {0}
+    Status(u32),
+}
+
+fn put_u32(buf: &mut [u8], offset: usize, value: u32) {
+    buf[offset..offset + 4].copy_from_slice(&value.to_ne_bytes());
+}
+
+fn put_i32(buf: &mut [u8], offset: usize, value: i32) {
+    buf[offset..offset + 4].copy_from_slice(&value.to_ne_bytes());
+}
+
+/// Writes a NUL terminated string, truncated to the field length.
+fn put_str(buf: &mut [u8], offset: usize, len: usize, value: &str) {
+    let bytes = value.as_bytes();
+    let bytes = &bytes[..bytes.len().min(len - 1)];
+
+    buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+fn get_u32(buf: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&buf[offset..offset + 4]);
+
+    u32::from_ne_bytes(bytes)
+}
+
+fn get_i32(buf: &[u8], offset: usize) -> i32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&buf[offset..offset + 4]);
+
+    i32::from_ne_bytes(bytes)
+}
+
+/// Reads a NUL terminated string from a fixed length field.
+fn get_str(buf: &[u8], offset: usize, len: usize) -> String {
+    let field = &buf[offset..offset + len];
+    let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Sizes and offsets of the C structures compiled for a 64-bit Linux target.
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn should_match_c_layout() {
+        assert_eq!(size_of::<layout::SwupdateRequest>(), 1056);
+        assert_eq!(offset_of!(layout::SwupdateRequest, len), 16);
+        assert_eq!(offset_of!(layout::SwupdateRequest, disable_store_swu), 1048);
+        assert_eq!(size_of::<layout::MsgData>(), 3112);
+        assert_eq!(IPC_MESSAGE_SIZE, 3120);
+        assert_eq!(DATA_OFFSET, 8);
+        assert_eq!(STATUS_DESC_OFFSET, 20);
+
+        assert_eq!(PROGRESS_MESSAGE_SIZE, 2416);
+        assert_eq!(ProgressMessage::NSTEPS_OFFSET, 24);
+        assert_eq!(ProgressMessage::CUR_IMAGE_OFFSET, 36);
+        assert_eq!(ProgressMessage::INFOLEN_OFFSET, 360);
+        assert_eq!(ProgressMessage::INFO_OFFSET, 364);
+    }
+
+    #[test]
+    fn should_encode_and_decode_status() {
+        let msg = IpcMessage::Status {
+            current: RecoveryStatus::Idle,
+            last_result: RecoveryStatus::Failure,
+            error: 1,
+            desc: "image not compatible".to_string(),
+        };
+
+        let buf: [u8; IPC_MESSAGE_SIZE] = msg.encode().try_into().unwrap();
+
+        assert_eq!(IpcMessage::decode(&buf).unwrap(), msg);
+    }
+
+    #[test]
+    fn should_reject_invalid_magic() {
+        let buf = [0; IPC_MESSAGE_SIZE];
+
+        assert!(matches!(IpcMessage::decode(&buf), Err(IpcError::Magic(0))));
+    }
+
+    #[test]
+    fn should_compute_progress_percentage() {
+        let mut msg = ProgressMessage {
+            status: RecoveryStatus::Run,
+            nsteps: 4,
+            cur_step: 3,
+            cur_percent: 60,
+            cur_image: "rootfs.ext4".to_string(),
+            info: String::new(),
+        };
+
+        assert_eq!(msg.percentage(), 65);
+
+        let buf: [u8; PROGRESS_MESSAGE_SIZE] = msg.encode().try_into().unwrap();
+        assert_eq!(ProgressMessage::decode(&buf).unwrap(), msg);
+
+        msg.nsteps = 0;
+        assert_eq!(msg.percentage(), 0);
+    }
+}
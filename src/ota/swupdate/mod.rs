@@ -0,0 +1,629 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! SWUpdate backend, talking with the daemon through its IPC Unix sockets.
+//!
+//! The bundle is sent on the control socket after the install request, while the daemon reports
+//! the progress on the progress socket. SWUpdate has no notion of slots, so the booted slot is
+//! the root device on the kernel command line and the update is confirmed through the update
+//! state in the bootloader environment.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use futures::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+use tracing::{debug, error, info, instrument, warn};
+
+use crate::error::DeviceManagerError;
+use crate::ota::rauc::BundleInfo;
+use crate::ota::{DeployProgress, DeployStatus, InstallOptions, ProgressStream, SystemUpdate};
+
+use self::description::{DescriptionError, SwDescription};
+use self::ipc::{
+    IPC_MESSAGE_SIZE, IpcError, IpcMessage, PROGRESS_MESSAGE_SIZE, ProgressMessage, RecoveryStatus,
+    UpdateState,
+};
+use super::config::SwUpdateConfig;
+
+mod description;
+mod ipc;
+
+const PROC_CMDLINE: &str = "/proc/cmdline";
+
+/// Error returned by the SWUpdate backend
+#[derive(Debug, thiserror::Error, displaydoc::Display)]
+pub enum SwUpdateError {
+    /// couldn't connect to the SWUpdate socket {path}
+    Connect {
+        path: PathBuf,
+        #[source]
+        backtrace: std::io::Error,
+    },
+    /// couldn't communicate with the SWUpdate daemon
+    Socket(#[source] std::io::Error),
+    /// invalid message from the SWUpdate daemon
+    Ipc(#[from] IpcError),
+    /// unexpected reply from the SWUpdate daemon: {0:?}
+    UnexpectedReply(IpcMessage),
+    /// the request was rejected by the SWUpdate daemon
+    Rejected,
+    /// SWUpdate can't install from an URL, the OTA streaming must be disabled
+    Streaming,
+    /// couldn't open the bundle {path}
+    Bundle {
+        path: PathBuf,
+        #[source]
+        backtrace: std::io::Error,
+    },
+    /// couldn't read the bundle description
+    Description(#[from] DescriptionError),
+    /// couldn't read the hardware revision from {path}
+    HwRevision {
+        path: PathBuf,
+        #[source]
+        backtrace: std::io::Error,
+    },
+    /// the hardware revision file {0} is empty
+    EmptyHwRevision(PathBuf),
+    /// couldn't read the kernel command line
+    Cmdline(#[source] std::io::Error),
+    /// couldn't find the root device in the kernel command line
+    MissingRoot,
+    /// unsupported slot state {0}
+    State(String),
+}
+
+/// Installs the bundles through SWUpdate.
+#[derive(Debug)]
+pub struct SwUpdate {
+    config: SwUpdateConfig,
+    cmdline: PathBuf,
+    /// Progress connection opened before the install request, not to miss any event.
+    progress: Mutex<Option<UnixStream>>,
+}
+
+impl SwUpdate {
+    pub fn new(config: SwUpdateConfig) -> Self {
+        Self {
+            config,
+            cmdline: PathBuf::from(PROC_CMDLINE),
+            progress: Mutex::new(None),
+        }
+    }
+
+    async fn connect(path: &Path) -> Result<UnixStream, SwUpdateError> {
+        UnixStream::connect(path)
+            .await
+            .map_err(|backtrace| SwUpdateError::Connect {
+                path: path.to_path_buf(),
+                backtrace,
+            })
+    }
+
+    /// Sends a message on the control socket and returns the reply.
+    async fn request(
+        control: &mut UnixStream,
+        msg: &IpcMessage,
+    ) -> Result<IpcMessage, SwUpdateError> {
+        control
+            .write_all(&msg.encode())
+            .await
+            .map_err(SwUpdateError::Socket)?;
+
+        let mut buf = [0; IPC_MESSAGE_SIZE];
+        control
+            .read_exact(&mut buf)
+            .await
+            .map_err(SwUpdateError::Socket)?;
+
+        IpcMessage::decode(&buf).map_err(SwUpdateError::from)
+    }
+
+    async fn status(&self) -> Result<(RecoveryStatus, RecoveryStatus, String), SwUpdateError> {
+        let mut control = Self::connect(&self.config.control_socket).await?;
+
+        match Self::request(&mut control, &IpcMessage::GetStatus).await? {
+            IpcMessage::Status {
+                current,
+                last_result,
+                desc,
+                ..
+            } => Ok((current, last_result, desc)),
+            reply => Err(SwUpdateError::UnexpectedReply(reply)),
+        }
+    }
+
+    async fn install(&self, source: &str) -> Result<(), SwUpdateError> {
+        if source.contains("://") {
+            return Err(SwUpdateError::Streaming);
+        }
+
+        let mut bundle =
+            tokio::fs::File::open(source)
+                .await
+                .map_err(|backtrace| SwUpdateError::Bundle {
+                    path: PathBuf::from(source),
+                    backtrace,
+                })?;
+
+        let progress = Self::connect(&self.config.progress_socket).await?;
+        let mut control = Self::connect(&self.config.control_socket).await?;
+
+        match Self::request(&mut control, &IpcMessage::ReqInstall).await? {
+            IpcMessage::Ack => {}
+            IpcMessage::Nack => return Err(SwUpdateError::Rejected),
+            reply => return Err(SwUpdateError::UnexpectedReply(reply)),
+        }
+
+        *self.progress.lock().unwrap_or_else(|err| err.into_inner()) = Some(progress);
+
+        // The daemon reports the result on the progress socket
+        tokio::spawn(async move {
+            let res = match tokio::io::copy(&mut bundle, &mut control).await {
+                Ok(size) => {
+                    debug!(size, "bundle sent to SWUpdate");
+
+                    control.shutdown().await
+                }
+                Err(err) => Err(err),
+            };
+
+            if let Err(err) = res {
+                error!(error = %err, "couldn't send the bundle to SWUpdate");
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn hw_revision(&self) -> Result<String, SwUpdateError> {
+        let path = &self.config.hwrevision;
+
+        let content = tokio::fs::read_to_string(path).await.map_err(|backtrace| {
+            SwUpdateError::HwRevision {
+                path: path.clone(),
+                backtrace,
+            }
+        })?;
+
+        let revision = content.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if revision.is_empty() {
+            return Err(SwUpdateError::EmptyHwRevision(path.clone()));
+        }
+
+        Ok(revision)
+    }
+
+    /// Returns the system compatible if the bundle can be installed on the board revision.
+    async fn bundle_info(&self, bundle: &str) -> Result<BundleInfo, SwUpdateError> {
+        let description = SwDescription::read(Path::new(bundle)).await?;
+        let system = self.hw_revision().await?;
+
+        let (board, revision) = system.split_once(' ').unwrap_or((&system, ""));
+
+        let compatible = if description.hardware_compatibility.is_empty()
+            || description
+                .hardware_compatibility
+                .iter()
+                .any(|compatible| compatible == revision)
+        {
+            system.clone()
+        } else {
+            format!(
+                "{board} [{}]",
+                description.hardware_compatibility.join(", ")
+            )
+        };
+
+        Ok(BundleInfo {
+            compatible,
+            version: description.version.unwrap_or_default(),
+        })
+    }
+
+    async fn root_device(&self) -> Result<String, SwUpdateError> {
+        let cmdline = tokio::fs::read_to_string(&self.cmdline)
+            .await
+            .map_err(SwUpdateError::Cmdline)?;
+
+        cmdline
+            .split_whitespace()
+            .find_map(|arg| arg.strip_prefix("root="))
+            .map(str::to_string)
+            .ok_or(SwUpdateError::MissingRoot)
+    }
+
+    async fn set_state(&self, state: &str, slot: &str) -> Result<(String, String), SwUpdateError> {
+        let update_state = match state {
+            "good" => UpdateState::Ok,
+            "bad" => UpdateState::Failed,
+            _ => return Err(SwUpdateError::State(state.to_string())),
+        };
+
+        let mut control = Self::connect(&self.config.control_socket).await?;
+
+        match Self::request(&mut control, &IpcMessage::SetUpdateState(update_state)).await? {
+            IpcMessage::Ack => Ok((slot.to_string(), format!("marked slot {slot} as {state}"))),
+            IpcMessage::Nack => Err(SwUpdateError::Rejected),
+            reply => Err(SwUpdateError::UnexpectedReply(reply)),
+        }
+    }
+
+    async fn progress_stream(&self) -> Result<ProgressStream, SwUpdateError> {
+        let progress = self
+            .progress
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take();
+
+        let progress = match progress {
+            Some(progress) => progress,
+            None => {
+                warn!("no install in progress, connecting to the progress socket");
+
+                Self::connect(&self.config.progress_socket).await?
+            }
+        };
+
+        let stream = futures::stream::unfold(Some(progress), |progress| async move {
+            let mut progress = progress?;
+
+            loop {
+                let mut buf = [0; PROGRESS_MESSAGE_SIZE];
+
+                if let Err(err) = progress.read_exact(&mut buf).await {
+                    if err.kind() == std::io::ErrorKind::UnexpectedEof {
+                        warn!("SWUpdate closed the progress socket");
+
+                        return None;
+                    }
+
+                    let err = DeviceManagerError::from(SwUpdateError::Socket(err));
+
+                    return Some((Err(err), None));
+                }
+
+                let msg = match ProgressMessage::decode(&buf) {
+                    Ok(msg) => msg,
+                    Err(err) => {
+                        return Some((Err(SwUpdateError::from(err).into()), None));
+                    }
+                };
+
+                debug!(status = %msg.status, step = msg.cur_step, "SWUpdate progress");
+
+                match msg.status {
+                    RecoveryStatus::Success => {
+                        return Some((Ok(DeployStatus::Completed { signal: 0 }), None));
+                    }
+                    RecoveryStatus::Failure => {
+                        error!(info = msg.info, "SWUpdate installation failed");
+
+                        return Some((Ok(DeployStatus::Completed { signal: 1 }), None));
+                    }
+                    RecoveryStatus::Run | RecoveryStatus::Progress => {
+                        let status = DeployStatus::Progress(DeployProgress {
+                            percentage: msg.percentage(),
+                            message: msg.cur_image,
+                        });
+
+                        return Some((Ok(status), Some(progress)));
+                    }
+                    RecoveryStatus::Idle
+                    | RecoveryStatus::Start
+                    | RecoveryStatus::Download
+                    | RecoveryStatus::Done
+                    | RecoveryStatus::Subprocess => {}
+                }
+            }
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+impl SystemUpdate for SwUpdate {
+    #[instrument(skip(self, _options))]
+    async fn install_bundle(
+        &self,
+        source: &str,
+        _options: &InstallOptions,
+    ) -> Result<(), DeviceManagerError> {
+        self.install(source).await?;
+
+        info!("bundle installation started");
+
+        Ok(())
+    }
+
+    async fn last_error(&self) -> Result<String, DeviceManagerError> {
+        let (_current, last_result, desc) = self.status().await?;
+
+        if last_result == RecoveryStatus::Failure {
+            Ok(desc)
+        } else {
+            Ok(String::new())
+        }
+    }
+
+    async fn info(&self, bundle: &str) -> Result<BundleInfo, DeviceManagerError> {
+        self.bundle_info(bundle).await.map_err(Into::into)
+    }
+
+    async fn operation(&self) -> Result<String, DeviceManagerError> {
+        let (current, _last_result, _desc) = self.status().await?;
+
+        Ok(current.to_string())
+    }
+
+    async fn compatible(&self) -> Result<String, DeviceManagerError> {
+        self.hw_revision().await.map_err(Into::into)
+    }
+
+    async fn boot_slot(&self) -> Result<String, DeviceManagerError> {
+        self.root_device().await.map_err(Into::into)
+    }
+
+    async fn receive_completed(&self) -> Result<ProgressStream, DeviceManagerError> {
+        self.progress_stream().await.map_err(Into::into)
+    }
+
+    async fn get_primary(&self) -> Result<String, DeviceManagerError> {
+        // The bootloader selected the booted slot
+        self.root_device().await.map_err(Into::into)
+    }
+
+    async fn mark(
+        &self,
+        state: &str,
+        slot_identifier: &str,
+    ) -> Result<(String, String), DeviceManagerError> {
+        self.set_state(state, slot_identifier)
+            .await
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+    use tokio::net::UnixListener;
+    use tokio::task::JoinHandle;
+
+    use super::*;
+
+    const DESCRIPTION: &str = r#"
+software =
+{
+    version = "2.0.0";
+    hardware-compatibility: [ "1.0", "1.2"];
+}
+"#;
+
+    fn config(dir: &Path) -> SwUpdateConfig {
+        SwUpdateConfig {
+            control_socket: dir.join("sockinstctrl"),
+            progress_socket: dir.join("swupdateprog"),
+            hwrevision: dir.join("hwrevision"),
+        }
+    }
+
+    async fn read_message(stream: &mut UnixStream) -> IpcMessage {
+        let mut buf = [0; IPC_MESSAGE_SIZE];
+        stream.read_exact(&mut buf).await.unwrap();
+
+        IpcMessage::decode(&buf).unwrap()
+    }
+
+    fn progress_msg(status: RecoveryStatus, cur_step: u32, cur_percent: u32) -> Vec<u8> {
+        ProgressMessage {
+            status,
+            nsteps: 2,
+            cur_step,
+            cur_percent,
+            cur_image: "rootfs.ext4".to_string(),
+            info: String::new(),
+        }
+        .encode()
+    }
+
+    /// Fake SWUpdate daemon, returns the received bundle.
+    fn fake_daemon(config: &SwUpdateConfig, result: RecoveryStatus) -> JoinHandle<Vec<u8>> {
+        let control = UnixListener::bind(&config.control_socket).unwrap();
+        let progress = UnixListener::bind(&config.progress_socket).unwrap();
+
+        tokio::spawn(async move {
+            let (mut progress, _) = progress.accept().await.unwrap();
+            let (mut control, _) = control.accept().await.unwrap();
+
+            assert_eq!(read_message(&mut control).await, IpcMessage::ReqInstall);
+            control.write_all(&IpcMessage::Ack.encode()).await.unwrap();
+
+            let mut bundle = Vec::new();
+            control.read_to_end(&mut bundle).await.unwrap();
+
+            for msg in [
+                progress_msg(RecoveryStatus::Start, 0, 0),
+                progress_msg(RecoveryStatus::Run, 1, 100),
+                progress_msg(RecoveryStatus::Run, 2, 50),
+                progress_msg(result, 2, 100),
+                progress_msg(RecoveryStatus::Done, 2, 100),
+            ] {
+                progress.write_all(&msg).await.unwrap();
+            }
+
+            bundle
+        })
+    }
+
+    #[tokio::test]
+    async fn should_install_bundle() {
+        let dir = TempDir::new("edgehog-swupdate").unwrap();
+        let config = config(dir.path());
+
+        let bundle_path = dir.path().join("update.swu");
+        let bundle = description::bundle(DESCRIPTION);
+        tokio::fs::write(&bundle_path, &bundle).await.unwrap();
+
+        let daemon = fake_daemon(&config, RecoveryStatus::Success);
+
+        let swupdate = SwUpdate::new(config);
+        swupdate
+            .install_bundle(bundle_path.to_str().unwrap(), &InstallOptions::default())
+            .await
+            .unwrap();
+
+        let events: Vec<DeployStatus> = swupdate
+            .receive_completed()
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        let exp = vec![
+            DeployStatus::Progress(DeployProgress {
+                percentage: 50,
+                message: "rootfs.ext4".to_string(),
+            }),
+            DeployStatus::Progress(DeployProgress {
+                percentage: 75,
+                message: "rootfs.ext4".to_string(),
+            }),
+            DeployStatus::Completed { signal: 0 },
+        ];
+
+        assert_eq!(events, exp);
+        assert_eq!(daemon.await.unwrap(), bundle);
+    }
+
+    #[tokio::test]
+    async fn should_report_failure() {
+        let dir = TempDir::new("edgehog-swupdate").unwrap();
+        let config = config(dir.path());
+
+        let bundle_path = dir.path().join("update.swu");
+        tokio::fs::write(&bundle_path, description::bundle(DESCRIPTION))
+            .await
+            .unwrap();
+
+        let daemon = fake_daemon(&config, RecoveryStatus::Failure);
+
+        let swupdate = SwUpdate::new(config);
+        swupdate
+            .install_bundle(bundle_path.to_str().unwrap(), &InstallOptions::default())
+            .await
+            .unwrap();
+
+        let last = swupdate
+            .receive_completed()
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .pop();
+
+        assert_eq!(last, Some(DeployStatus::Completed { signal: 1 }));
+        daemon.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_reject_url() {
+        let dir = TempDir::new("edgehog-swupdate").unwrap();
+        let swupdate = SwUpdate::new(config(dir.path()));
+
+        let err = swupdate
+            .install("https://example.com/update.swu")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, SwUpdateError::Streaming));
+    }
+
+    #[tokio::test]
+    async fn should_check_hardware_compatibility() {
+        let dir = TempDir::new("edgehog-swupdate").unwrap();
+        let config = config(dir.path());
+
+        let bundle_path = dir.path().join("update.swu");
+        tokio::fs::write(&bundle_path, description::bundle(DESCRIPTION))
+            .await
+            .unwrap();
+
+        tokio::fs::write(&config.hwrevision, "imx8-board 1.2\n")
+            .await
+            .unwrap();
+
+        let swupdate = SwUpdate::new(config.clone());
+        let info = swupdate.info(bundle_path.to_str().unwrap()).await.unwrap();
+
+        assert_eq!(info.version, "2.0.0");
+        assert_eq!(info.compatible, swupdate.compatible().await.unwrap());
+
+        tokio::fs::write(&config.hwrevision, "imx8-board 2.0\n")
+            .await
+            .unwrap();
+
+        let info = swupdate.info(bundle_path.to_str().unwrap()).await.unwrap();
+
+        assert_eq!(info.compatible, "imx8-board [1.0, 1.2]");
+        assert_ne!(info.compatible, swupdate.compatible().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_mark_good() {
+        let dir = TempDir::new("edgehog-swupdate").unwrap();
+        let config = config(dir.path());
+
+        let cmdline = dir.path().join("cmdline");
+        tokio::fs::write(&cmdline, "console=ttymxc0 root=/dev/mmcblk2p2 rootwait\n")
+            .await
+            .unwrap();
+
+        let control = UnixListener::bind(&config.control_socket).unwrap();
+        let daemon = tokio::spawn(async move {
+            let (mut control, _) = control.accept().await.unwrap();
+
+            let msg = read_message(&mut control).await;
+
+            control.write_all(&IpcMessage::Ack.encode()).await.unwrap();
+
+            msg
+        });
+
+        let mut swupdate = SwUpdate::new(config);
+        swupdate.cmdline = cmdline;
+
+        let slot = swupdate.get_primary().await.unwrap();
+        assert_eq!(slot, "/dev/mmcblk2p2");
+
+        let (marked, _) = swupdate.mark("good", &slot).await.unwrap();
+        assert_eq!(marked, slot);
+
+        assert_eq!(
+            daemon.await.unwrap(),
+            IpcMessage::SetUpdateState(UpdateState::Ok)
+        );
+    }
+}
@@ -0,0 +1,256 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of the bundle digest and signature, independent of the update backend.
+//!
+//! The signature is computed on the digest bytes of the bundle, so it's checked before the
+//! download, while the digest is computed while writing the bundle to disk.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use aws_lc_rs::error::KeyRejected;
+use aws_lc_rs::signature::{self, ParsedPublicKey, VerificationAlgorithm};
+use eyre::{OptionExt, WrapErr};
+use tracing::{debug, warn};
+
+use crate::io::digest::FileDigest;
+
+use super::config::{SignatureAlgorithm, VerificationConfig};
+
+/// Error returned while verifying the bundle
+#[derive(Debug, thiserror::Error, displaydoc::Display)]
+pub enum VerifyError {
+    /// couldn't read the public key {path}
+    ReadKey {
+        path: PathBuf,
+        #[source]
+        backtrace: std::io::Error,
+    },
+    /// invalid public key {path}
+    InvalidKey {
+        path: PathBuf,
+        #[source]
+        backtrace: KeyRejected,
+    },
+    /// the request is missing the bundle digest
+    MissingDigest,
+    /// the request is missing the bundle signature
+    MissingSignature,
+    /// the bundle signature is invalid
+    Signature,
+    /// the bundle digest doesn't match the expected one
+    DigestMismatch,
+}
+
+/// Expected digest of the bundle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleDigest {
+    pub algorithm: FileDigest,
+    pub value: Vec<u8>,
+}
+
+impl FromStr for BundleDigest {
+    type Err = eyre::Report;
+
+    /// Parses the digest in the `<algorithm>:<hex>` format, like `sha256:28babb1c...`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (algorithm, value) = s
+            .split_once(':')
+            .ok_or_eyre("couldn't parse digest, missing ':' delimiter")?;
+
+        let value = hex::decode(value).wrap_err("couldn't decode hex digest")?;
+
+        Ok(Self {
+            algorithm: algorithm.parse()?,
+            value,
+        })
+    }
+}
+
+/// Digest and signature of the bundle received with the request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BundleCheck {
+    pub digest: Option<BundleDigest>,
+    /// Signature of the digest bytes
+    pub signature: Option<Vec<u8>>,
+}
+
+impl BundleCheck {
+    /// Parses the optional digest and hex encoded signature, empty strings are ignored.
+    pub(crate) fn from_fields(
+        digest: Option<String>,
+        signature: Option<String>,
+    ) -> eyre::Result<Self> {
+        let digest = digest
+            .filter(|digest| !digest.is_empty())
+            .map(|digest| digest.parse())
+            .transpose()?;
+        let signature = signature
+            .filter(|signature| !signature.is_empty())
+            .map(|signature| hex::decode(signature).wrap_err("couldn't decode hex signature"))
+            .transpose()?;
+
+        Ok(Self { digest, signature })
+    }
+
+    /// Checks the signature of the digest with the configured public key.
+    ///
+    /// Without a public key the signature is not checked.
+    pub async fn verify_signature(&self, config: &VerificationConfig) -> Result<(), VerifyError> {
+        let Some(path) = &config.public_key else {
+            if self.signature.is_some() {
+                warn!("public key not configured, skipping the bundle signature verification");
+            }
+
+            return Ok(());
+        };
+
+        let digest = self.digest.as_ref().ok_or(VerifyError::MissingDigest)?;
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or(VerifyError::MissingSignature)?;
+
+        let key = tokio::fs::read(path)
+            .await
+            .map_err(|backtrace| VerifyError::ReadKey {
+                path: path.clone(),
+                backtrace,
+            })?;
+
+        let key = ParsedPublicKey::new(verification_algorithm(config.algorithm), key).map_err(
+            |backtrace| VerifyError::InvalidKey {
+                path: path.clone(),
+                backtrace,
+            },
+        )?;
+
+        key.verify_sig(&digest.value, signature)
+            .map_err(|_| VerifyError::Signature)?;
+
+        debug!("bundle signature verified");
+
+        Ok(())
+    }
+}
+
+fn verification_algorithm(algorithm: SignatureAlgorithm) -> &'static dyn VerificationAlgorithm {
+    match algorithm {
+        SignatureAlgorithm::Ed25519 => &signature::ED25519,
+        SignatureAlgorithm::EcdsaP256Sha256 => &signature::ECDSA_P256_SHA256_ASN1,
+        SignatureAlgorithm::RsaPkcs1Sha256 => &signature::RSA_PKCS1_2048_8192_SHA256,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_lc_rs::signature::{Ed25519KeyPair, KeyPair};
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    const DIGEST: &str = "sha256:28babb1cdf8aea6b62acc1097fdc83482cbf6e11c4fe7dcb39ae1682776baec5";
+
+    fn signed(dir: &TempDir) -> (VerificationConfig, BundleCheck) {
+        let pair = Ed25519KeyPair::generate().unwrap();
+
+        let path = dir.path().join("key.bin");
+        std::fs::write(&path, pair.public_key().as_ref()).unwrap();
+
+        let digest: BundleDigest = DIGEST.parse().unwrap();
+        let signature = pair.sign(&digest.value).as_ref().to_vec();
+
+        let config = VerificationConfig {
+            public_key: Some(path),
+            algorithm: SignatureAlgorithm::Ed25519,
+        };
+
+        let check = BundleCheck {
+            digest: Some(digest),
+            signature: Some(signature),
+        };
+
+        (config, check)
+    }
+
+    #[test]
+    fn should_parse_fields() {
+        let check =
+            BundleCheck::from_fields(Some(DIGEST.to_string()), Some(String::new())).unwrap();
+
+        assert_eq!(
+            check,
+            BundleCheck {
+                digest: Some(BundleDigest {
+                    algorithm: FileDigest::Sha256,
+                    value: hex::decode(
+                        "28babb1cdf8aea6b62acc1097fdc83482cbf6e11c4fe7dcb39ae1682776baec5"
+                    )
+                    .unwrap(),
+                }),
+                signature: None,
+            }
+        );
+
+        assert!(BundleCheck::from_fields(Some("md5:abcd".to_string()), None).is_err());
+        assert!(BundleCheck::from_fields(None, Some("not hex".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn should_verify_signature() {
+        let dir = TempDir::new("edgehog-verify").unwrap();
+
+        let (config, check) = signed(&dir);
+
+        check.verify_signature(&config).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_signature() {
+        let dir = TempDir::new("edgehog-verify").unwrap();
+
+        let (config, mut check) = signed(&dir);
+
+        if let Some(digest) = check.digest.as_mut() {
+            digest.value[0] ^= 0xff;
+        }
+
+        let err = check.verify_signature(&config).await.unwrap_err();
+        assert!(matches!(err, VerifyError::Signature), "got {err:?}");
+
+        check.signature = None;
+
+        let err = check.verify_signature(&config).await.unwrap_err();
+        assert!(matches!(err, VerifyError::MissingSignature), "got {err:?}");
+    }
+
+    #[tokio::test]
+    async fn should_skip_without_public_key() {
+        let check = BundleCheck {
+            digest: None,
+            signature: Some(vec![1, 2, 3]),
+        };
+
+        check
+            .verify_signature(&VerificationConfig::default())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuration for the power policy.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::{BatteryReading, PeriodMultipliers, PowerProfile};
+
+/// Configuration of the battery thresholds for the power profiles.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PowerPolicyConfig {
+    /// Flag to enable the power policy.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between the reads of the battery status.
+    #[serde(default = "PowerPolicyConfig::default_period")]
+    pub period: u64,
+    /// Battery percentage at or below which the low power profile is used.
+    #[serde(default = "PowerPolicyConfig::default_low_threshold")]
+    pub low_threshold: f64,
+    /// Battery percentage at or below which the critical profile is used.
+    #[serde(default = "PowerPolicyConfig::default_critical_threshold")]
+    pub critical_threshold: f64,
+    /// Percentage the battery needs to recover over a threshold to leave the profile.
+    #[serde(default = "PowerPolicyConfig::default_hysteresis")]
+    pub hysteresis: f64,
+    /// Multiplier of the telemetry periods in the low power profile.
+    #[serde(default = "PowerPolicyConfig::default_low_power_multiplier")]
+    pub low_power_multiplier: u32,
+    /// Multiplier of the telemetry periods in the critical profile.
+    #[serde(default = "PowerPolicyConfig::default_critical_multiplier")]
+    pub critical_multiplier: u32,
+}
+
+impl PowerPolicyConfig {
+    const fn default_period() -> u64 {
+        60
+    }
+
+    const fn default_low_threshold() -> f64 {
+        30.0
+    }
+
+    const fn default_critical_threshold() -> f64 {
+        15.0
+    }
+
+    const fn default_hysteresis() -> f64 {
+        5.0
+    }
+
+    fn default_low_power_multiplier() -> u32 {
+        PeriodMultipliers::default().low_power
+    }
+
+    fn default_critical_multiplier() -> u32 {
+        PeriodMultipliers::default().critical
+    }
+
+    pub(crate) fn period(&self) -> Duration {
+        Duration::from_secs(self.period.max(1))
+    }
+
+    pub(crate) fn period_multipliers(&self) -> PeriodMultipliers {
+        PeriodMultipliers {
+            low_power: self.low_power_multiplier,
+            critical: self.critical_multiplier,
+        }
+    }
+
+    /// Checks the thresholds are consistent.
+    pub(crate) fn validate(&self) -> eyre::Result<()> {
+        eyre::ensure!(
+            (0.0..=100.0).contains(&self.critical_threshold)
+                && (0.0..=100.0).contains(&self.low_threshold),
+            "the power policy thresholds must be percentages"
+        );
+        eyre::ensure!(
+            self.critical_threshold <= self.low_threshold,
+            "the critical threshold must be lower than the low threshold"
+        );
+        eyre::ensure!(
+            self.hysteresis >= 0.0,
+            "the power policy hysteresis must be positive"
+        );
+        eyre::ensure!(
+            self.low_power_multiplier >= 1 && self.critical_multiplier >= 1,
+            "the telemetry period multipliers must be at least 1"
+        );
+
+        Ok(())
+    }
+
+    /// Selects the profile for the battery reading, given the current one.
+    ///
+    /// The thresholds of the profiles the device is already in are raised by the hysteresis, so
+    /// a battery oscillating around a threshold doesn't flip the profile at every read.
+    pub(crate) fn select(&self, current: PowerProfile, reading: BatteryReading) -> PowerProfile {
+        if reading.charging {
+            return PowerProfile::Normal;
+        }
+
+        let threshold = |profile: PowerProfile, value: f64| {
+            if current >= profile {
+                value + self.hysteresis
+            } else {
+                value
+            }
+        };
+
+        if reading.level <= threshold(PowerProfile::Critical, self.critical_threshold) {
+            PowerProfile::Critical
+        } else if reading.level <= threshold(PowerProfile::LowPower, self.low_threshold) {
+            PowerProfile::LowPower
+        } else {
+            PowerProfile::Normal
+        }
+    }
+}
+
+impl Default for PowerPolicyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            period: Self::default_period(),
+            low_threshold: Self::default_low_threshold(),
+            critical_threshold: Self::default_critical_threshold(),
+            hysteresis: Self::default_hysteresis(),
+            low_power_multiplier: Self::default_low_power_multiplier(),
+            critical_multiplier: Self::default_critical_multiplier(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn should_deserialize() {
+        let string = r#"
+        enabled = true
+        period = 30
+        low_threshold = 40.0
+        critical_threshold = 10.0
+        hysteresis = 2.5
+        low_power_multiplier = 2
+        critical_multiplier = 6
+        "#;
+
+        let config: PowerPolicyConfig = toml::from_str(string).unwrap();
+
+        let exp = PowerPolicyConfig {
+            enabled: true,
+            period: 30,
+            low_threshold: 40.0,
+            critical_threshold: 10.0,
+            hysteresis: 2.5,
+            low_power_multiplier: 2,
+            critical_multiplier: 6,
+        };
+
+        assert_eq!(config, exp);
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn should_deserialize_default() {
+        let config: PowerPolicyConfig = toml::from_str("").unwrap();
+
+        assert_eq!(config, PowerPolicyConfig::default());
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn should_reject_inverted_thresholds() {
+        let config = PowerPolicyConfig {
+            low_threshold: 10.0,
+            critical_threshold: 20.0,
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn should_reject_zero_multiplier() {
+        let config = PowerPolicyConfig {
+            critical_multiplier: 0,
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_err());
+    }
+
+    #[rstest]
+    #[case(PowerProfile::Normal, 50.0, PowerProfile::Normal)]
+    #[case(PowerProfile::Normal, 32.0, PowerProfile::Normal)]
+    #[case(PowerProfile::Normal, 30.0, PowerProfile::LowPower)]
+    #[case(PowerProfile::Normal, 10.0, PowerProfile::Critical)]
+    #[case(PowerProfile::LowPower, 34.0, PowerProfile::LowPower)]
+    #[case(PowerProfile::LowPower, 36.0, PowerProfile::Normal)]
+    #[case(PowerProfile::LowPower, 15.0, PowerProfile::Critical)]
+    #[case(PowerProfile::Critical, 19.0, PowerProfile::Critical)]
+    #[case(PowerProfile::Critical, 21.0, PowerProfile::LowPower)]
+    #[case(PowerProfile::Critical, 40.0, PowerProfile::Normal)]
+    fn should_select_profile(
+        #[case] current: PowerProfile,
+        #[case] level: f64,
+        #[case] exp: PowerProfile,
+    ) {
+        let config = PowerPolicyConfig::default();
+
+        let reading = BatteryReading {
+            level,
+            charging: false,
+        };
+
+        assert_eq!(config.select(current, reading), exp);
+    }
+
+    #[test]
+    fn charging_is_normal() {
+        let config = PowerPolicyConfig::default();
+
+        let reading = BatteryReading {
+            level: 5.0,
+            charging: true,
+        };
+
+        assert_eq!(
+            config.select(PowerProfile::Critical, reading),
+            PowerProfile::Normal
+        );
+    }
+}
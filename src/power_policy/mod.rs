@@ -0,0 +1,240 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Battery aware power profiles.
+//!
+//! The current [`PowerProfile`] is shared with the services through a [`PowerState`], which
+//! adapt to it: the telemetry periods get longer, the Wi-Fi scans and file transfers are paused
+//! and the OTA updates are deferred.
+
+use std::fmt::Display;
+use std::time::Duration;
+
+use astarte_device_sdk::AstarteData;
+use tokio::sync::watch;
+
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub mod config;
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub(crate) mod monitor;
+
+/// Power profile of the runtime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PowerProfile {
+    /// No restrictions.
+    #[default]
+    Normal,
+    /// Longer telemetry periods, no Wi-Fi scans and deferred OTA updates.
+    LowPower,
+    /// Like the low power profile, with even longer periods and paused file transfers.
+    Critical,
+}
+
+impl PowerProfile {
+    pub(crate) fn pauses_wifi_scans(&self) -> bool {
+        *self >= PowerProfile::LowPower
+    }
+
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub(crate) fn defers_ota(&self) -> bool {
+        *self >= PowerProfile::LowPower
+    }
+
+    #[cfg(feature = "file-transfer")]
+    pub(crate) fn pauses_transfers(&self) -> bool {
+        *self >= PowerProfile::Critical
+    }
+}
+
+impl Display for PowerProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PowerProfile::Normal => write!(f, "Normal"),
+            PowerProfile::LowPower => write!(f, "LowPower"),
+            PowerProfile::Critical => write!(f, "Critical"),
+        }
+    }
+}
+
+impl From<PowerProfile> for AstarteData {
+    fn from(value: PowerProfile) -> Self {
+        AstarteData::String(value.to_string())
+    }
+}
+
+/// Aggregated status of the batteries.
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BatteryReading {
+    /// Lowest level between the batteries.
+    pub(crate) level: f64,
+    /// Any of the batteries is charging.
+    pub(crate) charging: bool,
+}
+
+/// Multipliers of the telemetry periods in the restricted profiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PeriodMultipliers {
+    pub(crate) low_power: u32,
+    pub(crate) critical: u32,
+}
+
+impl PeriodMultipliers {
+    /// Scales the telemetry period for the profile.
+    pub(crate) fn scale(&self, profile: PowerProfile, period: Duration) -> Duration {
+        match profile {
+            PowerProfile::Normal => period,
+            PowerProfile::LowPower => period.saturating_mul(self.low_power),
+            PowerProfile::Critical => period.saturating_mul(self.critical),
+        }
+    }
+}
+
+impl Default for PeriodMultipliers {
+    fn default() -> Self {
+        Self {
+            low_power: 4,
+            critical: 10,
+        }
+    }
+}
+
+/// Shared handle to the current power profile.
+#[derive(Debug, Clone)]
+pub struct PowerState {
+    rx: watch::Receiver<PowerProfile>,
+    multipliers: PeriodMultipliers,
+}
+
+impl PowerState {
+    pub(crate) fn new(rx: watch::Receiver<PowerProfile>, multipliers: PeriodMultipliers) -> Self {
+        Self { rx, multipliers }
+    }
+
+    /// Returns the current profile.
+    pub(crate) fn current(&self) -> PowerProfile {
+        *self.rx.borrow()
+    }
+
+    /// Scales the telemetry period for the current profile.
+    pub(crate) fn scale_period(&self, period: Duration) -> Duration {
+        self.multipliers.scale(self.current(), period)
+    }
+
+    /// Waits for the profile to change.
+    ///
+    /// If the policy is not running the profile will never change, so this never returns.
+    pub(crate) async fn changed(&mut self) -> PowerProfile {
+        if self.rx.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+
+        *self.rx.borrow_and_update()
+    }
+
+    /// Waits till the profile satisfies the predicate.
+    #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+    pub(crate) async fn wait_for(&mut self, f: impl Fn(&PowerProfile) -> bool) {
+        // Errors only if the policy is not running, in that case the profile doesn't change
+        let _ = self.rx.wait_for(f).await;
+    }
+}
+
+impl Default for PowerState {
+    /// State always in the [`PowerProfile::Normal`] profile, when the policy is disabled.
+    fn default() -> Self {
+        let (_tx, rx) = watch::channel(PowerProfile::Normal);
+
+        Self::new(rx, PeriodMultipliers::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_scale_period() {
+        let period = Duration::from_secs(60);
+        let multipliers = PeriodMultipliers {
+            low_power: 2,
+            critical: 5,
+        };
+
+        assert_eq!(multipliers.scale(PowerProfile::Normal, period), period);
+        assert_eq!(
+            multipliers.scale(PowerProfile::LowPower, period),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            multipliers.scale(PowerProfile::Critical, period),
+            Duration::from_secs(300)
+        );
+    }
+
+    #[test]
+    fn should_pause_wifi_scans() {
+        assert!(!PowerProfile::Normal.pauses_wifi_scans());
+        assert!(PowerProfile::LowPower.pauses_wifi_scans());
+        assert!(PowerProfile::Critical.pauses_wifi_scans());
+    }
+
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    #[test]
+    fn should_defer_ota() {
+        assert!(!PowerProfile::Normal.defers_ota());
+        assert!(PowerProfile::LowPower.defers_ota());
+        assert!(PowerProfile::Critical.defers_ota());
+    }
+
+    #[cfg(feature = "file-transfer")]
+    #[test]
+    fn should_pause_transfers() {
+        assert!(!PowerProfile::Normal.pauses_transfers());
+        assert!(!PowerProfile::LowPower.pauses_transfers());
+        assert!(PowerProfile::Critical.pauses_transfers());
+    }
+
+    #[cfg(feature = "file-transfer")]
+    #[tokio::test]
+    async fn default_state_is_normal() {
+        let mut state = PowerState::default();
+
+        assert_eq!(state.current(), PowerProfile::Normal);
+
+        // Returns immediately even if the policy is not running
+        state.wait_for(|profile| !profile.pauses_transfers()).await;
+    }
+
+    #[cfg(feature = "file-transfer")]
+    #[tokio::test]
+    async fn should_wait_for_profile() {
+        let (tx, rx) = watch::channel(PowerProfile::Critical);
+        let mut state = PowerState::new(rx, PeriodMultipliers::default());
+
+        let handle = tokio::spawn(async move {
+            state.wait_for(|profile| !profile.pauses_transfers()).await;
+
+            state.current()
+        });
+
+        tx.send_replace(PowerProfile::LowPower);
+
+        assert_eq!(handle.await.unwrap(), PowerProfile::LowPower);
+    }
+}
@@ -0,0 +1,231 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Task reading the batteries to select the power profile.
+
+use astarte_device_sdk::IntoAstarteObject;
+use astarte_device_sdk::chrono::Utc;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use crate::Client;
+use crate::data::send_object_with_timestamp;
+use crate::telemetry::stats::battery_status::{BatteryStatus, BatteryStatusTelemetry};
+
+use super::config::PowerPolicyConfig;
+use super::{BatteryReading, PowerProfile, PowerState};
+
+const EVENT_INTERFACE: &str = "io.edgehog.devicemanager.PowerProfileEvent";
+
+/// Event published at every transition of the power profile.
+#[derive(Debug, Clone, PartialEq, IntoAstarteObject)]
+#[astarte_object(rename_all = "camelCase")]
+pub(crate) struct PowerProfileEvent {
+    profile: PowerProfile,
+    previous_profile: PowerProfile,
+    level_percentage: f64,
+    charging: bool,
+}
+
+impl BatteryReading {
+    /// Aggregates the status of the batteries, ignoring the removed or unknown ones.
+    fn from_batteries<'a>(batteries: impl IntoIterator<Item = &'a BatteryStatus>) -> Option<Self> {
+        batteries
+            .into_iter()
+            .filter(|battery| !matches!(battery.status(), "Removed" | "Unknown"))
+            .fold(None, |reading: Option<Self>, battery| {
+                let charging = battery.status() == "Charging";
+
+                Some(match reading {
+                    Some(reading) => Self {
+                        level: reading.level.min(battery.level_percentage()),
+                        charging: reading.charging || charging,
+                    },
+                    None => Self {
+                        level: battery.level_percentage(),
+                        charging,
+                    },
+                })
+            })
+    }
+}
+
+/// Reads the battery status periodically and switches the power profile.
+#[derive(Debug)]
+pub(crate) struct PowerPolicy<C> {
+    client: C,
+    config: PowerPolicyConfig,
+    batteries: BatteryStatusTelemetry,
+    profile: watch::Sender<PowerProfile>,
+}
+
+impl<C> PowerPolicy<C> {
+    pub(crate) fn new(client: C, config: PowerPolicyConfig) -> (Self, PowerState) {
+        let (tx, rx) = watch::channel(PowerProfile::Normal);
+
+        let policy = Self {
+            client,
+            config,
+            batteries: BatteryStatusTelemetry::default(),
+            profile: tx,
+        };
+
+        let multipliers = config.period_multipliers();
+
+        (policy, PowerState::new(rx, multipliers))
+    }
+
+    pub(crate) async fn run(mut self, cancel: CancellationToken) -> eyre::Result<()>
+    where
+        C: Client + Send + Sync + 'static,
+    {
+        let mut interval = tokio::time::interval(self.config.period());
+
+        while cancel.run_until_cancelled(interval.tick()).await.is_some() {
+            let batteries = self.batteries.read().await;
+
+            let reading = BatteryReading::from_batteries(batteries.iter().map(|(_, b)| b));
+
+            match reading {
+                Some(reading) => self.update(reading).await,
+                None => {
+                    debug!("no battery to read, keeping the current power profile");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn update(&mut self, reading: BatteryReading)
+    where
+        C: Client + Send + Sync + 'static,
+    {
+        let current = *self.profile.borrow();
+        let profile = self.config.select(current, reading);
+
+        if profile == current {
+            return;
+        }
+
+        info!(
+            %profile,
+            previous = %current,
+            level = reading.level,
+            charging = reading.charging,
+            "switching power profile"
+        );
+
+        self.profile.send_replace(profile);
+
+        let event = PowerProfileEvent {
+            profile,
+            previous_profile: current,
+            level_percentage: reading.level,
+            charging: reading.charging,
+        };
+
+        send_object_with_timestamp(
+            &mut self.client,
+            EVENT_INTERFACE,
+            "/event",
+            event,
+            Utc::now(),
+        )
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astarte_device_sdk::AstarteData;
+    use astarte_device_sdk::store::SqliteStore;
+    use astarte_device_sdk::transport::mqtt::Mqtt;
+    use astarte_device_sdk_mock::MockDeviceClient;
+    use pretty_assertions::assert_eq;
+
+    use crate::telemetry::stats::battery_status::upower::BatteryState;
+
+    use super::*;
+
+    #[test]
+    fn should_aggregate_batteries() {
+        let batteries = [
+            BatteryStatus::new(80.0, BatteryState::Discharging, true),
+            BatteryStatus::new(40.0, BatteryState::Charging, true),
+            BatteryStatus::new(5.0, BatteryState::FullyCharged, false),
+        ];
+
+        let reading = BatteryReading::from_batteries(&batteries).unwrap();
+
+        assert_eq!(
+            reading,
+            BatteryReading {
+                level: 40.0,
+                charging: true
+            }
+        );
+    }
+
+    #[test]
+    fn no_batteries() {
+        let batteries = [BatteryStatus::new(0.0, BatteryState::Unknown, true)];
+
+        assert_eq!(BatteryReading::from_batteries(&batteries), None);
+    }
+
+    #[tokio::test]
+    async fn should_publish_transitions() {
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+
+        client
+            .expect_send_object_with_timestamp()
+            .once()
+            .withf(|interface, path, data, _| {
+                interface == EVENT_INTERFACE
+                    && path == "/event"
+                    && data.get("profile") == Some(&AstarteData::from("LowPower"))
+                    && data.get("previousProfile") == Some(&AstarteData::from("Normal"))
+                    && data.get("charging") == Some(&AstarteData::from(false))
+            })
+            .returning(|_, _, _, _| Ok(()));
+
+        let (mut policy, state) = PowerPolicy::new(client, PowerPolicyConfig::default());
+
+        let reading = BatteryReading {
+            level: 25.0,
+            charging: false,
+        };
+
+        policy.update(reading).await;
+        assert_eq!(state.current(), PowerProfile::LowPower);
+
+        // Same profile, nothing is published
+        policy.update(reading).await;
+        assert_eq!(state.current(), PowerProfile::LowPower);
+    }
+
+    #[test]
+    fn event_profile_data() {
+        assert_eq!(
+            AstarteData::from(PowerProfile::Critical),
+            AstarteData::String("Critical".to_string())
+        );
+    }
+}
@@ -0,0 +1,190 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Token bucket shared by the rate limits of the runtime.
+//!
+//! It's used by the [bandwidth limits](crate::bandwidth) and the telemetry schedule.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use tracing::{debug, trace};
+
+/// Token bucket shared by the clones of the limiter.
+///
+/// The default limiter has no limit.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+    /// Wakes the waiting tasks when the rate changes.
+    changed: Arc<Notify>,
+}
+
+/// Tokens that can be acquired.
+///
+/// The tokens can go negative, so a request bigger than the burst is served and the next ones
+/// wait for the debt to be repaid.
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+    /// Tokens added per second, 0 if not limited.
+    rate: f64,
+    /// Maximum tokens saved while idle.
+    burst: f64,
+}
+
+impl Bucket {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.last = now;
+    }
+}
+
+impl Default for Bucket {
+    fn default() -> Self {
+        Self {
+            tokens: 0.0,
+            last: Instant::now(),
+            rate: 0.0,
+            burst: 0.0,
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter with a full bucket.
+    ///
+    /// The rate is in tokens per second, and the burst is the maximum number of tokens saved.
+    pub(crate) fn new(rate: f64, burst: f64) -> Self {
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: burst,
+                last: Instant::now(),
+                rate,
+                burst,
+            })),
+            changed: Arc::default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Bucket> {
+        self.bucket
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Changes the rate and burst, 0 to disable the limit.
+    #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+    pub(crate) fn set_rate(&self, rate: f64, burst: f64) {
+        {
+            let mut bucket = self.lock();
+
+            bucket.refill();
+            bucket.rate = rate;
+            bucket.burst = burst;
+            bucket.tokens = bucket.tokens.min(burst);
+        }
+
+        self.changed.notify_waiters();
+    }
+
+    /// Returns the tokens added per second, 0 if not limited.
+    #[cfg(any(feature = "file-transfer", all(feature = "zbus", target_os = "linux")))]
+    pub(crate) fn rate(&self) -> f64 {
+        self.lock().rate
+    }
+
+    /// Waits till the bucket is not in debt, then takes the tokens.
+    pub(crate) async fn acquire(&self, tokens: f64) {
+        loop {
+            let changed = self.changed.notified();
+
+            let wait = {
+                let mut bucket = self.lock();
+
+                if bucket.rate <= 0.0 {
+                    return;
+                }
+
+                bucket.refill();
+
+                if bucket.tokens >= 0.0 {
+                    bucket.tokens -= tokens;
+
+                    return;
+                }
+
+                Duration::from_secs_f64(-bucket.tokens / bucket.rate)
+            };
+
+            trace!(?wait, "rate limited");
+
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = changed => {
+                    debug!("rate limit changed");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn should_allow_burst() {
+        // One token per second, with one saved token
+        let limiter = RateLimiter::new(1.0, 1.0);
+
+        let start = Instant::now();
+
+        // The saved token and the debt are available immediately
+        limiter.acquire(1.0).await;
+        limiter.acquire(1.0).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // Then one per second
+        limiter.acquire(1.0).await;
+        assert!(start.elapsed() >= Duration::from_secs(1));
+
+        limiter.acquire(1.0).await;
+        assert!(start.elapsed() >= Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn default_is_unlimited() {
+        let limiter = RateLimiter::default();
+
+        let start = Instant::now();
+
+        limiter.acquire(1_000_000.0).await;
+        limiter.acquire(1_000_000.0).await;
+
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,137 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Information on the cellular modems of the device.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use serde::Deserialize;
+use tracing::{error, warn};
+
+pub(crate) mod modem_manager;
+pub(crate) mod modems_service;
+
+/// Configuration for the cellular modems.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CellularConfig {
+    /// Service used to read the modems information.
+    #[serde(default)]
+    pub backend: CellularBackend,
+}
+
+/// Service used to read the modems information.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CellularBackend {
+    /// The `io.edgehog.CellularModems1` service on the session bus.
+    #[default]
+    CellularModems,
+    /// ModemManager on the system bus, falls back to the cellular modems service on error.
+    ModemManager,
+}
+
+impl Display for CellularBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellularBackend::CellularModems => write!(f, "cellular-modems"),
+            CellularBackend::ModemManager => write!(f, "modem-manager"),
+        }
+    }
+}
+
+/// Information on a modem.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ModemInfo {
+    pub(crate) apn: String,
+    pub(crate) imei: String,
+    pub(crate) imsi: String,
+    pub(crate) operator: Option<String>,
+    pub(crate) access_technology: Option<String>,
+    pub(crate) registration_state: Option<String>,
+}
+
+impl ModemInfo {
+    fn is_empty(&self) -> bool {
+        self.apn.is_empty() && self.imei.is_empty() && self.imsi.is_empty()
+    }
+}
+
+/// Reads the information of the modems with the configured backend.
+pub(crate) async fn read_modems(config: &CellularConfig) -> HashMap<String, ModemInfo> {
+    if config.backend == CellularBackend::ModemManager {
+        match modem_manager::ModemManager::connect().await {
+            Ok(modem_manager) => match modem_manager.modems_info().await {
+                Ok(modems) if !modems.is_empty() => return modems,
+                Ok(_) => {
+                    warn!("no modem found with ModemManager, using the cellular modems service");
+                }
+                Err(err) => {
+                    error!(
+                        error = format!("{err:#}"),
+                        "couldn't read the modems from ModemManager, using the cellular modems service"
+                    );
+                }
+            },
+            Err(err) => {
+                error!(
+                    error = format!("{err:#}"),
+                    "couldn't connect to ModemManager, using the cellular modems service"
+                );
+            }
+        }
+    }
+
+    match modems_service::modems_info().await {
+        Ok(modems) => modems,
+        Err(err) => {
+            error!("{err}");
+
+            HashMap::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_deserialize() {
+        let config: CellularConfig = toml::from_str(r#"backend = "modem-manager""#).unwrap();
+
+        assert_eq!(
+            config,
+            CellularConfig {
+                backend: CellularBackend::ModemManager
+            }
+        );
+
+        let config: CellularConfig = toml::from_str("").unwrap();
+
+        assert_eq!(
+            config,
+            CellularConfig {
+                backend: CellularBackend::CellularModems
+            }
+        );
+    }
+}
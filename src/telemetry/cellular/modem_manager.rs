@@ -0,0 +1,507 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Modems information from ModemManager.
+
+use std::collections::HashMap;
+
+use eyre::WrapErr;
+use tracing::{debug, warn};
+use zbus::proxy;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+use super::ModemInfo;
+
+const SERVICE: &str = "org.freedesktop.ModemManager1";
+const MANAGER_PATH: &str = "/org/freedesktop/ModemManager1";
+const MODEM_INTERFACE: &str = "org.freedesktop.ModemManager1.Modem";
+
+#[proxy(
+    interface = "org.freedesktop.ModemManager1.Modem",
+    default_service = "org.freedesktop.ModemManager1"
+)]
+trait Modem {
+    /// The path of the primary active SIM.
+    #[zbus(property)]
+    fn sim(&self) -> zbus::Result<OwnedObjectPath>;
+
+    /// The paths of the bearers of the modem.
+    #[zbus(property)]
+    fn bearers(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+
+    /// The identity of the device, the IMEI for GSM/UMTS modems.
+    #[zbus(property)]
+    fn equipment_identifier(&self) -> zbus::Result<String>;
+
+    /// A best-effort device identifier based on various device information.
+    #[zbus(property)]
+    fn device_identifier(&self) -> zbus::Result<String>;
+
+    /// Bitmask of the current access technologies used by the device.
+    #[zbus(property)]
+    fn access_technologies(&self) -> zbus::Result<u32>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.ModemManager1.Modem.Modem3gpp",
+    default_service = "org.freedesktop.ModemManager1"
+)]
+trait Modem3gpp {
+    /// The IMEI of the device.
+    #[zbus(property)]
+    fn imei(&self) -> zbus::Result<String>;
+
+    /// The mobile registration status.
+    #[zbus(property)]
+    fn registration_state(&self) -> zbus::Result<u32>;
+
+    /// Name of the operator to which the mobile is registered.
+    #[zbus(property)]
+    fn operator_name(&self) -> zbus::Result<String>;
+
+    /// The settings of the initial EPS bearer.
+    #[zbus(property)]
+    fn initial_eps_bearer_settings(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.ModemManager1.Sim",
+    default_service = "org.freedesktop.ModemManager1"
+)]
+trait Sim {
+    /// The IMSI of the SIM card.
+    #[zbus(property)]
+    fn imsi(&self) -> zbus::Result<String>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.ModemManager1.Bearer",
+    default_service = "org.freedesktop.ModemManager1"
+)]
+trait Bearer {
+    /// Whether the bearer is connected.
+    #[zbus(property)]
+    fn connected(&self) -> zbus::Result<bool>;
+
+    /// The properties requested when the bearer was created.
+    #[zbus(property)]
+    fn properties(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.ModemManager1.Modem.Signal",
+    default_service = "org.freedesktop.ModemManager1"
+)]
+trait Signal {
+    /// Setup the extended signal quality retrieval, the rate is in seconds.
+    fn setup(&self, rate: u32) -> zbus::Result<()>;
+
+    /// Refresh rate for the extended signal quality information updates, in seconds.
+    #[zbus(property)]
+    fn rate(&self) -> zbus::Result<u32>;
+
+    /// The GSM signal quality.
+    #[zbus(property)]
+    fn gsm(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+
+    /// The UMTS signal quality.
+    #[zbus(property)]
+    fn umts(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+
+    /// The LTE signal quality.
+    #[zbus(property)]
+    fn lte(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+
+    /// The 5G signal quality.
+    #[zbus(property)]
+    fn nr5g(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+}
+
+/// Signal quality of a modem.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ModemSignal {
+    pub(crate) rssi: Option<f64>,
+    pub(crate) rsrp: Option<f64>,
+    pub(crate) rsrq: Option<f64>,
+    pub(crate) sinr: Option<f64>,
+}
+
+impl ModemSignal {
+    /// Reads the values from the signal dictionary of a specific technology.
+    fn from_dict(dict: &HashMap<String, OwnedValue>) -> Self {
+        let get = |key: &str| {
+            dict.get(key)
+                .and_then(|value| value.downcast_ref::<f64>().ok())
+        };
+
+        Self {
+            rssi: get("rssi"),
+            rsrp: get("rsrp"),
+            rsrq: get("rsrq"),
+            // LTE reports the SNR, while 5G the SINR
+            sinr: get("sinr").or_else(|| get("snr")),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.rssi.is_none() && self.rsrp.is_none() && self.rsrq.is_none() && self.sinr.is_none()
+    }
+}
+
+/// Returns the name of the most advanced access technology in the bitmask.
+pub(crate) fn access_technology(mask: u32) -> Option<&'static str> {
+    // Ordered from the most to the least advanced
+    const TECHNOLOGIES: &[(u32, &str)] = &[
+        (1 << 15, "5GNR"),
+        (1 << 17, "NB-IoT"),
+        (1 << 16, "LTE-M"),
+        (1 << 14, "LTE"),
+        (1 << 9, "HSPA+"),
+        (1 << 8, "HSPA"),
+        (1 << 7, "HSUPA"),
+        (1 << 6, "HSDPA"),
+        (1 << 5, "UMTS"),
+        (1 << 4, "EDGE"),
+        (1 << 3, "GPRS"),
+        (1 << 2, "GSMCompact"),
+        (1 << 1, "GSM"),
+    ];
+
+    TECHNOLOGIES
+        .iter()
+        .find_map(|(bit, name)| (mask & bit != 0).then_some(*name))
+}
+
+/// Returns the registration status string of the 3GPP registration state.
+pub(crate) fn registration_state(state: u32) -> &'static str {
+    match state {
+        0 | 8 => "NotRegistered",
+        1 | 6 | 9 => "Registered",
+        2 => "SearchingOperator",
+        3 => "RegistrationDenied",
+        5 | 7 | 10 => "RegisteredRoaming",
+        _ => "Unknown",
+    }
+}
+
+/// Connection to ModemManager on the system bus.
+#[derive(Debug, Clone)]
+pub(crate) struct ModemManager {
+    connection: zbus::Connection,
+}
+
+impl ModemManager {
+    pub(crate) async fn connect() -> eyre::Result<Self> {
+        let connection = zbus::Connection::system()
+            .await
+            .wrap_err("couldn't connect to the system bus")?;
+
+        Ok(Self { connection })
+    }
+
+    /// Returns the paths of the modems.
+    pub(crate) async fn modems(&self) -> eyre::Result<Vec<OwnedObjectPath>> {
+        let manager = zbus::fdo::ObjectManagerProxy::builder(&self.connection)
+            .destination(SERVICE)?
+            .path(MANAGER_PATH)?
+            .build()
+            .await?;
+
+        let objects = manager
+            .get_managed_objects()
+            .await
+            .wrap_err("couldn't list the modems")?;
+
+        let modems = objects
+            .into_iter()
+            .filter(|(_, interfaces)| {
+                interfaces
+                    .keys()
+                    .any(|interface| interface.as_str() == MODEM_INTERFACE)
+            })
+            .map(|(path, _)| path)
+            .collect();
+
+        Ok(modems)
+    }
+
+    /// Returns the identifier of the modem used as the Astarte path.
+    pub(crate) async fn modem_id(&self, modem: &OwnedObjectPath) -> eyre::Result<String> {
+        let proxy = ModemProxy::builder(&self.connection)
+            .path(modem)?
+            .build()
+            .await?;
+
+        proxy
+            .device_identifier()
+            .await
+            .wrap_err("couldn't get the device identifier")
+    }
+
+    pub(crate) async fn modems_info(&self) -> eyre::Result<HashMap<String, ModemInfo>> {
+        let mut modems = HashMap::new();
+
+        for path in self.modems().await? {
+            let id = match self.modem_id(&path).await {
+                Ok(id) => id,
+                Err(err) => {
+                    warn!(%path, error = format!("{err:#}"), "couldn't get the modem id");
+
+                    continue;
+                }
+            };
+
+            let info = self
+                .modem_info(&path)
+                .await
+                .wrap_err_with(|| format!("couldn't read modem {path}"))?;
+
+            modems.insert(id, info);
+        }
+
+        Ok(modems)
+    }
+
+    async fn modem_info(&self, path: &OwnedObjectPath) -> eyre::Result<ModemInfo> {
+        let modem = ModemProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+        let modem_3gpp = Modem3gppProxy::builder(&self.connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        let imei = match modem_3gpp.imei().await {
+            Ok(imei) => imei,
+            Err(err) => {
+                debug!(error = %err, "couldn't get the 3GPP IMEI, using the equipment identifier");
+
+                modem.equipment_identifier().await?
+            }
+        };
+
+        let imsi = self.imsi(&modem).await.unwrap_or_else(|err| {
+            debug!(error = format!("{err:#}"), "couldn't get the IMSI");
+
+            String::new()
+        });
+
+        let apn = self.apn(&modem, &modem_3gpp).await.unwrap_or_default();
+
+        let operator = modem_3gpp
+            .operator_name()
+            .await
+            .ok()
+            .filter(|name| !name.is_empty());
+        let access_technology = modem
+            .access_technologies()
+            .await
+            .ok()
+            .and_then(access_technology)
+            .map(str::to_string);
+        let registration_state = modem_3gpp
+            .registration_state()
+            .await
+            .ok()
+            .map(|state| registration_state(state).to_string());
+
+        Ok(ModemInfo {
+            apn,
+            imei,
+            imsi,
+            operator,
+            access_technology,
+            registration_state,
+        })
+    }
+
+    async fn imsi(&self, modem: &ModemProxy<'_>) -> eyre::Result<String> {
+        let sim_path = modem.sim().await?;
+
+        // No SIM inserted
+        if sim_path.as_str() == "/" {
+            return Ok(String::new());
+        }
+
+        let sim = SimProxy::builder(&self.connection)
+            .path(sim_path)?
+            .build()
+            .await?;
+
+        sim.imsi().await.map_err(eyre::Error::from)
+    }
+
+    /// Returns the APN of the connected bearer, or of the initial EPS bearer.
+    async fn apn(&self, modem: &ModemProxy<'_>, modem_3gpp: &Modem3gppProxy<'_>) -> Option<String> {
+        let bearers = modem.bearers().await.unwrap_or_default();
+
+        let mut apn = None;
+
+        for path in bearers {
+            let Ok(builder) = BearerProxy::builder(&self.connection).path(path) else {
+                continue;
+            };
+            let Ok(bearer) = builder.build().await else {
+                continue;
+            };
+
+            let Some(bearer_apn) = bearer
+                .properties()
+                .await
+                .ok()
+                .and_then(|props| apn_of(&props))
+            else {
+                continue;
+            };
+
+            if bearer.connected().await.unwrap_or_default() {
+                return Some(bearer_apn);
+            }
+
+            apn.get_or_insert(bearer_apn);
+        }
+
+        if apn.is_some() {
+            return apn;
+        }
+
+        modem_3gpp
+            .initial_eps_bearer_settings()
+            .await
+            .ok()
+            .and_then(|props| apn_of(&props))
+    }
+
+    /// Returns the signal quality of the modem, setting up the polling with the given rate.
+    pub(crate) async fn signal(
+        &self,
+        modem: &OwnedObjectPath,
+        rate: u32,
+    ) -> eyre::Result<Option<ModemSignal>> {
+        let signal = SignalProxy::builder(&self.connection)
+            .path(modem)?
+            .build()
+            .await?;
+
+        if signal.rate().await? != rate {
+            debug!(%modem, rate, "setting up the signal refresh rate");
+
+            signal
+                .setup(rate)
+                .await
+                .wrap_err("couldn't setup the signal refresh rate")?;
+        }
+
+        // Ordered from the most to the least advanced
+        let technologies = [
+            signal.nr5g().await,
+            signal.lte().await,
+            signal.umts().await,
+            signal.gsm().await,
+        ];
+
+        let signal = technologies
+            .iter()
+            .filter_map(|dict| dict.as_ref().ok())
+            .map(ModemSignal::from_dict)
+            .find(|signal| !signal.is_empty());
+
+        Ok(signal)
+    }
+}
+
+fn apn_of(properties: &HashMap<String, OwnedValue>) -> Option<String> {
+    properties
+        .get("apn")
+        .and_then(|apn| apn.downcast_ref::<String>().ok())
+        .filter(|apn| !apn.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_get_access_technology() {
+        let cases = [
+            (0, None),
+            (1 << 1, Some("GSM")),
+            ((1 << 5) | (1 << 6), Some("HSDPA")),
+            ((1 << 14) | (1 << 1), Some("LTE")),
+            ((1 << 15) | (1 << 14), Some("5GNR")),
+        ];
+
+        for (mask, exp) in cases {
+            assert_eq!(access_technology(mask), exp, "mask {mask:#b}");
+        }
+    }
+
+    #[test]
+    fn should_get_registration_state() {
+        assert_eq!(registration_state(1), "Registered");
+        assert_eq!(registration_state(5), "RegisteredRoaming");
+        assert_eq!(registration_state(2), "SearchingOperator");
+        assert_eq!(registration_state(3), "RegistrationDenied");
+        assert_eq!(registration_state(0), "NotRegistered");
+        assert_eq!(registration_state(42), "Unknown");
+    }
+
+    #[test]
+    fn should_read_signal_dict() {
+        let lte = HashMap::from([
+            ("rssi".to_string(), OwnedValue::from(-65.0)),
+            ("rsrp".to_string(), OwnedValue::from(-95.0)),
+            ("rsrq".to_string(), OwnedValue::from(-10.0)),
+            ("snr".to_string(), OwnedValue::from(12.5)),
+            ("error-rate".to_string(), OwnedValue::from(0.0)),
+        ]);
+
+        let signal = ModemSignal::from_dict(&lte);
+
+        let exp = ModemSignal {
+            rssi: Some(-65.0),
+            rsrp: Some(-95.0),
+            rsrq: Some(-10.0),
+            sinr: Some(12.5),
+        };
+
+        assert_eq!(signal, exp);
+
+        assert!(ModemSignal::from_dict(&HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn should_get_apn() {
+        let props = HashMap::from([(
+            "apn".to_string(),
+            OwnedValue::from(zbus::zvariant::Str::from("internet")),
+        )]);
+
+        assert_eq!(apn_of(&props).as_deref(), Some("internet"));
+
+        let props = HashMap::from([(
+            "apn".to_string(),
+            OwnedValue::from(zbus::zvariant::Str::from("")),
+        )]);
+
+        assert_eq!(apn_of(&props), None);
+    }
+}
@@ -0,0 +1,95 @@
+// This file is part of Edgehog.
+//
+// Copyright 2024 - 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Modems information from the `io.edgehog.CellularModems1` service.
+
+use std::collections::HashMap;
+
+use eyre::WrapErr;
+use futures::StreamExt;
+use tracing::{debug, error};
+use zbus::proxy;
+use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
+
+use super::ModemInfo;
+
+#[derive(Debug, Clone, DeserializeDict, SerializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct ModemProperties {
+    apn: String,
+    imei: String,
+    imsi: String,
+}
+
+impl From<ModemProperties> for ModemInfo {
+    fn from(value: ModemProperties) -> Self {
+        Self {
+            apn: value.apn,
+            imei: value.imei,
+            imsi: value.imsi,
+            ..Default::default()
+        }
+    }
+}
+
+#[proxy(
+    interface = "io.edgehog.CellularModems1",
+    default_service = "io.edgehog.CellularModems",
+    default_path = "/io/edgehog/CellularModems"
+)]
+trait CellularModems {
+    fn list(&self) -> zbus::Result<Vec<String>>;
+    fn get(&self, id: String) -> zbus::Result<ModemProperties>;
+}
+
+pub(crate) async fn modems_info() -> eyre::Result<HashMap<String, ModemInfo>> {
+    let connection = zbus::Connection::session().await?;
+    let proxy = CellularModemsProxy::new(&connection).await?;
+
+    let modems = proxy.list().await?;
+
+    let properties = futures::stream::iter(modems)
+        .then(|id| async {
+            proxy
+                .get(id.clone())
+                .await
+                .wrap_err_with(|| format!("couldn't get modem {id}"))
+                .map(|modem| (id, ModemInfo::from(modem)))
+        })
+        .filter_map(|res| async {
+            let (id, modem) = match res {
+                Ok(id_modem) => id_modem,
+                Err(err) => {
+                    error!("{err}");
+
+                    return None;
+                }
+            };
+
+            if modem.is_empty() {
+                debug!("modem {id} fields are all empty");
+                None
+            } else {
+                Some((id, modem))
+            }
+        })
+        .collect()
+        .await;
+
+    Ok(properties)
+}
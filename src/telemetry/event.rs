@@ -25,6 +25,29 @@ use astarte_device_sdk::{
 };
 use tracing::warn;
 
+use crate::power_policy::PowerProfile;
+
+/// Message handled by the telemetry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelemetryMsg {
+    Config(TelemetryEvent),
+    Refresh(TelemetryRefresh),
+    /// The power profile changed, the tasks are restarted with the scaled periods
+    Power(PowerProfile),
+}
+
+impl From<TelemetryEvent> for TelemetryMsg {
+    fn from(value: TelemetryEvent) -> Self {
+        TelemetryMsg::Config(value)
+    }
+}
+
+impl From<TelemetryRefresh> for TelemetryMsg {
+    fn from(value: TelemetryRefresh) -> Self {
+        TelemetryMsg::Refresh(value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TelemetryEvent {
     pub interface: String,
@@ -73,6 +96,42 @@ impl TelemetryConfig {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TelemetryPeriod(pub Duration);
 
+/// Request to collect and send the telemetry immediately, without changing the period.
+#[derive(Debug, Clone, FromEvent, PartialEq, Eq)]
+#[from_event(
+    interface = "io.edgehog.devicemanager.TelemetryRefresh",
+    aggregation = "individual"
+)]
+pub enum TelemetryRefresh {
+    #[mapping(endpoint = "/request")]
+    Request(RefreshTarget),
+}
+
+/// Telemetry to refresh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefreshTarget {
+    /// The status properties sent on startup, like the OS and hardware info.
+    Status,
+    /// Name of a periodic telemetry interface.
+    Interface(String),
+}
+
+impl TryFrom<AstarteData> for RefreshTarget {
+    type Error = TypeError;
+
+    fn try_from(value: AstarteData) -> Result<Self, Self::Error> {
+        let value = String::try_from(value)?;
+
+        match value.as_str() {
+            "status" => Ok(RefreshTarget::Status),
+            "" => Err(TypeError::Conversion {
+                ctx: "empty telemetry refresh request".to_string(),
+            }),
+            _ => Ok(RefreshTarget::Interface(value)),
+        }
+    }
+}
+
 impl TryFrom<AstarteData> for TelemetryPeriod {
     type Error = TypeError;
 
@@ -91,6 +150,7 @@ impl TryFrom<AstarteData> for TelemetryPeriod {
 #[cfg(test)]
 mod tests {
     use astarte_device_sdk::Value;
+    use astarte_device_sdk::chrono::Utc;
 
     use crate::controller::event::RuntimeEvent;
 
@@ -130,4 +190,41 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn should_convert_refresh_from_event() {
+        let event = DeviceEvent {
+            interface: "io.edgehog.devicemanager.TelemetryRefresh".to_string(),
+            path: "/request".to_string(),
+            data: Value::Individual {
+                data: "io.edgehog.devicemanager.SystemStatus".into(),
+                timestamp: Utc::now(),
+            },
+        };
+
+        let res = RuntimeEvent::from_event(event).unwrap();
+
+        assert_eq!(
+            res,
+            RuntimeEvent::TelemetryRefresh(TelemetryRefresh::Request(RefreshTarget::Interface(
+                "io.edgehog.devicemanager.SystemStatus".to_string()
+            )))
+        );
+
+        let event = DeviceEvent {
+            interface: "io.edgehog.devicemanager.TelemetryRefresh".to_string(),
+            path: "/request".to_string(),
+            data: Value::Individual {
+                data: "status".into(),
+                timestamp: Utc::now(),
+            },
+        };
+
+        let res = RuntimeEvent::from_event(event).unwrap();
+
+        assert_eq!(
+            res,
+            RuntimeEvent::TelemetryRefresh(TelemetryRefresh::Request(RefreshTarget::Status))
+        );
+    }
 }
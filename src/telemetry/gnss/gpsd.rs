@@ -0,0 +1,309 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Client for the JSON protocol of gpsd.
+//!
+//! See <https://gpsd.gitlab.io/gpsd/gpsd_json.html>.
+
+use eyre::WrapErr;
+use serde::Deserialize;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tracing::{debug, trace};
+
+use super::{Fix, FixMode, GnssConfig, GpsdAddress};
+
+/// Enables the streaming of the reports in JSON.
+const WATCH: &[u8] = b"?WATCH={\"enable\":true,\"json\":true};\n";
+
+/// Report sent by gpsd, only the time-position-velocity one is used.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "class")]
+enum Report {
+    #[serde(rename = "TPV")]
+    Tpv(Tpv),
+    #[serde(other)]
+    Other,
+}
+
+/// Time-position-velocity report.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Tpv {
+    /// 0 unknown, 1 no fix, 2 2D fix, 3 3D fix.
+    #[serde(default)]
+    mode: u8,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    /// Altitude over the WGS84 ellipsoid.
+    #[serde(rename = "altHAE")]
+    alt_hae: Option<f64>,
+    /// Deprecated altitude, sent by older gpsd versions.
+    alt: Option<f64>,
+    speed: Option<f64>,
+    track: Option<f64>,
+    eph: Option<f64>,
+    epv: Option<f64>,
+}
+
+impl Tpv {
+    /// Returns the fix, or [`None`] if there is no fix.
+    fn into_fix(self) -> Option<Fix> {
+        let mode = match self.mode {
+            2 => FixMode::Fix2d,
+            3 => FixMode::Fix3d,
+            _ => return None,
+        };
+
+        let (Some(latitude), Some(longitude)) = (self.lat, self.lon) else {
+            return None;
+        };
+
+        let altitude = match mode {
+            FixMode::Fix2d => None,
+            FixMode::Fix3d => self.alt_hae.or(self.alt),
+        };
+
+        Some(Fix {
+            mode,
+            latitude,
+            longitude,
+            altitude,
+            speed: self.speed,
+            heading: self.track,
+            horizontal_accuracy: self.eph,
+            vertical_accuracy: self.epv,
+        })
+    }
+}
+
+/// Connects to gpsd and reads the current fix.
+///
+/// Returns [`None`] if the receiver has no fix.
+pub(crate) async fn read_fix(config: &GnssConfig) -> eyre::Result<Option<Fix>> {
+    let read = async {
+        match &config.gpsd {
+            GpsdAddress::Tcp(addr) => {
+                let stream = tokio::net::TcpStream::connect(addr)
+                    .await
+                    .wrap_err_with(|| format!("couldn't connect to gpsd at {addr}"))?;
+
+                next_tpv(stream).await
+            }
+            #[cfg(unix)]
+            GpsdAddress::Unix(path) => {
+                let stream = tokio::net::UnixStream::connect(path)
+                    .await
+                    .wrap_err_with(|| format!("couldn't connect to gpsd at {}", path.display()))?;
+
+                next_tpv(stream).await
+            }
+        }
+    };
+
+    let tpv = tokio::time::timeout(config.timeout(), read)
+        .await
+        .wrap_err("timed out waiting for gpsd")??;
+
+    Ok(tpv.into_fix())
+}
+
+/// Enables the watch mode and waits for the next TPV report.
+async fn next_tpv<S>(stream: S) -> eyre::Result<Tpv>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = BufReader::new(stream);
+
+    stream
+        .write_all(WATCH)
+        .await
+        .wrap_err("couldn't enable the gpsd watch")?;
+
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        let read = stream
+            .read_line(&mut line)
+            .await
+            .wrap_err("couldn't read from gpsd")?;
+
+        eyre::ensure!(read > 0, "gpsd closed the connection");
+
+        trace!(line = line.trim(), "gpsd report");
+
+        match serde_json::from_str::<Report>(&line) {
+            Ok(Report::Tpv(tpv)) => return Ok(tpv),
+            Ok(Report::Other) => {}
+            Err(err) => {
+                debug!(error = %err, "couldn't parse the gpsd report");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use pretty_assertions::assert_eq;
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+
+    pub(crate) const TPV_3D: &str = r#"{"class":"TPV","device":"/dev/ttyACM0","mode":3,"time":"2026-10-18T10:00:00.000Z","ept":0.005,"lat":45.0703,"lon":7.6869,"altHAE":283.5,"altMSL":239.2,"alt":239.2,"epx":3.2,"epy":4.1,"epv":7.5,"track":92.3,"speed":12.4,"climb":0.1,"eph":5.2}"#;
+    pub(crate) const TPV_NO_FIX: &str = r#"{"class":"TPV","device":"/dev/ttyACM0","mode":1}"#;
+
+    const VERSION: &str =
+        r#"{"class":"VERSION","release":"3.25","rev":"3.25","proto_major":3,"proto_minor":15}"#;
+    const DEVICES: &str = r#"{"class":"DEVICES","devices":[{"class":"DEVICE","path":"/dev/ttyACM0","driver":"u-blox"}]}"#;
+
+    /// Fake gpsd that replies with the given reports after the watch command.
+    pub(crate) async fn fake_gpsd<S>(mut stream: S, tpv: &str)
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        stream
+            .write_all(format!("{VERSION}\n").as_bytes())
+            .await
+            .unwrap();
+
+        let mut buf = vec![0; WATCH.len()];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, WATCH);
+
+        stream
+            .write_all(format!("{DEVICES}\n{tpv}\n").as_bytes())
+            .await
+            .unwrap();
+    }
+
+    /// Spawns a fake gpsd on a local TCP socket.
+    pub(crate) async fn tcp_gpsd(tpv: &'static str) -> GnssConfig {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+
+            fake_gpsd(stream, tpv).await;
+        });
+
+        GnssConfig {
+            gpsd: GpsdAddress::Tcp(addr.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_convert_3d_fix() {
+        let Report::Tpv(tpv) = serde_json::from_str(TPV_3D).unwrap() else {
+            panic!("not a TPV report");
+        };
+
+        let exp = Fix {
+            mode: FixMode::Fix3d,
+            latitude: 45.0703,
+            longitude: 7.6869,
+            altitude: Some(283.5),
+            speed: Some(12.4),
+            heading: Some(92.3),
+            horizontal_accuracy: Some(5.2),
+            vertical_accuracy: Some(7.5),
+        };
+
+        assert_eq!(tpv.into_fix(), Some(exp));
+    }
+
+    #[test]
+    fn should_convert_2d_fix() {
+        let tpv = Tpv {
+            mode: 2,
+            lat: Some(45.0),
+            lon: Some(7.0),
+            alt: Some(200.0),
+            ..Default::default()
+        };
+
+        let fix = tpv.into_fix().unwrap();
+
+        assert_eq!(fix.mode, FixMode::Fix2d);
+        assert_eq!(fix.altitude, None);
+    }
+
+    #[test]
+    fn no_fix() {
+        let Report::Tpv(tpv) = serde_json::from_str(TPV_NO_FIX).unwrap() else {
+            panic!("not a TPV report");
+        };
+
+        assert_eq!(tpv.into_fix(), None);
+    }
+
+    #[tokio::test]
+    async fn should_read_fix_tcp() {
+        let config = tcp_gpsd(TPV_3D).await;
+
+        let fix = read_fix(&config).await.unwrap().unwrap();
+
+        assert_eq!(fix.mode, FixMode::Fix3d);
+        assert_eq!(fix.latitude, 45.0703);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn should_read_fix_unix() {
+        let dir = tempdir::TempDir::new("edgehog-gpsd").unwrap();
+        let path = dir.path().join("gpsd.sock");
+
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+
+            fake_gpsd(stream, TPV_NO_FIX).await;
+        });
+
+        let config = GnssConfig {
+            gpsd: GpsdAddress::Unix(path),
+            ..Default::default()
+        };
+
+        let fix = read_fix(&config).await.unwrap();
+
+        assert_eq!(fix, None);
+    }
+
+    #[tokio::test]
+    async fn closed_connection() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+
+            drop(stream);
+        });
+
+        let config = GnssConfig {
+            gpsd: GpsdAddress::Tcp(addr.to_string()),
+            ..Default::default()
+        };
+
+        assert!(read_fix(&config).await.is_err());
+    }
+}
@@ -0,0 +1,144 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Position of the device from the GNSS receivers.
+
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+pub(crate) mod gpsd;
+
+/// Configuration for the GNSS position.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct GnssConfig {
+    /// Socket of the gpsd daemon.
+    #[serde(default)]
+    pub gpsd: GpsdAddress,
+    /// Seconds to wait for a report from gpsd.
+    #[serde(default = "GnssConfig::default_timeout")]
+    pub timeout: u64,
+}
+
+impl GnssConfig {
+    const fn default_timeout() -> u64 {
+        5
+    }
+
+    pub(crate) fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+}
+
+impl Default for GnssConfig {
+    fn default() -> Self {
+        Self {
+            gpsd: GpsdAddress::default(),
+            timeout: Self::default_timeout(),
+        }
+    }
+}
+
+/// Socket of the gpsd daemon.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GpsdAddress {
+    /// TCP address, as `host:port`.
+    Tcp(String),
+    /// Path of the Unix socket.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl Default for GpsdAddress {
+    fn default() -> Self {
+        GpsdAddress::Tcp("localhost:2947".to_string())
+    }
+}
+
+/// Mode of the fix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FixMode {
+    Fix2d,
+    Fix3d,
+}
+
+impl std::fmt::Display for FixMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FixMode::Fix2d => write!(f, "2D"),
+            FixMode::Fix3d => write!(f, "3D"),
+        }
+    }
+}
+
+/// Position of a fix.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Fix {
+    pub(crate) mode: FixMode,
+    /// Latitude in degrees.
+    pub(crate) latitude: f64,
+    /// Longitude in degrees.
+    pub(crate) longitude: f64,
+    /// Altitude in meters, only with a 3D fix.
+    pub(crate) altitude: Option<f64>,
+    /// Speed over ground in meters per second.
+    pub(crate) speed: Option<f64>,
+    /// Course over ground in degrees from the true north.
+    pub(crate) heading: Option<f64>,
+    /// Estimated horizontal error in meters.
+    pub(crate) horizontal_accuracy: Option<f64>,
+    /// Estimated vertical error in meters.
+    pub(crate) vertical_accuracy: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_deserialize() {
+        let string = r#"
+        gpsd = { tcp = "192.168.1.10:2947" }
+        timeout = 2
+        "#;
+
+        let config: GnssConfig = toml::from_str(string).unwrap();
+
+        let exp = GnssConfig {
+            gpsd: GpsdAddress::Tcp("192.168.1.10:2947".to_string()),
+            timeout: 2,
+        };
+
+        assert_eq!(config, exp);
+        assert_eq!(config.timeout(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn should_deserialize_default() {
+        let config: GnssConfig = toml::from_str("").unwrap();
+
+        assert_eq!(config, GnssConfig::default());
+        assert_eq!(config.gpsd, GpsdAddress::Tcp("localhost:2947".to_string()));
+    }
+}
@@ -19,31 +19,50 @@
 use std::str::FromStr;
 use std::{borrow::Cow, collections::HashMap, ops::Deref, path::PathBuf};
 
-use event::{TelemetryConfig, TelemetryEvent};
+use event::{RefreshTarget, TelemetryConfig, TelemetryEvent, TelemetryMsg, TelemetryRefresh};
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 use tokio::time::Duration;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error};
+use tracing::{debug, error, info};
 
 use crate::Client;
+use crate::power_policy::PowerState;
 
 use crate::{
     controller::actor::Actor,
     repository::{StateRepository, file_state_repository::FileStateRepository},
 };
 
+use self::schedule::Scheduler;
 use self::sender::Task;
 use self::stats::TelemetryInterface;
 
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub mod cellular;
 pub mod event;
+pub mod gnss;
+pub mod schedule;
 mod sender;
-mod stats;
+pub(crate) mod stats;
 pub mod status;
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub mod time_sync;
 
 const TELEMETRY_PATH: &str = "telemetry.json";
 
 const DEFAULT_PERIOD: Duration = Duration::from_secs(60);
 
+/// Configuration of the sources read by the telemetry.
+#[derive(Debug, Clone, Default)]
+pub struct SourcesConfig {
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub cellular: self::cellular::CellularConfig,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    pub time_sync: self::time_sync::TimeSyncConfig,
+    pub gnss: self::gnss::GnssConfig,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelemetryInterfaceConfig<'a> {
     pub interface_name: Cow<'a, str>,
@@ -174,6 +193,8 @@ pub struct Telemetry<C> {
     configs: HashMap<TelemetryInterface, TaskConfig>,
     tasks: TelemetryTasks,
     file_state: FileStateRepository<Vec<TelemetryInterfaceConfig<'static>>>,
+    power: PowerState,
+    sources: SourcesConfig,
     #[cfg(feature = "containers")]
     containers: std::sync::Arc<tokio::sync::OnceCell<edgehog_containers::local::ContainerHandle>>,
 }
@@ -183,6 +204,9 @@ impl<C> Telemetry<C> {
         client: C,
         configs: &[TelemetryInterfaceConfig<'_>],
         store_directory: PathBuf,
+        power: PowerState,
+        sources: SourcesConfig,
+        schedule: self::schedule::ScheduleConfig,
         #[cfg(feature = "containers")] containers: std::sync::Arc<
             tokio::sync::OnceCell<edgehog_containers::local::ContainerHandle>,
         >,
@@ -206,8 +230,10 @@ impl<C> Telemetry<C> {
         let mut telemetry = Telemetry {
             client,
             configs,
-            tasks: TelemetryTasks::new(),
+            tasks: TelemetryTasks::new(Scheduler::with_device_seed(schedule).await),
             file_state: FileStateRepository::new(&store_directory, TELEMETRY_PATH),
+            power,
+            sources,
             #[cfg(feature = "containers")]
             containers,
         };
@@ -264,10 +290,30 @@ impl<C> Telemetry<C> {
     where
         C: Client + Send + Sync + 'static,
     {
-        self::status::initial_telemetry(&mut self.client).await;
+        self::status::initial_telemetry(
+            &mut self.client,
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            &self.sources.cellular,
+        )
+        .await;
         self::stats::initial_telemetry(&mut self.client).await;
     }
 
+    /// Returns the configuration of the task, with the period scaled by the power profile.
+    ///
+    /// The scaled period is only applied to the running task, it's never saved.
+    fn task_config(&self, config: TaskConfig) -> TaskConfig {
+        let mut config = config;
+
+        let period = self.power.scale_period(*config.period);
+
+        if period != *config.period {
+            config.period.set(period);
+        }
+
+        config
+    }
+
     pub fn run_telemetry(&mut self)
     where
         C: Client + Send + Sync + 'static,
@@ -276,7 +322,9 @@ impl<C> Telemetry<C> {
             self.tasks.spawn_task(
                 &self.client,
                 *interface,
-                *config,
+                self.task_config(*config),
+                &self.power,
+                &self.sources,
                 #[cfg(feature = "containers")]
                 &self.containers,
             );
@@ -310,7 +358,7 @@ impl<C> Actor for Telemetry<C>
 where
     C: Client + Send + Sync + 'static,
 {
-    type Msg = TelemetryEvent;
+    type Msg = TelemetryMsg;
 
     fn task() -> &'static str {
         "telemetry"
@@ -325,6 +373,25 @@ where
     }
 
     async fn handle(&mut self, msg: Self::Msg) -> eyre::Result<()> {
+        match msg {
+            TelemetryMsg::Config(event) => self.configure(event).await,
+            TelemetryMsg::Refresh(TelemetryRefresh::Request(target)) => self.refresh(target).await,
+            TelemetryMsg::Power(profile) => {
+                info!(%profile, "power profile changed, restarting the telemetry tasks");
+
+                self.run_telemetry();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<C> Telemetry<C>
+where
+    C: Client + Send + Sync + 'static,
+{
+    async fn configure(&mut self, msg: TelemetryEvent) {
         let interface = match TelemetryInterface::from_str(&msg.interface) {
             Ok(itf) => itf,
             Err(err) => {
@@ -333,7 +400,7 @@ where
                     "couldn't parse telemetry interface"
                 );
 
-                return Ok(());
+                return;
             }
         };
 
@@ -354,33 +421,103 @@ where
             }
         };
 
+        let config = *config;
+
         // This function will check if we actually need to start the task
         self.tasks.spawn_task(
             &self.client,
             interface,
-            *config,
+            self.task_config(config),
+            &self.power,
+            &self.sources,
             #[cfg(feature = "containers")]
             &self.containers,
         );
 
         self.save_telemetry_config().await;
+    }
 
-        Ok(())
+    /// Sends the requested telemetry immediately, without changing the configured period.
+    async fn refresh(&mut self, target: RefreshTarget) {
+        let interface = match target {
+            RefreshTarget::Status => {
+                info!("refreshing the status telemetry");
+
+                self::status::initial_telemetry(
+                    &mut self.client,
+                    #[cfg(all(feature = "zbus", target_os = "linux"))]
+                    &self.sources.cellular,
+                )
+                .await;
+
+                return;
+            }
+            RefreshTarget::Interface(interface) => interface,
+        };
+
+        let interface = match TelemetryInterface::from_str(&interface) {
+            Ok(itf) => itf,
+            Err(err) => {
+                error!(
+                    error = format!("{:#}", eyre::Report::new(err)),
+                    "couldn't parse telemetry interface to refresh"
+                );
+
+                return;
+            }
+        };
+
+        let period = self
+            .configs
+            .get(&interface)
+            .map_or(DEFAULT_PERIOD, |config| *config.period.get());
+
+        self.tasks.spawn_once(
+            &self.client,
+            interface,
+            period,
+            &self.sources,
+            #[cfg(feature = "containers")]
+            &self.containers,
+        );
     }
 }
 
+/// Forwards the changes of the power profile to the telemetry.
+pub(crate) async fn forward_power_profile(
+    mut power: PowerState,
+    telemetry_tx: mpsc::Sender<TelemetryMsg>,
+    cancel: CancellationToken,
+) -> eyre::Result<()> {
+    while let Some(profile) = cancel.run_until_cancelled(power.changed()).await {
+        if telemetry_tx
+            .send(TelemetryMsg::Power(profile))
+            .await
+            .is_err()
+        {
+            debug!("telemetry disconnected, stop forwarding the power profile");
+
+            break;
+        }
+    }
+
+    Ok(())
+}
+
 /// Handle spawning and cancellation for the telemetry tasks
 #[derive(Debug)]
 struct TelemetryTasks {
     cancellation: CancellationToken,
     tasks: HashMap<TelemetryInterface, CancellationToken>,
+    scheduler: Scheduler,
 }
 
 impl TelemetryTasks {
-    fn new() -> Self {
+    fn new(scheduler: Scheduler) -> Self {
         Self {
             cancellation: CancellationToken::new(),
             tasks: HashMap::new(),
+            scheduler,
         }
     }
 
@@ -389,6 +526,8 @@ impl TelemetryTasks {
         client: &C,
         t_itf: TelemetryInterface,
         task_config: TaskConfig,
+        power: &PowerState,
+        sources: &SourcesConfig,
         #[cfg(feature = "containers")] containers: &std::sync::Arc<
             tokio::sync::OnceCell<edgehog_containers::local::ContainerHandle>,
         >,
@@ -424,17 +563,50 @@ impl TelemetryTasks {
 
         let cancel = self.cancellation.child_token();
 
-        Task::spawn(
+        let schedule = self.scheduler.for_task(t_itf, *period);
+
+        Task::new(
             client.clone(),
-            cancel.clone(),
             t_itf,
+            cancel.clone(),
             *period,
+            power.clone(),
+            schedule,
+        )
+        .spawn(
+            sources,
             #[cfg(feature = "containers")]
             containers,
         );
 
         self.tasks.insert(t_itf, cancel);
     }
+
+    /// Sends the telemetry once, independently from the periodic task.
+    fn spawn_once<C>(
+        &self,
+        client: &C,
+        t_itf: TelemetryInterface,
+        period: Duration,
+        sources: &SourcesConfig,
+        #[cfg(feature = "containers")] containers: &std::sync::Arc<
+            tokio::sync::OnceCell<edgehog_containers::local::ContainerHandle>,
+        >,
+    ) where
+        C: Client + Sync + Send + 'static,
+    {
+        Task::once(
+            client.clone(),
+            t_itf,
+            self.cancellation.child_token(),
+            period,
+        )
+        .spawn(
+            sources,
+            #[cfg(feature = "containers")]
+            containers,
+        );
+    }
 }
 
 #[cfg(test)]
@@ -469,8 +641,10 @@ pub(crate) mod tests {
             Telemetry {
                 client,
                 configs: HashMap::new(),
-                tasks: TelemetryTasks::new(),
+                tasks: TelemetryTasks::new(Scheduler::default()),
                 file_state: FileStateRepository::new(&path, TELEMETRY_PATH),
+                power: PowerState::default(),
+                sources: SourcesConfig::default(),
                 #[cfg(feature = "containers")]
                 containers: std::sync::Arc::default(),
             },
@@ -495,6 +669,9 @@ pub(crate) mod tests {
             client,
             &configs,
             t_dir,
+            PowerState::default(),
+            SourcesConfig::default(),
+            Default::default(),
             #[cfg(feature = "containers")]
             std::sync::Arc::default(),
         )
@@ -523,6 +700,9 @@ pub(crate) mod tests {
             client,
             &configs,
             t_dir.clone(),
+            PowerState::default(),
+            SourcesConfig::default(),
+            Default::default(),
             #[cfg(feature = "containers")]
             std::sync::Arc::default(),
         )
@@ -540,7 +720,7 @@ pub(crate) mod tests {
         ];
 
         for e in events {
-            tel.handle(e).await.unwrap();
+            tel.handle(e.into()).await.unwrap();
         }
 
         let config = tel.configs.get(&TelemetryInterface::SystemStatus).unwrap();
@@ -583,6 +763,9 @@ pub(crate) mod tests {
             client,
             &configs,
             t_dir.clone(),
+            PowerState::default(),
+            SourcesConfig::default(),
+            Default::default(),
             #[cfg(feature = "containers")]
             std::sync::Arc::default(),
         )
@@ -600,7 +783,7 @@ pub(crate) mod tests {
         ];
 
         for e in events {
-            tel.handle(e).await.unwrap();
+            tel.handle(e.into()).await.unwrap();
         }
 
         let config = tel.configs.get(&TelemetryInterface::SystemStatus).unwrap();
@@ -616,6 +799,52 @@ pub(crate) mod tests {
         assert!(saved_config.is_empty());
     }
 
+    #[tokio::test]
+    async fn refresh_telemetry_interface() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+
+        client.expect_clone().once().returning(move || {
+            let tx = tx.clone();
+            let mut client = MockDeviceClient::new();
+
+            client
+                .expect_send_object_with_timestamp()
+                .once()
+                .with(
+                    predicate::eq("io.edgehog.devicemanager.SystemStatus"),
+                    predicate::eq("/systemStatus"),
+                    predicate::always(),
+                    predicate::always(),
+                )
+                .returning(move |_, _, _, _| {
+                    tx.send(()).unwrap();
+
+                    Ok(())
+                });
+
+            client
+        });
+
+        let (mut tel, _dir) = mock_telemetry(client);
+
+        tel.handle(TelemetryMsg::Refresh(TelemetryRefresh::Request(
+            RefreshTarget::Interface("io.edgehog.devicemanager.SystemStatus".to_string()),
+        )))
+        .await
+        .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        // The period is not configured by the refresh
+        assert!(tel.configs.is_empty());
+        assert!(tel.tasks.tasks.is_empty());
+    }
+
     #[tokio::test]
     async fn send_initial_telemetry_success() {
         let client = {
@@ -712,4 +941,37 @@ pub(crate) mod tests {
 
         telemetry.initial_telemetry().await;
     }
+
+    #[test]
+    fn should_scale_task_period() {
+        let client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let (mut telemetry, _dir) = mock_telemetry(client);
+
+        let (_tx, rx) = tokio::sync::watch::channel(crate::power_policy::PowerProfile::LowPower);
+        telemetry.power = PowerState::new(
+            rx,
+            crate::power_policy::PeriodMultipliers {
+                low_power: 3,
+                critical: 10,
+            },
+        );
+
+        let config = TaskConfig {
+            enabled: Overridable::new(true),
+            period: Overridable::new(Duration::from_secs(60)),
+        };
+
+        let scaled = telemetry.task_config(config);
+        assert_eq!(*scaled.period, Duration::from_secs(180));
+        assert_eq!(scaled.period.default, Duration::from_secs(60));
+
+        // Scales the period set from Astarte
+        let config = TaskConfig {
+            enabled: Overridable::new(true),
+            period: Overridable::with_override(DEFAULT_PERIOD, Duration::from_secs(30)),
+        };
+
+        let scaled = telemetry.task_config(config);
+        assert_eq!(*scaled.period, Duration::from_secs(90));
+    }
 }
@@ -0,0 +1,316 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Spreads the periodic telemetry over time.
+//!
+//! When many devices boot at the same time their telemetry tasks would tick in lockstep, so each
+//! task waits a random initial delay and a random jitter before every collection. The random
+//! values are seeded per device, and a rate limiter shared by all the tasks caps the collections.
+
+use std::path::Path;
+use std::time::Duration;
+
+use aws_lc_rs::digest::{Context, SHA256};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use crate::rate_limit::RateLimiter;
+
+use super::stats::TelemetryInterface;
+
+const MACHINE_ID: &str = "/etc/machine-id";
+
+/// Configuration to spread the telemetry over time.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleConfig {
+    /// Maximum seconds to delay the first collection of each task.
+    #[serde(default)]
+    pub initial_delay: u64,
+    /// Maximum seconds of random delay before each collection.
+    ///
+    /// It's capped to half of the task period.
+    #[serde(default)]
+    pub jitter: u64,
+    /// Maximum number of collections per minute for all the tasks, 0 to disable the limit.
+    #[serde(default)]
+    pub rate_limit: u32,
+    /// Number of collections that can exceed the rate limit in a burst.
+    #[serde(default = "ScheduleConfig::default_burst")]
+    pub burst: u32,
+}
+
+impl ScheduleConfig {
+    const fn default_burst() -> u32 {
+        1
+    }
+}
+
+/// Creates the [`Schedule`] for each task.
+#[derive(Debug, Clone)]
+pub(crate) struct Scheduler {
+    config: ScheduleConfig,
+    seed: u64,
+    limiter: Option<RateLimiter>,
+}
+
+impl Scheduler {
+    pub(crate) fn new(config: ScheduleConfig, seed: u64) -> Self {
+        // The limiter serves a request in debt, so it saves one token less than the burst
+        let limiter = (config.rate_limit > 0).then(|| {
+            RateLimiter::new(
+                f64::from(config.rate_limit) / 60.0,
+                f64::from(config.burst.saturating_sub(1)),
+            )
+        });
+
+        Self {
+            config,
+            seed,
+            limiter,
+        }
+    }
+
+    /// Seeds the scheduler with the machine id, to have different delays on each device.
+    pub(crate) async fn with_device_seed(config: ScheduleConfig) -> Self {
+        Self::new(config, device_seed(Path::new(MACHINE_ID)).await)
+    }
+
+    /// Returns the schedule of a periodic task.
+    pub(crate) fn for_task(&self, interface: TelemetryInterface, period: Duration) -> Schedule {
+        let mut ctx = Context::new(&SHA256);
+        ctx.update(&self.seed.to_le_bytes());
+        ctx.update(interface.as_interface().as_bytes());
+
+        let mut rng = StdRng::seed_from_u64(hash_to_u64(ctx.finish().as_ref()));
+
+        let initial_delay = random_below(&mut rng, Duration::from_secs(self.config.initial_delay));
+        let max_jitter = Duration::from_secs(self.config.jitter).min(period / 2);
+
+        Schedule {
+            initial_delay,
+            max_jitter,
+            rng,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new(ScheduleConfig::default(), 0)
+    }
+}
+
+/// First 8 bytes of a digest, stable across the Rust releases unlike the std hashers.
+fn hash_to_u64(digest: &[u8]) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&digest[..8]);
+
+    u64::from_le_bytes(bytes)
+}
+
+/// Reads the seed from the machine id, or a random one if it's missing.
+async fn device_seed(path: &Path) -> u64 {
+    match tokio::fs::read_to_string(path).await {
+        Ok(id) => {
+            let digest = aws_lc_rs::digest::digest(&SHA256, id.trim().as_bytes());
+
+            hash_to_u64(digest.as_ref())
+        }
+        Err(err) => {
+            warn!(
+                error = %err,
+                path = %path.display(),
+                "couldn't read the machine id, using a random seed for the telemetry schedule"
+            );
+
+            rand::rng().random()
+        }
+    }
+}
+
+/// Delays of a single telemetry task.
+#[derive(Debug, Clone)]
+pub(crate) struct Schedule {
+    initial_delay: Duration,
+    max_jitter: Duration,
+    rng: StdRng,
+    limiter: Option<RateLimiter>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::ZERO,
+            max_jitter: Duration::ZERO,
+            rng: StdRng::seed_from_u64(0),
+            limiter: None,
+        }
+    }
+}
+
+impl Schedule {
+    /// Delay of the first collection.
+    pub(crate) fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// Waits the jitter and the rate limiter before a collection.
+    pub(crate) async fn wait(&mut self) {
+        let jitter = random_below(&mut self.rng, self.max_jitter);
+
+        if !jitter.is_zero() {
+            debug!(?jitter, "delaying the telemetry collection");
+
+            tokio::time::sleep(jitter).await;
+        }
+
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire(1.0).await;
+        }
+    }
+}
+
+/// Random duration between 0 and max, with a millisecond resolution.
+fn random_below(rng: &mut StdRng, max: Duration) -> Duration {
+    let max = u64::try_from(max.as_millis()).unwrap_or(u64::MAX);
+
+    Duration::from_millis(rng.random_range(0..=max))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+    use tokio::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn should_deserialize() {
+        let string = r#"
+        initial_delay = 300
+        jitter = 10
+        rate_limit = 120
+        burst = 5
+        "#;
+
+        let config: ScheduleConfig = toml::from_str(string).unwrap();
+
+        let exp = ScheduleConfig {
+            initial_delay: 300,
+            jitter: 10,
+            rate_limit: 120,
+            burst: 5,
+        };
+
+        assert_eq!(config, exp);
+    }
+
+    #[test]
+    fn default_has_no_delays() {
+        let config: ScheduleConfig = toml::from_str("").unwrap();
+
+        let schedule = Scheduler::new(config, 42)
+            .for_task(TelemetryInterface::SystemStatus, Duration::from_secs(60));
+
+        assert_eq!(schedule.initial_delay(), Duration::ZERO);
+        assert_eq!(schedule.max_jitter, Duration::ZERO);
+        assert!(schedule.limiter.is_none());
+    }
+
+    #[test]
+    fn should_seed_per_device_and_task() {
+        let config = ScheduleConfig {
+            initial_delay: 600,
+            ..Default::default()
+        };
+
+        let delay = |seed, interface| {
+            Scheduler::new(config, seed)
+                .for_task(interface, Duration::from_secs(60))
+                .initial_delay()
+        };
+
+        let first = delay(1, TelemetryInterface::SystemStatus);
+
+        assert!(first <= Duration::from_secs(600));
+        // Same device and task, same delay
+        assert_eq!(first, delay(1, TelemetryInterface::SystemStatus));
+        assert_ne!(first, delay(2, TelemetryInterface::SystemStatus));
+        assert_ne!(first, delay(1, TelemetryInterface::StorageUsage));
+    }
+
+    #[test]
+    fn jitter_is_capped_to_half_period() {
+        let config = ScheduleConfig {
+            jitter: 60,
+            ..Default::default()
+        };
+
+        let mut schedule = Scheduler::new(config, 1)
+            .for_task(TelemetryInterface::SystemStatus, Duration::from_secs(10));
+
+        assert_eq!(schedule.max_jitter, Duration::from_secs(5));
+
+        for _ in 0..100 {
+            assert!(random_below(&mut schedule.rng, schedule.max_jitter) <= Duration::from_secs(5));
+        }
+    }
+
+    #[tokio::test]
+    async fn should_read_device_seed() {
+        let dir = TempDir::new("edgehog-schedule").unwrap();
+        let path = dir.path().join("machine-id");
+
+        tokio::fs::write(&path, "b08dfa6083e7567a1921a715000001fb\n")
+            .await
+            .unwrap();
+
+        assert_eq!(device_seed(&path).await, device_seed(&path).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_limit_rate() {
+        let config = ScheduleConfig {
+            rate_limit: 60,
+            burst: 2,
+            ..Default::default()
+        };
+        let scheduler = Scheduler::new(config, 1);
+        let mut schedule =
+            scheduler.for_task(TelemetryInterface::SystemStatus, Duration::from_secs(60));
+
+        let start = Instant::now();
+
+        // The burst is available immediately
+        schedule.wait().await;
+        schedule.wait().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // Then one per second
+        schedule.wait().await;
+        assert!(start.elapsed() >= Duration::from_secs(1));
+
+        schedule.wait().await;
+        assert!(start.elapsed() >= Duration::from_secs(2));
+    }
+}
@@ -25,7 +25,11 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
 
 use crate::Client;
+use crate::power_policy::PowerState;
+use crate::telemetry::SourcesConfig;
+use crate::telemetry::schedule::Schedule;
 use crate::telemetry::stats::TelemetryInterface;
+use crate::telemetry::stats::gnss::GnssTelemetry;
 use crate::telemetry::stats::storage_usage::StorageUsage;
 use crate::telemetry::stats::system_status::SystemStatusTelemetry;
 
@@ -43,6 +47,10 @@ pub struct Task<C> {
     interface: TelemetryInterface,
     cancel: CancellationToken,
     period: Duration,
+    power: PowerState,
+    schedule: Schedule,
+    /// Sends the telemetry only once, on request.
+    once: bool,
 }
 
 impl<C> Task<C> {
@@ -51,20 +59,43 @@ impl<C> Task<C> {
         interface: TelemetryInterface,
         cancel: CancellationToken,
         period: Duration,
+        power: PowerState,
+        schedule: Schedule,
     ) -> Self {
         Self {
             client,
             interface,
             cancel,
             period,
+            power,
+            schedule,
+            once: false,
         }
     }
 
-    pub(crate) fn spawn(
+    /// Creates a task that sends the telemetry once, without waiting for the period.
+    pub fn once(
         client: C,
-        cancel: CancellationToken,
         interface: TelemetryInterface,
+        cancel: CancellationToken,
         period: Duration,
+    ) -> Self {
+        Self {
+            once: true,
+            ..Self::new(
+                client,
+                interface,
+                cancel,
+                period,
+                PowerState::default(),
+                Schedule::default(),
+            )
+        }
+    }
+
+    pub(crate) fn spawn(
+        self,
+        sources: &SourcesConfig,
         #[cfg(feature = "containers")] containers: &std::sync::Arc<
             tokio::sync::OnceCell<edgehog_containers::local::ContainerHandle>,
         >,
@@ -72,27 +103,21 @@ impl<C> Task<C> {
         C: Client + Send + Sync + 'static,
     {
         tokio::spawn(Self::for_interface(
-            client,
-            cancel,
-            interface,
-            period,
+            self,
+            sources.clone(),
             #[cfg(feature = "containers")]
             edgehog_containers::stats::StatsMonitor::new(std::sync::Arc::clone(containers)),
         ));
     }
 
     async fn for_interface(
-        client: C,
-        cancel: CancellationToken,
-        interface: TelemetryInterface,
-        period: Duration,
+        task: Self,
+        sources: SourcesConfig,
         #[cfg(feature = "containers")] containers: edgehog_containers::stats::StatsMonitor,
     ) where
         C: Client + Send + Sync + 'static,
     {
-        let task = Task::new(client, interface, cancel, period);
-
-        match interface {
+        match task.interface {
             TelemetryInterface::SystemStatus => {
                 let telemetry = SystemStatusTelemetry::default();
 
@@ -125,6 +150,33 @@ impl<C> Task<C> {
                     }
                 }
             }
+            TelemetryInterface::CellularSignalQuality => {
+                cfg_if::cfg_if! {
+                    if #[cfg(all(feature = "zbus", target_os = "linux"))] {
+                        let telemetry = super::stats::cellular_signal::CellularSignalTelemetry::new(task.period);
+
+                        task.run(telemetry).await;
+                    } else {
+                        tracing::warn!("the cellular signal telemetry interface is not supported because the zbus feature is missing")
+                    }
+                }
+            }
+            TelemetryInterface::TimeSyncStatus => {
+                cfg_if::cfg_if! {
+                    if #[cfg(all(feature = "zbus", target_os = "linux"))] {
+                        let telemetry = super::stats::time_sync::TimeSyncTelemetry::new(sources.time_sync);
+
+                        task.run(telemetry).await;
+                    } else {
+                        tracing::warn!("the time sync telemetry interface is not supported because the zbus feature is missing")
+                    }
+                }
+            }
+            TelemetryInterface::GnssPosition => {
+                let telemetry = GnssTelemetry::new(sources.gnss);
+
+                task.run(telemetry).await;
+            }
             TelemetryInterface::ContainerBlkio => {
                 task.container(
                     ContainerInterface::ContainerBlkio,
@@ -202,22 +254,54 @@ impl<C> Task<C> {
         }
     }
 
+    /// Sends the telemetry periodically.
+    ///
+    /// The task is restarted with the scaled period when the power profile changes.
     pub async fn run<T>(mut self, mut telemetry: T)
     where
         C: Client + Send + Sync + 'static,
         T: TelemetryTask,
     {
-        let mut interval = tokio::time::interval(self.period);
+        if self.once {
+            info!(interface = %self.interface, "collecting telemetry on request");
+
+            tokio::select! {
+                _ = self.cancel.cancelled() => {}
+                _ = telemetry.send(&mut self.client) => {}
+            }
+
+            return;
+        }
+
+        let start = tokio::time::Instant::now() + self.schedule.initial_delay();
+        let mut interval = tokio::time::interval_at(start, self.period);
 
-        while self
-            .cancel
-            .run_until_cancelled(interval.tick())
-            .await
-            .is_some()
-        {
-            info!(interface = %self.interface, "collecting telemetry",);
+        loop {
+            tokio::select! {
+                _ = self.cancel.cancelled() => break,
+                _ = interval.tick() => {
+                    let profile = self.power.current();
+
+                    if self.interface == TelemetryInterface::WiFiScanResults && profile.pauses_wifi_scans() {
+                        debug!(%profile, "wifi scan paused by the power profile");
+
+                        continue;
+                    }
 
-            telemetry.send(&mut self.client).await;
+                    let cancelled = tokio::select! {
+                        _ = self.cancel.cancelled() => true,
+                        _ = self.schedule.wait() => false,
+                    };
+
+                    if cancelled {
+                        break;
+                    }
+
+                    info!(interface = %self.interface, "collecting telemetry",);
+
+                    telemetry.send(&mut self.client).await;
+                }
+            }
         }
 
         debug!(interface = %self.interface, "telemetry task cancelled");
@@ -16,13 +16,16 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod sysfs;
 pub(crate) mod upower;
 
 use astarte_device_sdk::IntoAstarteObject;
 use astarte_device_sdk::chrono::Utc;
-use tracing::{debug, error};
+use tokio::time::{Duration, Instant};
+use tracing::{debug, error, warn};
 use zbus::zvariant::OwnedObjectPath;
 
+use self::sysfs::PowerSupply;
 use self::upower::{BatteryState, DeviceProxy, PowerDeviceType, UPowerProxy};
 use crate::Client;
 use crate::data::send_object_with_timestamp;
@@ -30,6 +33,9 @@ use crate::telemetry::sender::TelemetryTask;
 
 pub(crate) const INTERFACE: &str = "io.edgehog.devicemanager.BatteryStatus";
 
+const UPOWER_MIN_BACKOFF: Duration = Duration::from_secs(60);
+const UPOWER_MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
 #[derive(Debug, IntoAstarteObject, PartialEq)]
 #[astarte_object(rename_all = "camelCase")]
 pub struct BatteryStatus {
@@ -51,6 +57,15 @@ impl BatteryStatus {
         }
     }
 
+    pub(crate) fn level_percentage(&self) -> f64 {
+        self.level_percentage
+    }
+
+    /// Returns the battery status string.
+    pub(crate) fn status(&self) -> &str {
+        &self.status
+    }
+
     async fn read(
         connection: &zbus::Connection,
         device_path: &OwnedObjectPath,
@@ -84,9 +99,17 @@ impl BatteryStatus {
     }
 }
 
+/// Reads the batteries from UPower, falling back to sysfs when the service is not available.
+///
+/// UPower is retried with a backoff, so a temporary error doesn't disable it.
 #[derive(Debug, Default)]
 pub(crate) struct BatteryStatusTelemetry {
     connection: Option<zbus::Connection>,
+    sysfs: PowerSupply,
+    /// UPower is skipped till this instant after a failure.
+    upower_retry: Option<Instant>,
+    /// Delay before retrying UPower, doubled after each failure.
+    upower_backoff: Duration,
 }
 
 impl BatteryStatusTelemetry {
@@ -109,39 +132,87 @@ impl BatteryStatusTelemetry {
 
         self.connection.as_ref()
     }
-}
 
-impl TelemetryTask for BatteryStatusTelemetry {
-    async fn send<C>(&mut self, client: &mut C)
-    where
-        C: Client + Send,
-    {
-        let Some(connection) = self.connect().await else {
-            return;
-        };
+    /// Returns the battery slot and status of all the batteries.
+    pub(crate) async fn read(&mut self) -> Vec<(String, BatteryStatus)> {
+        if self.upower_available() {
+            match self.read_upower().await {
+                Ok(batteries) => {
+                    self.upower_retry = None;
+                    self.upower_backoff = Duration::ZERO;
+
+                    return batteries;
+                }
+                Err(err) => {
+                    self.upower_failed();
+
+                    warn!(
+                        error = format!("{err:#}"),
+                        retry_in = ?self.upower_backoff,
+                        "UPower not available, reading the batteries from sysfs"
+                    );
+                }
+            }
+        }
 
-        let devices = match enumerate_devices(connection).await {
-            Ok(devices) => devices,
+        match self.sysfs.batteries().await {
+            Ok(batteries) => batteries,
             Err(err) => {
-                error!("couldn't enumerate the device: {}", eyre::Report::new(err));
+                error!(
+                    error = format!("{err:#}"),
+                    "couldn't read the batteries from sysfs"
+                );
 
-                return;
+                Vec::new()
             }
-        };
+        }
+    }
+
+    fn upower_available(&self) -> bool {
+        self.upower_retry
+            .is_none_or(|retry| Instant::now() >= retry)
+    }
+
+    /// Skips UPower for an increasing delay, and reconnects to the bus on the next try.
+    fn upower_failed(&mut self) {
+        self.upower_backoff =
+            (self.upower_backoff * 2).clamp(UPOWER_MIN_BACKOFF, UPOWER_MAX_BACKOFF);
+        self.upower_retry = Some(Instant::now() + self.upower_backoff);
+        self.connection = None;
+    }
+
+    async fn read_upower(&mut self) -> eyre::Result<Vec<(String, BatteryStatus)>> {
+        let connection = self
+            .connect()
+            .await
+            .ok_or_else(|| eyre::eyre!("couldn't connect to system dbus"))?;
+
+        let devices = enumerate_devices(connection)
+            .await
+            .map_err(|err| eyre::Report::new(err).wrap_err("couldn't enumerate the device"))?;
+
+        let mut batteries = Vec::with_capacity(devices.len());
 
         for device_path in devices {
-            let (battery_slot, status) = match BatteryStatus::read(connection, &device_path).await {
-                Ok(Some(res)) => res,
-                Ok(None) => {
-                    continue;
-                }
+            match BatteryStatus::read(connection, &device_path).await {
+                Ok(Some(res)) => batteries.push(res),
+                Ok(None) => {}
                 Err(err) => {
                     error!("couldn't get battery status for {device_path}: {err}");
-
-                    continue;
                 }
-            };
+            }
+        }
+
+        Ok(batteries)
+    }
+}
 
+impl TelemetryTask for BatteryStatusTelemetry {
+    async fn send<C>(&mut self, client: &mut C)
+    where
+        C: Client + Send,
+    {
+        for (battery_slot, status) in self.read().await {
             debug!(battery_slot, ?status, "found battery");
 
             send_object_with_timestamp(client, INTERFACE, &battery_slot, status, Utc::now()).await;
@@ -183,6 +254,7 @@ fn get_error_level(device_state: BatteryState) -> f64 {
 
 #[cfg(test)]
 mod tests {
+    use astarte_device_sdk::AstarteData;
     use astarte_device_sdk::store::SqliteStore;
     use astarte_device_sdk::transport::mqtt::Mqtt;
     use astarte_device_sdk_mock::MockDeviceClient;
@@ -298,6 +370,61 @@ mod tests {
         BatteryStatusTelemetry::default().send(&mut client).await;
     }
 
+    #[tokio::test]
+    async fn should_fall_back_to_sysfs() {
+        let dir = tempdir::TempDir::new("edgehog-power-supply").unwrap();
+        let battery = dir.path().join("class/power_supply/BAT0");
+        std::fs::create_dir_all(&battery).unwrap();
+        std::fs::write(battery.join("type"), "Battery\n").unwrap();
+        std::fs::write(battery.join("status"), "Charging\n").unwrap();
+        std::fs::write(battery.join("capacity"), "75\n").unwrap();
+
+        // Simulate UPower missing on the system
+        let mut telemetry = BatteryStatusTelemetry {
+            connection: None,
+            sysfs: PowerSupply::new(dir.path()),
+            upower_retry: Some(Instant::now() + UPOWER_MIN_BACKOFF),
+            upower_backoff: UPOWER_MIN_BACKOFF,
+        };
+
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+
+        client
+            .expect_send_object_with_timestamp()
+            .once()
+            .withf(|interface, path, data, _| {
+                interface == "io.edgehog.devicemanager.BatteryStatus"
+                    && path == "/BAT0"
+                    && data.get("status") == Some(&"Charging".into())
+                    && data.get("levelPercentage") == Some(&AstarteData::try_from(75.0).unwrap())
+            })
+            .returning(|_, _, _, _| Ok(()));
+
+        telemetry.send(&mut client).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_retry_upower_with_backoff() {
+        let mut telemetry = BatteryStatusTelemetry::default();
+
+        assert!(telemetry.upower_available());
+
+        telemetry.upower_failed();
+        assert!(!telemetry.upower_available());
+        assert_eq!(telemetry.upower_backoff, UPOWER_MIN_BACKOFF);
+
+        tokio::time::advance(UPOWER_MIN_BACKOFF).await;
+        assert!(telemetry.upower_available());
+
+        telemetry.upower_failed();
+        assert_eq!(telemetry.upower_backoff, UPOWER_MIN_BACKOFF * 2);
+
+        for _ in 0..10 {
+            telemetry.upower_failed();
+        }
+        assert_eq!(telemetry.upower_backoff, UPOWER_MAX_BACKOFF);
+    }
+
     #[test]
     fn get_status_test() {
         assert_eq!(
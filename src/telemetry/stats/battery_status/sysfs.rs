@@ -0,0 +1,336 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Battery status read from the kernel `power_supply` class in sysfs.
+//!
+//! Used when the UPower service is not available on the device.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use eyre::WrapErr;
+use tracing::{debug, error};
+
+use super::BatteryStatus;
+use super::upower::BatteryState;
+
+const DEFAULT_SYSFS_ROOT: &str = "/sys";
+
+/// Reads the batteries under `<root>/class/power_supply`.
+#[derive(Debug, Clone)]
+pub(crate) struct PowerSupply {
+    root: PathBuf,
+}
+
+impl PowerSupply {
+    /// Create the reader with a custom sysfs root.
+    pub(crate) fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn class_dir(&self) -> PathBuf {
+        self.root.join("class/power_supply")
+    }
+
+    /// Returns the battery slot and status for every battery in the `power_supply` class.
+    pub(crate) async fn batteries(&self) -> eyre::Result<Vec<(String, BatteryStatus)>> {
+        let class_dir = self.class_dir();
+
+        let mut entries = tokio::fs::read_dir(&class_dir)
+            .await
+            .wrap_err_with(|| format!("couldn't read {}", class_dir.display()))?;
+
+        let mut batteries = Vec::new();
+
+        while let Some(entry) = entries.next_entry().await? {
+            let dir = entry.path();
+
+            match read_supply(&dir).await {
+                Ok(Some(battery)) => batteries.push(battery),
+                Ok(None) => {
+                    debug!("power supply {} is not a battery", dir.display());
+                }
+                Err(err) => {
+                    error!(
+                        error = format!("{err:#}"),
+                        "couldn't read power supply {}",
+                        dir.display()
+                    );
+                }
+            }
+        }
+
+        batteries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(batteries)
+    }
+}
+
+impl Default for PowerSupply {
+    fn default() -> Self {
+        Self::new(DEFAULT_SYSFS_ROOT)
+    }
+}
+
+async fn read_supply(dir: &Path) -> eyre::Result<Option<(String, BatteryStatus)>> {
+    let Some(supply_type) = read_attr(dir, "type").await? else {
+        return Ok(None);
+    };
+
+    if supply_type != "Battery" {
+        return Ok(None);
+    }
+
+    // The attribute is optional, batteries that cannot be removed don't have it.
+    let is_present = read_attr(dir, "present")
+        .await?
+        .is_none_or(|present| present != "0");
+
+    let state = read_attr(dir, "status")
+        .await?
+        .map(|status| battery_state(&status))
+        .unwrap_or(BatteryState::Unknown);
+
+    let capacity = read_number(dir, "capacity").await?;
+    let estimated = estimate_level(dir).await?;
+
+    let Some(level_percentage) = capacity.or(estimated) else {
+        debug!("battery {} has no capacity information", dir.display());
+
+        return Ok(None);
+    };
+
+    let mut status = BatteryStatus::new(level_percentage, state, is_present);
+
+    // When both are available, the gap between the reported capacity and the one computed from
+    // the energy or charge counters is used as the error of the level.
+    if let (Some(capacity), Some(estimated)) = (capacity, estimated) {
+        status.level_absolute_error = status
+            .level_absolute_error
+            .max((capacity - estimated).abs());
+    }
+
+    let slot = match read_attr(dir, "serial_number").await? {
+        Some(serial) if !serial.is_empty() => serial,
+        _ => dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+
+    Ok(Some((format!("/{}", slot.trim_matches('/')), status)))
+}
+
+/// Computes the level from the `energy_*` attributes, or from the `charge_*` ones.
+async fn estimate_level(dir: &Path) -> eyre::Result<Option<f64>> {
+    for (now, full) in [("energy_now", "energy_full"), ("charge_now", "charge_full")] {
+        let (Some(now), Some(full)) = (read_number(dir, now).await?, read_number(dir, full).await?)
+        else {
+            continue;
+        };
+
+        if full <= 0.0 {
+            continue;
+        }
+
+        return Ok(Some((now * 100.0 / full).clamp(0.0, 100.0)));
+    }
+
+    Ok(None)
+}
+
+/// Maps the sysfs status to the UPower state, to share the status strings.
+fn battery_state(status: &str) -> BatteryState {
+    match status {
+        "Charging" => BatteryState::Charging,
+        "Discharging" => BatteryState::Discharging,
+        "Full" => BatteryState::FullyCharged,
+        "Not charging" => BatteryState::PendingCharge,
+        _ => BatteryState::Unknown,
+    }
+}
+
+async fn read_attr(dir: &Path, name: &str) -> eyre::Result<Option<String>> {
+    let path = dir.join(name);
+
+    match tokio::fs::read_to_string(&path).await {
+        Ok(value) => Ok(Some(value.trim().to_string())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).wrap_err_with(|| format!("couldn't read {}", path.display())),
+    }
+}
+
+async fn read_number(dir: &Path, name: &str) -> eyre::Result<Option<f64>> {
+    let Some(value) = read_attr(dir, name).await? else {
+        return Ok(None);
+    };
+
+    value
+        .parse::<f64>()
+        .map(Some)
+        .wrap_err_with(|| format!("invalid value for {name}: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn supply(root: &Path, name: &str, attrs: &[(&str, &str)]) {
+        let dir = root.join("class/power_supply").join(name);
+
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (attr, value) in attrs {
+            std::fs::write(dir.join(attr), format!("{value}\n")).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn should_read_batteries() {
+        let dir = TempDir::new("edgehog-power-supply").unwrap();
+
+        supply(dir.path(), "AC", &[("type", "Mains"), ("online", "1")]);
+        supply(
+            dir.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Discharging"),
+                ("present", "1"),
+                ("capacity", "80"),
+                ("serial_number", "ABC123"),
+            ],
+        );
+        supply(
+            dir.path(),
+            "BAT1",
+            &[
+                ("type", "Battery"),
+                ("status", "Full"),
+                ("present", "0"),
+                ("charge_now", "2000000"),
+                ("charge_full", "4000000"),
+            ],
+        );
+
+        let batteries = PowerSupply::new(dir.path()).batteries().await.unwrap();
+
+        assert_eq!(
+            batteries,
+            [
+                (
+                    "/ABC123".to_string(),
+                    BatteryStatus {
+                        level_percentage: 80.0,
+                        level_absolute_error: 0.0,
+                        status: "Discharging".to_string(),
+                    }
+                ),
+                (
+                    "/BAT1".to_string(),
+                    BatteryStatus {
+                        level_percentage: 50.0,
+                        level_absolute_error: 0.0,
+                        status: "Removed".to_string(),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_estimate_error_from_energy() {
+        let dir = TempDir::new("edgehog-power-supply").unwrap();
+
+        supply(
+            dir.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Charging"),
+                ("capacity", "60"),
+                ("energy_now", "55000"),
+                ("energy_full", "100000"),
+            ],
+        );
+
+        let batteries = PowerSupply::new(dir.path()).batteries().await.unwrap();
+
+        assert_eq!(
+            batteries,
+            [(
+                "/BAT0".to_string(),
+                BatteryStatus {
+                    level_percentage: 60.0,
+                    level_absolute_error: 5.0,
+                    status: "Charging".to_string(),
+                }
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_status_has_full_error() {
+        let dir = TempDir::new("edgehog-power-supply").unwrap();
+
+        supply(
+            dir.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Unknown"),
+                ("capacity", "42"),
+            ],
+        );
+
+        let batteries = PowerSupply::new(dir.path()).batteries().await.unwrap();
+
+        assert_eq!(
+            batteries,
+            [(
+                "/BAT0".to_string(),
+                BatteryStatus {
+                    level_percentage: 42.0,
+                    level_absolute_error: 100.0,
+                    status: "Unknown".to_string(),
+                }
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_class_dir_is_an_error() {
+        let dir = TempDir::new("edgehog-power-supply").unwrap();
+
+        let res = PowerSupply::new(dir.path()).batteries().await;
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn should_map_sysfs_status() {
+        assert_eq!(battery_state("Charging"), BatteryState::Charging);
+        assert_eq!(battery_state("Discharging"), BatteryState::Discharging);
+        assert_eq!(battery_state("Full"), BatteryState::FullyCharged);
+        assert_eq!(battery_state("Not charging"), BatteryState::PendingCharge);
+        assert_eq!(battery_state("Unknown"), BatteryState::Unknown);
+        assert_eq!(battery_state("garbage"), BatteryState::Unknown);
+    }
+}
@@ -0,0 +1,203 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signal quality of the cellular modems, read from ModemManager.
+
+use std::time::Duration;
+
+use astarte_device_sdk::AstarteData;
+use astarte_device_sdk::chrono::Utc;
+use tracing::{debug, error};
+
+use crate::Client;
+use crate::data::send_individual_with_timestamp;
+use crate::telemetry::cellular::modem_manager::{ModemManager, ModemSignal};
+use crate::telemetry::sender::TelemetryTask;
+
+pub(crate) const INTERFACE: &str = "io.edgehog.devicemanager.CellularSignalQuality";
+
+#[derive(Debug)]
+pub(crate) struct CellularSignalTelemetry {
+    modem_manager: Option<ModemManager>,
+    /// Refresh rate of the signal in ModemManager, in seconds.
+    rate: u32,
+}
+
+impl CellularSignalTelemetry {
+    pub(crate) fn new(period: Duration) -> Self {
+        Self {
+            modem_manager: None,
+            rate: u32::try_from(period.as_secs()).unwrap_or(u32::MAX).max(1),
+        }
+    }
+
+    async fn connect(&mut self) -> Option<&ModemManager> {
+        if self.modem_manager.is_none() {
+            match ModemManager::connect().await {
+                Ok(modem_manager) => {
+                    self.modem_manager = Some(modem_manager);
+                }
+                Err(err) => {
+                    error!(
+                        error = format!("{err:#}"),
+                        "couldn't connect to ModemManager"
+                    );
+
+                    return None;
+                }
+            }
+        }
+
+        self.modem_manager.as_ref()
+    }
+}
+
+async fn send_signal<C>(client: &mut C, id: &str, signal: ModemSignal)
+where
+    C: Client,
+{
+    let timestamp = Utc::now();
+
+    let values = [
+        ("rssi", signal.rssi),
+        ("rsrp", signal.rsrp),
+        ("rsrq", signal.rsrq),
+        ("sinr", signal.sinr),
+    ];
+
+    for (name, value) in values {
+        let Some(value) = value else {
+            continue;
+        };
+
+        let value = match AstarteData::try_from(value) {
+            Ok(value) => value,
+            Err(err) => {
+                error!(id, name, error = %err, "invalid signal value");
+
+                continue;
+            }
+        };
+
+        send_individual_with_timestamp(
+            client,
+            INTERFACE,
+            &format!("/{id}/{name}"),
+            value,
+            timestamp,
+        )
+        .await;
+    }
+}
+
+impl TelemetryTask for CellularSignalTelemetry {
+    async fn send<C>(&mut self, client: &mut C)
+    where
+        C: Client + Send + Sync + 'static,
+    {
+        let rate = self.rate;
+
+        let Some(modem_manager) = self.connect().await else {
+            return;
+        };
+
+        let modems = match modem_manager.modems().await {
+            Ok(modems) => modems,
+            Err(err) => {
+                error!(error = format!("{err:#}"), "couldn't list the modems");
+
+                return;
+            }
+        };
+
+        for path in modems {
+            let id = match modem_manager.modem_id(&path).await {
+                Ok(id) => id,
+                Err(err) => {
+                    error!(%path, error = format!("{err:#}"), "couldn't get the modem id");
+
+                    continue;
+                }
+            };
+
+            match modem_manager.signal(&path, rate).await {
+                Ok(Some(signal)) => send_signal(client, &id, signal).await,
+                Ok(None) => {
+                    debug!(id, "no signal information available");
+                }
+                Err(err) => {
+                    error!(
+                        id,
+                        error = format!("{err:#}"),
+                        "couldn't read the modem signal"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astarte_device_sdk::store::SqliteStore;
+    use astarte_device_sdk::transport::mqtt::Mqtt;
+    use astarte_device_sdk_mock::MockDeviceClient;
+    use mockall::{Sequence, predicate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_send_available_values() {
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut seq = Sequence::new();
+
+        for (path, value) in [("/modem/rsrp", -95.0), ("/modem/sinr", 12.5)] {
+            client
+                .expect_send_individual_with_timestamp()
+                .once()
+                .in_sequence(&mut seq)
+                .with(
+                    predicate::eq(INTERFACE),
+                    predicate::eq(path),
+                    predicate::eq(AstarteData::try_from(value).unwrap()),
+                    predicate::always(),
+                )
+                .returning(|_, _, _, _| Ok(()));
+        }
+
+        let signal = ModemSignal {
+            rssi: None,
+            rsrp: Some(-95.0),
+            rsrq: None,
+            sinr: Some(12.5),
+        };
+
+        send_signal(&mut client, "modem", signal).await;
+    }
+
+    #[test]
+    fn rate_from_period() {
+        let telemetry = CellularSignalTelemetry::new(Duration::from_millis(10));
+
+        assert_eq!(telemetry.rate, 1);
+
+        let telemetry = CellularSignalTelemetry::new(Duration::from_secs(60));
+
+        assert_eq!(telemetry.rate, 60);
+    }
+}
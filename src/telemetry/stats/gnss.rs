@@ -0,0 +1,158 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Position of the device from gpsd.
+
+use astarte_device_sdk::IntoAstarteObject;
+use astarte_device_sdk::chrono::Utc;
+use tracing::{debug, error};
+
+use crate::Client;
+use crate::data::send_object_with_timestamp;
+use crate::telemetry::gnss::gpsd::read_fix;
+use crate::telemetry::gnss::{Fix, GnssConfig};
+use crate::telemetry::sender::TelemetryTask;
+
+const INTERFACE: &str = "io.edgehog.devicemanager.GnssPosition";
+
+/// Position sent to Astarte, the values not reported by the receiver are 0.
+#[derive(Debug, Clone, PartialEq, IntoAstarteObject)]
+#[astarte_object(rename_all = "camelCase")]
+pub(crate) struct GnssPosition {
+    latitude: f64,
+    longitude: f64,
+    /// Altitude in meters, only with a 3D fix.
+    altitude: f64,
+    /// Speed in meters per second.
+    speed: f64,
+    /// Heading in degrees from the true north.
+    heading: f64,
+    /// "2D" or "3D"
+    fix_mode: String,
+    /// Horizontal accuracy in meters.
+    accuracy: f64,
+    /// Vertical accuracy in meters.
+    altitude_accuracy: f64,
+}
+
+impl From<Fix> for GnssPosition {
+    fn from(value: Fix) -> Self {
+        Self {
+            latitude: value.latitude,
+            longitude: value.longitude,
+            altitude: value.altitude.unwrap_or_default(),
+            speed: value.speed.unwrap_or_default(),
+            heading: value.heading.unwrap_or_default(),
+            fix_mode: value.mode.to_string(),
+            accuracy: value.horizontal_accuracy.unwrap_or_default(),
+            altitude_accuracy: value.vertical_accuracy.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct GnssTelemetry {
+    config: GnssConfig,
+}
+
+impl GnssTelemetry {
+    pub(crate) fn new(config: GnssConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl TelemetryTask for GnssTelemetry {
+    async fn send<C>(&mut self, client: &mut C)
+    where
+        C: Client + Send + Sync + 'static,
+    {
+        let fix = match read_fix(&self.config).await {
+            Ok(Some(fix)) => fix,
+            Ok(None) => {
+                debug!("no GNSS fix");
+
+                return;
+            }
+            Err(err) => {
+                error!(
+                    error = format!("{err:#}"),
+                    "couldn't read the GNSS position"
+                );
+
+                return;
+            }
+        };
+
+        send_object_with_timestamp(
+            client,
+            INTERFACE,
+            "/position",
+            GnssPosition::from(fix),
+            Utc::now(),
+        )
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astarte_device_sdk::store::SqliteStore;
+    use astarte_device_sdk::transport::mqtt::Mqtt;
+    use astarte_device_sdk::{AstarteData, aggregate::AstarteObject};
+    use astarte_device_sdk_mock::MockDeviceClient;
+    use mockall::predicate;
+
+    use crate::telemetry::gnss::gpsd::tests::{TPV_3D, TPV_NO_FIX, tcp_gpsd};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_send_position() {
+        let config = tcp_gpsd(TPV_3D).await;
+
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+
+        client
+            .expect_send_object_with_timestamp()
+            .once()
+            .with(
+                predicate::eq(INTERFACE),
+                predicate::eq("/position"),
+                predicate::function(|data: &AstarteObject| {
+                    data.get("latitude") == Some(&AstarteData::try_from(45.0703).unwrap())
+                        && data.get("altitude") == Some(&AstarteData::try_from(283.5).unwrap())
+                        && data.get("fixMode") == Some(&AstarteData::from("3D"))
+                        && data.get("accuracy") == Some(&AstarteData::try_from(5.2).unwrap())
+                }),
+                predicate::always(),
+            )
+            .returning(|_, _, _, _| Ok(()));
+
+        GnssTelemetry::new(config).send(&mut client).await;
+    }
+
+    #[tokio::test]
+    async fn no_fix_sends_nothing() {
+        let config = tcp_gpsd(TPV_NO_FIX).await;
+
+        // Panics if anything is sent
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+
+        GnssTelemetry::new(config).send(&mut client).await;
+    }
+}
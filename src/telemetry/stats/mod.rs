@@ -30,10 +30,15 @@ use super::sender::TelemetryTask;
 
 #[cfg(all(feature = "zbus", target_os = "linux"))]
 pub(crate) mod battery_status;
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub(crate) mod cellular_signal;
 #[cfg(feature = "containers")]
 pub(crate) mod container;
+pub(crate) mod gnss;
 pub(crate) mod storage_usage;
 pub(crate) mod system_status;
+#[cfg(all(feature = "zbus", target_os = "linux"))]
+pub(crate) mod time_sync;
 #[cfg(feature = "wifiscanner")]
 pub(crate) mod wifi_scan;
 
@@ -63,6 +68,9 @@ pub(crate) enum TelemetryInterface {
     StorageUsage,
     BatteryStatus,
     WiFiScanResults,
+    CellularSignalQuality,
+    TimeSyncStatus,
+    GnssPosition,
     ContainerBlkio,
     ContainerCpu,
     ContainerMemory,
@@ -79,6 +87,11 @@ impl TelemetryInterface {
             TelemetryInterface::StorageUsage => storage_usage::INTERFACE,
             TelemetryInterface::BatteryStatus => "io.edgehog.devicemanager.BatteryStatus",
             TelemetryInterface::WiFiScanResults => "io.edgehog.devicemanager.WiFiScanResults",
+            TelemetryInterface::CellularSignalQuality => {
+                "io.edgehog.devicemanager.CellularSignalQuality"
+            }
+            TelemetryInterface::TimeSyncStatus => "io.edgehog.devicemanager.TimeSyncStatus",
+            TelemetryInterface::GnssPosition => "io.edgehog.devicemanager.GnssPosition",
             TelemetryInterface::ContainerBlkio => {
                 "io.edgehog.devicemanager.apps.stats.ContainerBlkio"
             }
@@ -109,6 +122,11 @@ impl FromStr for TelemetryInterface {
             "io.edgehog.devicemanager.StorageUsage" => TelemetryInterface::StorageUsage,
             "io.edgehog.devicemanager.BatteryStatus" => TelemetryInterface::BatteryStatus,
             "io.edgehog.devicemanager.WiFiScanResults" => TelemetryInterface::WiFiScanResults,
+            "io.edgehog.devicemanager.CellularSignalQuality" => {
+                TelemetryInterface::CellularSignalQuality
+            }
+            "io.edgehog.devicemanager.TimeSyncStatus" => TelemetryInterface::TimeSyncStatus,
+            "io.edgehog.devicemanager.GnssPosition" => TelemetryInterface::GnssPosition,
             "io.edgehog.devicemanager.apps.stats.ContainerBlkio" => {
                 TelemetryInterface::ContainerBlkio
             }
@@ -189,6 +207,18 @@ mod tests {
                 "io.edgehog.devicemanager.BatteryStatus",
                 TelemetryInterface::BatteryStatus,
             ),
+            (
+                "io.edgehog.devicemanager.CellularSignalQuality",
+                TelemetryInterface::CellularSignalQuality,
+            ),
+            (
+                "io.edgehog.devicemanager.TimeSyncStatus",
+                TelemetryInterface::TimeSyncStatus,
+            ),
+            (
+                "io.edgehog.devicemanager.GnssPosition",
+                TelemetryInterface::GnssPosition,
+            ),
             (
                 "io.edgehog.devicemanager.apps.stats.ContainerBlkio",
                 TelemetryInterface::ContainerBlkio,
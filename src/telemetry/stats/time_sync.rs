@@ -0,0 +1,258 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Synchronization status of the system clock and detection of the clock jumps.
+
+use std::time::{Duration, SystemTime};
+
+use astarte_device_sdk::chrono::{DateTime, Utc};
+use astarte_device_sdk::{AstarteData, IntoAstarteObject};
+use tracing::{error, warn};
+
+use crate::Client;
+use crate::data::{send_individual_with_timestamp, send_object_with_timestamp};
+use crate::telemetry::sender::TelemetryTask;
+use crate::telemetry::time_sync::{TimeSync, TimeSyncConfig, read_time_sync};
+
+const INTERFACE: &str = "io.edgehog.devicemanager.TimeSyncStatus";
+const ALERT_INTERFACE: &str = "io.edgehog.devicemanager.TimeSyncAlert";
+
+#[derive(Debug, Clone, PartialEq, IntoAstarteObject)]
+#[astarte_object(rename_all = "camelCase")]
+pub(crate) struct TimeSyncStatus {
+    synchronized: bool,
+    backend: String,
+    /// Empty if the source is unknown.
+    source: String,
+    /// Offset from the source in seconds, 0 if unknown.
+    offset_seconds: f64,
+    /// The Unix epoch if the clock was never synchronized.
+    last_sync: DateTime<Utc>,
+}
+
+impl From<TimeSync> for TimeSyncStatus {
+    fn from(value: TimeSync) -> Self {
+        Self {
+            synchronized: value.synchronized,
+            backend: value.backend.to_string(),
+            source: value.source.unwrap_or_default(),
+            offset_seconds: value.offset.unwrap_or_default(),
+            last_sync: value.last_sync.unwrap_or(DateTime::UNIX_EPOCH),
+        }
+    }
+}
+
+/// Detects the jumps of the wall clock, comparing it with the boot time one.
+///
+/// The boot time clock also advances while the system is suspended, so a resume is not a jump.
+#[derive(Debug, Default)]
+struct ClockWatch {
+    last: Option<(Duration, SystemTime)>,
+}
+
+impl ClockWatch {
+    /// Returns the jump in seconds since the last check, if it's over the threshold.
+    fn check(&mut self, boottime: Duration, wall: SystemTime, threshold: Duration) -> Option<f64> {
+        let (last_boottime, last_wall) = self.last.replace((boottime, wall))?;
+
+        let elapsed = boottime.saturating_sub(last_boottime).as_secs_f64();
+        let wall_elapsed = match wall.duration_since(last_wall) {
+            Ok(elapsed) => elapsed.as_secs_f64(),
+            Err(err) => -err.duration().as_secs_f64(),
+        };
+
+        let jump = wall_elapsed - elapsed;
+
+        (jump.abs() > threshold.as_secs_f64()).then_some(jump)
+    }
+}
+
+/// Returns the `CLOCK_BOOTTIME`, the time since boot including the time suspended.
+fn boottime() -> Duration {
+    let now = rustix::time::clock_gettime(rustix::time::ClockId::Boottime);
+
+    Duration::new(now.tv_sec as u64, now.tv_nsec as u32)
+}
+
+#[derive(Debug)]
+pub(crate) struct TimeSyncTelemetry {
+    config: TimeSyncConfig,
+    clock: ClockWatch,
+}
+
+impl TimeSyncTelemetry {
+    pub(crate) fn new(config: TimeSyncConfig) -> Self {
+        Self {
+            config,
+            clock: ClockWatch::default(),
+        }
+    }
+
+    async fn check_clock_jump<C>(&mut self, client: &mut C)
+    where
+        C: Client,
+    {
+        let Some(threshold) = self.config.jump_threshold() else {
+            return;
+        };
+
+        let Some(jump) = self.clock.check(boottime(), SystemTime::now(), threshold) else {
+            return;
+        };
+
+        warn!(
+            jump_seconds = jump,
+            "system clock jumped, the job and telemetry timestamps could be wrong"
+        );
+
+        if !self.config.alert {
+            return;
+        }
+
+        match AstarteData::try_from(jump) {
+            Ok(data) => {
+                send_individual_with_timestamp(
+                    client,
+                    ALERT_INTERFACE,
+                    "/clockJump",
+                    data,
+                    Utc::now(),
+                )
+                .await;
+            }
+            Err(err) => error!(error = %err, "invalid clock jump value"),
+        }
+    }
+}
+
+impl TelemetryTask for TimeSyncTelemetry {
+    async fn send<C>(&mut self, client: &mut C)
+    where
+        C: Client + Send + Sync + 'static,
+    {
+        self.check_clock_jump(client).await;
+
+        let status = match read_time_sync(&self.config).await {
+            Ok(status) => TimeSyncStatus::from(status),
+            Err(err) => {
+                error!(
+                    error = format!("{err:#}"),
+                    "couldn't read the time synchronization status"
+                );
+
+                return;
+            }
+        };
+
+        send_object_with_timestamp(client, INTERFACE, "/timeSync", status, Utc::now()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astarte_device_sdk::store::SqliteStore;
+    use astarte_device_sdk::transport::mqtt::Mqtt;
+    use astarte_device_sdk_mock::MockDeviceClient;
+    use mockall::predicate;
+    use pretty_assertions::assert_eq;
+
+    use crate::telemetry::time_sync::TimeSyncBackend;
+
+    use super::*;
+
+    #[test]
+    fn should_convert_status() {
+        let status = TimeSyncStatus::from(TimeSync {
+            backend: TimeSyncBackend::Timedated,
+            synchronized: true,
+            source: None,
+            offset: None,
+            last_sync: None,
+        });
+
+        assert_eq!(
+            status,
+            TimeSyncStatus {
+                synchronized: true,
+                backend: "timedated".to_string(),
+                source: String::new(),
+                offset_seconds: 0.0,
+                last_sync: DateTime::UNIX_EPOCH,
+            }
+        );
+    }
+
+    #[test]
+    fn should_detect_clock_jumps() {
+        let threshold = Duration::from_secs(5);
+        let mut clock = ClockWatch::default();
+
+        let boottime = Duration::from_secs(100);
+        let wall = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        assert_eq!(clock.check(boottime, wall, threshold), None);
+
+        // Both advanced by the same amount
+        let boottime = boottime + Duration::from_secs(60);
+        let wall = wall + Duration::from_secs(61);
+        assert_eq!(clock.check(boottime, wall, threshold), None);
+
+        // Jumped forward
+        let boottime = boottime + Duration::from_secs(60);
+        let wall = wall + Duration::from_secs(3660);
+        assert_eq!(clock.check(boottime, wall, threshold), Some(3600.0));
+
+        // Jumped backward
+        let boottime = boottime + Duration::from_secs(60);
+        let wall = wall - Duration::from_secs(40);
+        assert_eq!(clock.check(boottime, wall, threshold), Some(-100.0));
+
+        // Suspended, both advanced
+        let boottime = boottime + Duration::from_secs(7200);
+        let wall = wall + Duration::from_secs(7200);
+        assert_eq!(clock.check(boottime, wall, threshold), None);
+    }
+
+    #[tokio::test]
+    async fn should_publish_clock_jump_alert() {
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+
+        client
+            .expect_send_individual_with_timestamp()
+            .once()
+            .with(
+                predicate::eq(ALERT_INTERFACE),
+                predicate::eq("/clockJump"),
+                predicate::function(|data: &AstarteData| {
+                    f64::try_from(data.clone()).is_ok_and(|jump| jump > 3000.0)
+                }),
+                predicate::always(),
+            )
+            .returning(|_, _, _, _| Ok(()));
+
+        let mut telemetry = TimeSyncTelemetry::new(TimeSyncConfig {
+            alert: true,
+            ..Default::default()
+        });
+
+        // Simulates a previous check an hour earlier on the wall clock
+        telemetry.clock.last = Some((boottime(), SystemTime::now() - Duration::from_secs(3600)));
+
+        telemetry.check_clock_jump(&mut client).await;
+    }
+}
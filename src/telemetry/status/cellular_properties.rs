@@ -1,6 +1,6 @@
 // This file is part of Edgehog.
 //
-// Copyright 2024 - 2025 SECO Mind Srl
+// Copyright 2024 - 2026 SECO Mind Srl
 //
 // Licensed under the Apache License, Version 2.0 (the "License");
 // you may not use this file except in compliance with the License.
@@ -18,90 +18,30 @@
 
 //! Cellular connection properties telemetry information.
 
-use eyre::WrapErr;
-use futures::StreamExt;
 use std::collections::HashMap;
-use tracing::{debug, error};
-use zbus::proxy;
-use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
 
 use crate::Client;
-use crate::data::set_property;
+use crate::data::{set_property, unset_property};
+use crate::telemetry::cellular::{CellularConfig, ModemInfo, read_modems};
 
 const INTERFACE: &str = "io.edgehog.devicemanager.CellularConnectionProperties";
-
-#[derive(Debug, Clone, DeserializeDict, SerializeDict, Type)]
-#[zvariant(signature = "dict")]
-pub struct ModemProperties {
-    apn: String,
-    imei: String,
-    imsi: String,
-}
-
-#[proxy(
-    interface = "io.edgehog.CellularModems1",
-    default_service = "io.edgehog.CellularModems",
-    default_path = "/io/edgehog/CellularModems"
-)]
-trait CellularModems {
-    fn list(&self) -> zbus::Result<Vec<String>>;
-    fn get(&self, id: String) -> zbus::Result<ModemProperties>;
-}
+/// Registration of the modem to the network, reported only by the ModemManager backend.
+///
+/// It's a separate interface to keep the published mappings of [`INTERFACE`] unchanged.
+const REGISTRATION_INTERFACE: &str = "io.edgehog.devicemanager.CellularRegistrationProperties";
 
 #[derive(Debug, Clone, Default)]
 pub struct CellularConnection {
-    properties: HashMap<String, ModemProperties>,
+    properties: HashMap<String, ModemInfo>,
 }
 
 impl CellularConnection {
-    pub async fn read() -> CellularConnection {
-        match Self::get_cellular_properties().await {
-            Ok(properties) => CellularConnection { properties },
-            Err(err) => {
-                error!("{err}");
-
-                CellularConnection::default()
-            }
+    pub async fn read(config: &CellularConfig) -> CellularConnection {
+        CellularConnection {
+            properties: read_modems(config).await,
         }
     }
 
-    async fn get_cellular_properties() -> eyre::Result<HashMap<String, ModemProperties>> {
-        let connection = zbus::Connection::session().await?;
-        let proxy = CellularModemsProxy::new(&connection).await?;
-
-        let modems = proxy.list().await?;
-
-        let properties = futures::stream::iter(modems)
-            .then(|id| async {
-                proxy
-                    .get(id.clone())
-                    .await
-                    .wrap_err_with(|| format!("couldn't get modem {id}"))
-                    .map(|modem| (id, modem))
-            })
-            .filter_map(|res| async {
-                let (id, modem) = match res {
-                    Ok(id_modem) => id_modem,
-                    Err(err) => {
-                        error!("{err}");
-
-                        return None;
-                    }
-                };
-
-                if modem.apn.is_empty() && modem.imei.is_empty() && modem.imsi.is_empty() {
-                    debug!("modem {id} fields are all empty");
-                    None
-                } else {
-                    Some((id, modem))
-                }
-            })
-            .collect()
-            .await;
-
-        Ok(properties)
-    }
-
     pub async fn send<C>(self, client: &mut C)
     where
         C: Client,
@@ -110,6 +50,20 @@ impl CellularConnection {
             set_property(client, INTERFACE, &format!("/{id}/apn"), modem.apn).await;
             set_property(client, INTERFACE, &format!("/{id}/imei"), modem.imei).await;
             set_property(client, INTERFACE, &format!("/{id}/imsi"), modem.imsi).await;
+
+            // Unset the fields no longer reported, e.g. when the modem lost the registration
+            for (endpoint, value) in [
+                ("operator", modem.operator),
+                ("accessTechnology", modem.access_technology),
+                ("registrationState", modem.registration_state),
+            ] {
+                let path = format!("/{id}/{endpoint}");
+
+                match value {
+                    Some(value) => set_property(client, REGISTRATION_INTERFACE, &path, value).await,
+                    None => unset_property(client, REGISTRATION_INTERFACE, &path).await,
+                }
+            }
         }
     }
 }
@@ -127,10 +81,11 @@ mod tests {
     #[tokio::test]
     async fn get_modem_properties_test() {
         let modem_id = "id";
-        let modem = ModemProperties {
+        let modem = ModemInfo {
             apn: "apn".to_string(),
             imei: "imei".to_string(),
             imsi: "imsi".to_string(),
+            ..Default::default()
         };
 
         let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
@@ -169,10 +124,68 @@ mod tests {
             })
             .returning(|_, _, _| Ok(()));
 
+        for path in [
+            "/id/operator",
+            "/id/accessTechnology",
+            "/id/registrationState",
+        ] {
+            client
+                .expect_unset_property()
+                .once()
+                .in_sequence(&mut seq)
+                .with(predicate::eq(REGISTRATION_INTERFACE), predicate::eq(path))
+                .returning(|_, _| Ok(()));
+        }
+
         CellularConnection {
             properties: HashMap::from([(modem_id.to_string(), modem)]),
         }
         .send(&mut client)
         .await;
     }
+
+    #[tokio::test]
+    async fn send_modem_manager_properties() {
+        let modem = ModemInfo {
+            apn: "apn".to_string(),
+            imei: "imei".to_string(),
+            imsi: "imsi".to_string(),
+            operator: Some("Operator".to_string()),
+            access_technology: Some("LTE".to_string()),
+            registration_state: Some("Registered".to_string()),
+        };
+
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut seq = Sequence::new();
+
+        for (interface, path, value) in [
+            (INTERFACE, "/id/apn", "apn"),
+            (INTERFACE, "/id/imei", "imei"),
+            (INTERFACE, "/id/imsi", "imsi"),
+            (REGISTRATION_INTERFACE, "/id/operator", "Operator"),
+            (REGISTRATION_INTERFACE, "/id/accessTechnology", "LTE"),
+            (
+                REGISTRATION_INTERFACE,
+                "/id/registrationState",
+                "Registered",
+            ),
+        ] {
+            client
+                .expect_set_property()
+                .once()
+                .in_sequence(&mut seq)
+                .with(
+                    predicate::eq(interface),
+                    predicate::eq(path),
+                    predicate::eq(AstarteData::from(value)),
+                )
+                .returning(|_, _, _| Ok(()));
+        }
+
+        CellularConnection {
+            properties: HashMap::from([("id".to_string(), modem)]),
+        }
+        .send(&mut client)
+        .await;
+    }
 }
@@ -34,11 +34,22 @@ pub mod hardware_info;
 pub(crate) mod net_interfaces;
 pub mod os_release;
 pub mod runtime_info;
+#[cfg(any(
+    feature = "containers",
+    feature = "file-transfer",
+    feature = "forwarder",
+    feature = "service",
+    all(feature = "zbus", target_os = "linux")
+))]
+pub mod subsystems;
 pub(crate) mod system_info;
 
 /// Sends the initial telemetry on startup
-pub(crate) async fn initial_telemetry<C>(client: &mut C)
-where
+pub(crate) async fn initial_telemetry<C>(
+    client: &mut C,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    cellular: &crate::telemetry::cellular::CellularConfig,
+) where
     C: Client,
 {
     #[cfg(all(feature = "systemd", target_os = "linux"))]
@@ -56,5 +67,5 @@ where
     SystemInfo::read().send(client).await;
 
     #[cfg(all(feature = "zbus", target_os = "linux"))]
-    CellularConnection::read().await.send(client).await;
+    CellularConnection::read(cellular).await.send(client).await;
 }
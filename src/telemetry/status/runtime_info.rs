@@ -23,10 +23,38 @@ use serde::Deserialize;
 use crate::Client;
 use crate::data::set_property;
 
-const INTERFACE: &str = "io.edgehog.devicemanager.RuntimeInfo";
+pub(super) const INTERFACE: &str = "io.edgehog.devicemanager.RuntimeInfo";
 
 pub const RUNTIME_INFO: RuntimeInfo<'static> = RuntimeInfo::read();
 
+/// Cargo features the runtime was compiled with.
+pub const FEATURES: &[&str] = &[
+    #[cfg(feature = "containers")]
+    "containers",
+    #[cfg(feature = "file-transfer")]
+    "file-transfer",
+    #[cfg(feature = "forwarder")]
+    "forwarder",
+    #[cfg(feature = "message-hub")]
+    "message-hub",
+    #[cfg(feature = "platform-verifier")]
+    "platform-verifier",
+    #[cfg(feature = "security-events")]
+    "security-events",
+    #[cfg(feature = "service")]
+    "service",
+    #[cfg(feature = "systemd")]
+    "systemd",
+    #[cfg(feature = "udev")]
+    "udev",
+    #[cfg(feature = "webpki-roots")]
+    "webpki-roots",
+    #[cfg(feature = "wifiscanner")]
+    "wifiscanner",
+    #[cfg(feature = "zbus")]
+    "zbus",
+];
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct RuntimeInfo<'a> {
     pub name: Cow<'a, str>,
@@ -60,6 +88,10 @@ impl RuntimeInfo<'static> {
         for (path, data) in values {
             set_property(client, INTERFACE, path, data.as_ref()).await;
         }
+
+        let features = FEATURES.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+        set_property(client, INTERFACE, "/features", features).await;
     }
 }
 
@@ -120,6 +152,19 @@ pub(crate) mod tests {
             .once()
             .in_sequence(seq)
             .returning(|_, _, _| Ok(()));
+
+        client
+            .expect_set_property()
+            .with(
+                predicate::eq("io.edgehog.devicemanager.RuntimeInfo"),
+                predicate::eq("/features"),
+                predicate::eq(AstarteData::StringArray(
+                    FEATURES.iter().map(|f| f.to_string()).collect(),
+                )),
+            )
+            .once()
+            .in_sequence(seq)
+            .returning(|_, _, _| Ok(()));
     }
 
     #[tokio::test]
@@ -131,4 +176,14 @@ pub(crate) mod tests {
 
         RUNTIME_INFO.send(&mut client).await;
     }
+
+    #[test]
+    fn should_list_features() {
+        assert_eq!(
+            FEATURES.contains(&"file-transfer"),
+            cfg!(feature = "file-transfer")
+        );
+        assert_eq!(FEATURES.contains(&"zbus"), cfg!(feature = "zbus"));
+        assert!(!FEATURES.contains(&"bindgen"));
+    }
 }
@@ -0,0 +1,226 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Status of the runtime subsystems, published in the runtime info.
+
+use super::runtime_info::INTERFACE;
+use crate::Client;
+use crate::data::{set_property, unset_property};
+use std::fmt::Display;
+
+/// Subsystem of the runtime that can be enabled in the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    #[cfg(feature = "containers")]
+    Containers,
+    #[cfg(feature = "file-transfer")]
+    FileTransfer,
+    #[cfg(feature = "forwarder")]
+    Forwarder,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    Network,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    Ota,
+    #[cfg(all(feature = "zbus", target_os = "linux"))]
+    PowerPolicy,
+    #[cfg(feature = "service")]
+    Service,
+}
+
+impl Display for Subsystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match *self {
+            #[cfg(feature = "containers")]
+            Subsystem::Containers => "containers",
+            #[cfg(feature = "file-transfer")]
+            Subsystem::FileTransfer => "fileTransfer",
+            #[cfg(feature = "forwarder")]
+            Subsystem::Forwarder => "forwarder",
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            Subsystem::Network => "network",
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            Subsystem::Ota => "ota",
+            #[cfg(all(feature = "zbus", target_os = "linux"))]
+            Subsystem::PowerPolicy => "powerPolicy",
+            #[cfg(feature = "service")]
+            Subsystem::Service => "service",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Status of a subsystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubsystemStatus {
+    /// Disabled in the configuration.
+    Disabled,
+    /// Enabled, but still initializing.
+    Enabled,
+    /// Initialized and running.
+    Initialized,
+    /// Running, but some functionality is not available.
+    Degraded(String),
+    /// Couldn't be initialized.
+    Failed(String),
+}
+
+impl SubsystemStatus {
+    /// Status of a subsystem that's initialized if enabled.
+    pub fn from_enabled(enabled: bool) -> Self {
+        if enabled {
+            SubsystemStatus::Initialized
+        } else {
+            SubsystemStatus::Disabled
+        }
+    }
+
+    fn reason(&self) -> Option<&str> {
+        match self {
+            SubsystemStatus::Disabled | SubsystemStatus::Enabled | SubsystemStatus::Initialized => {
+                None
+            }
+            SubsystemStatus::Degraded(reason) | SubsystemStatus::Failed(reason) => Some(reason),
+        }
+    }
+}
+
+impl Display for SubsystemStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubsystemStatus::Disabled => write!(f, "Disabled"),
+            SubsystemStatus::Enabled => write!(f, "Enabled"),
+            SubsystemStatus::Initialized => write!(f, "Initialized"),
+            SubsystemStatus::Degraded(_) => write!(f, "Degraded"),
+            SubsystemStatus::Failed(_) => write!(f, "Failed"),
+        }
+    }
+}
+
+/// Publishes the status of a subsystem, the reason is unset if not degraded or failed.
+pub(crate) async fn send_subsystem_status<C>(
+    client: &mut C,
+    subsystem: Subsystem,
+    status: &SubsystemStatus,
+) where
+    C: Client,
+{
+    set_property(
+        client,
+        INTERFACE,
+        &format!("/subsystems/{subsystem}/status"),
+        status.to_string(),
+    )
+    .await;
+
+    let reason_path = format!("/subsystems/{subsystem}/reason");
+
+    match status.reason() {
+        Some(reason) => set_property(client, INTERFACE, &reason_path, reason).await,
+        None => unset_property(client, INTERFACE, &reason_path).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_have_reason() {
+        assert_eq!(
+            SubsystemStatus::from_enabled(false),
+            SubsystemStatus::Disabled
+        );
+        assert_eq!(SubsystemStatus::Initialized.reason(), None);
+
+        let status = SubsystemStatus::Degraded("listener stopped".to_string());
+
+        assert_eq!(status.to_string(), "Degraded");
+        assert_eq!(status.reason(), Some("listener stopped"));
+    }
+
+    #[cfg(feature = "file-transfer")]
+    #[tokio::test]
+    async fn should_send_subsystem_status() {
+        use astarte_device_sdk::AstarteData;
+        use astarte_device_sdk::store::SqliteStore;
+        use astarte_device_sdk::transport::mqtt::Mqtt;
+        use astarte_device_sdk_mock::MockDeviceClient;
+        use mockall::{Sequence, predicate};
+
+        let mut client = MockDeviceClient::<Mqtt<SqliteStore>>::new();
+        let mut seq = Sequence::new();
+
+        client
+            .expect_set_property()
+            .with(
+                predicate::eq("io.edgehog.devicemanager.RuntimeInfo"),
+                predicate::eq("/subsystems/fileTransfer/status"),
+                predicate::eq(AstarteData::from("Failed")),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
+        client
+            .expect_set_property()
+            .with(
+                predicate::eq("io.edgehog.devicemanager.RuntimeInfo"),
+                predicate::eq("/subsystems/fileTransfer/reason"),
+                predicate::eq(AstarteData::from("store not writable")),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
+        client
+            .expect_set_property()
+            .with(
+                predicate::eq("io.edgehog.devicemanager.RuntimeInfo"),
+                predicate::eq("/subsystems/fileTransfer/status"),
+                predicate::eq(AstarteData::from("Initialized")),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
+        client
+            .expect_unset_property()
+            .with(
+                predicate::eq("io.edgehog.devicemanager.RuntimeInfo"),
+                predicate::eq("/subsystems/fileTransfer/reason"),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+
+        send_subsystem_status(
+            &mut client,
+            Subsystem::FileTransfer,
+            &SubsystemStatus::Failed("store not writable".to_string()),
+        )
+        .await;
+
+        send_subsystem_status(
+            &mut client,
+            Subsystem::FileTransfer,
+            &SubsystemStatus::from_enabled(true),
+        )
+        .await;
+    }
+}
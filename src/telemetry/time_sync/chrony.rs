@@ -0,0 +1,134 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Synchronization status from the `chronyc tracking` command.
+
+use astarte_device_sdk::chrono::DateTime;
+use eyre::WrapErr;
+
+use super::{TimeSync, TimeSyncBackend};
+
+/// Leap status reported when chrony is not synchronized.
+const NOT_SYNCHRONISED: &str = "Not synchronised";
+
+/// Runs `chronyc -c tracking` and parses the output.
+pub(crate) async fn tracking() -> eyre::Result<TimeSync> {
+    let output = tokio::process::Command::new("chronyc")
+        .args(["-c", "tracking"])
+        .output()
+        .await
+        .wrap_err("couldn't run chronyc")?;
+
+    eyre::ensure!(
+        output.status.success(),
+        "chronyc tracking failed: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+
+    let stdout = String::from_utf8(output.stdout).wrap_err("invalid chronyc output")?;
+
+    parse_tracking(&stdout)
+}
+
+/// Parses the CSV output of `chronyc -c tracking`.
+///
+/// The fields are: reference ID, reference name, stratum, reference time, system time offset,
+/// last offset, RMS offset, frequency, residual frequency, skew, root delay, root dispersion,
+/// update interval and leap status.
+fn parse_tracking(output: &str) -> eyre::Result<TimeSync> {
+    let fields: Vec<&str> = output.trim().split(',').collect();
+
+    eyre::ensure!(
+        fields.len() >= 14,
+        "expected 14 fields in chronyc output, got {}",
+        fields.len()
+    );
+
+    let source = fields[1].trim();
+    let ref_time: f64 = fields[3].parse().wrap_err("invalid reference time")?;
+    let offset: f64 = fields[4].parse().wrap_err("invalid system time offset")?;
+    let leap = fields[13].trim();
+
+    // The reference time is 0 when chrony never synchronized
+    let last_sync = (ref_time > 0.0)
+        .then(|| {
+            let secs = ref_time.trunc() as i64;
+            let nanos = (ref_time.fract() * 1e9) as u32;
+
+            DateTime::from_timestamp(secs, nanos)
+        })
+        .flatten();
+
+    let synchronized = leap != NOT_SYNCHRONISED && last_sync.is_some();
+
+    Ok(TimeSync {
+        backend: TimeSyncBackend::Chrony,
+        synchronized,
+        source: (!source.is_empty()).then(|| source.to_string()),
+        offset: Some(offset),
+        last_sync,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_synchronized() {
+        let output = "A29FC87B,time.cloudflare.com,4,1760790000.500000000,-0.000012345,0.000001234,0.000023456,-12.345,0.001,0.012,0.012345678,0.000456789,1024.5,Normal\n";
+
+        let status = parse_tracking(output).unwrap();
+
+        assert_eq!(
+            status,
+            TimeSync {
+                backend: TimeSyncBackend::Chrony,
+                synchronized: true,
+                source: Some("time.cloudflare.com".to_string()),
+                offset: Some(-0.000012345),
+                last_sync: DateTime::from_timestamp(1760790000, 500_000_000),
+            }
+        );
+    }
+
+    #[test]
+    fn should_parse_not_synchronized() {
+        let output = "00000000,,0,0.000000000,0.000000000,0.000000000,0.000000000,0.000,0.000,0.000,1.000000000,1.000000000,0.0,Not synchronised\n";
+
+        let status = parse_tracking(output).unwrap();
+
+        assert_eq!(
+            status,
+            TimeSync {
+                backend: TimeSyncBackend::Chrony,
+                synchronized: false,
+                source: None,
+                offset: Some(0.0),
+                last_sync: None,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_output() {
+        assert!(parse_tracking("506 Cannot talk to daemon").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Synchronization status of the system clock.
+
+use std::fmt::Display;
+use std::time::Duration;
+
+use astarte_device_sdk::chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+pub(crate) mod chrony;
+pub(crate) mod timedate;
+
+/// Configuration for the time synchronization status.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TimeSyncConfig {
+    /// Service used to read the synchronization status.
+    #[serde(default)]
+    pub backend: TimeSyncBackend,
+    /// Seconds the clock needs to jump between two reads to be reported.
+    ///
+    /// A value of 0 disables the check.
+    #[serde(default = "TimeSyncConfig::default_jump_threshold")]
+    pub jump_threshold: u64,
+    /// Publish an alert on Astarte when the clock jumps.
+    #[serde(default)]
+    pub alert: bool,
+}
+
+impl TimeSyncConfig {
+    const fn default_jump_threshold() -> u64 {
+        5
+    }
+
+    /// Returns the clock jump threshold, if enabled.
+    pub(crate) fn jump_threshold(&self) -> Option<Duration> {
+        (self.jump_threshold > 0).then(|| Duration::from_secs(self.jump_threshold))
+    }
+}
+
+impl Default for TimeSyncConfig {
+    fn default() -> Self {
+        Self {
+            backend: TimeSyncBackend::default(),
+            jump_threshold: Self::default_jump_threshold(),
+            alert: false,
+        }
+    }
+}
+
+/// Service used to read the synchronization status.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeSyncBackend {
+    /// Uses systemd-timesyncd if running, then chrony and lastly timedated.
+    #[default]
+    Auto,
+    /// The systemd-timesyncd service on the system bus.
+    Timesyncd,
+    /// Only the synchronization flag from systemd-timedated.
+    Timedated,
+    /// The `chronyc tracking` command.
+    Chrony,
+}
+
+impl Display for TimeSyncBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeSyncBackend::Auto => write!(f, "auto"),
+            TimeSyncBackend::Timesyncd => write!(f, "timesyncd"),
+            TimeSyncBackend::Timedated => write!(f, "timedated"),
+            TimeSyncBackend::Chrony => write!(f, "chrony"),
+        }
+    }
+}
+
+/// Synchronization status of the clock.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TimeSync {
+    /// Service the status was read from.
+    pub(crate) backend: TimeSyncBackend,
+    pub(crate) synchronized: bool,
+    /// Server the clock is synchronized with.
+    pub(crate) source: Option<String>,
+    /// Offset of the system clock from the source, in seconds.
+    pub(crate) offset: Option<f64>,
+    pub(crate) last_sync: Option<DateTime<Utc>>,
+}
+
+/// Reads the synchronization status with the configured backend.
+pub(crate) async fn read_time_sync(config: &TimeSyncConfig) -> eyre::Result<TimeSync> {
+    match config.backend {
+        TimeSyncBackend::Timesyncd => timedate::timesyncd().await,
+        TimeSyncBackend::Timedated => timedate::timedated().await,
+        TimeSyncBackend::Chrony => chrony::tracking().await,
+        TimeSyncBackend::Auto => {
+            match timedate::timesyncd().await {
+                Ok(status) => return Ok(status),
+                Err(err) => {
+                    debug!(
+                        error = format!("{err:#}"),
+                        "systemd-timesyncd not available"
+                    );
+                }
+            }
+
+            match chrony::tracking().await {
+                Ok(status) => return Ok(status),
+                Err(err) => {
+                    debug!(error = format!("{err:#}"), "chrony not available");
+                }
+            }
+
+            warn!("no NTP client found, reading only the synchronization flag from timedated");
+
+            timedate::timedated().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_deserialize() {
+        let string = r#"
+        backend = "chrony"
+        jump_threshold = 10
+        alert = true
+        "#;
+
+        let config: TimeSyncConfig = toml::from_str(string).unwrap();
+
+        let exp = TimeSyncConfig {
+            backend: TimeSyncBackend::Chrony,
+            jump_threshold: 10,
+            alert: true,
+        };
+
+        assert_eq!(config, exp);
+        assert_eq!(config.jump_threshold(), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn should_deserialize_default() {
+        let config: TimeSyncConfig = toml::from_str("").unwrap();
+
+        assert_eq!(config, TimeSyncConfig::default());
+        assert_eq!(config.backend, TimeSyncBackend::Auto);
+        assert_eq!(config.jump_threshold(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn jump_check_disabled() {
+        let config = TimeSyncConfig {
+            jump_threshold: 0,
+            ..Default::default()
+        };
+
+        assert_eq!(config.jump_threshold(), None);
+    }
+}
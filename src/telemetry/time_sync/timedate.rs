@@ -0,0 +1,225 @@
+// This file is part of Edgehog.
+//
+// Copyright 2026 SECO Mind Srl
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Synchronization status from systemd-timedated and systemd-timesyncd.
+
+use astarte_device_sdk::chrono::{DateTime, Utc};
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use zbus::proxy;
+use zbus::zvariant::{OwnedValue, Type, Value};
+
+use super::{TimeSync, TimeSyncBackend};
+
+#[proxy(
+    interface = "org.freedesktop.timedate1",
+    default_service = "org.freedesktop.timedate1",
+    default_path = "/org/freedesktop/timedate1"
+)]
+trait Timedate {
+    /// Whether the system clock is synchronized with a remote NTP server.
+    #[zbus(property, name = "NTPSynchronized")]
+    fn ntp_synchronized(&self) -> zbus::Result<bool>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.timesync1.Manager",
+    default_service = "org.freedesktop.timesync1",
+    default_path = "/org/freedesktop/timesync1"
+)]
+trait Timesync {
+    /// Name of the NTP server currently used.
+    #[zbus(property)]
+    fn server_name(&self) -> zbus::Result<String>;
+
+    /// Last NTP message received from the server.
+    #[zbus(property, name = "NTPMessage")]
+    fn ntp_message(&self) -> zbus::Result<NtpMessage>;
+}
+
+/// Last NTP response, the timestamps are in microseconds since the epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Type, Value, OwnedValue)]
+pub(crate) struct NtpMessage {
+    leap: u32,
+    version: u32,
+    mode: u32,
+    stratum: u32,
+    precision: i32,
+    root_delay: u64,
+    root_dispersion: u64,
+    reference: Vec<u8>,
+    origin: u64,
+    receive: u64,
+    transmit: u64,
+    destination: u64,
+    spike: bool,
+    packet_count: u64,
+    jitter: u64,
+}
+
+impl NtpMessage {
+    /// Returns `true` if no message was received yet.
+    fn is_empty(&self) -> bool {
+        self.packet_count == 0 || self.destination == 0
+    }
+
+    /// Clock offset from the server, in seconds.
+    fn offset(&self) -> f64 {
+        let receive = self.receive as f64 - self.origin as f64;
+        let transmit = self.transmit as f64 - self.destination as f64;
+
+        (receive + transmit) / 2.0 / 1e6
+    }
+
+    /// Time the message was received.
+    fn received_at(&self) -> Option<DateTime<Utc>> {
+        i64::try_from(self.destination)
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+    }
+}
+
+async fn is_synchronized(connection: &zbus::Connection) -> eyre::Result<bool> {
+    let timedate = TimedateProxy::new(connection).await?;
+
+    timedate
+        .ntp_synchronized()
+        .await
+        .wrap_err("couldn't read the NTP synchronized flag")
+}
+
+/// Reads the status from systemd-timesyncd.
+pub(crate) async fn timesyncd() -> eyre::Result<TimeSync> {
+    let connection = zbus::Connection::system().await?;
+    let timesync = TimesyncProxy::new(&connection).await?;
+
+    let server = timesync
+        .server_name()
+        .await
+        .wrap_err("couldn't read the timesyncd server")?;
+    let message = timesync
+        .ntp_message()
+        .await
+        .wrap_err("couldn't read the timesyncd NTP message")?;
+
+    let synchronized = is_synchronized(&connection).await?;
+
+    Ok(from_timesyncd(synchronized, server, &message))
+}
+
+fn from_timesyncd(synchronized: bool, server: String, message: &NtpMessage) -> TimeSync {
+    let (offset, last_sync) = if message.is_empty() {
+        (None, None)
+    } else {
+        (Some(message.offset()), message.received_at())
+    };
+
+    TimeSync {
+        backend: TimeSyncBackend::Timesyncd,
+        synchronized,
+        source: (!server.is_empty()).then_some(server),
+        offset,
+        last_sync,
+    }
+}
+
+/// Reads only the synchronization flag from systemd-timedated.
+pub(crate) async fn timedated() -> eyre::Result<TimeSync> {
+    let connection = zbus::Connection::system().await?;
+
+    let synchronized = is_synchronized(&connection).await?;
+
+    Ok(TimeSync {
+        backend: TimeSyncBackend::Timedated,
+        synchronized,
+        source: None,
+        offset: None,
+        last_sync: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn message(origin: u64, receive: u64, transmit: u64, destination: u64) -> NtpMessage {
+        NtpMessage {
+            leap: 0,
+            version: 4,
+            mode: 4,
+            stratum: 2,
+            precision: -20,
+            root_delay: 0,
+            root_dispersion: 0,
+            reference: Vec::new(),
+            origin,
+            receive,
+            transmit,
+            destination,
+            spike: false,
+            packet_count: 1,
+            jitter: 0,
+        }
+    }
+
+    #[test]
+    fn should_compute_offset() {
+        // The server is 1.5 seconds ahead, with 200ms of round trip
+        let origin = 1_760_790_000_000_000;
+        let msg = message(
+            origin,
+            origin + 1_600_000,
+            origin + 1_600_000,
+            origin + 200_000,
+        );
+
+        let status = from_timesyncd(true, "pool.ntp.org".to_string(), &msg);
+
+        assert_eq!(
+            status,
+            TimeSync {
+                backend: TimeSyncBackend::Timesyncd,
+                synchronized: true,
+                source: Some("pool.ntp.org".to_string()),
+                offset: Some(1.5),
+                last_sync: DateTime::from_timestamp_micros(origin as i64 + 200_000),
+            }
+        );
+    }
+
+    #[test]
+    fn no_message_received() {
+        let mut msg = message(0, 0, 0, 0);
+        msg.packet_count = 0;
+
+        let status = from_timesyncd(false, String::new(), &msg);
+
+        assert_eq!(
+            status,
+            TimeSync {
+                backend: TimeSyncBackend::Timesyncd,
+                synchronized: false,
+                source: None,
+                offset: None,
+                last_sync: None,
+            }
+        );
+    }
+}